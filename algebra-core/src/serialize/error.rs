@@ -27,6 +27,21 @@ impl From<io::Error> for SerializationError {
     }
 }
 
+impl From<SerializationError> for io::Error {
+    fn from(e: SerializationError) -> io::Error {
+        match e {
+            SerializationError::IoError(e) => e,
+            SerializationError::NotEnoughSpace => crate::error(
+                "the last byte does not have enough space to encode the extra info bits",
+            ),
+            SerializationError::InvalidData => {
+                crate::error("the input buffer contained invalid data")
+            },
+            SerializationError::UnexpectedFlags => crate::error("the call expects empty flags"),
+        }
+    }
+}
+
 impl fmt::Display for SerializationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         match self {
@@ -98,6 +98,13 @@ pub trait CanonicalDeserialize: Sized {
     fn deserialize_uncompressed<R: Read>(reader: &mut R) -> Result<Self, SerializationError> {
         Self::deserialize(reader)
     }
+
+    /// Reads `Self` from `reader` without compression, and without performing
+    /// validity checks. Should be used *only* when the input is trusted.
+    #[inline]
+    fn deserialize_unchecked<R: Read>(reader: &mut R) -> Result<Self, SerializationError> {
+        Self::deserialize_uncompressed(reader)
+    }
 }
 
 impl CanonicalSerialize for u64 {
@@ -126,6 +133,32 @@ impl CanonicalDeserialize for u64 {
     }
 }
 
+impl CanonicalSerialize for u8 {
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), SerializationError> {
+        Ok(writer.write_all(&[*self])?)
+    }
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        Self::SERIALIZED_SIZE
+    }
+}
+
+impl ConstantSerializedSize for u8 {
+    const SERIALIZED_SIZE: usize = 1;
+    const UNCOMPRESSED_SIZE: usize = Self::SERIALIZED_SIZE;
+}
+
+impl CanonicalDeserialize for u8 {
+    #[inline]
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self, SerializationError> {
+        let mut byte = [0u8; 1];
+        reader.read_exact(&mut byte)?;
+        Ok(byte[0])
+    }
+}
+
 impl<T: CanonicalSerialize> CanonicalSerialize for Vec<T> {
     #[inline]
     fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), SerializationError> {
@@ -184,6 +217,16 @@ impl<T: CanonicalDeserialize> CanonicalDeserialize for Vec<T> {
         }
         Ok(values)
     }
+
+    #[inline]
+    fn deserialize_unchecked<R: Read>(reader: &mut R) -> Result<Self, SerializationError> {
+        let len = u64::deserialize(reader)?;
+        let mut values = vec![];
+        for _ in 0..len {
+            values.push(T::deserialize_unchecked(reader)?);
+        }
+        Ok(values)
+    }
 }
 
 #[inline]
@@ -369,11 +412,22 @@ macro_rules! impl_sw_curve_serializer {
                     CanonicalDeserializeWithFlags::deserialize_with_flags(reader)?;
 
                 let p = GroupAffine::<P>::new(x, y, flags.is_infinity());
-                if !p.is_in_correct_subgroup_assuming_on_curve() {
+                if !p.is_on_curve() || !p.is_in_correct_subgroup_assuming_on_curve() {
                     return Err(crate::serialize::SerializationError::InvalidData);
                 }
                 Ok(p)
             }
+
+            #[allow(unused_qualifications)]
+            fn deserialize_unchecked<R: crate::io::Read>(
+                reader: &mut R,
+            ) -> Result<Self, crate::serialize::SerializationError> {
+                let x: P::BaseField = CanonicalDeserialize::deserialize(reader)?;
+                let (y, flags): (P::BaseField, crate::serialize::SWFlags) =
+                    CanonicalDeserializeWithFlags::deserialize_with_flags(reader)?;
+
+                Ok(GroupAffine::<P>::new(x, y, flags.is_infinity()))
+            }
         }
     };
 }
@@ -453,11 +507,21 @@ macro_rules! impl_edwards_curve_serializer {
                 let y: P::BaseField = CanonicalDeserialize::deserialize(reader)?;
 
                 let p = GroupAffine::<P>::new(x, y);
-                if !p.is_in_correct_subgroup_assuming_on_curve() {
+                if !p.is_on_curve() || !p.is_in_correct_subgroup_assuming_on_curve() {
                     return Err(crate::serialize::SerializationError::InvalidData);
                 }
                 Ok(p)
             }
+
+            #[allow(unused_qualifications)]
+            fn deserialize_unchecked<R: crate::io::Read>(
+                reader: &mut R,
+            ) -> Result<Self, crate::serialize::SerializationError> {
+                let x: P::BaseField = CanonicalDeserialize::deserialize(reader)?;
+                let y: P::BaseField = CanonicalDeserialize::deserialize(reader)?;
+
+                Ok(GroupAffine::<P>::new(x, y))
+            }
         }
     };
 }
@@ -0,0 +1,83 @@
+use algebra_core::{
+    fields::{Field, Fp6Parameters},
+    UniformRand,
+};
+use rand::SeedableRng;
+use rand_xorshift::XorShiftRng;
+
+use crate::{
+    bn254::{Fq, Fq12, Fq2, Fq6, Fq6Parameters, Fr},
+    tests::fields::{field_test, frobenius_test, primefield_test, sqrt_field_test},
+};
+
+pub(crate) const ITERATIONS: usize = 5;
+
+#[test]
+fn test_fr() {
+    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+    for _ in 0..ITERATIONS {
+        let a: Fr = UniformRand::rand(&mut rng);
+        let b: Fr = UniformRand::rand(&mut rng);
+        field_test(a, b);
+        primefield_test::<Fr>();
+        sqrt_field_test(b);
+    }
+}
+
+#[test]
+fn test_fq() {
+    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+    for _ in 0..ITERATIONS {
+        let a: Fq = UniformRand::rand(&mut rng);
+        let b: Fq = UniformRand::rand(&mut rng);
+        field_test(a, b);
+        primefield_test::<Fq>();
+        sqrt_field_test(a);
+    }
+}
+
+#[test]
+fn test_fq2() {
+    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+    for _ in 0..ITERATIONS {
+        let a: Fq2 = UniformRand::rand(&mut rng);
+        let b: Fq2 = UniformRand::rand(&mut rng);
+        field_test(a, b);
+        sqrt_field_test(a);
+    }
+    frobenius_test::<Fq2, _>(Fq::characteristic(), 13);
+}
+
+#[test]
+fn test_fq6() {
+    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+    for _ in 0..ITERATIONS {
+        let g: Fq6 = UniformRand::rand(&mut rng);
+        let h: Fq6 = UniformRand::rand(&mut rng);
+        field_test(g, h);
+    }
+    frobenius_test::<Fq6, _>(Fq::characteristic(), 13);
+}
+
+#[test]
+fn test_fq12() {
+    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+    for _ in 0..ITERATIONS {
+        let g: Fq12 = UniformRand::rand(&mut rng);
+        let h: Fq12 = UniformRand::rand(&mut rng);
+        field_test(g, h);
+    }
+    frobenius_test::<Fq12, _>(Fq::characteristic(), 13);
+}
+
+#[test]
+fn test_fq6_mul_fp2_by_nonresidue() {
+    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+    for _ in 0..1000 {
+        let a: Fq2 = UniformRand::rand(&mut rng);
+        assert_eq!(
+            Fq6Parameters::mul_fp2_by_nonresidue(&a),
+            a * Fq6Parameters::NONRESIDUE
+        );
+    }
+}
@@ -0,0 +1,7 @@
+#[cfg(feature = "bn254")]
+mod curves;
+mod fields;
+
+#[cfg(feature = "bn254")]
+pub use curves::*;
+pub use fields::*;
@@ -121,6 +121,13 @@ pub mod bn_382;
 pub use bn_382::Bn_382;
 ///////////////////////////////////////////////////////////////////////////////
 
+///////////////////////////////////////////////////////////////////////////////
+#[cfg(feature = "bn254")]
+pub mod bn254;
+#[cfg(feature = "bn254")]
+pub use bn254::Bn254;
+///////////////////////////////////////////////////////////////////////////////
+
 ///////////////////////////////////////////////////////////////////////////////
 #[cfg(feature = "tweedle")]
 pub mod tweedle;
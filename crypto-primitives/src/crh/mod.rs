@@ -5,6 +5,7 @@ use rand::Rng;
 pub mod bowe_hopwood;
 pub mod injective_map;
 pub mod pedersen;
+pub mod sha256;
 
 use crate::Error;
 
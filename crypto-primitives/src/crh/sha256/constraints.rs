@@ -0,0 +1,158 @@
+use algebra_core::{Field, PrimeField};
+use r1cs_core::{ConstraintSystem, SynthesisError};
+use r1cs_std::prelude::*;
+
+use crate::{
+    crh::{sha256::Sha256CRH, FixedLengthCRH, FixedLengthCRHGadget},
+    prf::sha256::constraints::{sha256_gadget, Sha256OutputGadget},
+};
+
+use core::borrow::Borrow;
+
+#[derive(Clone)]
+pub struct Sha256CRHParametersGadget;
+
+pub struct Sha256CRHGadget;
+
+impl<ConstraintF: PrimeField> FixedLengthCRHGadget<Sha256CRH, ConstraintF> for Sha256CRHGadget {
+    type OutputGadget = Sha256OutputGadget;
+    type ParametersGadget = Sha256CRHParametersGadget;
+
+    fn check_evaluation_gadget<CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        _: &Self::ParametersGadget,
+        input: &[UInt8],
+    ) -> Result<Self::OutputGadget, SynthesisError> {
+        if input.len() * 8 > Sha256CRH::INPUT_SIZE_BITS {
+            return Err(SynthesisError::InputTooLong);
+        }
+        let mut padded_input = input.to_vec();
+        // Pad the input if it is not the current length.
+        padded_input.resize(Sha256CRH::INPUT_SIZE_BITS / 8, UInt8::constant(0u8));
+        Ok(Sha256OutputGadget(sha256_gadget(cs, &padded_input)?))
+    }
+}
+
+impl<ConstraintF: Field> AllocGadget<(), ConstraintF> for Sha256CRHParametersGadget {
+    fn alloc_constant<T, CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        val: T,
+    ) -> Result<Self, SynthesisError>
+    where
+        T: Borrow<()>,
+    {
+        Self::alloc(cs, || Ok(val))
+    }
+
+    fn alloc<F, T, CS: ConstraintSystem<ConstraintF>>(_: CS, _: F) -> Result<Self, SynthesisError>
+    where
+        F: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<()>,
+    {
+        Ok(Sha256CRHParametersGadget)
+    }
+
+    fn alloc_input<F, T, CS: ConstraintSystem<ConstraintF>>(
+        _: CS,
+        _: F,
+    ) -> Result<Self, SynthesisError>
+    where
+        F: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<()>,
+    {
+        Ok(Sha256CRHParametersGadget)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crh::{
+        sha256::{
+            constraints::{Sha256CRHGadget, Sha256CRHParametersGadget},
+            Sha256CRH,
+        },
+        FixedLengthCRH, FixedLengthCRHGadget,
+    };
+    use algebra::{jubjub::Fq as Fr, test_rng};
+    use r1cs_core::{ConstraintSystem, SynthesisError};
+    use r1cs_std::{prelude::*, test_constraint_system::TestConstraintSystem};
+    use rand::Rng;
+
+    type TestOutputGadget = <Sha256CRHGadget as FixedLengthCRHGadget<Sha256CRH, Fr>>::OutputGadget;
+
+    #[test]
+    fn crh_primitive_gadget_test() {
+        let rng = &mut test_rng();
+        let parameters = Sha256CRH::setup(rng).unwrap();
+
+        // Both a full input and a short one, which is padded with zeros.
+        let mut input = [0u8; 64];
+        rng.fill(&mut input[..]);
+        let mut results = Vec::new();
+        for &len in &[64, 30] {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+            let input_bytes = UInt8::alloc_vec(&mut cs.ns(|| "input"), &input[..len]).unwrap();
+            let gadget_parameters =
+                <Sha256CRHGadget as FixedLengthCRHGadget<Sha256CRH, Fr>>::ParametersGadget::alloc(
+                    &mut cs.ns(|| "gadget_parameters"),
+                    || Ok(&parameters),
+                )
+                .unwrap();
+            let gadget_result =
+                <Sha256CRHGadget as FixedLengthCRHGadget<Sha256CRH, Fr>>::check_evaluation_gadget(
+                    &mut cs.ns(|| "gadget_evaluation"),
+                    &gadget_parameters,
+                    &input_bytes,
+                )
+                .unwrap();
+            assert!(cs.is_satisfied());
+
+            let primitive_result = Sha256CRH::evaluate(&parameters, &input[..len]).unwrap();
+            let gadget_value: Vec<u8> = gadget_result
+                .0
+                .iter()
+                .map(|byte| byte.get_value().unwrap())
+                .collect();
+            assert_eq!(gadget_value, primitive_result.to_vec());
+            results.push(primitive_result);
+        }
+        assert_ne!(results[0], results[1]);
+
+        // A longer input is rejected by both.
+        assert!(Sha256CRH::evaluate(&parameters, &[0u8; 65]).is_err());
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let input_bytes = UInt8::alloc_vec(&mut cs.ns(|| "input"), &[0u8; 65]).unwrap();
+        let result =
+            <Sha256CRHGadget as FixedLengthCRHGadget<Sha256CRH, Fr>>::check_evaluation_gadget(
+                &mut cs.ns(|| "gadget_evaluation"),
+                &Sha256CRHParametersGadget,
+                &input_bytes,
+            );
+        assert!(matches!(result, Err(SynthesisError::InputTooLong)));
+    }
+
+    #[test]
+    fn crh_output_select_test() {
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let a = [1u8; 32];
+        let b = [2u8; 32];
+        let a_gadget = TestOutputGadget::alloc(&mut cs.ns(|| "a"), || Ok(a)).unwrap();
+        let b_gadget = TestOutputGadget::alloc(&mut cs.ns(|| "b"), || Ok(b)).unwrap();
+        for &cond in &[true, false] {
+            let mut cs = cs.ns(|| format!("select {}", cond));
+            let cond_gadget = Boolean::alloc(&mut cs.ns(|| "cond"), || Ok(cond)).unwrap();
+            let selected = TestOutputGadget::conditionally_select(
+                &mut cs.ns(|| "select"),
+                &cond_gadget,
+                &a_gadget,
+                &b_gadget,
+            )
+            .unwrap();
+            let expected = if cond { &a_gadget } else { &b_gadget };
+            selected
+                .enforce_equal(&mut cs.ns(|| "check"), expected)
+                .unwrap();
+        }
+        assert!(cs.is_satisfied());
+    }
+}
@@ -0,0 +1,35 @@
+use rand::Rng;
+
+use crate::{crh::FixedLengthCRH, prf::sha256::Sha256, Box, CryptoError, Error};
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+/// A CRH that maps 512 bits, such as the two children of a node of a Merkle
+/// tree, to their SHA-256 digest. Shorter inputs are padded with zeros.
+pub struct Sha256CRH;
+
+impl FixedLengthCRH for Sha256CRH {
+    const INPUT_SIZE_BITS: usize = 512;
+    type Output = [u8; 32];
+    type Parameters = ();
+
+    fn setup<R: Rng>(_: &mut R) -> Result<Self::Parameters, Error> {
+        Ok(())
+    }
+
+    fn evaluate(_: &Self::Parameters, input: &[u8]) -> Result<Self::Output, Error> {
+        let eval_time = start_timer!(|| "Sha256CRH::Eval");
+
+        if input.len() * 8 > Self::INPUT_SIZE_BITS {
+            return Err(Box::new(CryptoError::IncorrectInputLength(input.len())));
+        }
+        let mut padded_input = input.to_vec();
+        padded_input.resize(Self::INPUT_SIZE_BITS / 8, 0u8);
+        let result = Sha256::hash(&padded_input);
+
+        end_timer!(eval_time);
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,415 @@
+use algebra_core::PrimeField;
+use r1cs_core::{ConstraintSystem, SynthesisError};
+
+use crate::{
+    prf::{
+        sha256::{padding, Sha256, H0, K},
+        PRFGadget,
+    },
+    Vec,
+};
+use r1cs_std::prelude::*;
+
+use core::borrow::Borrow;
+
+// Packs four bytes into a word, the first byte being the most significant
+// one as in FIPS 180-4.
+fn word_from_be_bytes(bytes: &[UInt8]) -> UInt32 {
+    let bits = bytes
+        .iter()
+        .rev()
+        .flat_map(|byte| byte.into_bits_le())
+        .collect::<Vec<_>>();
+    UInt32::from_bits_le(&bits)
+}
+
+// Unpacks a word into four bytes, the first byte being the most significant
+// one as in FIPS 180-4.
+fn word_to_be_bytes(word: &UInt32) -> Vec<UInt8> {
+    let bits = word.to_bits_le();
+    bits.chunks(8).rev().map(UInt8::from_bits_le).collect()
+}
+
+// Computes a ^ b ^ c.
+fn xor3<ConstraintF: PrimeField, CS: ConstraintSystem<ConstraintF>>(
+    mut cs: CS,
+    a: &UInt32,
+    b: &UInt32,
+    c: &UInt32,
+) -> Result<UInt32, SynthesisError> {
+    a.xor(cs.ns(|| "first xor"), b)?
+        .xor(cs.ns(|| "second xor"), c)
+}
+
+// The compression function of FIPS 180-4, section 6.2.2, which updates the
+// hash value `h` with a message block of sixteen words.
+fn sha256_compression<ConstraintF: PrimeField, CS: ConstraintSystem<ConstraintF>>(
+    mut cs: CS,
+    h: &mut [UInt32],
+    block: &[UInt32],
+) -> Result<(), SynthesisError> {
+    assert_eq!(h.len(), 8);
+    assert_eq!(block.len(), 16);
+
+    // Prepare the message schedule.
+    let mut w = block.to_vec();
+    for t in 16..64 {
+        let mut cs = cs.ns(|| format!("schedule word {}", t));
+
+        let s0 = xor3(
+            cs.ns(|| "sigma 0"),
+            &w[t - 15].rotr(7),
+            &w[t - 15].rotr(18),
+            &w[t - 15].shr(3),
+        )?;
+        let s1 = xor3(
+            cs.ns(|| "sigma 1"),
+            &w[t - 2].rotr(17),
+            &w[t - 2].rotr(19),
+            &w[t - 2].shr(10),
+        )?;
+        let word = UInt32::addmany(
+            cs.ns(|| "sum"),
+            &[w[t - 16].clone(), s0, w[t - 7].clone(), s1],
+        )?;
+        w.push(word);
+    }
+
+    // Run the 64 rounds on the working variables a, ..., h.
+    let mut v = h.to_vec();
+    for t in 0..64 {
+        let mut cs = cs.ns(|| format!("round {}", t));
+
+        let s1 = xor3(
+            cs.ns(|| "Sigma 1"),
+            &v[4].rotr(6),
+            &v[4].rotr(11),
+            &v[4].rotr(25),
+        )?;
+        // Ch(e, f, g) = (e & f) ^ (!e & g)
+        let ch = {
+            let e_and_f = v[4].and(cs.ns(|| "e and f"), &v[5])?;
+            let not_e_and_g = v[4].not().and(cs.ns(|| "not e and g"), &v[6])?;
+            e_and_f.xor(cs.ns(|| "Ch"), &not_e_and_g)?
+        };
+        let s0 = xor3(
+            cs.ns(|| "Sigma 0"),
+            &v[0].rotr(2),
+            &v[0].rotr(13),
+            &v[0].rotr(22),
+        )?;
+        // Maj(a, b, c) = (a & b) ^ (a & c) ^ (b & c)
+        let maj = {
+            let a_and_b = v[0].and(cs.ns(|| "a and b"), &v[1])?;
+            let a_and_c = v[0].and(cs.ns(|| "a and c"), &v[2])?;
+            let b_and_c = v[1].and(cs.ns(|| "b and c"), &v[2])?;
+            xor3(cs.ns(|| "Maj"), &a_and_b, &a_and_c, &b_and_c)?
+        };
+
+        // T1 = h + Sigma 1 + Ch + K[t] + W[t], and T2 = Sigma 0 + Maj. Both
+        // sums are computed at once, which costs less than computing T1
+        // first.
+        let t1 = [v[7].clone(), s1, ch, UInt32::constant(K[t]), w[t].clone()];
+        let mut new_a = t1.to_vec();
+        new_a.extend_from_slice(&[s0, maj]);
+        let mut new_e = t1.to_vec();
+        new_e.push(v[3].clone());
+
+        let new_a = UInt32::addmany(cs.ns(|| "new a"), &new_a)?;
+        let new_e = UInt32::addmany(cs.ns(|| "new e"), &new_e)?;
+        v = vec![
+            new_a,
+            v[0].clone(),
+            v[1].clone(),
+            v[2].clone(),
+            new_e,
+            v[4].clone(),
+            v[5].clone(),
+            v[6].clone(),
+        ];
+    }
+
+    // Compute the intermediate hash value.
+    for (i, (h, v)) in h.iter_mut().zip(v).enumerate() {
+        *h = UInt32::addmany(cs.ns(|| format!("hash word {}", i)), &[h.clone(), v])?;
+    }
+
+    Ok(())
+}
+
+/// Computes the SHA-256 digest of `input`, as specified in FIPS 180-4.
+pub fn sha256_gadget<ConstraintF: PrimeField, CS: ConstraintSystem<ConstraintF>>(
+    mut cs: CS,
+    input: &[UInt8],
+) -> Result<Vec<UInt8>, SynthesisError> {
+    let mut message = input.to_vec();
+    message.extend(padding(input.len()).into_iter().map(UInt8::constant));
+
+    let mut h = H0
+        .iter()
+        .map(|&word| UInt32::constant(word))
+        .collect::<Vec<_>>();
+    for (i, chunk) in message.chunks(64).enumerate() {
+        let block = chunk.chunks(4).map(word_from_be_bytes).collect::<Vec<_>>();
+        sha256_compression(cs.ns(|| format!("block {}", i)), &mut h, &block)?;
+    }
+
+    Ok(h.iter().flat_map(word_to_be_bytes).collect())
+}
+
+pub struct Sha256Gadget;
+#[derive(Clone, Debug)]
+pub struct Sha256OutputGadget(pub Vec<UInt8>);
+
+impl PartialEq for Sha256OutputGadget {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for Sha256OutputGadget {}
+
+impl<ConstraintF: PrimeField> EqGadget<ConstraintF> for Sha256OutputGadget {}
+
+impl<ConstraintF: PrimeField> ConditionalEqGadget<ConstraintF> for Sha256OutputGadget {
+    #[inline]
+    fn conditional_enforce_equal<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        condition: &Boolean,
+    ) -> Result<(), SynthesisError> {
+        for (i, (a, b)) in self.0.iter().zip(other.0.iter()).enumerate() {
+            a.conditional_enforce_equal(
+                &mut cs.ns(|| format!("sha256_equal_{}", i)),
+                b,
+                condition,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn cost() -> usize {
+        32 * <UInt8 as ConditionalEqGadget<ConstraintF>>::cost()
+    }
+}
+
+impl<ConstraintF: PrimeField> CondSelectGadget<ConstraintF> for Sha256OutputGadget {
+    fn conditionally_select<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        cond: &Boolean,
+        true_value: &Self,
+        false_value: &Self,
+    ) -> Result<Self, SynthesisError> {
+        let mut bytes = Vec::with_capacity(32);
+        for (i, (a, b)) in true_value.0.iter().zip(&false_value.0).enumerate() {
+            let mut bits = Vec::with_capacity(8);
+            for (j, (a, b)) in a.into_bits_le().iter().zip(&b.into_bits_le()).enumerate() {
+                let cs = cs.ns(|| format!("select bit {} of byte {}", j, i));
+                bits.push(Boolean::conditionally_select(cs, cond, a, b)?);
+            }
+            bytes.push(UInt8::from_bits_le(&bits));
+        }
+        Ok(Sha256OutputGadget(bytes))
+    }
+
+    fn cost() -> usize {
+        256 * <Boolean as CondSelectGadget<ConstraintF>>::cost()
+    }
+}
+
+impl<ConstraintF: PrimeField> ToBytesGadget<ConstraintF> for Sha256OutputGadget {
+    #[inline]
+    fn to_bytes<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        _cs: CS,
+    ) -> Result<Vec<UInt8>, SynthesisError> {
+        Ok(self.0.clone())
+    }
+}
+
+impl<ConstraintF: PrimeField> AllocGadget<[u8; 32], ConstraintF> for Sha256OutputGadget {
+    #[inline]
+    fn alloc_constant<T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        val: T,
+    ) -> Result<Self, SynthesisError>
+    where
+        T: Borrow<[u8; 32]>,
+    {
+        let mut bytes = vec![];
+        for (i, b) in val.borrow().iter().enumerate() {
+            bytes.push(UInt8::alloc_constant(cs.ns(|| format!("value {}", i)), b)?)
+        }
+
+        Ok(Sha256OutputGadget(bytes))
+    }
+
+    #[inline]
+    fn alloc<F, T, CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        value_gen: F,
+    ) -> Result<Self, SynthesisError>
+    where
+        F: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<[u8; 32]>,
+    {
+        let zeros = [0u8; 32];
+        let value = match value_gen() {
+            Ok(val) => *(val.borrow()),
+            Err(_) => zeros,
+        };
+        let bytes = <UInt8>::alloc_vec(cs, &value)?;
+
+        Ok(Sha256OutputGadget(bytes))
+    }
+
+    #[inline]
+    fn alloc_input<F, T, CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        value_gen: F,
+    ) -> Result<Self, SynthesisError>
+    where
+        F: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<[u8; 32]>,
+    {
+        let zeros = [0u8; 32];
+        let value = match value_gen() {
+            Ok(val) => *(val.borrow()),
+            Err(_) => zeros,
+        };
+        let bytes = <UInt8>::alloc_input_vec(cs, &value)?;
+
+        Ok(Sha256OutputGadget(bytes))
+    }
+}
+
+impl<ConstraintF: PrimeField> PRFGadget<Sha256, ConstraintF> for Sha256Gadget {
+    type OutputGadget = Sha256OutputGadget;
+
+    fn new_seed<CS: ConstraintSystem<ConstraintF>>(mut cs: CS, seed: &[u8; 32]) -> Vec<UInt8> {
+        UInt8::alloc_vec(&mut cs.ns(|| "alloc_seed"), seed).unwrap()
+    }
+
+    fn check_evaluation_gadget<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        seed: &[UInt8],
+        input: &[UInt8],
+    ) -> Result<Self::OutputGadget, SynthesisError> {
+        assert_eq!(seed.len(), 32);
+        let mut message = seed.to_vec();
+        message.extend_from_slice(input);
+        let result = sha256_gadget(cs.ns(|| "SHA-256 Eval"), &message)?;
+        Ok(Sha256OutputGadget(result))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use algebra::{jubjub::Fq as Fr, One};
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    use crate::prf::sha256::{
+        constraints::sha256_gadget,
+        test::{from_hex, NIST_VECTORS},
+        Sha256,
+    };
+    use r1cs_core::ConstraintSystem;
+
+    use super::Sha256Gadget;
+    use r1cs_std::{prelude::*, test_constraint_system::TestConstraintSystem};
+
+    fn digest_value(digest: &[UInt8]) -> Vec<u8> {
+        digest
+            .iter()
+            .map(|byte| byte.get_value().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_sha256_nist_vectors() {
+        for (message, digest) in NIST_VECTORS.iter() {
+            let mut cs = TestConstraintSystem::<Fr>::new();
+            let input = UInt8::alloc_vec(&mut cs.ns(|| "input"), message.as_bytes()).unwrap();
+            let result = sha256_gadget(&mut cs.ns(|| "sha256"), &input).unwrap();
+            assert!(cs.is_satisfied());
+            assert_eq!(digest_value(&result), from_hex(digest));
+        }
+    }
+
+    #[test]
+    fn test_sha256() {
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+
+        // Cover the lengths at which the padding spills into another block.
+        for &input_len in &[0, 1, 32, 55, 56, 63, 64, 65, 119, 120, 128] {
+            let data: Vec<u8> = (0..input_len).map(|_| rng.gen()).collect();
+
+            let mut cs = TestConstraintSystem::<Fr>::new();
+            let input = UInt8::alloc_vec(&mut cs.ns(|| "input"), &data).unwrap();
+            let result = sha256_gadget(&mut cs.ns(|| "sha256"), &input).unwrap();
+            assert!(cs.is_satisfied());
+            assert_eq!(digest_value(&result), Sha256::hash(&data).to_vec());
+
+            // Flipping a bit of the digest must violate a constraint.
+            if input_len > 0 {
+                let path = "sha256/block 0/hash word 0/result bit_gadget 0/boolean";
+                let bit = cs.get(path);
+                cs.set(path, Fr::one() - &bit);
+                assert!(!cs.is_satisfied());
+            }
+        }
+    }
+
+    #[test]
+    fn test_sha256_constant_constraints() {
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let data: Vec<u8> = (0..64).map(|_| rng.gen()).collect();
+        let result = sha256_gadget(&mut cs, &UInt8::constant_vec(&data)).unwrap();
+        assert_eq!(cs.num_constraints(), 0);
+        assert_eq!(digest_value(&result), Sha256::hash(&data).to_vec());
+    }
+
+    #[test]
+    fn test_sha256_prf() {
+        use crate::prf::{PRFGadget, PRF};
+
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        let mut cs = TestConstraintSystem::<Fr>::new();
+
+        let mut seed = [0u8; 32];
+        rng.fill(&mut seed);
+
+        let mut input = [0u8; 32];
+        rng.fill(&mut input);
+
+        let seed_gadget = Sha256Gadget::new_seed(&mut cs.ns(|| "declare_seed"), &seed);
+        let input_gadget = UInt8::alloc_vec(&mut cs.ns(|| "declare_input"), &input).unwrap();
+        let out = Sha256::evaluate(&seed, &input).unwrap();
+        let actual_out_gadget = <Sha256Gadget as PRFGadget<_, Fr>>::OutputGadget::alloc(
+            &mut cs.ns(|| "declare_output"),
+            || Ok(out),
+        )
+        .unwrap();
+
+        let output_gadget = Sha256Gadget::check_evaluation_gadget(
+            &mut cs.ns(|| "eval_sha256"),
+            &seed_gadget,
+            &input_gadget,
+        )
+        .unwrap();
+        output_gadget
+            .enforce_equal(&mut cs, &actual_out_gadget)
+            .unwrap();
+
+        if !cs.is_satisfied() {
+            println!(
+                "which is unsatisfied: {:?}",
+                cs.which_is_unsatisfied().unwrap()
+            );
+        }
+        assert!(cs.is_satisfied());
+    }
+}
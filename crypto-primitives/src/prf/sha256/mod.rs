@@ -0,0 +1,176 @@
+use alloc::vec::Vec;
+
+use super::PRF;
+use crate::CryptoError;
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+/// The initial hash value of SHA-256 (FIPS 180-4, section 5.3.3).
+pub(crate) const H0: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// The round constants of SHA-256 (FIPS 180-4, section 4.2.2).
+pub(crate) const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Pads a message of `len` bytes as in FIPS 180-4, section 5.1.1: with a
+/// single one bit, then zeros up to 56 bytes modulo 64, then the length of
+/// the message in bits as a big-endian 64-bit integer.
+pub(crate) fn padding(len: usize) -> Vec<u8> {
+    let mut padding = vec![0x80u8];
+    while (len + padding.len()) % 64 != 56 {
+        padding.push(0);
+    }
+    padding.extend_from_slice(&((len as u64) * 8).to_be_bytes());
+    padding
+}
+
+/// Applies the SHA-256 compression function to the hash value `h` and a
+/// message block of sixteen words (FIPS 180-4, section 6.2.2).
+fn compress(h: &mut [u32; 8], block: &[u32; 16]) {
+    let mut w = [0u32; 64];
+    w[..16].copy_from_slice(block);
+    for t in 16..64 {
+        let s0 = w[t - 15].rotate_right(7) ^ w[t - 15].rotate_right(18) ^ (w[t - 15] >> 3);
+        let s1 = w[t - 2].rotate_right(17) ^ w[t - 2].rotate_right(19) ^ (w[t - 2] >> 10);
+        w[t] = w[t - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[t - 7])
+            .wrapping_add(s1);
+    }
+
+    let mut v = *h;
+    for t in 0..64 {
+        let s1 = v[4].rotate_right(6) ^ v[4].rotate_right(11) ^ v[4].rotate_right(25);
+        let ch = (v[4] & v[5]) ^ (!v[4] & v[6]);
+        let t1 = v[7]
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(K[t])
+            .wrapping_add(w[t]);
+        let s0 = v[0].rotate_right(2) ^ v[0].rotate_right(13) ^ v[0].rotate_right(22);
+        let maj = (v[0] & v[1]) ^ (v[0] & v[2]) ^ (v[1] & v[2]);
+        let t2 = s0.wrapping_add(maj);
+        v = [
+            t1.wrapping_add(t2),
+            v[0],
+            v[1],
+            v[2],
+            v[3].wrapping_add(t1),
+            v[4],
+            v[5],
+            v[6],
+        ];
+    }
+
+    for (h, v) in h.iter_mut().zip(&v) {
+        *h = h.wrapping_add(*v);
+    }
+}
+
+#[derive(Clone)]
+pub struct Sha256;
+
+impl Sha256 {
+    /// Computes the SHA-256 digest of `input`, as specified in FIPS 180-4.
+    pub fn hash(input: &[u8]) -> [u8; 32] {
+        let mut message = input.to_vec();
+        message.extend_from_slice(&padding(input.len()));
+
+        let mut h = H0;
+        for chunk in message.chunks(64) {
+            let mut block = [0u32; 16];
+            for (word, bytes) in block.iter_mut().zip(chunk.chunks(4)) {
+                *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+            }
+            compress(&mut h, &block);
+        }
+
+        let mut result = [0u8; 32];
+        for (bytes, word) in result.chunks_mut(4).zip(&h) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+        result
+    }
+}
+
+impl PRF for Sha256 {
+    type Input = [u8; 32];
+    type Output = [u8; 32];
+    type Seed = [u8; 32];
+
+    fn evaluate(seed: &Self::Seed, input: &Self::Input) -> Result<Self::Output, CryptoError> {
+        let eval_time = start_timer!(|| "Sha256::Eval");
+        let mut message = Vec::with_capacity(64);
+        message.extend_from_slice(seed);
+        message.extend_from_slice(input);
+        let result = Self::hash(&message);
+        end_timer!(eval_time);
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::Sha256;
+    use crate::{prf::PRF, Vec};
+
+    pub(crate) fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    /// The examples of FIPS 180-4 for SHA-256, as messages and digests.
+    pub(crate) const NIST_VECTORS: [(&str, &str); 2] = [
+        (
+            "abc",
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        ),
+        (
+            "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+        ),
+    ];
+
+    #[test]
+    fn test_sha256_nist_vectors() {
+        for (message, digest) in NIST_VECTORS.iter() {
+            assert_eq!(Sha256::hash(message.as_bytes()).to_vec(), from_hex(digest));
+        }
+
+        // The long message example: one million repetitions of "a".
+        let message = vec![b'a'; 1_000_000];
+        assert_eq!(
+            Sha256::hash(&message).to_vec(),
+            from_hex("cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0")
+        );
+    }
+
+    #[test]
+    fn test_sha256_prf() {
+        let seed = [1u8; 32];
+        let input = [2u8; 32];
+        let mut message = seed.to_vec();
+        message.extend_from_slice(&input);
+        assert_eq!(
+            Sha256::evaluate(&seed, &input).unwrap(),
+            Sha256::hash(&message)
+        );
+        assert_ne!(
+            Sha256::evaluate(&seed, &input).unwrap(),
+            Sha256::evaluate(&input, &seed).unwrap()
+        );
+    }
+}
@@ -0,0 +1,438 @@
+use algebra_core::{
+    msm::VariableBaseMSM, AffineCurve, One, PairingEngine, PrimeField, ProjectiveCurve,
+    UniformRand, Zero,
+};
+use core::{fmt, ops::Neg};
+use ff_fft::GeneralEvaluationDomain;
+use r1cs_core::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaChaRng;
+
+use crate::{generator::KeypairAssembly, r1cs_to_sap::R1CStoSAP, Parameters, Vec};
+
+/// This is an error that could occur while checking that parameters are
+/// consistent with a circuit and with their verifying key.
+#[derive(Debug)]
+pub enum ParameterError {
+    /// A query does not have the length that the circuit, or the rest of the
+    /// parameters, require.
+    QueryLength {
+        query: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    /// An element is the identity, although the circuit requires it not to be.
+    UnexpectedIdentity { query: &'static str, index: usize },
+    /// An element is not the identity, although the circuit requires it to be.
+    ExpectedIdentity { query: &'static str, index: usize },
+    /// A pairing check relating the named elements failed.
+    Inconsistent(&'static str),
+    /// The circuit could not be synthesized.
+    Synthesis(SynthesisError),
+}
+
+impl From<SynthesisError> for ParameterError {
+    fn from(e: SynthesisError) -> ParameterError {
+        ParameterError::Synthesis(e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParameterError {}
+
+impl fmt::Display for ParameterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            ParameterError::QueryLength {
+                query,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} has {} elements, but {} are required",
+                query, found, expected
+            ),
+            ParameterError::UnexpectedIdentity { query, index } => {
+                write!(f, "{}[{}] is unexpectedly the identity", query, index)
+            },
+            ParameterError::ExpectedIdentity { query, index } => {
+                write!(f, "{}[{}] should be the identity", query, index)
+            },
+            ParameterError::Inconsistent(elements) => write!(f, "inconsistent {}", elements),
+            ParameterError::Synthesis(err) => write!(f, "synthesis error: {}", err),
+        }
+    }
+}
+
+/// Checks that `params` are consistent with `circuit` and with their own
+/// verifying key.
+///
+/// The circuit is reduced to a SAP, whose polynomials are evaluated at a
+/// random point to determine which query elements must be the identity. The
+/// elements of the parameters are then related to the verifying key with
+/// pairing checks, on random linear combinations of the queries:
+/// * the elements checked by [`verify_crs`], which do not depend on the
+///   circuit;
+/// * the A query against the A polynomials of the SAP, multiplied by
+///   `Z(t)` and evaluated at `t` with `g_gamma2_z_t`;
+/// * the first C query, for the variables whose A polynomial is zero,
+///   against their C polynomials, evaluated in the same way;
+/// * the first C query, for the variables whose C polynomial is zero, and
+///   `vk.query`, for the inputs whose C polynomial is zero, against the A
+///   and B queries.
+///
+/// Only elements that the prover holds anyway are used, because giving it
+/// `gamma^2 * t^i` without the factor `Z(t)` would let it forge proofs. This
+/// leaves parts of the parameters that cannot be checked:
+/// * the elements of `g_gamma2_z_t` other than the first and the last,
+///   which nothing relates to a single `t`, so that the A and C polynomials
+///   are only checked against whatever powers of `t` they hold;
+/// * the elements of the first C query and of `vk.query` for variables
+///   whose A and C polynomials are both non-zero, which mix `gamma` and
+///   `alpha + beta` in a way no pairing of the elements can separate.
+///
+/// These checks catch parameters generated for another circuit, and queries
+/// mixed from different setups.
+pub fn check_parameters<E, C, R>(
+    params: &Parameters<E>,
+    circuit: C,
+    rng: &mut R,
+) -> Result<(), ParameterError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    R: Rng,
+{
+    let check_time = start_timer!(|| "Check parameters");
+    let mut assembly = KeypairAssembly::<E> {
+        num_inputs: 0,
+        num_aux: 0,
+        num_constraints: 0,
+        at: vec![],
+        bt: vec![],
+        ct: vec![],
+    };
+
+    // Allocate the "one" input variable
+    assembly.alloc_input(|| "", || Ok(E::Fr::one()))?;
+
+    // Synthesize the circuit.
+    let synthesis_time = start_timer!(|| "Constraint synthesis");
+    circuit.generate_constraints(&mut assembly)?;
+    end_timer!(synthesis_time);
+
+    let reduction_time = start_timer!(|| "R1CS to SAP Instance Map with Evaluation");
+    let t = E::Fr::rand(rng);
+    let (a, c, _, sap_num_variables, m_raw) = R1CStoSAP::instance_map_with_evaluation::<
+        E,
+        GeneralEvaluationDomain<E::Fr>,
+    >(&assembly, &t)?;
+    end_timer!(reduction_time);
+
+    let num_inputs = assembly.num_inputs;
+    let num_variables = sap_num_variables + 1;
+    check_length("a_query", &params.a_query, num_variables)?;
+    check_length("b_query", &params.b_query, num_variables)?;
+    check_length("c_query_1", &params.c_query_1, num_variables - num_inputs)?;
+    check_length("c_query_2", &params.c_query_2, num_variables)?;
+    check_length("g_gamma2_z_t", &params.g_gamma2_z_t, m_raw + 1)?;
+    check_length("vk.query", &params.vk.query, num_inputs)?;
+
+    // Elements that are never the identity.
+    let vk = &params.vk;
+    check_non_zero_elements(params)?;
+
+    // The identity elements of each query must match the zero polynomials of
+    // the SAP.
+    let ac_zero = (0..num_variables)
+        .map(|i| a[i].is_zero() && c[i].is_zero())
+        .collect::<Vec<_>>();
+    check_zero_pattern("a_query", &params.a_query, |i| a[i].is_zero())?;
+    check_zero_pattern("b_query", &params.b_query, |i| a[i].is_zero())?;
+    check_zero_pattern("c_query_2", &params.c_query_2, |i| a[i].is_zero())?;
+    check_zero_pattern("vk.query", &vk.query, |i| ac_zero[i])?;
+    check_zero_pattern("c_query_1", &params.c_query_1, |i| ac_zero[num_inputs + i])?;
+
+    let pairing_time = start_timer!(|| "Pairing checks");
+    check_common_pairings(params, rng)?;
+    let to_repr = |v: &[E::Fr]| v.iter().map(|v| v.into_repr()).collect::<Vec<_>>();
+
+    // For a random combination a_r = sum_i r_i * a_i of the A polynomials,
+    //   e(a_r, h_gamma_z) = e(gamma^2 * Z(t) * a_r(t), h),
+    // where the `gamma^2 * Z(t) * t^j` are taken from g_gamma2_z_t.
+    let r = (0..num_variables)
+        .map(|_| E::Fr::from(rng.gen::<u128>()))
+        .collect::<Vec<_>>();
+    let (a_coeffs, _) =
+        R1CStoSAP::combine_polynomials::<E, GeneralEvaluationDomain<E::Fr>>(&assembly, &r)?;
+    let a_t = msm(&params.g_gamma2_z_t[..a_coeffs.len()], &to_repr(&a_coeffs));
+    let a_r = msm(&params.a_query, &to_repr(&r));
+    if !is_one::<E>(&[(a_r, params.h_gamma_z.neg()), (a_t, vk.h_g2)]) {
+        return Err(ParameterError::Inconsistent("a_query with the SAP"));
+    }
+
+    // For the auxiliary variables whose A polynomial is zero, the first C
+    // query hides gamma * c_i(t), so similarly
+    //   e(c_1_r, h_gamma_z) = e(gamma^2 * Z(t) * c_r(t), h).
+    let no_a = |i: usize| i >= num_inputs && a[i].is_zero();
+    let r = (0..num_variables)
+        .map(|i| {
+            if no_a(i) {
+                E::Fr::from(rng.gen::<u128>())
+            } else {
+                E::Fr::zero()
+            }
+        })
+        .collect::<Vec<_>>();
+    let (_, c_coeffs) =
+        R1CStoSAP::combine_polynomials::<E, GeneralEvaluationDomain<E::Fr>>(&assembly, &r)?;
+    let c_t = msm(&params.g_gamma2_z_t[..c_coeffs.len()], &to_repr(&c_coeffs));
+    let c_r = msm(&params.c_query_1, &to_repr(&r[num_inputs..]));
+    if !is_one::<E>(&[(c_r, params.h_gamma_z.neg()), (c_t, vk.h_g2)]) {
+        return Err(ParameterError::Inconsistent("c_query_1 with the SAP"));
+    }
+
+    // For the variables whose C polynomial is zero,
+    //   e(c_1_i, h_gamma) = e(a_i, h_beta) * e(g_alpha, b_i),
+    // and similarly for vk.query.
+    let no_c = |i: usize| c[i].is_zero();
+    let inputs = (0..num_inputs).filter(|&i| no_c(i)).collect::<Vec<_>>();
+    let aux = (num_inputs..num_variables)
+        .filter(|&i| no_c(i))
+        .collect::<Vec<_>>();
+    let checks = [
+        (inputs, &vk.query, 0, "vk.query with the A and B queries"),
+        (
+            aux,
+            &params.c_query_1,
+            num_inputs,
+            "c_query_1 with the A and B queries",
+        ),
+    ];
+    for (indices, query, offset, elements) in checks.iter() {
+        if indices.is_empty() {
+            continue;
+        }
+        let r = random_scalars::<E::Fr, _>(indices.len(), rng);
+        let select_g1 = |v: &[E::G1Affine], offset: usize| {
+            indices.iter().map(|&i| v[i - offset]).collect::<Vec<_>>()
+        };
+        let query = msm(&select_g1(query, *offset), &r);
+        let a = msm(&select_g1(&params.a_query, 0), &r);
+        let b = msm(
+            &indices
+                .iter()
+                .map(|&i| params.b_query[i])
+                .collect::<Vec<_>>(),
+            &r,
+        );
+        if !is_one::<E>(&[
+            (query, vk.h_gamma_g2),
+            (a.neg(), vk.h_beta_g2),
+            (vk.g_alpha_g1.neg(), b),
+        ]) {
+            return Err(ParameterError::Inconsistent(elements));
+        }
+    }
+    end_timer!(pairing_time);
+    end_timer!(check_time);
+
+    Ok(())
+}
+
+/// Checks that `params` are well-formed without knowing the circuit, so that
+/// a prover can refuse to use parameters that a malicious setup may have
+/// subverted to learn about its witness.
+///
+/// The checks are those of [`check_parameters`] that do not depend on the
+/// circuit:
+/// * the queries have consistent lengths and no unexpected identities, and
+///   the A, B and second C queries are the identity at the same positions;
+/// * the `gamma * Z(t)` elements are consistent with `g_gamma`, `h_gamma`,
+///   `g_alpha` and `h_beta`;
+/// * the A and B queries hide the same polynomial evaluations, scaled by the
+///   same `gamma`, and the second C query hides them scaled by
+///   `2 * gamma^2 * Z(t)`;
+/// * `g_gamma2_z_t` starts at `gamma^2 * Z(t)`, and its last element is
+///   consistent with `Z(t) = t^n - 1`.
+///
+/// The random linear combinations are derived by hashing the parameters.
+///
+/// The other elements of `g_gamma2_z_t` cannot be checked: that would take
+/// `t` in G2 and `gamma^2 * t^i` in G1, with which a prover could forge
+/// proofs. The values of the A query, `vk.query` and the first C query are
+/// evaluations of the polynomials of the circuit, so they can only be
+/// checked against the circuit, with [`check_parameters`], which leaves
+/// some of them unchecked too. A prover must use that check before trusting
+/// the parameters with a witness.
+pub fn verify_crs<E: PairingEngine>(params: &Parameters<E>) -> Result<(), ParameterError> {
+    let verify_time = start_timer!(|| "Verify CRS");
+    let num_variables = params.a_query.len();
+    let num_inputs = params.vk.query.len();
+    if num_inputs == 0 || num_inputs > num_variables {
+        return Err(ParameterError::QueryLength {
+            query: "vk.query",
+            expected: num_variables.saturating_sub(params.c_query_1.len()),
+            found: num_inputs,
+        });
+    }
+    check_length("b_query", &params.b_query, num_variables)?;
+    check_length("c_query_1", &params.c_query_1, num_variables - num_inputs)?;
+    check_length("c_query_2", &params.c_query_2, num_variables)?;
+    if params.g_gamma2_z_t.len() < 2 {
+        return Err(ParameterError::QueryLength {
+            query: "g_gamma2_z_t",
+            expected: 2,
+            found: params.g_gamma2_z_t.len(),
+        });
+    }
+
+    check_non_zero_elements(params)?;
+    let a_is_zero = |i: usize| params.a_query[i].is_zero();
+    check_zero_pattern("b_query", &params.b_query, a_is_zero)?;
+    check_zero_pattern("c_query_2", &params.c_query_2, a_is_zero)?;
+
+    let rng = &mut ChaChaRng::from_seed(params.fingerprint());
+    check_common_pairings(params, rng)?;
+    end_timer!(verify_time);
+
+    Ok(())
+}
+
+/// Checks that none of the elements of `params` that are never the identity
+/// are.
+fn check_non_zero_elements<E: PairingEngine>(params: &Parameters<E>) -> Result<(), ParameterError> {
+    let vk = &params.vk;
+    check_non_zero("vk.h_g2", &[vk.h_g2])?;
+    check_non_zero("vk.g_alpha_g1", &[vk.g_alpha_g1])?;
+    check_non_zero("vk.h_beta_g2", &[vk.h_beta_g2])?;
+    check_non_zero("vk.g_gamma_g1", &[vk.g_gamma_g1])?;
+    check_non_zero("vk.h_gamma_g2", &[vk.h_gamma_g2])?;
+    check_non_zero("g_gamma_z", &[params.g_gamma_z])?;
+    check_non_zero("h_gamma_z", &[params.h_gamma_z])?;
+    check_non_zero("g_ab_gamma_z", &[params.g_ab_gamma_z])?;
+    check_non_zero("g_gamma2_z2", &[params.g_gamma2_z2])?;
+    check_non_zero("g_gamma2_z_t", &params.g_gamma2_z_t)
+}
+
+/// Relates the elements of `params` to each other and to the verifying key
+/// with the pairing checks that do not depend on the circuit.
+///
+/// `g_gamma2_z_t` must have at least 2 elements.
+fn check_common_pairings<E: PairingEngine, R: Rng>(
+    params: &Parameters<E>,
+    rng: &mut R,
+) -> Result<(), ParameterError> {
+    let vk = &params.vk;
+    let h_neg = vk.h_g2.neg();
+    let h_gamma_neg = vk.h_gamma_g2.neg();
+
+    // e(g_gamma_z, h_gamma) = e(g_gamma, h_gamma_z)
+    if !is_one::<E>(&[
+        (params.g_gamma_z, h_gamma_neg),
+        (vk.g_gamma_g1, params.h_gamma_z),
+    ]) {
+        return Err(ParameterError::Inconsistent("g_gamma_z and h_gamma_z"));
+    }
+
+    // e(g_gamma2_z2, h) = e(g_gamma_z, h_gamma_z)
+    if !is_one::<E>(&[
+        (params.g_gamma2_z2, h_neg),
+        (params.g_gamma_z, params.h_gamma_z),
+    ]) {
+        return Err(ParameterError::Inconsistent("g_gamma2_z2"));
+    }
+
+    // e(g_ab_gamma_z, h) = e(g_alpha, h_gamma_z) * e(g_gamma_z, h_beta)
+    if !is_one::<E>(&[
+        (params.g_ab_gamma_z, h_neg),
+        (vk.g_alpha_g1, params.h_gamma_z),
+        (params.g_gamma_z, vk.h_beta_g2),
+    ]) {
+        return Err(ParameterError::Inconsistent("g_ab_gamma_z"));
+    }
+
+    // e(g_gamma2_z_t[0], h) = e(g_gamma_z, h_gamma)
+    if !is_one::<E>(&[
+        (params.g_gamma2_z_t[0], h_neg),
+        (params.g_gamma_z, vk.h_gamma_g2),
+    ]) {
+        return Err(ParameterError::Inconsistent("g_gamma2_z_t"));
+    }
+
+    let n = params.g_gamma2_z_t.len() - 1;
+    // g_gamma2_z_t[n] - g_gamma2_z_t[0] = gamma^2 * Z(t) * (t^n - 1)
+    //                                  = gamma^2 * Z(t)^2
+    let mut difference = params.g_gamma2_z_t[n].into_projective();
+    difference.add_assign_mixed(&params.g_gamma2_z_t[0].neg());
+    if difference.into_affine() != params.g_gamma2_z2 {
+        return Err(ParameterError::Inconsistent("g_gamma2_z_t and g_gamma2_z2"));
+    }
+
+    // e(a, h_gamma) = e(g_gamma, b) and e(c_2, h) = e(2 * a, h_gamma_z) for
+    // random combinations of the A, B and second C queries.
+    let r = random_scalars::<E::Fr, _>(params.a_query.len(), rng);
+    let a_acc = msm(&params.a_query, &r);
+    let b_acc = msm(&params.b_query, &r);
+    let c_2_acc = msm(&params.c_query_2, &r);
+    if !is_one::<E>(&[(a_acc, h_gamma_neg), (vk.g_gamma_g1, b_acc)]) {
+        return Err(ParameterError::Inconsistent("a_query and b_query"));
+    }
+    let double_a_acc = a_acc.into_projective().double().into_affine();
+    if !is_one::<E>(&[(c_2_acc, h_neg), (double_a_acc, params.h_gamma_z)]) {
+        return Err(ParameterError::Inconsistent("a_query and c_query_2"));
+    }
+
+    Ok(())
+}
+
+fn check_length<T>(query: &'static str, v: &[T], expected: usize) -> Result<(), ParameterError> {
+    if v.len() != expected {
+        return Err(ParameterError::QueryLength {
+            query,
+            expected,
+            found: v.len(),
+        });
+    }
+    Ok(())
+}
+
+fn check_non_zero<G: AffineCurve>(query: &'static str, v: &[G]) -> Result<(), ParameterError> {
+    match v.iter().position(|g| g.is_zero()) {
+        Some(index) => Err(ParameterError::UnexpectedIdentity { query, index }),
+        None => Ok(()),
+    }
+}
+
+fn check_zero_pattern<G: AffineCurve>(
+    query: &'static str,
+    v: &[G],
+    is_zero: impl Fn(usize) -> bool,
+) -> Result<(), ParameterError> {
+    for (index, g) in v.iter().enumerate() {
+        match (g.is_zero(), is_zero(index)) {
+            (true, false) => return Err(ParameterError::UnexpectedIdentity { query, index }),
+            (false, true) => return Err(ParameterError::ExpectedIdentity { query, index }),
+            _ => {},
+        }
+    }
+    Ok(())
+}
+
+fn random_scalars<F: PrimeField, R: Rng>(n: usize, rng: &mut R) -> Vec<F::BigInt> {
+    (0..n)
+        .map(|_| F::from(rng.gen::<u128>()).into_repr())
+        .collect()
+}
+
+fn msm<G: AffineCurve>(bases: &[G], scalars: &[<G::ScalarField as PrimeField>::BigInt]) -> G {
+    VariableBaseMSM::multi_scalar_mul(bases, scalars).into_affine()
+}
+
+fn is_one<E: PairingEngine>(pairs: &[(E::G1Affine, E::G2Affine)]) -> bool {
+    let pairs = pairs
+        .iter()
+        .map(|(g1, g2)| ((*g1).into(), (*g2).into()))
+        .collect::<Vec<_>>();
+    E::product_of_pairings(&pairs).is_one()
+}
@@ -6,11 +6,14 @@ use ff_fft::{cfg_into_iter, cfg_iter, EvaluationDomain};
 use r1cs_core::{
     ConstraintSynthesizer, ConstraintSystem, Index, LinearCombination, SynthesisError, Variable,
 };
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaChaRng;
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
-use crate::{r1cs_to_sap::R1CStoSAP, Parameters, String, Vec, VerifyingKey};
+use crate::{
+    circuit_digest, r1cs_to_sap::R1CStoSAP, Parameters, String, Trapdoor, Vec, VerifyingKey,
+};
 
 /// Generates a random common reference string for
 /// a circuit.
@@ -33,6 +36,45 @@ where
     generate_parameters::<E, C, D, R>(circuit, alpha, beta, gamma, g, h, rng)
 }
 
+/// Generates a common reference string for a circuit deterministically
+/// from `seed`.
+///
+/// Anyone who knows the seed can recompute the trapdoor and forge proofs, so
+/// this is only suitable for development and testing.
+pub fn generate_parameters_from_seed<E, C, D>(
+    circuit: C,
+    seed: [u8; 32],
+) -> Result<Parameters<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+{
+    let rng = &mut ChaChaRng::from_seed(seed);
+    generate_random_parameters::<E, C, D, _>(circuit, rng)
+}
+
+/// Generates a random common reference string for
+/// a circuit, and returns it together with its trapdoor.
+pub fn generate_random_parameters_with_trapdoor<E, C, D, R>(
+    circuit: C,
+    rng: &mut R,
+) -> Result<(Parameters<E>, Trapdoor<E>), SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+{
+    let alpha = E::Fr::rand(rng);
+    let beta = E::Fr::rand(rng);
+    let gamma = E::Fr::one();
+    let g = E::G1Projective::rand(rng);
+    let h = E::G2Projective::rand(rng);
+
+    generate_parameters_with_trapdoor::<E, C, D, R>(circuit, alpha, beta, gamma, g, h, rng)
+}
+
 /// This is our assembly structure that we'll use to synthesize the
 /// circuit into a SAP.
 pub struct KeypairAssembly<E: PairingEngine> {
@@ -154,6 +196,27 @@ pub fn generate_parameters<E, C, D, R>(
     h: E::G2Projective,
     rng: &mut R,
 ) -> Result<Parameters<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+{
+    generate_parameters_with_trapdoor::<E, C, D, R>(circuit, alpha, beta, gamma, g, h, rng)
+        .map(|(params, _)| params)
+}
+
+/// Create parameters for a circuit, given some toxic waste, and return them
+/// together with the full trapdoor.
+pub fn generate_parameters_with_trapdoor<E, C, D, R>(
+    circuit: C,
+    alpha: E::Fr,
+    beta: E::Fr,
+    gamma: E::Fr,
+    g: E::G1Projective,
+    h: E::G2Projective,
+    rng: &mut R,
+) -> Result<(Parameters<E>, Trapdoor<E>), SynthesisError>
 where
     E: PairingEngine,
     C: ConstraintSynthesizer<E::Fr>,
@@ -254,14 +317,11 @@ where
 
     // Compute the C_1-query
     let c1_time = start_timer!(|| "Calculate C1");
-    let result = FixedBaseMSM::multi_scalar_mul::<E::G1Projective>(
-        scalar_bits,
-        g_window,
-        &g_table,
-        &cfg_into_iter!(0..sap_num_variables + 1)
-            .map(|i| c[i] * &gamma + &(a[i] * &alpha_beta))
-            .collect::<Vec<_>>(),
-    );
+    let c1 = cfg_into_iter!(0..sap_num_variables + 1)
+        .map(|i| c[i] * &gamma + &(a[i] * &alpha_beta))
+        .collect::<Vec<_>>();
+    let result =
+        FixedBaseMSM::multi_scalar_mul::<E::G1Projective>(scalar_bits, g_window, &g_table, &c1);
     let (verifier_query, c_query_1) = result.split_at(assembly.num_inputs);
     end_timer!(c1_time);
 
@@ -325,7 +385,7 @@ where
     E::G1Projective::batch_normalization(g_gamma2_z_t.as_mut_slice());
     end_timer!(batch_normalization_time);
 
-    Ok(Parameters {
+    let params = Parameters {
         vk,
         a_query: a_query.into_iter().map(Into::into).collect(),
         b_query: b_query.into_iter().map(Into::into).collect(),
@@ -336,5 +396,23 @@ where
         g_ab_gamma_z: g_ab_gamma_z.into_affine(),
         g_gamma2_z2: g_gamma2_z2.into_affine(),
         g_gamma2_z_t: g_gamma2_z_t.into_iter().map(Into::into).collect(),
-    })
+        circuit_digest: circuit_digest(
+            assembly.num_inputs,
+            assembly.num_aux,
+            &assembly.at,
+            &assembly.bt,
+            &assembly.ct,
+        ),
+    };
+    let trapdoor = Trapdoor {
+        alpha,
+        beta,
+        gamma,
+        tau: t,
+        g1_generator: g.into_affine(),
+        g2_generator: h.into_affine(),
+        input_base_logs: c1[..assembly.num_inputs].to_vec(),
+    };
+
+    Ok((params, trapdoor))
 }
@@ -1,4 +1,4 @@
-use crate::Parameters;
+use crate::{Parameters, Trapdoor};
 use algebra_core::PairingEngine;
 use ff_fft::GeneralEvaluationDomain;
 use r1cs_core::{ConstraintSynthesizer, SynthesisError};
@@ -25,6 +25,44 @@ where
     )
 }
 
+/// Generates a common reference string for a circuit deterministically
+/// from `seed`, so that the same circuit and seed always give the same
+/// parameters.
+///
+/// Anyone who knows the seed can recompute the trapdoor and forge proofs, so
+/// this is only suitable for development and testing.
+#[inline]
+pub fn generate_parameters_from_seed<E, C>(
+    circuit: C,
+    seed: [u8; 32],
+) -> Result<Parameters<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+{
+    self::generic::generate_parameters_from_seed::<E, C, GeneralEvaluationDomain<E::Fr>>(
+        circuit, seed,
+    )
+}
+
+/// Generates a random common reference string for
+/// a circuit, and returns it together with its trapdoor, which can be used to
+/// [simulate](crate::simulate_proof) proofs.
+#[inline]
+pub fn generate_random_parameters_with_trapdoor<E, C, R>(
+    circuit: C,
+    rng: &mut R,
+) -> Result<(Parameters<E>, Trapdoor<E>), SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    R: Rng,
+{
+    self::generic::generate_random_parameters_with_trapdoor::<E, C, GeneralEvaluationDomain<E::Fr>, R>(
+        circuit, rng,
+    )
+}
+
 /// Create parameters for a circuit, given some toxic waste.
 #[inline]
 pub fn generate_parameters<E, C, R>(
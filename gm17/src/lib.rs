@@ -27,9 +27,10 @@ use algebra_core::{
     bytes::ToBytes,
     io::{self, Result as IoResult},
     serialize::*,
-    PairingEngine,
+    Field, PairingEngine,
 };
-use r1cs_core::SynthesisError;
+use blake2::{Blake2s, Digest};
+use r1cs_core::{CircuitDigest, CircuitHasher, Index, SynthesisError};
 
 /// Reduce an R1CS instance to a *Square Arithmetic Program* instance.
 pub mod r1cs_to_sap;
@@ -43,10 +44,16 @@ pub mod prover;
 /// Verify proofs for the GM17 zkSNARK construction.
 pub mod verifier;
 
+/// Simulate proofs for the GM17 zkSNARK construction, given the trapdoor.
+pub mod simulator;
+
+/// Check parameters for the GM17 zkSNARK construction against a circuit.
+pub mod check;
+
 #[cfg(test)]
 mod test;
 
-pub use self::{generator::*, prover::*, verifier::*};
+pub use self::{check::*, generator::*, prover::*, simulator::*, verifier::*};
 
 /// A proof in the GM17 SNARK.
 #[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
@@ -83,16 +90,22 @@ impl<E: PairingEngine> Default for Proof<E> {
 
 impl<E: PairingEngine> Proof<E> {
     /// Serialize the proof into bytes, for storage on disk or transmission
-    /// over the network.
-    pub fn write<W: Write>(&self, mut _writer: W) -> io::Result<()> {
-        // TODO: implement serialization
-        unimplemented!()
+    /// over the network. Group elements are written in compressed form.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_versioned(self, &mut writer, true)
+    }
+
+    /// Serialize the proof into bytes, writing group elements in
+    /// uncompressed form.
+    pub fn write_uncompressed<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_versioned(self, &mut writer, false)
     }
 
-    /// Deserialize the proof from bytes.
-    pub fn read<R: Read>(mut _reader: R) -> io::Result<Self> {
-        // TODO: implement serialization
-        unimplemented!()
+    /// Deserialize the proof from bytes. Proofs usually come from untrusted
+    /// parties, so every group element is checked to be on the curve and in
+    /// the prime order subgroup.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        read_versioned(&mut reader, true)
     }
 }
 
@@ -147,16 +160,28 @@ impl<E: PairingEngine> PartialEq for VerifyingKey<E> {
 
 impl<E: PairingEngine> VerifyingKey<E> {
     /// Serialize the verification key into bytes, for storage on disk
-    /// or transmission over the network.
-    pub fn write<W: Write>(&self, mut _writer: W) -> io::Result<()> {
-        // TODO: implement serialization
-        unimplemented!()
+    /// or transmission over the network. Group elements are written in
+    /// compressed form.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_versioned(self, &mut writer, true)
+    }
+
+    /// Serialize the verification key into bytes, writing group elements in
+    /// uncompressed form.
+    pub fn write_uncompressed<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_versioned(self, &mut writer, false)
     }
 
-    /// Deserialize the verification key from bytes.
-    pub fn read<R: Read>(mut _reader: R) -> io::Result<Self> {
-        // TODO: implement serialization
-        unimplemented!()
+    /// Deserialize the verification key from bytes, checking that every
+    /// group element is on the curve and in the prime order subgroup.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        read_versioned(&mut reader, true)
+    }
+
+    /// A stable fingerprint of the verification key: the BLAKE2s hash of its
+    /// compressed canonical serialization.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        fingerprint(self)
     }
 }
 
@@ -173,6 +198,9 @@ pub struct Parameters<E: PairingEngine> {
     pub g_ab_gamma_z: E::G1Affine,
     pub g_gamma2_z2: E::G1Affine,
     pub g_gamma2_z_t: Vec<E::G1Affine>,
+    /// The digest of the circuit that the parameters were generated for,
+    /// against which the prover checks the circuit it is given.
+    pub circuit_digest: CircuitDigest,
 }
 
 impl<E: PairingEngine> PartialEq for Parameters<E> {
@@ -187,23 +215,132 @@ impl<E: PairingEngine> PartialEq for Parameters<E> {
             && self.g_ab_gamma_z == other.g_ab_gamma_z
             && self.g_gamma2_z2 == other.g_gamma2_z2
             && self.g_gamma2_z_t == other.g_gamma2_z_t
+            && self.circuit_digest == other.circuit_digest
     }
 }
 
+/// The secret randomness from which [`Parameters`] were generated.
+///
+/// Anyone who knows the trapdoor can forge proofs for any statement with
+/// [`simulate_proof`], so it must be destroyed once the parameters are
+/// generated. It is only useful for testing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trapdoor<E: PairingEngine> {
+    pub alpha: E::Fr,
+    pub beta: E::Fr,
+    pub gamma: E::Fr,
+    pub tau: E::Fr,
+    pub g1_generator: E::G1Affine,
+    pub g2_generator: E::G2Affine,
+    /// The discrete logarithms of `vk.query`, the bases of the public inputs,
+    /// to the base `g1_generator`.
+    pub input_base_logs: Vec<E::Fr>,
+}
+
 impl<E: PairingEngine> Parameters<E> {
-    /// Serialize the parameters to bytes.
-    pub fn write<W: Write>(&self, mut _writer: W) -> io::Result<()> {
-        // TODO: implement serialization
-        unimplemented!()
+    /// Serialize the parameters to bytes. Group elements are written in
+    /// compressed form.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_versioned(self, &mut writer, true)
+    }
+
+    /// Serialize the parameters to bytes, writing group elements in
+    /// uncompressed form. This is larger, but much faster to read back.
+    pub fn write_uncompressed<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_versioned(self, &mut writer, false)
     }
 
     /// Deserialize the public parameters from bytes.
-    pub fn read<R: Read>(mut _reader: R, _checked: bool) -> io::Result<Self> {
-        // TODO: implement serialization
-        unimplemented!()
+    ///
+    /// If `checked` is set, every group element is checked to be on the curve
+    /// and in the prime order subgroup. Unchecked reads of uncompressed
+    /// parameters skip these checks, and should only be used for trusted
+    /// input. Compressed points are always validated while decompressing.
+    pub fn read<R: Read>(mut reader: R, checked: bool) -> io::Result<Self> {
+        read_versioned(&mut reader, checked)
+    }
+
+    /// A stable fingerprint of the parameters, including the verification
+    /// key: the BLAKE2s hash of their compressed canonical serialization.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        fingerprint(self)
     }
 }
 
+/// Version of the binary format produced by the `write` methods of
+/// [`Proof`], [`VerifyingKey`] and [`Parameters`].
+///
+/// Every encoding starts with this version byte, followed by a byte
+/// indicating whether group elements are compressed, followed by the
+/// canonical serialization of the value.
+///
+/// Version 2 added the circuit digest to the end of [`Parameters`].
+pub const SERIALIZATION_VERSION: u8 = 2;
+
+const COMPRESSED: u8 = 0;
+const UNCOMPRESSED: u8 = 1;
+
+fn write_versioned<T: CanonicalSerialize, W: Write>(
+    value: &T,
+    writer: &mut W,
+    compressed: bool,
+) -> io::Result<()> {
+    let mode = if compressed { COMPRESSED } else { UNCOMPRESSED };
+    writer.write_all(&[SERIALIZATION_VERSION, mode])?;
+    if compressed {
+        value.serialize(writer)?;
+    } else {
+        value.serialize_uncompressed(writer)?;
+    }
+    Ok(())
+}
+
+fn read_versioned<T: CanonicalDeserialize, R: Read>(
+    reader: &mut R,
+    checked: bool,
+) -> io::Result<T> {
+    let mut header = [0u8; 2];
+    reader.read_exact(&mut header)?;
+    if header[0] != SERIALIZATION_VERSION {
+        return Err(SerializationError::InvalidData.into());
+    }
+    let value = match header[1] {
+        COMPRESSED => T::deserialize(reader)?,
+        UNCOMPRESSED if checked => T::deserialize_uncompressed(reader)?,
+        UNCOMPRESSED => T::deserialize_unchecked(reader)?,
+        _ => return Err(SerializationError::InvalidData.into()),
+    };
+    Ok(value)
+}
+
+/// Hashes the compressed canonical serialization of `value` with BLAKE2s.
+fn fingerprint<T: CanonicalSerialize>(value: &T) -> [u8; 32] {
+    let mut bytes = Vec::with_capacity(value.serialized_size());
+    value
+        .serialize(&mut bytes)
+        .expect("serializing into a vector never fails");
+    let mut fingerprint = [0u8; 32];
+    fingerprint.copy_from_slice(Blake2s::digest(&bytes).as_slice());
+    fingerprint
+}
+
+/// Computes the digest of a circuit that was synthesized into the matrices
+/// `at`, `bt` and `ct` over `num_inputs` input variables, including the "one"
+/// variable, and `num_aux` auxiliary variables.
+pub(crate) fn circuit_digest<F: Field, R: AsRef<[(F, Index)]>>(
+    num_inputs: usize,
+    num_aux: usize,
+    at: &[R],
+    bt: &[R],
+    ct: &[R],
+) -> CircuitDigest {
+    let mut hasher = CircuitHasher::with_variables(num_inputs, num_aux);
+    for ((a, b), c) in at.iter().zip(bt).zip(ct) {
+        hasher.hash_constraint(a.as_ref(), b.as_ref(), c.as_ref());
+    }
+    hasher.finish()
+}
+
 /// Preprocessed verification key parameters that enable faster verification
 /// at the expense of larger size in memory.
 #[derive(Clone)]
@@ -247,7 +384,7 @@ impl<E: PairingEngine> Default for PreparedVerifyingKey<E> {
 
 impl<E: PairingEngine> ToBytes for PreparedVerifyingKey<E> {
     fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
-        self.vk.write(&mut writer)?;
+        ToBytes::write(&self.vk, &mut writer)?;
         self.g_alpha.write(&mut writer)?;
         self.h_beta.write(&mut writer)?;
         self.g_alpha_h_beta_ml.write(&mut writer)?;
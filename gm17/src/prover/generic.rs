@@ -7,11 +7,12 @@ use algebra_core::{
     UniformRand, Zero,
 };
 
-use crate::{r1cs_to_sap::R1CStoSAP, Parameters, Proof, String, Vec};
+use crate::{circuit_digest, r1cs_to_sap::R1CStoSAP, Parameters, Proof, String, Vec};
 use ff_fft::{cfg_into_iter, EvaluationDomain};
 
 use r1cs_core::{
-    ConstraintSynthesizer, ConstraintSystem, Index, LinearCombination, SynthesisError, Variable,
+    CancellationToken, ConstraintSynthesizer, ConstraintSystem, Index, LinearCombination,
+    ProverObserver, ProverPhase, SynthesisError, UnsatisfiedConstraint, Variable,
 };
 
 use smallvec::SmallVec;
@@ -72,9 +73,115 @@ pub struct ProvingAssignment<E: PairingEngine> {
     pub(crate) num_inputs: usize,
     pub(crate) num_aux: usize,
     pub(crate) num_constraints: usize,
+
+    // Names of variables and constraints, if they are recorded
+    pub(crate) names: Option<Names>,
+}
+
+/// The full names of the variables and constraints of an assignment, which
+/// are only recorded when diagnosing a circuit.
+#[derive(Default)]
+pub(crate) struct Names {
+    current_namespace: Vec<String>,
+    inputs: Vec<String>,
+    aux: Vec<String>,
+    constraints: Vec<String>,
+}
+
+impl Names {
+    fn path(&self, name: String) -> String {
+        let mut path = self.current_namespace.join("/");
+        if !path.is_empty() {
+            path.push('/');
+        }
+        path.push_str(&name);
+        path
+    }
+}
+
+impl<E: PairingEngine> Default for ProvingAssignment<E> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<E: PairingEngine> ProvingAssignment<E> {
+    /// Creates an assignment that holds just the "one" input variable.
+    pub fn new() -> Self {
+        Self {
+            at: vec![],
+            bt: vec![],
+            ct: vec![],
+            a: vec![],
+            b: vec![],
+            c: vec![],
+            input_assignment: vec![E::Fr::one()],
+            aux_assignment: vec![],
+            num_inputs: 1,
+            num_aux: 0,
+            num_constraints: 0,
+            names: None,
+        }
+    }
+
+    /// Creates an assignment like `new`, which also records the full names of
+    /// variables and constraints, so that `unsatisfied_constraints` can
+    /// report them.
+    pub fn new_with_names() -> Self {
+        let mut names = Names::default();
+        names.inputs.push("ONE".into());
+        Self {
+            names: Some(names),
+            ..Self::new()
+        }
+    }
+
+    /// Returns every constraint that the assignment does not satisfy, with
+    /// the values of its linear combinations and of its variables.
+    ///
+    /// Unless the assignment was created by `new_with_names`, constraints
+    /// and variables are named by their indices.
+    pub fn unsatisfied_constraints(&self) -> Vec<UnsatisfiedConstraint<E::Fr>> {
+        let mut unsatisfied = Vec::new();
+        for i in 0..self.num_constraints {
+            if self.a[i] * &self.b[i] == self.c[i] {
+                continue;
+            }
+
+            let name = match &self.names {
+                Some(names) => names.constraints[i].clone(),
+                None => format!("constraint {}", i),
+            };
+            let indices = self.at[i]
+                .iter()
+                .chain(&self.bt[i])
+                .chain(&self.ct[i])
+                .map(|(_, index)| *index);
+            unsatisfied.push(UnsatisfiedConstraint::new(
+                i,
+                name,
+                (self.a[i], self.b[i], self.c[i]),
+                indices,
+                |index| match index {
+                    Index::Input(j) => (
+                        self.names.as_ref().map_or_else(
+                            || format!("input {}", j),
+                            |names| names.inputs[j].clone(),
+                        ),
+                        self.input_assignment[j],
+                    ),
+                    Index::Aux(j) => (
+                        self.names
+                            .as_ref()
+                            .map_or_else(|| format!("aux {}", j), |names| names.aux[j].clone()),
+                        self.aux_assignment[j],
+                    ),
+                },
+            ));
+        }
+        unsatisfied
+    }
+
     pub fn which_is_unsatisfied(&self) -> Option<usize> {
         for (i, ((a_i, b_i), c_i)) in (self.a.iter().zip(self.b.iter()))
             .zip(self.c.iter())
@@ -92,7 +199,7 @@ impl<E: PairingEngine> ConstraintSystem<E::Fr> for ProvingAssignment<E> {
     type Root = Self;
 
     #[inline]
-    fn alloc<F, A, AR>(&mut self, _: A, f: F) -> Result<Variable, SynthesisError>
+    fn alloc<F, A, AR>(&mut self, annotation: A, f: F) -> Result<Variable, SynthesisError>
     where
         F: FnOnce() -> Result<E::Fr, SynthesisError>,
         A: FnOnce() -> AR,
@@ -102,11 +209,15 @@ impl<E: PairingEngine> ConstraintSystem<E::Fr> for ProvingAssignment<E> {
         self.num_aux += 1;
 
         self.aux_assignment.push(f()?);
+        if let Some(names) = &mut self.names {
+            let path = names.path(annotation().into());
+            names.aux.push(path);
+        }
         Ok(Variable::new_unchecked(Index::Aux(index)))
     }
 
     #[inline]
-    fn alloc_input<F, A, AR>(&mut self, _: A, f: F) -> Result<Variable, SynthesisError>
+    fn alloc_input<F, A, AR>(&mut self, annotation: A, f: F) -> Result<Variable, SynthesisError>
     where
         F: FnOnce() -> Result<E::Fr, SynthesisError>,
         A: FnOnce() -> AR,
@@ -116,11 +227,15 @@ impl<E: PairingEngine> ConstraintSystem<E::Fr> for ProvingAssignment<E> {
         self.num_inputs += 1;
 
         self.input_assignment.push(f()?);
+        if let Some(names) = &mut self.names {
+            let path = names.path(annotation().into());
+            names.inputs.push(path);
+        }
         Ok(Variable::new_unchecked(Index::Input(index)))
     }
 
     #[inline]
-    fn enforce<A, AR, LA, LB, LC>(&mut self, _: A, a: LA, b: LB, c: LC)
+    fn enforce<A, AR, LA, LB, LC>(&mut self, annotation: A, a: LA, b: LB, c: LC)
     where
         A: FnOnce() -> AR,
         AR: Into<String>,
@@ -128,6 +243,11 @@ impl<E: PairingEngine> ConstraintSystem<E::Fr> for ProvingAssignment<E> {
         LB: FnOnce(LinearCombination<E::Fr>) -> LinearCombination<E::Fr>,
         LC: FnOnce(LinearCombination<E::Fr>) -> LinearCombination<E::Fr>,
     {
+        if let Some(names) = &mut self.names {
+            let path = names.path(annotation().into());
+            names.constraints.push(path);
+        }
+
         self.at.push(CoeffVec::new());
         self.bt.push(CoeffVec::new());
         self.ct.push(CoeffVec::new());
@@ -157,16 +277,21 @@ impl<E: PairingEngine> ConstraintSystem<E::Fr> for ProvingAssignment<E> {
         self.num_constraints += 1;
     }
 
-    fn push_namespace<NR, N>(&mut self, _: N)
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
     where
         NR: Into<String>,
         N: FnOnce() -> NR,
     {
-        // Do nothing; we don't care about namespaces in this context.
+        // Namespaces only matter when names are recorded.
+        if let Some(names) = &mut self.names {
+            names.current_namespace.push(name_fn().into());
+        }
     }
 
     fn pop_namespace(&mut self) {
-        // Do nothing; we don't care about namespaces in this context.
+        if let Some(names) = &mut self.names {
+            assert!(names.current_namespace.pop().is_some());
+        }
     }
 
     fn get_root(&mut self) -> &mut Self::Root {
@@ -208,31 +333,87 @@ where
     C: ConstraintSynthesizer<E::Fr>,
     D: EvaluationDomain<E::Fr>,
 {
-    let prover_time = start_timer!(|| "Prover");
-    let mut prover = ProvingAssignment {
-        at: vec![],
-        bt: vec![],
-        ct: vec![],
-        a: vec![],
-        b: vec![],
-        c: vec![],
-        input_assignment: vec![],
-        aux_assignment: vec![],
-        num_inputs: 0,
-        num_aux: 0,
-        num_constraints: 0,
+    create_proof_with_observer::<E, C, D, _>(
+        circuit,
+        params,
+        d1,
+        d2,
+        r,
+        &|_: ProverPhase, _: f64| {},
+        &CancellationToken::new(),
+    )
+}
+
+pub fn create_random_proof_with_observer<E, C, D, R, O>(
+    circuit: C,
+    params: &Parameters<E>,
+    rng: &mut R,
+    observer: &O,
+    cancel: &CancellationToken,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+    O: ProverObserver,
+{
+    let d1 = E::Fr::rand(rng);
+    let d2 = E::Fr::rand(rng);
+    let r = E::Fr::rand(rng);
+
+    create_proof_with_observer::<E, C, D, O>(circuit, params, d1, d2, r, observer, cancel)
+}
+
+/// Creates a proof, reporting progress to `observer` and stopping with
+/// `SynthesisError::Cancelled` as soon as possible once `cancel` is
+/// cancelled.
+pub fn create_proof_with_observer<E, C, D, O>(
+    circuit: C,
+    params: &Parameters<E>,
+    d1: E::Fr,
+    d2: E::Fr,
+    r: E::Fr,
+    observer: &O,
+    cancel: &CancellationToken,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+    O: ProverObserver,
+{
+    let report = |phase: ProverPhase, fraction: f64| -> Result<(), SynthesisError> {
+        cancel.check()?;
+        observer.on_progress(phase, fraction);
+        Ok(())
     };
 
-    // Allocate the "one" input variable
-    prover.alloc_input(|| "", || Ok(E::Fr::one()))?;
+    let prover_time = start_timer!(|| "Prover");
+    // The assignment starts with the "one" input variable
+    let mut prover = ProvingAssignment::new();
 
     // Synthesize the circuit.
     let synthesis_time = start_timer!(|| "Constraint synthesis");
+    report(ProverPhase::Synthesis, 0.0)?;
     circuit.generate_constraints(&mut prover)?;
+    report(ProverPhase::Synthesis, 1.0)?;
     end_timer!(synthesis_time);
+    let digest = circuit_digest(
+        prover.num_inputs,
+        prover.num_aux,
+        &prover.at,
+        &prover.bt,
+        &prover.ct,
+    );
+    if digest != params.circuit_digest {
+        return Err(SynthesisError::CircuitMismatch);
+    }
 
     let witness_map_time = start_timer!(|| "R1CS to SAP witness map");
+    report(ProverPhase::WitnessMap, 0.0)?;
     let (full_input_assignment, h, _) = R1CStoSAP::witness_map::<E, D>(&prover, &d1, &d2)?;
+    report(ProverPhase::WitnessMap, 1.0)?;
     end_timer!(witness_map_time);
 
     let input_assignment = full_input_assignment[1..prover.num_inputs]
@@ -256,6 +437,7 @@ where
 
     // Compute A
     let a_acc_time = start_timer!(|| "Compute A");
+    report(ProverPhase::Msm("A"), 0.0)?;
     let (a_inputs_source, a_aux_source) = params.get_a_query(prover.num_inputs)?;
     let a_inputs_acc = VariableBaseMSM::multi_scalar_mul(a_inputs_source, &input_assignment);
     let a_aux_acc = VariableBaseMSM::multi_scalar_mul(a_aux_source, &aux_assignment);
@@ -268,10 +450,12 @@ where
     g_a.add_assign(&d1_g);
     g_a.add_assign(&a_inputs_acc);
     g_a.add_assign(&a_aux_acc);
+    report(ProverPhase::Msm("A"), 1.0)?;
     end_timer!(a_acc_time);
 
     // Compute B
     let b_acc_time = start_timer!(|| "Compute B");
+    report(ProverPhase::Msm("B"), 0.0)?;
 
     let (b_inputs_source, b_aux_source) = params.get_b_query(prover.num_inputs)?;
     let b_inputs_acc = VariableBaseMSM::multi_scalar_mul(b_inputs_source, &input_assignment);
@@ -285,6 +469,7 @@ where
     g_b.add_assign(&d1_h);
     g_b.add_assign(&b_inputs_acc);
     g_b.add_assign(&b_aux_acc);
+    report(ProverPhase::Msm("B"), 1.0)?;
     end_timer!(b_acc_time);
 
     // Compute C
@@ -294,27 +479,33 @@ where
     let d1_r_2 = d1 * &r_2;
 
     let c1_acc_time = start_timer!(|| "Compute C1");
+    report(ProverPhase::Msm("C1"), 0.0)?;
     let (_, c1_aux_source) = params.get_c_query_1(0)?;
     let c1_acc = VariableBaseMSM::multi_scalar_mul(c1_aux_source, &aux_assignment);
+    report(ProverPhase::Msm("C1"), 1.0)?;
     end_timer!(c1_acc_time);
 
     let c2_acc_time = start_timer!(|| "Compute C2");
+    report(ProverPhase::Msm("C2"), 0.0)?;
 
     let (c2_inputs_source, c2_aux_source) = params.get_c_query_2(prover.num_inputs)?;
     let c2_inputs_acc = VariableBaseMSM::multi_scalar_mul(c2_inputs_source, &input_assignment);
     let c2_aux_acc = VariableBaseMSM::multi_scalar_mul(c2_aux_source, &aux_assignment);
 
     let c2_acc = c2_inputs_acc + &c2_aux_acc;
+    report(ProverPhase::Msm("C2"), 1.0)?;
     end_timer!(c2_acc_time);
 
     // Compute G
     let g_acc_time = start_timer!(|| "Compute G");
+    report(ProverPhase::Msm("G"), 0.0)?;
 
     let (g_inputs_source, g_aux_source) = params.get_g_gamma2_z_t(prover.num_inputs)?;
     let g_inputs_acc = VariableBaseMSM::multi_scalar_mul(g_inputs_source, &h_input);
     let g_aux_acc = VariableBaseMSM::multi_scalar_mul(g_aux_source, &h_aux);
 
     let g_acc = g_inputs_acc + &g_aux_acc;
+    report(ProverPhase::Msm("G"), 1.0)?;
     end_timer!(g_acc_time);
 
     let r2_g_gamma2_z2 = params.get_g_gamma2_z2()?.mul(r2);
@@ -1,13 +1,16 @@
 use crate::{Parameters, Proof};
 use algebra_core::PairingEngine;
 use ff_fft::GeneralEvaluationDomain;
-use r1cs_core::{ConstraintSynthesizer, SynthesisError};
+use r1cs_core::{CancellationToken, ConstraintSynthesizer, ProverObserver, SynthesisError};
 use rand::Rng;
 
 pub mod generic;
 
 pub use generic::ProvingAssignment;
 
+/// Creates a proof for `circuit` under `params`, which fails with
+/// `SynthesisError::CircuitMismatch` if the structure of `circuit` differs from
+/// that of the circuit that `params` were generated for.
 #[inline]
 pub fn create_random_proof<E, C, R>(
     circuit: C,
@@ -38,3 +41,48 @@ where
 {
     self::generic::create_proof::<E, C, GeneralEvaluationDomain<E::Fr>>(circuit, params, d1, d2, r)
 }
+
+/// Like [`create_random_proof`], but reports the progress of each phase of
+/// the prover to `observer`, and fails with `SynthesisError::Cancelled` once
+/// `cancel` is cancelled.
+#[inline]
+pub fn create_random_proof_with_observer<E, C, R, O>(
+    circuit: C,
+    params: &Parameters<E>,
+    rng: &mut R,
+    observer: &O,
+    cancel: &CancellationToken,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    R: Rng,
+    O: ProverObserver,
+{
+    self::generic::create_random_proof_with_observer::<E, C, GeneralEvaluationDomain<E::Fr>, R, O>(
+        circuit, params, rng, observer, cancel,
+    )
+}
+
+/// Like [`create_proof`], but reports the progress of each phase of the
+/// prover to `observer`, and fails with `SynthesisError::Cancelled` once
+/// `cancel` is cancelled.
+#[inline]
+pub fn create_proof_with_observer<E, C, O>(
+    circuit: C,
+    params: &Parameters<E>,
+    d1: E::Fr,
+    d2: E::Fr,
+    r: E::Fr,
+    observer: &O,
+    cancel: &CancellationToken,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    O: ProverObserver,
+{
+    self::generic::create_proof_with_observer::<E, C, GeneralEvaluationDomain<E::Fr>, O>(
+        circuit, params, d1, d2, r, observer, cancel,
+    )
+}
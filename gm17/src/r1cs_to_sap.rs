@@ -96,6 +96,71 @@ impl R1CStoSAP {
         Ok((a, c, zt, sap_num_variables, domain_size))
     }
 
+    /// Computes the coefficients of the polynomials `sum_i r[i] * a_i` and
+    /// `sum_i r[i] * c_i`, where `a_i` and `c_i` are the polynomials of the
+    /// SAP that `instance_map_with_evaluation` evaluates, and `r` has one
+    /// entry per SAP variable.
+    pub(crate) fn combine_polynomials<E: PairingEngine, D: EvaluationDomain<E::Fr>>(
+        assembly: &KeypairAssembly<E>,
+        r: &[E::Fr],
+    ) -> Result<(Vec<E::Fr>, Vec<E::Fr>), SynthesisError> {
+        let domain_size = 2 * assembly.num_constraints + 2 * (assembly.num_inputs - 1) + 1;
+        let domain = D::new(domain_size).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+
+        let extra_var_offset = (assembly.num_inputs - 1) + assembly.num_aux + 1;
+        let extra_constr_offset = 2 * assembly.num_constraints;
+        let extra_var_offset2 =
+            (assembly.num_inputs - 1) + assembly.num_aux + assembly.num_constraints;
+        let index = |index: Index| match index {
+            Index::Input(i) => i,
+            Index::Aux(i) => assembly.num_inputs + i,
+        };
+
+        // The evaluations of the combinations over the domain, which are their
+        // coefficients in the Lagrange basis.
+        let mut a = vec![E::Fr::zero(); domain.size()];
+        let mut c = vec![E::Fr::zero(); domain.size()];
+
+        for i in 0..assembly.num_constraints {
+            for &(ref coeff, var) in assembly.at[i].iter() {
+                let term = r[index(var)] * coeff;
+                a[2 * i] += &term;
+                a[2 * i + 1] += &term;
+            }
+
+            for &(ref coeff, var) in assembly.bt[i].iter() {
+                let term = r[index(var)] * coeff;
+                a[2 * i] += &term;
+                a[2 * i + 1] -= &term;
+            }
+
+            for &(ref coeff, var) in assembly.ct[i].iter() {
+                c[2 * i] += &((r[index(var)] * coeff).double().double());
+            }
+            c[2 * i] += &r[extra_var_offset + i];
+            c[2 * i + 1] += &r[extra_var_offset + i];
+        }
+
+        a[extra_constr_offset] += &r[0];
+        c[extra_constr_offset] += &r[0];
+
+        for i in 1..assembly.num_inputs {
+            // First extra constraint
+            let k = extra_constr_offset + 2 * i - 1;
+            a[k] += &(r[i] + &r[0]);
+            c[k] += &(r[i].double().double() + &r[extra_var_offset2 + i]);
+
+            // Second extra constraint
+            let k = extra_constr_offset + 2 * i;
+            a[k] += &(r[i] - &r[0]);
+            c[k] += &r[extra_var_offset2 + i];
+        }
+
+        domain.ifft_in_place(&mut a);
+        domain.ifft_in_place(&mut c);
+        Ok((a, c))
+    }
+
     #[inline]
     pub(crate) fn witness_map<E: PairingEngine, D: EvaluationDomain<E::Fr>>(
         prover: &ProvingAssignment<E>,
@@ -0,0 +1,43 @@
+use crate::{Proof, Trapdoor};
+use algebra_core::{AffineCurve, PairingEngine, UniformRand};
+use r1cs_core::SynthesisError;
+use rand::Rng;
+
+/// Forges a proof that `public_inputs` is a valid statement, using the
+/// trapdoor of the parameters instead of a witness.
+///
+/// For random `mu`, the proof is `(mu * G, mu * H, c * G)` where
+/// `c = (mu + alpha) * (mu + beta) - alpha * beta - gamma * psi`, and `psi` is
+/// the discrete logarithm of `sum_i x_i * vk.query[i]`. This satisfies both
+/// verification equations, and is distributed identically to honestly
+/// generated proofs.
+pub fn simulate_proof<E, R>(
+    trapdoor: &Trapdoor<E>,
+    public_inputs: &[E::Fr],
+    rng: &mut R,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    R: Rng,
+{
+    if (public_inputs.len() + 1) != trapdoor.input_base_logs.len() {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+
+    let mu = E::Fr::rand(rng);
+
+    let mut psi = trapdoor.input_base_logs[0];
+    for (input, log) in public_inputs.iter().zip(&trapdoor.input_base_logs[1..]) {
+        psi += &(*input * log);
+    }
+
+    let c = (mu + &trapdoor.alpha) * &(mu + &trapdoor.beta)
+        - &(trapdoor.alpha * &trapdoor.beta)
+        - &(trapdoor.gamma * &psi);
+
+    Ok(Proof {
+        a: trapdoor.g1_generator.mul(mu).into(),
+        b: trapdoor.g2_generator.mul(mu).into(),
+        c: trapdoor.g1_generator.mul(c).into(),
+    })
+}
@@ -110,3 +110,793 @@ mod sw6 {
         assert!(!verify_proof(&pvk, &proof, &[Fr::zero()]).unwrap());
     }
 }
+
+mod serialization {
+    use super::*;
+    use crate::{
+        create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof,
+        Parameters, Proof, VerifyingKey, SERIALIZATION_VERSION,
+    };
+    use algebra_core::{test_rng, PairingEngine, UniformRand};
+
+    fn serialization_round_trip<E: PairingEngine>() {
+        let rng = &mut test_rng();
+
+        let params =
+            generate_random_parameters::<E, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+
+        let a = E::Fr::rand(rng);
+        let b = E::Fr::rand(rng);
+        let c = a * &b;
+        let proof = create_random_proof(
+            MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            },
+            &params,
+            rng,
+        )
+        .unwrap();
+
+        for &compressed in &[true, false] {
+            let write_params = |params: &Parameters<E>, bytes: &mut Vec<u8>| {
+                if compressed {
+                    params.write(bytes).unwrap();
+                } else {
+                    params.write_uncompressed(bytes).unwrap();
+                }
+            };
+            let mut bytes = Vec::new();
+            write_params(&params, &mut bytes);
+            assert_eq!(bytes[0], SERIALIZATION_VERSION);
+            for &checked in &[true, false] {
+                let read = Parameters::<E>::read(&bytes[..], checked).unwrap();
+                let mut read_bytes = Vec::new();
+                write_params(&read, &mut read_bytes);
+                assert_eq!(bytes, read_bytes);
+            }
+
+            let write_vk = |vk: &VerifyingKey<E>, bytes: &mut Vec<u8>| {
+                if compressed {
+                    vk.write(bytes).unwrap();
+                } else {
+                    vk.write_uncompressed(bytes).unwrap();
+                }
+            };
+            let mut bytes = Vec::new();
+            write_vk(&params.vk, &mut bytes);
+            let vk = VerifyingKey::<E>::read(&bytes[..]).unwrap();
+            let mut read_bytes = Vec::new();
+            write_vk(&vk, &mut read_bytes);
+            assert_eq!(bytes, read_bytes);
+
+            let write_proof = |proof: &Proof<E>, bytes: &mut Vec<u8>| {
+                if compressed {
+                    proof.write(bytes).unwrap();
+                } else {
+                    proof.write_uncompressed(bytes).unwrap();
+                }
+            };
+            let mut bytes = Vec::new();
+            write_proof(&proof, &mut bytes);
+            let read_proof = Proof::<E>::read(&bytes[..]).unwrap();
+            let mut read_bytes = Vec::new();
+            write_proof(&read_proof, &mut read_bytes);
+            assert_eq!(bytes, read_bytes);
+
+            let pvk = prepare_verifying_key(&vk);
+            assert!(verify_proof(&pvk, &read_proof, &[c]).unwrap());
+        }
+    }
+
+    fn serialization_rejects_malformed<E: PairingEngine>() {
+        let rng = &mut test_rng();
+
+        let params =
+            generate_random_parameters::<E, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+
+        // Unknown version.
+        let mut bytes = Vec::new();
+        params.vk.write(&mut bytes).unwrap();
+        bytes[0] = SERIALIZATION_VERSION + 1;
+        assert!(VerifyingKey::<E>::read(&bytes[..]).is_err());
+
+        // Truncated input.
+        let mut bytes = Vec::new();
+        params.vk.write(&mut bytes).unwrap();
+        bytes.pop();
+        assert!(VerifyingKey::<E>::read(&bytes[..]).is_err());
+
+        // A point that is moved off the curve is rejected by a checked read,
+        // but accepted by an unchecked one.
+        let mut bytes = Vec::new();
+        params.write_uncompressed(&mut bytes).unwrap();
+        bytes[2] ^= 1;
+        assert!(Parameters::<E>::read(&bytes[..], true).is_err());
+        assert!(Parameters::<E>::read(&bytes[..], false).is_ok());
+    }
+
+    macro_rules! serialization_tests {
+        ($($name:ident => $engine:ty),*) => {
+            $(
+                mod $name {
+                    #[test]
+                    fn round_trip() {
+                        super::serialization_round_trip::<$engine>();
+                    }
+
+                    #[test]
+                    fn rejects_malformed() {
+                        super::serialization_rejects_malformed::<$engine>();
+                    }
+                }
+            )*
+        };
+    }
+
+    serialization_tests!(
+        bls12_377 => algebra::bls12_377::Bls12_377,
+        bls12_381 => algebra::bls12_381::Bls12_381,
+        bn_382 => algebra::bn_382::Bn_382,
+        sw6 => algebra::sw6::SW6,
+        mnt4_298 => algebra::mnt4_298::MNT4_298,
+        mnt4_753 => algebra::mnt4_753::MNT4_753,
+        mnt6_298 => algebra::mnt6_298::MNT6_298,
+        mnt6_753 => algebra::mnt6_753::MNT6_753
+    );
+}
+
+mod batch_verification {
+    use super::*;
+    use crate::{
+        batch_verify_proofs, create_random_proof, generate_random_parameters, prepare_verifying_key,
+    };
+    use algebra_core::{test_rng, PairingEngine, UniformRand};
+
+    fn batch_verify<E: PairingEngine>() {
+        let rng = &mut test_rng();
+
+        let params =
+            generate_random_parameters::<E, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+        let pvk = prepare_verifying_key::<E>(&params.vk);
+
+        let mut proofs = Vec::new();
+        let mut inputs = Vec::new();
+        for _ in 0..10 {
+            let a = E::Fr::rand(rng);
+            let b = E::Fr::rand(rng);
+            let proof = create_random_proof(
+                MySillyCircuit {
+                    a: Some(a),
+                    b: Some(b),
+                },
+                &params,
+                rng,
+            )
+            .unwrap();
+            proofs.push(proof);
+            inputs.push(vec![a * &b]);
+        }
+
+        assert!(batch_verify_proofs(&pvk, &proofs, &inputs, rng)
+            .unwrap()
+            .is_empty());
+        assert!(batch_verify_proofs(&pvk, &proofs[..1], &inputs[..1], rng)
+            .unwrap()
+            .is_empty());
+        assert!(batch_verify_proofs::<E, Vec<E::Fr>, _>(&pvk, &[], &[], rng)
+            .unwrap()
+            .is_empty());
+
+        // Invalidate two of the statements and check that the bisection finds them.
+        inputs[3][0] = E::Fr::rand(rng);
+        inputs[7][0] = E::Fr::rand(rng);
+        assert_eq!(
+            batch_verify_proofs(&pvk, &proofs, &inputs, rng).unwrap(),
+            vec![3, 7]
+        );
+
+        // Swapping proofs between valid statements must also be caught.
+        let mut swapped = proofs.clone();
+        swapped.swap(0, 1);
+        assert_eq!(
+            batch_verify_proofs(&pvk, &swapped[..3], &inputs[..3], rng).unwrap(),
+            vec![0, 1]
+        );
+
+        // Public inputs of the wrong length are rejected.
+        assert!(batch_verify_proofs(&pvk, &proofs[..1], &[vec![]], rng).is_err());
+        // So is a number of public inputs that differs from the number of proofs.
+        assert!(matches!(
+            batch_verify_proofs(&pvk, &proofs[..2], &inputs[..1], rng),
+            Err(SynthesisError::ProofCountMismatch)
+        ));
+    }
+
+    #[test]
+    fn bls12_377_batch_verify() {
+        batch_verify::<algebra::bls12_377::Bls12_377>();
+    }
+
+    #[test]
+    fn mnt4_298_batch_verify() {
+        batch_verify::<algebra::mnt4_298::MNT4_298>();
+    }
+}
+
+mod simulation {
+    use super::*;
+    use crate::{
+        generate_random_parameters_with_trapdoor, prepare_verifying_key, simulate_proof,
+        verify_proof,
+    };
+    use algebra_core::{test_rng, AffineCurve, PairingEngine, UniformRand};
+
+    fn simulate<E: PairingEngine>() {
+        let rng = &mut test_rng();
+
+        let (params, trapdoor) = generate_random_parameters_with_trapdoor::<E, _, _>(
+            MySillyCircuit { a: None, b: None },
+            rng,
+        )
+        .unwrap();
+        assert!(params.vk.g_alpha_g1 == trapdoor.g1_generator.mul(trapdoor.alpha).into());
+        assert!(params.vk.h_beta_g2 == trapdoor.g2_generator.mul(trapdoor.beta).into());
+
+        let pvk = prepare_verifying_key::<E>(&params.vk);
+        for _ in 0..5 {
+            // No witness is needed: `c` need not even be a product.
+            let c = E::Fr::rand(rng);
+            let proof = simulate_proof(&trapdoor, &[c], rng).unwrap();
+            assert!(verify_proof(&pvk, &proof, &[c]).unwrap());
+            assert!(!verify_proof(&pvk, &proof, &[c.double()]).unwrap());
+        }
+        assert!(simulate_proof(&trapdoor, &[], rng).is_err());
+    }
+
+    #[test]
+    fn bls12_377() {
+        simulate::<algebra::bls12_377::Bls12_377>();
+    }
+
+    #[test]
+    fn mnt4_298() {
+        simulate::<algebra::mnt4_298::MNT4_298>();
+    }
+}
+
+mod parameter_check {
+    use super::*;
+    use crate::{check_parameters, generate_random_parameters, ParameterError};
+
+    use algebra::bls12_377::{Bls12_377, Fr, G1Affine};
+    use algebra_core::{test_rng, AffineCurve, ProjectiveCurve, Zero};
+
+    struct SquareCircuit;
+
+    impl<F: Field> ConstraintSynthesizer<F> for SquareCircuit {
+        fn generate_constraints<CS: ConstraintSystem<F>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let a = cs.alloc(|| "a", || Err(SynthesisError::AssignmentMissing))?;
+            let c = cs.alloc_input(|| "c", || Err(SynthesisError::AssignmentMissing))?;
+            cs.enforce(|| "a*a=c", |lc| lc + a, |lc| lc + a, |lc| lc + c);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn check() {
+        let rng = &mut test_rng();
+        let circuit = || MySillyCircuit::<Fr> { a: None, b: None };
+
+        let params = generate_random_parameters::<Bls12_377, _, _>(circuit(), rng).unwrap();
+        let other = generate_random_parameters::<Bls12_377, _, _>(circuit(), rng).unwrap();
+        check_parameters(&params, circuit(), rng).unwrap();
+        check_parameters(&other, circuit(), rng).unwrap();
+
+        // Parameters for another circuit.
+        let square = generate_random_parameters::<Bls12_377, _, _>(SquareCircuit, rng).unwrap();
+        assert!(check_parameters(&square, circuit(), rng).is_err());
+        assert!(check_parameters(&params, SquareCircuit, rng).is_err());
+
+        // Queries mixed from two setups for the same circuit.
+        let mut mixed = params.clone();
+        mixed.b_query = other.b_query.clone();
+        assert!(matches!(
+            check_parameters(&mixed, circuit(), rng),
+            Err(ParameterError::Inconsistent(_))
+        ));
+
+        let mut mixed = params.clone();
+        mixed.c_query_1 = other.c_query_1.clone();
+        assert!(matches!(
+            check_parameters(&mixed, circuit(), rng),
+            Err(ParameterError::Inconsistent(_))
+        ));
+
+        let mut mixed = params.clone();
+        mixed.c_query_2 = other.c_query_2.clone();
+        assert!(matches!(
+            check_parameters(&mixed, circuit(), rng),
+            Err(ParameterError::Inconsistent(_))
+        ));
+
+        let mut mixed = params.clone();
+        mixed.g_ab_gamma_z = other.g_ab_gamma_z;
+        assert!(matches!(
+            check_parameters(&mixed, circuit(), rng),
+            Err(ParameterError::Inconsistent(_))
+        ));
+
+        // Elements that do not match the polynomials of the SAP, although
+        // they pass the checks that do not depend on the circuit.
+        let double = |g: G1Affine| g.into_projective().double().into_affine();
+        // The first auxiliary variable has no C polynomial, and the last
+        // one no A polynomial.
+        let checks = [
+            (0, "c_query_1 with the A and B queries"),
+            (params.c_query_1.len() - 1, "c_query_1 with the SAP"),
+        ];
+        for &(index, elements) in checks.iter() {
+            let mut tampered = params.clone();
+            tampered.c_query_1[index] = double(tampered.c_query_1[index]);
+            assert!(matches!(
+                check_parameters(&tampered, circuit(), rng),
+                Err(ParameterError::Inconsistent(e)) if e == elements
+            ));
+        }
+
+        // Scaling an A query element, together with the B and second C
+        // query elements that must match it, changes the polynomial.
+        let mut tampered = params.clone();
+        tampered.a_query[1] = double(tampered.a_query[1]);
+        tampered.b_query[1] = tampered.b_query[1].into_projective().double().into_affine();
+        tampered.c_query_2[1] = double(tampered.c_query_2[1]);
+        crate::verify_crs(&tampered).unwrap();
+        assert!(matches!(
+            check_parameters(&tampered, circuit(), rng),
+            Err(ParameterError::Inconsistent("a_query with the SAP"))
+        ));
+
+        // Identity elements where the SAP has none.
+        let mut tampered = params.clone();
+        tampered.a_query[1] = <Bls12_377 as algebra_core::PairingEngine>::G1Affine::zero();
+        assert!(matches!(
+            check_parameters(&tampered, circuit(), rng),
+            Err(ParameterError::UnexpectedIdentity {
+                query: "a_query",
+                index: 1
+            })
+        ));
+
+        let mut tampered = params;
+        tampered.g_gamma2_z_t.pop();
+        assert!(matches!(
+            check_parameters(&tampered, circuit(), rng),
+            Err(ParameterError::QueryLength {
+                query: "g_gamma2_z_t",
+                ..
+            })
+        ));
+    }
+}
+
+mod progress {
+    use super::*;
+    use crate::{
+        create_random_proof_with_observer, generate_random_parameters, prepare_verifying_key,
+        verify_proof,
+    };
+    use algebra::bls12_377::{Bls12_377, Fr};
+    use algebra_core::{test_rng, UniformRand};
+    use r1cs_core::{CancellationToken, ProverPhase};
+    use std::cell::RefCell;
+
+    #[test]
+    fn reports_every_phase() {
+        let rng = &mut test_rng();
+        let params =
+            generate_random_parameters::<Bls12_377, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+
+        let a = Fr::rand(rng);
+        let b = Fr::rand(rng);
+        let reports = RefCell::new(Vec::new());
+        let proof = create_random_proof_with_observer(
+            MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            },
+            &params,
+            rng,
+            &|phase: ProverPhase, fraction: f64| reports.borrow_mut().push((phase, fraction)),
+            &CancellationToken::new(),
+        )
+        .unwrap();
+        assert!(verify_proof(&pvk, &proof, &[a * &b]).unwrap());
+
+        let expected = [
+            ProverPhase::Synthesis,
+            ProverPhase::WitnessMap,
+            ProverPhase::Msm("A"),
+            ProverPhase::Msm("B"),
+            ProverPhase::Msm("C1"),
+            ProverPhase::Msm("C2"),
+            ProverPhase::Msm("G"),
+        ]
+        .iter()
+        .flat_map(|phase| vec![(*phase, 0.0), (*phase, 1.0)])
+        .collect::<Vec<_>>();
+        assert_eq!(reports.into_inner(), expected);
+    }
+
+    #[test]
+    fn cancellation_stops_the_prover() {
+        let rng = &mut test_rng();
+        let params =
+            generate_random_parameters::<Bls12_377, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let result = create_random_proof_with_observer(
+            MySillyCircuit {
+                a: Some(Fr::from(2u64)),
+                b: Some(Fr::from(3u64)),
+            },
+            &params,
+            rng,
+            &|_: ProverPhase, _: f64| {},
+            &cancel,
+        );
+        assert!(matches!(result, Err(SynthesisError::Cancelled)));
+    }
+}
+
+mod seeded_parameters {
+    use super::*;
+    use crate::{
+        create_random_proof, generate_parameters_from_seed, prepare_verifying_key, verify_proof,
+        Parameters, VerifyingKey,
+    };
+    use algebra::bls12_377::{Bls12_377, Fr};
+    use algebra_core::{test_rng, UniformRand};
+
+    struct SquareCircuit;
+
+    impl<F: Field> ConstraintSynthesizer<F> for SquareCircuit {
+        fn generate_constraints<CS: ConstraintSystem<F>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let a = cs.alloc(|| "a", || Err(SynthesisError::AssignmentMissing))?;
+            let c = cs.alloc_input(|| "c", || Err(SynthesisError::AssignmentMissing))?;
+            cs.enforce(|| "a*a=c", |lc| lc + a, |lc| lc + a, |lc| lc + c);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn same_seed_same_parameters() {
+        let circuit = || MySillyCircuit::<Fr> { a: None, b: None };
+        let params = generate_parameters_from_seed::<Bls12_377, _>(circuit(), [7u8; 32]).unwrap();
+        let again = generate_parameters_from_seed::<Bls12_377, _>(circuit(), [7u8; 32]).unwrap();
+        assert!(params == again);
+        assert_eq!(params.fingerprint(), again.fingerprint());
+        assert_eq!(params.vk.fingerprint(), again.vk.fingerprint());
+        assert_ne!(params.fingerprint(), params.vk.fingerprint());
+
+        // The fingerprints survive a round trip through serialization.
+        let mut bytes = Vec::new();
+        params.write(&mut bytes).unwrap();
+        let read = Parameters::<Bls12_377>::read(&bytes[..], true).unwrap();
+        assert_eq!(read.fingerprint(), params.fingerprint());
+        let mut bytes = Vec::new();
+        params.vk.write_uncompressed(&mut bytes).unwrap();
+        let read = VerifyingKey::<Bls12_377>::read(&bytes[..]).unwrap();
+        assert_eq!(read.fingerprint(), params.vk.fingerprint());
+
+        // The seeded parameters are ordinary parameters.
+        let rng = &mut test_rng();
+        let pvk = prepare_verifying_key(&params.vk);
+        let a = Fr::rand(rng);
+        let b = Fr::rand(rng);
+        let proof = create_random_proof(
+            MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            },
+            &params,
+            rng,
+        )
+        .unwrap();
+        assert!(verify_proof(&pvk, &proof, &[a * &b]).unwrap());
+    }
+
+    #[test]
+    fn fingerprints_detect_changes() {
+        let circuit = || MySillyCircuit::<Fr> { a: None, b: None };
+        let params = generate_parameters_from_seed::<Bls12_377, _>(circuit(), [7u8; 32]).unwrap();
+
+        let other_seed =
+            generate_parameters_from_seed::<Bls12_377, _>(circuit(), [8u8; 32]).unwrap();
+        assert_ne!(params.fingerprint(), other_seed.fingerprint());
+        assert_ne!(params.vk.fingerprint(), other_seed.vk.fingerprint());
+
+        let other_circuit =
+            generate_parameters_from_seed::<Bls12_377, _>(SquareCircuit, [7u8; 32]).unwrap();
+        assert_ne!(params.fingerprint(), other_circuit.fingerprint());
+    }
+}
+
+mod crs_verification {
+    use super::*;
+    use crate::{check_parameters, generate_random_parameters, verify_crs, ParameterError};
+
+    use algebra::bls12_377::{Bls12_377, Fr};
+    use algebra_core::{test_rng, AffineCurve, PairingEngine, ProjectiveCurve};
+
+    type G1Affine = <Bls12_377 as PairingEngine>::G1Affine;
+
+    #[test]
+    fn accepts_honest_parameters() {
+        let rng = &mut test_rng();
+        for _ in 0..3 {
+            let params = generate_random_parameters::<Bls12_377, _, _>(
+                MySillyCircuit::<Fr> { a: None, b: None },
+                rng,
+            )
+            .unwrap();
+            verify_crs(&params).unwrap();
+        }
+    }
+
+    #[test]
+    fn rejects_tampered_parameters() {
+        let rng = &mut test_rng();
+        let circuit = || MySillyCircuit::<Fr> { a: None, b: None };
+        let params = generate_random_parameters::<Bls12_377, _, _>(circuit(), rng).unwrap();
+        let other = generate_random_parameters::<Bls12_377, _, _>(circuit(), rng).unwrap();
+        let double = |g: G1Affine| g.into_projective().double().into_affine();
+
+        let mut tampered = params.clone();
+        tampered.a_query[2] = double(tampered.a_query[2]);
+        assert!(matches!(
+            verify_crs(&tampered),
+            Err(ParameterError::Inconsistent("a_query and b_query"))
+        ));
+
+        let mut tampered = params.clone();
+        tampered.c_query_2 = other.c_query_2.clone();
+        assert!(matches!(
+            verify_crs(&tampered),
+            Err(ParameterError::Inconsistent("a_query and c_query_2"))
+        ));
+
+        let mut tampered = params.clone();
+        tampered.g_gamma_z = other.g_gamma_z;
+        assert!(matches!(
+            verify_crs(&tampered),
+            Err(ParameterError::Inconsistent("g_gamma_z and h_gamma_z"))
+        ));
+
+        let mut tampered = params.clone();
+        tampered.vk.g_alpha_g1 = other.vk.g_alpha_g1;
+        assert!(matches!(
+            verify_crs(&tampered),
+            Err(ParameterError::Inconsistent("g_ab_gamma_z"))
+        ));
+
+        let mut tampered = params.clone();
+        let last = tampered.g_gamma2_z_t.len() - 1;
+        tampered.g_gamma2_z_t[last] = double(tampered.g_gamma2_z_t[last]);
+        assert!(matches!(
+            verify_crs(&tampered),
+            Err(ParameterError::Inconsistent("g_gamma2_z_t and g_gamma2_z2"))
+        ));
+
+        // Nothing relates the middle elements of g_gamma2_z_t to the rest
+        // of the CRS, so only the check against the circuit catches them.
+        let mut tampered = params.clone();
+        let middle = tampered.g_gamma2_z_t.len() / 2;
+        tampered.g_gamma2_z_t[middle] = double(tampered.g_gamma2_z_t[middle]);
+        verify_crs(&tampered).unwrap();
+        assert!(matches!(
+            check_parameters(&tampered, circuit(), rng),
+            Err(ParameterError::Inconsistent("a_query with the SAP"))
+        ));
+
+        let mut tampered = params.clone();
+        tampered.g_gamma2_z_t.pop();
+        assert!(matches!(
+            verify_crs(&tampered),
+            Err(ParameterError::Inconsistent("g_gamma2_z_t and g_gamma2_z2"))
+        ));
+
+        let mut tampered = params;
+        tampered.b_query.pop();
+        assert!(matches!(
+            verify_crs(&tampered),
+            Err(ParameterError::QueryLength {
+                query: "b_query",
+                ..
+            })
+        ));
+    }
+}
+
+mod unsatisfied_constraints {
+    use super::*;
+    use crate::ProvingAssignment;
+    use algebra::bls12_377::{Bls12_377, Fr};
+    use algebra_core::One;
+
+    // Claims that `x * y = z` for arbitrary values, inside a namespace.
+    struct ClaimedProduct {
+        x: Fr,
+        y: Fr,
+        z: Fr,
+    }
+
+    impl ConstraintSynthesizer<Fr> for ClaimedProduct {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let z = cs.alloc_input(|| "z", || Ok(self.z))?;
+            let mut cs = cs.ns(|| "product");
+            let x = cs.alloc(|| "x", || Ok(self.x))?;
+            let y = cs.alloc(|| "y", || Ok(self.y))?;
+            cs.enforce(|| "x*y=z", |lc| lc + x, |lc| lc + y, |lc| lc + z);
+            cs.enforce(|| "x*1=x", |lc| lc + x, |lc| lc + CS::one(), |lc| lc + x);
+            cs.enforce(|| "y*y=z", |lc| lc + y, |lc| lc + y, |lc| lc + z);
+            Ok(())
+        }
+    }
+
+    fn circuit() -> ClaimedProduct {
+        ClaimedProduct {
+            x: Fr::from(2u64),
+            y: Fr::from(3u64),
+            z: Fr::from(7u64),
+        }
+    }
+
+    #[test]
+    fn reports_names_and_values() {
+        let mut prover = ProvingAssignment::<Bls12_377>::new_with_names();
+        circuit().generate_constraints(&mut prover).unwrap();
+
+        assert_eq!(prover.which_is_unsatisfied(), Some(0));
+        let unsatisfied = prover.unsatisfied_constraints();
+        assert_eq!(unsatisfied.len(), 2);
+
+        assert_eq!(unsatisfied[0].index, 0);
+        assert_eq!(unsatisfied[0].name, "product/x*y=z");
+        assert_eq!(unsatisfied[0].namespace, "product");
+        assert_eq!(unsatisfied[0].a * &unsatisfied[0].b, Fr::from(6u64));
+        assert_eq!(unsatisfied[0].c, Fr::from(7u64));
+        assert_eq!(
+            unsatisfied[0].variables,
+            vec![
+                ("product/x".to_string(), Fr::from(2u64)),
+                ("product/y".to_string(), Fr::from(3u64)),
+                ("z".to_string(), Fr::from(7u64)),
+            ]
+        );
+
+        assert_eq!(unsatisfied[1].index, 2);
+        assert_eq!(unsatisfied[1].name, "product/y*y=z");
+        assert_eq!(unsatisfied[1].variables.len(), 2);
+    }
+
+    #[test]
+    fn reports_indices_without_names() {
+        let mut prover = ProvingAssignment::<Bls12_377>::new();
+        circuit().generate_constraints(&mut prover).unwrap();
+
+        let unsatisfied = prover.unsatisfied_constraints();
+        assert_eq!(unsatisfied.len(), 2);
+        assert_eq!(unsatisfied[0].name, "constraint 0");
+        assert_eq!(unsatisfied[0].namespace, "");
+        assert_eq!(unsatisfied[0].variables[0].0, "aux 0");
+        assert_eq!(unsatisfied[0].variables[2].0, "input 1");
+
+        let mut satisfied = ProvingAssignment::<Bls12_377>::new();
+        ClaimedProduct {
+            x: Fr::one(),
+            y: Fr::one(),
+            z: Fr::one(),
+        }
+        .generate_constraints(&mut satisfied)
+        .unwrap();
+        assert!(satisfied.unsatisfied_constraints().is_empty());
+    }
+}
+
+mod circuit_digest {
+    use super::*;
+    use crate::{
+        create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof,
+        Parameters,
+    };
+    use algebra::bls12_377::{Bls12_377, Fr};
+    use algebra_core::{test_rng, UniformRand};
+    use r1cs_core::CircuitDigest;
+
+    // `MySillyCircuit` with an extra constraint `1 * 1 = 1`, so that it has
+    // the same variables but a different structure.
+    struct ScaledProduct {
+        a: Option<Fr>,
+        b: Option<Fr>,
+    }
+
+    impl ConstraintSynthesizer<Fr> for ScaledProduct {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            MySillyCircuit {
+                a: self.a,
+                b: self.b,
+            }
+            .generate_constraints(cs)?;
+            cs.enforce(
+                || "1*1=1",
+                |lc| lc + CS::one(),
+                |lc| lc + CS::one(),
+                |lc| lc + CS::one(),
+            );
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn prover_rejects_other_circuit() {
+        let rng = &mut test_rng();
+        let params =
+            generate_random_parameters::<Bls12_377, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+        assert_eq!(
+            params.circuit_digest,
+            CircuitDigest::of(MySillyCircuit::<Fr> { a: None, b: None }).unwrap()
+        );
+
+        let mut bytes = vec![];
+        params.write(&mut bytes).unwrap();
+        let read = Parameters::<Bls12_377>::read(&bytes[..], true).unwrap();
+        assert_eq!(read.circuit_digest, params.circuit_digest);
+
+        let a = Fr::rand(rng);
+        let b = Fr::rand(rng);
+        assert!(matches!(
+            create_random_proof(
+                ScaledProduct {
+                    a: Some(a),
+                    b: Some(b),
+                },
+                &params,
+                rng,
+            ),
+            Err(SynthesisError::CircuitMismatch)
+        ));
+
+        let pvk = prepare_verifying_key(&params.vk);
+        let proof = create_random_proof(
+            MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            },
+            &params,
+            rng,
+        )
+        .unwrap();
+        assert!(verify_proof(&pvk, &proof, &[a * &b]).unwrap());
+    }
+}
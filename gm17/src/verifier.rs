@@ -1,11 +1,18 @@
-use algebra_core::{AffineCurve, One, PairingEngine, ProjectiveCurve};
+use algebra_core::{
+    msm::VariableBaseMSM, AffineCurve, One, PairingEngine, PrimeField, ProjectiveCurve, Zero,
+};
+use ff_fft::cfg_iter;
+use rand::Rng;
 
 use super::{PreparedVerifyingKey, Proof, VerifyingKey};
 
-use crate::SynthesisError;
+use crate::{SynthesisError, Vec};
 
 use core::ops::Neg;
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
 pub fn prepare_verifying_key<E: PairingEngine>(vk: &VerifyingKey<E>) -> PreparedVerifyingKey<E> {
     PreparedVerifyingKey {
         vk: vk.clone(),
@@ -66,3 +73,158 @@ pub fn verify_proof<E: PairingEngine>(
 
     Ok(test1.is_one() && test2.is_one())
 }
+
+/// Verify a batch of proofs that share the same verifying key.
+///
+/// Both GM17 verification equations of every proof are combined with
+/// independent random 128-bit coefficients, so that the whole batch costs a
+/// single multi-Miller loop and a single final exponentiation. If the combined
+/// check fails, the batch is bisected to locate the invalid proofs.
+///
+/// Returns the indices of the proofs that failed to verify; an empty vector
+/// means that every proof in the batch is valid. Fails with
+/// `SynthesisError::ProofCountMismatch` unless there are as many sets of
+/// public inputs as proofs.
+pub fn batch_verify_proofs<E, I, R>(
+    pvk: &PreparedVerifyingKey<E>,
+    proofs: &[Proof<E>],
+    public_inputs: &[I],
+    rng: &mut R,
+) -> Result<Vec<usize>, SynthesisError>
+where
+    E: PairingEngine,
+    I: AsRef<[E::Fr]>,
+    R: Rng,
+{
+    if proofs.len() != public_inputs.len() {
+        return Err(SynthesisError::ProofCountMismatch);
+    }
+    for inputs in public_inputs {
+        if (inputs.as_ref().len() + 1) != pvk.query.len() {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+    }
+
+    let batch_time = start_timer!(|| "Batch verification");
+    let mut failed = Vec::new();
+    bisect_batch(pvk, proofs, public_inputs, 0, rng, &mut failed)?;
+    end_timer!(batch_time);
+
+    Ok(failed)
+}
+
+fn bisect_batch<E, I, R>(
+    pvk: &PreparedVerifyingKey<E>,
+    proofs: &[Proof<E>],
+    public_inputs: &[I],
+    offset: usize,
+    rng: &mut R,
+    failed: &mut Vec<usize>,
+) -> Result<(), SynthesisError>
+where
+    E: PairingEngine,
+    I: AsRef<[E::Fr]>,
+    R: Rng,
+{
+    if proofs.is_empty() || verify_batch(pvk, proofs, public_inputs, rng)? {
+        return Ok(());
+    }
+    if proofs.len() == 1 {
+        failed.push(offset);
+        return Ok(());
+    }
+
+    let mid = proofs.len() / 2;
+    bisect_batch(
+        pvk,
+        &proofs[..mid],
+        &public_inputs[..mid],
+        offset,
+        rng,
+        failed,
+    )?;
+    bisect_batch(
+        pvk,
+        &proofs[mid..],
+        &public_inputs[mid..],
+        offset + mid,
+        rng,
+        failed,
+    )
+}
+
+/// Checks the random linear combination of both verification equations
+/// prod_i e(r_i * (A_i + G^{alpha}), B_i + H^{beta})
+///     * e(sum_i (s_i * A_i - r_i * psi_i), H^{gamma}) * e(-sum_i r_i * C_i, H)
+///     * e(G^{gamma}, -sum_i s_i * B_i) * e(-(sum_i r_i) * G^{alpha}, H^{beta})
+///       = 1.
+fn verify_batch<E, I, R>(
+    pvk: &PreparedVerifyingKey<E>,
+    proofs: &[Proof<E>],
+    public_inputs: &[I],
+    rng: &mut R,
+) -> Result<bool, SynthesisError>
+where
+    E: PairingEngine,
+    I: AsRef<[E::Fr]>,
+    R: Rng,
+{
+    let rs = (0..proofs.len())
+        .map(|_| E::Fr::from(rng.gen::<u128>()))
+        .collect::<Vec<_>>();
+    let ss = (0..proofs.len())
+        .map(|_| E::Fr::from(rng.gen::<u128>()))
+        .collect::<Vec<_>>();
+    let r_sum: E::Fr = rs.iter().sum();
+
+    // Fold the public inputs of all proofs into a single MSM over `query`.
+    let mut input_scalars = vec![E::Fr::zero(); pvk.query.len()];
+    input_scalars[0] = r_sum;
+    for (r, inputs) in rs.iter().zip(public_inputs) {
+        for (acc, input) in input_scalars[1..].iter_mut().zip(inputs.as_ref()) {
+            *acc += &(*r * input);
+        }
+    }
+    let input_scalars = cfg_iter!(input_scalars)
+        .map(|s| s.into_repr())
+        .collect::<Vec<_>>();
+    let g_psi = VariableBaseMSM::multi_scalar_mul(&pvk.query, &input_scalars);
+
+    let r_reprs = cfg_iter!(rs).map(|r| r.into_repr()).collect::<Vec<_>>();
+    let s_reprs = cfg_iter!(ss).map(|s| s.into_repr()).collect::<Vec<_>>();
+
+    let a_bases = proofs.iter().map(|proof| proof.a).collect::<Vec<_>>();
+    let b_bases = proofs.iter().map(|proof| proof.b).collect::<Vec<_>>();
+    let c_bases = proofs.iter().map(|proof| proof.c).collect::<Vec<_>>();
+    let s_a = VariableBaseMSM::multi_scalar_mul(&a_bases, &s_reprs);
+    let s_b = VariableBaseMSM::multi_scalar_mul(&b_bases, &s_reprs);
+    let r_c = VariableBaseMSM::multi_scalar_mul(&c_bases, &r_reprs);
+
+    let r_a_g_alpha = cfg_iter!(proofs)
+        .zip(&r_reprs)
+        .map(|(proof, r)| proof.a.into_projective().add_mixed(&pvk.g_alpha).mul(*r))
+        .collect::<Vec<_>>();
+    let r_a_g_alpha = E::G1Projective::batch_normalization_into_affine(&r_a_g_alpha);
+    let b_h_beta = cfg_iter!(proofs)
+        .map(|proof| proof.b.into_projective().add_mixed(&pvk.h_beta))
+        .collect::<Vec<_>>();
+    let b_h_beta = E::G2Projective::batch_normalization_into_affine(&b_h_beta);
+
+    let mut pairs = r_a_g_alpha
+        .into_iter()
+        .zip(b_h_beta)
+        .map(|(a, b)| (a.into(), b.into()))
+        .collect::<Vec<(E::G1Prepared, E::G2Prepared)>>();
+    pairs.push(((s_a - &g_psi).into_affine().into(), pvk.h_gamma_pc.clone()));
+    pairs.push((r_c.neg().into_affine().into(), pvk.h_pc.clone()));
+    pairs.push((pvk.g_gamma_pc.clone(), s_b.neg().into_affine().into()));
+    pairs.push((
+        pvk.g_alpha.mul(r_sum).neg().into_affine().into(),
+        pvk.h_beta.into(),
+    ));
+
+    let test = E::final_exponentiation(&E::miller_loop(pairs.iter()))
+        .ok_or(SynthesisError::UnexpectedIdentity)?;
+
+    Ok(test.is_one())
+}
@@ -0,0 +1,321 @@
+//! Aggregation of Groth16 proofs that share a verifying key, after
+//! [SnarkPack](https://eprint.iacr.org/2021/529.pdf).
+//!
+//! The `n` proofs `(A_i, B_i, C_i)` are checked together through the random
+//! linear combination
+//!     prod_i e(A_i, B_i)^{r^i} = e(alpha, beta)^{sum_i r^i}
+//!         * e(sum_i r^i * IC_i, gamma) * e(sum_i r^i * C_i, delta),
+//! where `IC_i` is the public input term of the `i`-th proof. The aggregate
+//! proof holds the two sides `Z_AB` and `Z_C` of the inner products, together
+//! with a logarithmic-size argument that they were computed from the vectors
+//! committed to in pairing-based commitments:
+//! * a TIPP (target inner pairing product) argument for `Z_AB`, and
+//! * a MIPP (multi-exponentiation inner product) argument for `Z_C`,
+//!
+//! which share their challenges. Both arguments recursively halve the
+//! committed vectors and the commitment keys, and end with KZG openings
+//! proving that the final commitment keys were folded correctly.
+//!
+//! The commitment keys are powers of two secrets `alpha` and `beta` (not to
+//! be confused with those of the Groth16 parameters), taken from the
+//! accumulators of two independent powers of tau ceremonies.
+
+use algebra_core::{
+    io, msm::VariableBaseMSM, serialize::*, AffineCurve, Field, PairingEngine, PrimeField,
+    ProjectiveCurve,
+};
+use core::fmt;
+use ff_fft::cfg_iter;
+use r1cs_core::SynthesisError;
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+
+use crate::{
+    ceremony::{hash, to_bytes, Digest64},
+    read_versioned, write_versioned, Vec,
+};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The structured reference string of the aggregation scheme.
+pub mod srs;
+
+mod prover;
+mod verifier;
+
+pub use self::{
+    prover::*,
+    srs::{GenericSRS, ProverSRS, VerifierSRS},
+    verifier::*,
+};
+
+/// A proof that `n` Groth16 proofs are valid, of size logarithmic in `n`.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregateProof<E: PairingEngine> {
+    /// The commitment to the `A` and `B` vectors.
+    pub com_ab: Commitment<E>,
+    /// The commitment to the `C` vector.
+    pub com_c: Commitment<E>,
+    /// `prod_i e(A_i, B_i)^{r^i}`.
+    pub z_ab: E::Fqk,
+    /// `sum_i r^i * C_i`.
+    pub z_c: E::G1Affine,
+    /// The argument that `z_ab` and `z_c` are consistent with the
+    /// commitments.
+    pub tmipp: TippMippProof<E>,
+}
+
+/// A pair of commitments to the same vectors, under the `alpha` and `beta`
+/// keys respectively.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Commitment<E: PairingEngine> {
+    pub alpha: E::Fqk,
+    pub beta: E::Fqk,
+}
+
+/// The combined TIPP and MIPP argument. It holds the cross terms of every
+/// halving round, in order, followed by the final folded vectors and keys and
+/// the KZG openings of the keys.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct TippMippProof<E: PairingEngine> {
+    pub z_ab_l: Vec<E::Fqk>,
+    pub z_ab_r: Vec<E::Fqk>,
+    pub com_ab_l: Vec<Commitment<E>>,
+    pub com_ab_r: Vec<Commitment<E>>,
+    pub com_c_l: Vec<Commitment<E>>,
+    pub com_c_r: Vec<Commitment<E>>,
+    pub z_c_l: Vec<E::G1Affine>,
+    pub z_c_r: Vec<E::G1Affine>,
+    pub final_a: E::G1Affine,
+    pub final_b: E::G2Affine,
+    pub final_c: E::G1Affine,
+    /// The folded `v` keys, for `alpha` and `beta`.
+    pub final_vkey: (E::G2Affine, E::G2Affine),
+    /// The folded `w` keys, for `alpha` and `beta`.
+    pub final_wkey: (E::G1Affine, E::G1Affine),
+    /// KZG openings of `final_vkey`, for `alpha` and `beta`.
+    pub vkey_opening: (E::G2Affine, E::G2Affine),
+    /// KZG openings of `final_wkey`, for `alpha` and `beta`.
+    pub wkey_opening: (E::G1Affine, E::G1Affine),
+}
+
+impl<E: PairingEngine> AggregateProof<E> {
+    /// Serialize the aggregate proof into bytes. Group elements are written
+    /// in compressed form.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_versioned(self, &mut writer, true)
+    }
+
+    /// Deserialize the aggregate proof from bytes, checking that every group
+    /// element is on the curve and in the prime order subgroup.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        read_versioned(&mut reader, true)
+    }
+}
+
+/// This is an error that could occur while aggregating proofs or verifying
+/// an aggregate proof.
+#[derive(Debug)]
+pub enum AggregationError {
+    /// The number of proofs is not a power of two, or is less than two.
+    InvalidProofCount,
+    /// The structured reference string is too small for the number of
+    /// proofs.
+    SrsTooSmall,
+    /// The accumulators of the structured reference string do not share
+    /// their generators.
+    InconsistentSrs,
+    /// A proof or a verifying key was malformed.
+    Synthesis(SynthesisError),
+}
+
+impl From<SynthesisError> for AggregationError {
+    fn from(e: SynthesisError) -> AggregationError {
+        AggregationError::Synthesis(e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AggregationError {}
+
+impl fmt::Display for AggregationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            AggregationError::InvalidProofCount => {
+                write!(f, "the number of proofs is not a power of two")
+            },
+            AggregationError::SrsTooSmall => {
+                write!(f, "the reference string is too small for the proofs")
+            },
+            AggregationError::InconsistentSrs => {
+                write!(f, "the reference string mixes different generators")
+            },
+            AggregationError::Synthesis(err) => write!(f, "synthesis error: {}", err),
+        }
+    }
+}
+
+/// A Fiat-Shamir transcript, which derives challenges from a BLAKE2b hash
+/// chain over everything the prover has sent so far.
+pub(crate) struct Transcript {
+    state: Digest64,
+}
+
+impl Transcript {
+    pub(crate) fn new(label: &[u8]) -> Self {
+        Self {
+            state: hash(&[label]),
+        }
+    }
+
+    pub(crate) fn append<T: CanonicalSerialize>(&mut self, value: &T) {
+        self.state = hash(&[&self.state[..], &to_bytes(value)]);
+    }
+
+    /// Returns a non-zero challenge, so that it can be inverted.
+    pub(crate) fn challenge<F: PrimeField>(&mut self) -> F {
+        loop {
+            self.state = hash(&[&self.state[..], b"challenge"]);
+            let mut seed = [0u8; 32];
+            seed.copy_from_slice(&self.state[..32]);
+            let challenge = F::rand(&mut ChaChaRng::from_seed(seed));
+            if !challenge.is_zero() {
+                return challenge;
+            }
+        }
+    }
+}
+
+/// Starts the transcript of an aggregate proof, and derives from it the
+/// challenge `r` of the random linear combination.
+pub(crate) fn initial_transcript<E, I>(
+    com_ab: &Commitment<E>,
+    com_c: &Commitment<E>,
+    public_inputs: &[I],
+) -> (Transcript, E::Fr)
+where
+    E: PairingEngine,
+    I: AsRef<[E::Fr]>,
+{
+    let mut transcript = Transcript::new(b"groth16-aggregation");
+    transcript.append(com_ab);
+    transcript.append(com_c);
+    for inputs in public_inputs {
+        transcript.append(&inputs.as_ref().to_vec());
+    }
+    let r = transcript.challenge();
+    (transcript, r)
+}
+
+/// Appends the cross terms of the `round`-th halving round to the
+/// transcript, and returns the challenge that folds the vectors.
+pub(crate) fn round_challenge<E: PairingEngine>(
+    transcript: &mut Transcript,
+    proof: &TippMippProof<E>,
+    round: usize,
+) -> E::Fr {
+    transcript.append(&proof.z_ab_l[round]);
+    transcript.append(&proof.z_ab_r[round]);
+    transcript.append(&proof.com_ab_l[round]);
+    transcript.append(&proof.com_ab_r[round]);
+    transcript.append(&proof.com_c_l[round]);
+    transcript.append(&proof.com_c_r[round]);
+    transcript.append(&proof.z_c_l[round]);
+    transcript.append(&proof.z_c_r[round]);
+    transcript.challenge()
+}
+
+/// Appends the final folded vectors and keys to the transcript, and returns
+/// the point at which the KZG openings are evaluated.
+pub(crate) fn opening_challenge<E: PairingEngine>(
+    transcript: &mut Transcript,
+    proof: &TippMippProof<E>,
+) -> E::Fr {
+    transcript.append(&proof.final_a);
+    transcript.append(&proof.final_b);
+    transcript.append(&proof.final_c);
+    transcript.append(&proof.final_vkey.0);
+    transcript.append(&proof.final_vkey.1);
+    transcript.append(&proof.final_wkey.0);
+    transcript.append(&proof.final_wkey.1);
+    transcript.challenge()
+}
+
+/// Returns `prod_i e(left_i, right_i)`.
+pub(crate) fn inner_pairing_product<E: PairingEngine>(
+    left: &[E::G1Affine],
+    right: &[E::G2Affine],
+) -> Result<E::Fqk, SynthesisError> {
+    let pairs = left
+        .iter()
+        .zip(right)
+        .map(|(l, r)| ((*l).into(), (*r).into()))
+        .collect::<Vec<(E::G1Prepared, E::G2Prepared)>>();
+    E::final_exponentiation(&E::miller_loop(pairs.iter())).ok_or(SynthesisError::UnexpectedIdentity)
+}
+
+/// Returns `left_i + scalar * right_i` for every `i`.
+pub(crate) fn fold<G: AffineCurve>(left: &[G], right: &[G], scalar: G::ScalarField) -> Vec<G> {
+    let folded = cfg_iter!(left)
+        .zip(right)
+        .map(|(l, r)| {
+            let mut folded = r.mul(scalar);
+            folded.add_assign_mixed(l);
+            folded
+        })
+        .collect::<Vec<_>>();
+    G::Projective::batch_normalization_into_affine(&folded)
+}
+
+pub(crate) fn msm<G: AffineCurve>(bases: &[G], scalars: &[G::ScalarField]) -> G::Projective {
+    let scalars = cfg_iter!(scalars)
+        .map(|s| s.into_repr())
+        .collect::<Vec<_>>();
+    VariableBaseMSM::multi_scalar_mul(bases, &scalars)
+}
+
+/// Multiplies every element of `bases` by the corresponding scalar.
+pub(crate) fn scale<G: AffineCurve>(bases: &[G], scalars: &[G::ScalarField]) -> Vec<G> {
+    let scaled = cfg_iter!(bases)
+        .zip(scalars)
+        .map(|(base, scalar)| base.mul(*scalar))
+        .collect::<Vec<_>>();
+    G::Projective::batch_normalization_into_affine(&scaled)
+}
+
+/// Returns the coefficients of
+///     prod_j (1 + challenges_j * X^{2^{k - 1 - j}}),
+/// where `k` is the number of challenges. The folded commitment keys are
+/// commitments to such polynomials.
+pub(crate) fn folding_polynomial<F: Field>(challenges: &[F]) -> Vec<F> {
+    let mut coeffs = vec![F::one()];
+    for challenge in challenges.iter().rev() {
+        let shifted = coeffs.iter().map(|c| *c * challenge).collect::<Vec<_>>();
+        coeffs.extend(shifted);
+    }
+    coeffs
+}
+
+/// Evaluates the polynomial of [`folding_polynomial`] at `point`, in time
+/// logarithmic in its degree.
+pub(crate) fn evaluate_folding_polynomial<F: Field>(challenges: &[F], point: F) -> F {
+    let mut result = F::one();
+    let mut power = point;
+    for challenge in challenges.iter().rev() {
+        result *= &(F::one() + &(*challenge * &power));
+        power.square_in_place();
+    }
+    result
+}
+
+/// Returns the quotient of `poly(X) - poly(point)` by `X - point`.
+pub(crate) fn kzg_quotient<F: Field>(poly: &[F], point: F) -> Vec<F> {
+    let mut quotient = vec![F::zero(); poly.len().saturating_sub(1)];
+    let mut carry = F::zero();
+    for i in (1..poly.len()).rev() {
+        carry = poly[i] + &(carry * &point);
+        quotient[i - 1] = carry;
+    }
+    quotient
+}
@@ -0,0 +1,224 @@
+use algebra_core::{AffineCurve, Field, One, PairingEngine, ProjectiveCurve, Zero};
+
+use super::{
+    fold, folding_polynomial, initial_transcript, inner_pairing_product, kzg_quotient, msm,
+    opening_challenge, round_challenge, scale, AggregateProof, AggregationError, Commitment,
+    ProverSRS, TippMippProof,
+};
+use crate::{ceremony::powers, Proof, SynthesisError, Vec};
+
+/// Aggregates `proofs`, which must all verify against the same verifying
+/// key, into a single proof of size logarithmic in their number. The number
+/// of proofs must be the one that `srs` was specialized to.
+///
+/// The public inputs of the proofs are bound into the aggregate proof, and
+/// must be given again to verify it.
+pub fn aggregate_proofs<E, I>(
+    srs: &ProverSRS<E>,
+    proofs: &[Proof<E>],
+    public_inputs: &[I],
+) -> Result<AggregateProof<E>, AggregationError>
+where
+    E: PairingEngine,
+    I: AsRef<[E::Fr]>,
+{
+    let n = proofs.len();
+    if n != srs.n() || public_inputs.len() != n {
+        return Err(AggregationError::InvalidProofCount);
+    }
+
+    let aggregation_time = start_timer!(|| format!("Aggregate {} proofs", n));
+    let a = proofs.iter().map(|proof| proof.a).collect::<Vec<_>>();
+    let b = proofs.iter().map(|proof| proof.b).collect::<Vec<_>>();
+    let c = proofs.iter().map(|proof| proof.c).collect::<Vec<_>>();
+    let (w_alpha, w_beta) = srs.wkey();
+
+    let commit_time = start_timer!(|| "Commit to A, B and C");
+    let com_ab = commit_double::<E>(
+        &a,
+        &b,
+        &srs.h_alpha_powers,
+        &srs.h_beta_powers,
+        w_alpha,
+        w_beta,
+    )?;
+    let com_c = commit_single::<E>(&c, &srs.h_alpha_powers, &srs.h_beta_powers)?;
+    end_timer!(commit_time);
+
+    let (mut transcript, r) = initial_transcript(&com_ab, &com_c, public_inputs);
+    let r_inverse = r.inverse().ok_or(SynthesisError::UnexpectedIdentity)?;
+    let r_powers = powers(r, E::Fr::one(), n);
+    let r_inverse_powers = powers(r_inverse, E::Fr::one(), n);
+
+    // Scaling the vectors by `r^i` and the `v` keys by `r^{-i}` leaves the
+    // commitments unchanged.
+    let inner_product_time = start_timer!(|| "Compute Z_AB and Z_C");
+    let a_r = scale(&a, &r_powers);
+    let c_r = scale(&c, &r_powers);
+    let z_ab = inner_pairing_product::<E>(&a_r, &b)?;
+    let z_c = msm(&c, &r_powers).into_affine();
+    end_timer!(inner_product_time);
+    transcript.append(&z_ab);
+    transcript.append(&z_c);
+
+    let mut tmipp = TippMippProof {
+        z_ab_l: Vec::new(),
+        z_ab_r: Vec::new(),
+        com_ab_l: Vec::new(),
+        com_ab_r: Vec::new(),
+        com_c_l: Vec::new(),
+        com_c_r: Vec::new(),
+        z_c_l: Vec::new(),
+        z_c_r: Vec::new(),
+        final_a: E::G1Affine::zero(),
+        final_b: E::G2Affine::zero(),
+        final_c: E::G1Affine::zero(),
+        final_vkey: (E::G2Affine::zero(), E::G2Affine::zero()),
+        final_wkey: (E::G1Affine::zero(), E::G1Affine::zero()),
+        vkey_opening: (E::G2Affine::zero(), E::G2Affine::zero()),
+        wkey_opening: (E::G1Affine::zero(), E::G1Affine::zero()),
+    };
+
+    let gipa_time = start_timer!(|| "Prove TIPP and MIPP");
+    let mut a = a_r;
+    let mut b = b;
+    let mut c = c_r;
+    let mut v_alpha = scale(&srs.h_alpha_powers, &r_inverse_powers);
+    let mut v_beta = scale(&srs.h_beta_powers, &r_inverse_powers);
+    let mut w_alpha = w_alpha.to_vec();
+    let mut w_beta = w_beta.to_vec();
+    // The MIPP proves `Z_C = <C, 1>`. Folding keeps every entry of the
+    // all-ones vector equal, to `ones`.
+    let mut ones = E::Fr::one();
+    let mut challenges = Vec::new();
+    let mut round = 0;
+    while a.len() > 1 {
+        let half = a.len() / 2;
+        let (a_l, a_r) = a.split_at(half);
+        let (b_l, b_r) = b.split_at(half);
+        let (c_l, c_r) = c.split_at(half);
+        let (v_alpha_l, v_alpha_r) = v_alpha.split_at(half);
+        let (v_beta_l, v_beta_r) = v_beta.split_at(half);
+        let (w_alpha_l, w_alpha_r) = w_alpha.split_at(half);
+        let (w_beta_l, w_beta_r) = w_beta.split_at(half);
+
+        tmipp.z_ab_l.push(inner_pairing_product::<E>(a_r, b_l)?);
+        tmipp.z_ab_r.push(inner_pairing_product::<E>(a_l, b_r)?);
+        tmipp.com_ab_l.push(commit_double::<E>(
+            a_r, b_l, v_alpha_l, v_beta_l, w_alpha_r, w_beta_r,
+        )?);
+        tmipp.com_ab_r.push(commit_double::<E>(
+            a_l, b_r, v_alpha_r, v_beta_r, w_alpha_l, w_beta_l,
+        )?);
+        tmipp
+            .com_c_l
+            .push(commit_single::<E>(c_r, v_alpha_l, v_beta_l)?);
+        tmipp
+            .com_c_r
+            .push(commit_single::<E>(c_l, v_alpha_r, v_beta_r)?);
+        tmipp.z_c_l.push(sum(c_r).mul(ones).into_affine());
+        tmipp.z_c_r.push(sum(c_l).mul(ones).into_affine());
+
+        let x = round_challenge(&mut transcript, &tmipp, round);
+        let x_inverse = x.inverse().ok_or(SynthesisError::UnexpectedIdentity)?;
+
+        let next_a = fold(a_l, a_r, x);
+        let next_b = fold(b_l, b_r, x_inverse);
+        let next_c = fold(c_l, c_r, x);
+        let next_v_alpha = fold(v_alpha_l, v_alpha_r, x_inverse);
+        let next_v_beta = fold(v_beta_l, v_beta_r, x_inverse);
+        let next_w_alpha = fold(w_alpha_l, w_alpha_r, x);
+        let next_w_beta = fold(w_beta_l, w_beta_r, x);
+        a = next_a;
+        b = next_b;
+        c = next_c;
+        v_alpha = next_v_alpha;
+        v_beta = next_v_beta;
+        w_alpha = next_w_alpha;
+        w_beta = next_w_beta;
+        ones *= &(E::Fr::one() + &x_inverse);
+        challenges.push(x);
+        round += 1;
+    }
+    end_timer!(gipa_time);
+
+    tmipp.final_a = a[0];
+    tmipp.final_b = b[0];
+    tmipp.final_c = c[0];
+    tmipp.final_vkey = (v_alpha[0], v_beta[0]);
+    tmipp.final_wkey = (w_alpha[0], w_beta[0]);
+    let z = opening_challenge(&mut transcript, &tmipp);
+
+    let opening_time = start_timer!(|| "Open the final commitment keys");
+    // The folded `v` keys commit to `f(X / r)`, and the folded `w` keys to
+    // `X^n * g(X)`, where `f` and `g` are the folding polynomials of the
+    // inverse challenges and of the challenges respectively.
+    let challenge_inverses = challenges
+        .iter()
+        .map(|x| x.inverse().ok_or(SynthesisError::UnexpectedIdentity))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut v_poly = folding_polynomial(&challenge_inverses);
+    for (coeff, r_inverse_power) in v_poly.iter_mut().zip(&r_inverse_powers) {
+        *coeff *= r_inverse_power;
+    }
+    let v_quotient = kzg_quotient(&v_poly, z);
+    tmipp.vkey_opening = (
+        msm(&srs.h_alpha_powers[..v_quotient.len()], &v_quotient).into_affine(),
+        msm(&srs.h_beta_powers[..v_quotient.len()], &v_quotient).into_affine(),
+    );
+
+    let mut w_poly = vec![E::Fr::zero(); n];
+    w_poly.extend(folding_polynomial(&challenges));
+    let w_quotient = kzg_quotient(&w_poly, z);
+    tmipp.wkey_opening = (
+        msm(&srs.g_alpha_powers[..w_quotient.len()], &w_quotient).into_affine(),
+        msm(&srs.g_beta_powers[..w_quotient.len()], &w_quotient).into_affine(),
+    );
+    end_timer!(opening_time);
+    end_timer!(aggregation_time);
+
+    Ok(AggregateProof {
+        com_ab,
+        com_c,
+        z_ab,
+        z_c,
+        tmipp,
+    })
+}
+
+/// Commits to `a` and `b` as `e(a, v) * e(w, b)`, under both keys.
+fn commit_double<E: PairingEngine>(
+    a: &[E::G1Affine],
+    b: &[E::G2Affine],
+    v_alpha: &[E::G2Affine],
+    v_beta: &[E::G2Affine],
+    w_alpha: &[E::G1Affine],
+    w_beta: &[E::G1Affine],
+) -> Result<Commitment<E>, SynthesisError> {
+    let g1 = |w: &[E::G1Affine]| a.iter().chain(w).cloned().collect::<Vec<_>>();
+    let g2 = |v: &[E::G2Affine]| v.iter().chain(b).cloned().collect::<Vec<_>>();
+    Ok(Commitment {
+        alpha: inner_pairing_product::<E>(&g1(w_alpha), &g2(v_alpha))?,
+        beta: inner_pairing_product::<E>(&g1(w_beta), &g2(v_beta))?,
+    })
+}
+
+/// Commits to `c` as `e(c, v)`, under both keys.
+fn commit_single<E: PairingEngine>(
+    c: &[E::G1Affine],
+    v_alpha: &[E::G2Affine],
+    v_beta: &[E::G2Affine],
+) -> Result<Commitment<E>, SynthesisError> {
+    Ok(Commitment {
+        alpha: inner_pairing_product::<E>(c, v_alpha)?,
+        beta: inner_pairing_product::<E>(c, v_beta)?,
+    })
+}
+
+fn sum<G: AffineCurve>(elements: &[G]) -> G::Projective {
+    let mut sum = G::Projective::zero();
+    for element in elements {
+        sum.add_assign_mixed(element);
+    }
+    sum
+}
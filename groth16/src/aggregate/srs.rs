@@ -0,0 +1,119 @@
+use algebra_core::{serialize::*, PairingEngine};
+
+use super::AggregationError;
+use crate::{ceremony::Accumulator, Vec};
+
+/// A structured reference string for aggregating any number of proofs up to
+/// a maximum, made of the powers of tau of two independent ceremonies. We
+/// call the secret of the first ceremony `alpha` and the secret of the
+/// second one `beta`.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct GenericSRS<E: PairingEngine> {
+    pub g_alpha_powers: Vec<E::G1Affine>,
+    pub h_alpha_powers: Vec<E::G2Affine>,
+    pub g_beta_powers: Vec<E::G1Affine>,
+    pub h_beta_powers: Vec<E::G2Affine>,
+}
+
+/// The part of the structured reference string used to aggregate exactly
+/// `n` proofs. It holds `g^{alpha^i}` and `g^{beta^i}` for `0 <= i < 2n`,
+/// and `h^{alpha^i}` and `h^{beta^i}` for `0 <= i < n`.
+///
+/// The commitment keys are `v = h^{alpha^i}` in G2 and `w = g^{alpha^{n+i}}`
+/// in G1 for `0 <= i < n`, and likewise for `beta`.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProverSRS<E: PairingEngine> {
+    pub g_alpha_powers: Vec<E::G1Affine>,
+    pub h_alpha_powers: Vec<E::G2Affine>,
+    pub g_beta_powers: Vec<E::G1Affine>,
+    pub h_beta_powers: Vec<E::G2Affine>,
+    pub vk: VerifierSRS<E>,
+}
+
+/// The part of the structured reference string needed to verify an
+/// aggregate of `n` proofs.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerifierSRS<E: PairingEngine> {
+    pub n: u64,
+    pub g: E::G1Affine,
+    pub h: E::G2Affine,
+    pub g_alpha: E::G1Affine,
+    pub g_beta: E::G1Affine,
+    pub h_alpha: E::G2Affine,
+    pub h_beta: E::G2Affine,
+}
+
+impl<E: PairingEngine> GenericSRS<E> {
+    /// Builds the reference string from the final accumulators of two
+    /// independent powers of tau ceremonies. Both accumulators must have
+    /// been verified beforehand.
+    ///
+    /// An accumulator of degree `d` supports aggregating up to `d - 1`
+    /// proofs.
+    pub fn from_accumulators(
+        alpha: &Accumulator<E>,
+        beta: &Accumulator<E>,
+    ) -> Result<Self, AggregationError> {
+        if alpha.tau_powers_g1[0] != beta.tau_powers_g1[0]
+            || alpha.tau_powers_g2[0] != beta.tau_powers_g2[0]
+        {
+            return Err(AggregationError::InconsistentSrs);
+        }
+        Ok(Self {
+            g_alpha_powers: alpha.tau_powers_g1.clone(),
+            h_alpha_powers: alpha.tau_powers_g2.clone(),
+            g_beta_powers: beta.tau_powers_g1.clone(),
+            h_beta_powers: beta.tau_powers_g2.clone(),
+        })
+    }
+
+    /// Returns the keys used to aggregate and verify exactly `num_proofs`
+    /// proofs, which must be a power of two.
+    pub fn specialize(
+        &self,
+        num_proofs: usize,
+    ) -> Result<(ProverSRS<E>, VerifierSRS<E>), AggregationError> {
+        if num_proofs < 2 || !num_proofs.is_power_of_two() {
+            return Err(AggregationError::InvalidProofCount);
+        }
+        let g_len = 2 * num_proofs;
+        if self.g_alpha_powers.len() < g_len
+            || self.g_beta_powers.len() < g_len
+            || self.h_alpha_powers.len() < num_proofs
+            || self.h_beta_powers.len() < num_proofs
+        {
+            return Err(AggregationError::SrsTooSmall);
+        }
+
+        let vk = VerifierSRS {
+            n: num_proofs as u64,
+            g: self.g_alpha_powers[0],
+            h: self.h_alpha_powers[0],
+            g_alpha: self.g_alpha_powers[1],
+            g_beta: self.g_beta_powers[1],
+            h_alpha: self.h_alpha_powers[1],
+            h_beta: self.h_beta_powers[1],
+        };
+        let pk = ProverSRS {
+            g_alpha_powers: self.g_alpha_powers[..g_len].to_vec(),
+            h_alpha_powers: self.h_alpha_powers[..num_proofs].to_vec(),
+            g_beta_powers: self.g_beta_powers[..g_len].to_vec(),
+            h_beta_powers: self.h_beta_powers[..num_proofs].to_vec(),
+            vk: vk.clone(),
+        };
+        Ok((pk, vk))
+    }
+}
+
+impl<E: PairingEngine> ProverSRS<E> {
+    /// The number of proofs that this key aggregates.
+    pub fn n(&self) -> usize {
+        self.h_alpha_powers.len()
+    }
+
+    /// The `w` commitment keys, for `alpha` and `beta`.
+    pub(crate) fn wkey(&self) -> (&[E::G1Affine], &[E::G1Affine]) {
+        let n = self.n();
+        (&self.g_alpha_powers[n..], &self.g_beta_powers[n..])
+    }
+}
@@ -0,0 +1,208 @@
+use algebra_core::{AffineCurve, Field, One, PairingEngine, PrimeField, ProjectiveCurve, Zero};
+use core::ops::Neg;
+
+use super::{
+    evaluate_folding_polynomial, initial_transcript, inner_pairing_product, msm, opening_challenge,
+    round_challenge, AggregateProof, AggregationError, VerifierSRS,
+};
+use crate::{ceremony::powers, PreparedVerifyingKey, SynthesisError, Vec};
+
+/// Verifies that `proof` aggregates valid proofs for the statements
+/// `public_inputs` under the verifying key `pvk`.
+///
+/// This costs a number of pairings and target group exponentiations
+/// logarithmic in the number of proofs, plus a multi-scalar multiplication
+/// over the public inputs.
+pub fn verify_aggregate_proof<E, I>(
+    srs: &VerifierSRS<E>,
+    pvk: &PreparedVerifyingKey<E>,
+    public_inputs: &[I],
+    proof: &AggregateProof<E>,
+) -> Result<bool, AggregationError>
+where
+    E: PairingEngine,
+    I: AsRef<[E::Fr]>,
+{
+    let n = srs.n as usize;
+    if public_inputs.len() != n {
+        return Err(AggregationError::InvalidProofCount);
+    }
+    for inputs in public_inputs {
+        if (inputs.as_ref().len() + 1) != pvk.gamma_abc_g1.len() {
+            return Err(SynthesisError::MalformedVerifyingKey.into());
+        }
+    }
+    let tmipp = &proof.tmipp;
+    let rounds = n.trailing_zeros() as usize;
+    if tmipp.z_ab_l.len() != rounds
+        || tmipp.z_ab_r.len() != rounds
+        || tmipp.com_ab_l.len() != rounds
+        || tmipp.com_ab_r.len() != rounds
+        || tmipp.com_c_l.len() != rounds
+        || tmipp.com_c_r.len() != rounds
+        || tmipp.z_c_l.len() != rounds
+        || tmipp.z_c_r.len() != rounds
+    {
+        return Ok(false);
+    }
+
+    let verification_time = start_timer!(|| format!("Verify aggregate of {} proofs", n));
+    let (mut transcript, r) = initial_transcript(&proof.com_ab, &proof.com_c, public_inputs);
+    let r_inverse = r.inverse().ok_or(SynthesisError::UnexpectedIdentity)?;
+    transcript.append(&proof.z_ab);
+    transcript.append(&proof.z_c);
+
+    // Replay the halving rounds on the commitments and inner products.
+    let gipa_time = start_timer!(|| "Fold commitments and inner products");
+    let mut com_ab = proof.com_ab.clone();
+    let mut com_c = proof.com_c.clone();
+    let mut z_ab = proof.z_ab;
+    let mut z_c = proof.z_c.into_projective();
+    let mut ones = E::Fr::one();
+    let mut challenges = Vec::with_capacity(rounds);
+    for round in 0..rounds {
+        let x = round_challenge(&mut transcript, tmipp, round);
+        let x_inverse = x.inverse().ok_or(SynthesisError::UnexpectedIdentity)?;
+        let (x_repr, x_inverse_repr) = (x.into_repr(), x_inverse.into_repr());
+        let fold = |value: &mut E::Fqk, left: &E::Fqk, right: &E::Fqk| {
+            *value *= &left.pow(x_repr);
+            *value *= &right.pow(x_inverse_repr);
+        };
+
+        fold(&mut z_ab, &tmipp.z_ab_l[round], &tmipp.z_ab_r[round]);
+        fold(
+            &mut com_ab.alpha,
+            &tmipp.com_ab_l[round].alpha,
+            &tmipp.com_ab_r[round].alpha,
+        );
+        fold(
+            &mut com_ab.beta,
+            &tmipp.com_ab_l[round].beta,
+            &tmipp.com_ab_r[round].beta,
+        );
+        fold(
+            &mut com_c.alpha,
+            &tmipp.com_c_l[round].alpha,
+            &tmipp.com_c_r[round].alpha,
+        );
+        fold(
+            &mut com_c.beta,
+            &tmipp.com_c_l[round].beta,
+            &tmipp.com_c_r[round].beta,
+        );
+        z_c += &tmipp.z_c_l[round].mul(x);
+        z_c += &tmipp.z_c_r[round].mul(x_inverse);
+        ones *= &(E::Fr::one() + &x_inverse);
+        challenges.push(x);
+    }
+    end_timer!(gipa_time);
+
+    // Check the final folded values against each other.
+    let final_time = start_timer!(|| "Check the final folded values");
+    let (final_a, final_b, final_c) = (tmipp.final_a, tmipp.final_b, tmipp.final_c);
+    let (v_alpha, v_beta) = tmipp.final_vkey;
+    let (w_alpha, w_beta) = tmipp.final_wkey;
+    let folded_ok = inner_pairing_product::<E>(&[final_a, w_alpha], &[v_alpha, final_b])?
+        == com_ab.alpha
+        && inner_pairing_product::<E>(&[final_a, w_beta], &[v_beta, final_b])? == com_ab.beta
+        && inner_pairing_product::<E>(&[final_a], &[final_b])? == z_ab
+        && inner_pairing_product::<E>(&[final_c], &[v_alpha])? == com_c.alpha
+        && inner_pairing_product::<E>(&[final_c], &[v_beta])? == com_c.beta
+        && final_c.mul(ones) == z_c;
+    end_timer!(final_time);
+    if !folded_ok {
+        end_timer!(verification_time);
+        return Ok(false);
+    }
+
+    // Check that the final keys were folded from the reference string.
+    let kzg_time = start_timer!(|| "Check the KZG openings of the final keys");
+    let z = opening_challenge(&mut transcript, tmipp);
+    let challenge_inverses = challenges
+        .iter()
+        .map(|x| x.inverse().ok_or(SynthesisError::UnexpectedIdentity))
+        .collect::<Result<Vec<_>, _>>()?;
+    let v_eval = evaluate_folding_polynomial(&challenge_inverses, z * &r_inverse);
+    let w_eval = z.pow([n as u64]) * &evaluate_folding_polynomial(&challenges, z);
+    let kzg_ok = check_g2_opening(srs, srs.g_alpha, v_alpha, tmipp.vkey_opening.0, z, v_eval)
+        && check_g2_opening(srs, srs.g_beta, v_beta, tmipp.vkey_opening.1, z, v_eval)
+        && check_g1_opening(srs, srs.h_alpha, w_alpha, tmipp.wkey_opening.0, z, w_eval)
+        && check_g1_opening(srs, srs.h_beta, w_beta, tmipp.wkey_opening.1, z, w_eval);
+    end_timer!(kzg_time);
+    if !kzg_ok {
+        end_timer!(verification_time);
+        return Ok(false);
+    }
+
+    // Finally, check the random linear combination of the Groth16
+    // verification equations.
+    let groth16_time = start_timer!(|| "Check the Groth16 equation");
+    let r_powers = powers(r, E::Fr::one(), n);
+    let r_sum: E::Fr = r_powers.iter().sum();
+    let mut input_scalars = vec![E::Fr::zero(); pvk.gamma_abc_g1.len()];
+    input_scalars[0] = r_sum;
+    for (r, inputs) in r_powers.iter().zip(public_inputs) {
+        for (acc, input) in input_scalars[1..].iter_mut().zip(inputs.as_ref()) {
+            *acc += &(*r * input);
+        }
+    }
+    let g_ic = msm(&pvk.gamma_abc_g1, &input_scalars);
+
+    let qap = E::miller_loop(
+        [
+            (g_ic.into_affine().into(), pvk.gamma_g2_neg_pc.clone()),
+            (proof.z_c.into(), pvk.delta_g2_neg_pc.clone()),
+        ]
+        .iter(),
+    );
+    let test = E::final_exponentiation(&qap).ok_or(SynthesisError::UnexpectedIdentity)?;
+    let groth16_ok = test * &proof.z_ab == pvk.alpha_g1_beta_g2.pow(r_sum.into_repr());
+    end_timer!(groth16_time);
+    end_timer!(verification_time);
+
+    Ok(groth16_ok)
+}
+
+/// Checks that `opening` proves that the G2 commitment `commitment` opens to
+/// `eval` at `point`, for the secret `s` with `s_g1 = g^s`:
+///     e(g^s / g^point, opening) = e(g, commitment / h^eval).
+fn check_g2_opening<E: PairingEngine>(
+    srs: &VerifierSRS<E>,
+    s_g1: E::G1Affine,
+    commitment: E::G2Affine,
+    opening: E::G2Affine,
+    point: E::Fr,
+    eval: E::Fr,
+) -> bool {
+    let mut shifted_s = s_g1.into_projective();
+    shifted_s -= &srs.g.mul(point);
+    let mut shifted_commitment = commitment.into_projective();
+    shifted_commitment -= &srs.h.mul(eval);
+    E::product_of_pairings(&[
+        (shifted_s.into_affine().into(), opening.into()),
+        (srs.g.neg().into(), shifted_commitment.into_affine().into()),
+    ])
+    .is_one()
+}
+
+/// Checks that `opening` proves that the G1 commitment `commitment` opens to
+/// `eval` at `point`, for the secret `s` with `s_g2 = h^s`:
+///     e(opening, h^s / h^point) = e(commitment / g^eval, h).
+fn check_g1_opening<E: PairingEngine>(
+    srs: &VerifierSRS<E>,
+    s_g2: E::G2Affine,
+    commitment: E::G1Affine,
+    opening: E::G1Affine,
+    point: E::Fr,
+    eval: E::Fr,
+) -> bool {
+    let mut shifted_s = s_g2.into_projective();
+    shifted_s -= &srs.h.mul(point);
+    let mut shifted_commitment = srs.g.mul(eval);
+    shifted_commitment -= &commitment.into_projective();
+    E::product_of_pairings(&[
+        (opening.into(), shifted_s.into_affine().into()),
+        (shifted_commitment.into_affine().into(), srs.h.into()),
+    ])
+    .is_one()
+}
@@ -0,0 +1,247 @@
+//! The ceremony runs in two phases. The first phase is a universal
+//! [`powers_of_tau`] ceremony, which produces an [`Accumulator`] holding
+//! powers of a secret `tau` together with `alpha` and `beta` multiples of
+//! them. The accumulator can be used for any circuit up to its degree.
+//! The second phase, implemented in [`phase2`], specializes an accumulator to
+//! a particular circuit and lets participants sequentially randomize `delta`.
+//!
+//! Each contribution comes with a [`PublicKey`] that proves knowledge of the
+//! contributed randomness, and every transformation is checked with pairing
+//! equations. The resulting parameters are secure as long as at least one
+//! participant in each phase was honest and destroyed their randomness.
+//!
+//! [`Accumulator`]: powers_of_tau::Accumulator
+//! [`PublicKey`]: powers_of_tau::PublicKey
+
+use algebra_core::{
+    io, msm::VariableBaseMSM, AffineCurve, CanonicalSerialize, One, PairingEngine, PrimeField,
+    ProjectiveCurve, SerializationError, UniformRand, Zero,
+};
+use blake2::{Blake2b, Digest};
+use core::{fmt, ops::Neg};
+use r1cs_core::SynthesisError;
+use rand::Rng;
+
+use crate::Vec;
+
+/// The universal first phase of the ceremony.
+pub mod powers_of_tau;
+
+/// The circuit-specific second phase of the ceremony.
+pub mod phase2;
+
+pub use self::{
+    phase2::MPCParameters,
+    powers_of_tau::{Accumulator, Transcript, MAX_DEGREE},
+};
+
+/// The BLAKE2b digest identifying an accumulator, a circuit or a contribution.
+pub type Digest64 = [u8; 64];
+
+/// This is an error that could occur while running or verifying a ceremony.
+#[derive(Debug)]
+pub enum CeremonyError {
+    /// The accumulator does not hold enough powers of tau for the circuit.
+    AccumulatorTooSmall,
+    /// A proof of knowledge of the contributed randomness did not verify.
+    InvalidProofOfKnowledge,
+    /// The accumulator was not updated by the contributed randomness.
+    InvalidAccumulatorUpdate,
+    /// The accumulator does not hold consistent powers of tau.
+    InconsistentPowers,
+    /// The degree of a transcript is out of range, or does not match its
+    /// accumulators.
+    InvalidDegree,
+    /// The ceremony has no contributions, so its secrets are publicly known.
+    NoContributions,
+    /// The `delta` elements do not match the chain of contributions.
+    InvalidDelta,
+    /// Elements that a contribution must leave untouched were modified.
+    ParametersMismatch,
+    /// The circuit could not be synthesized.
+    Synthesis(SynthesisError),
+    /// A transcript could not be read or written.
+    Serialization(SerializationError),
+}
+
+impl From<SynthesisError> for CeremonyError {
+    fn from(e: SynthesisError) -> CeremonyError {
+        CeremonyError::Synthesis(e)
+    }
+}
+
+impl From<SerializationError> for CeremonyError {
+    fn from(e: SerializationError) -> CeremonyError {
+        CeremonyError::Serialization(e)
+    }
+}
+
+impl From<io::Error> for CeremonyError {
+    fn from(e: io::Error) -> CeremonyError {
+        CeremonyError::Serialization(SerializationError::IoError(e))
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CeremonyError {}
+
+impl fmt::Display for CeremonyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            CeremonyError::AccumulatorTooSmall => {
+                write!(f, "the accumulator is too small for the circuit")
+            },
+            CeremonyError::InvalidProofOfKnowledge => {
+                write!(f, "invalid proof of knowledge of the contribution")
+            },
+            CeremonyError::InvalidAccumulatorUpdate => {
+                write!(f, "the accumulator was not updated by the contribution")
+            },
+            CeremonyError::InconsistentPowers => {
+                write!(f, "the accumulator holds inconsistent powers of tau")
+            },
+            CeremonyError::InvalidDegree => write!(f, "the transcript has an invalid degree"),
+            CeremonyError::NoContributions => write!(f, "the ceremony has no contributions"),
+            CeremonyError::InvalidDelta => write!(f, "delta does not match the contributions"),
+            CeremonyError::ParametersMismatch => {
+                write!(f, "parameters were modified outside of delta")
+            },
+            CeremonyError::Synthesis(err) => write!(f, "synthesis error: {}", err),
+            CeremonyError::Serialization(err) => write!(f, "serialization error: {}", err),
+        }
+    }
+}
+
+/// Hashes the concatenation of `chunks` with BLAKE2b.
+pub(crate) fn hash(chunks: &[&[u8]]) -> Digest64 {
+    let mut hasher = Blake2b::default();
+    for chunk in chunks {
+        hasher.input(chunk);
+    }
+    let mut digest = [0u8; 64];
+    digest.copy_from_slice(hasher.result().as_slice());
+    digest
+}
+
+/// Maps a digest to a G2 element of unknown discrete logarithm, by
+/// try-and-increment: the x-coordinate is read from the hash of the digest
+/// and a counter, which is incremented until it gives a point of the curve,
+/// and the point is then multiplied by the cofactor to land in the prime
+/// order subgroup.
+///
+/// Sampling a scalar and multiplying the generator by it would not do, since
+/// anyone could recompute the scalar from the digest.
+pub(crate) fn hash_to_g2<E: PairingEngine>(digest: &Digest64) -> E::G2Affine {
+    let len = E::G2Affine::zero().serialized_size();
+    let mut counter = 0u64;
+    loop {
+        let mut bytes = Vec::with_capacity(len + 64);
+        let mut block = 0u8;
+        while bytes.len() < len {
+            bytes.extend_from_slice(&hash(&[&digest[..], &counter.to_le_bytes(), &[block]]));
+            block += 1;
+        }
+        bytes.truncate(len);
+
+        if let Some(point) = E::G2Affine::from_random_bytes(&bytes) {
+            let point = point.mul_by_cofactor();
+            if !point.is_zero() {
+                return point;
+            }
+        }
+        counter += 1;
+    }
+}
+
+/// Checks that `g1.1 / g1.0 == g2.1 / g2.0` in the exponent, i.e. that
+/// `e(g1.0, g2.1) == e(g1.1, g2.0)`.
+pub(crate) fn same_ratio<E: PairingEngine>(
+    g1: (E::G1Affine, E::G1Affine),
+    g2: (E::G2Affine, E::G2Affine),
+) -> bool {
+    if g1.0.is_zero() || g1.1.is_zero() || g2.0.is_zero() || g2.1.is_zero() {
+        return false;
+    }
+    E::product_of_pairings(&[(g1.0.into(), g2.1.into()), (g1.1.neg().into(), g2.0.into())]).is_one()
+}
+
+/// Takes the same random linear combination of `v1` and `v2`, so that
+/// checking the ratio of the two results checks the ratio of every pair
+/// `(v1[i], v2[i])` at once.
+pub(crate) fn merge_pairs<G: AffineCurve, R: Rng>(v1: &[G], v2: &[G], rng: &mut R) -> (G, G) {
+    assert_eq!(v1.len(), v2.len());
+    let scalars = (0..v1.len())
+        .map(|_| G::ScalarField::from(rng.gen::<u128>()).into_repr())
+        .collect::<Vec<_>>();
+    (
+        VariableBaseMSM::multi_scalar_mul(v1, &scalars).into_affine(),
+        VariableBaseMSM::multi_scalar_mul(v2, &scalars).into_affine(),
+    )
+}
+
+/// Returns a pair whose ratio is the common ratio of consecutive elements of
+/// `v`, when it exists.
+pub(crate) fn power_pairs<G: AffineCurve, R: Rng>(v: &[G], rng: &mut R) -> (G, G) {
+    merge_pairs(&v[..(v.len() - 1)], &v[1..], rng)
+}
+
+/// Multiplies every element of `bases` by the corresponding scalar.
+pub(crate) fn batch_mul<G: AffineCurve>(bases: &mut [G], scalars: &[G::ScalarField]) {
+    let products = bases
+        .iter()
+        .zip(scalars)
+        .map(|(base, scalar)| base.mul(*scalar))
+        .collect::<Vec<_>>();
+    bases.copy_from_slice(&G::Projective::batch_normalization_into_affine(&products));
+}
+
+/// Returns `[coeff, coeff * x, coeff * x^2, ..., coeff * x^{n-1}]`.
+pub(crate) fn powers<F: PrimeField>(x: F, coeff: F, n: usize) -> Vec<F> {
+    let mut powers = Vec::with_capacity(n);
+    let mut cur = coeff;
+    for _ in 0..n {
+        powers.push(cur);
+        cur *= &x;
+    }
+    powers
+}
+
+/// Serializes `value` uncompressed, for hashing.
+pub(crate) fn to_bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(value.uncompressed_size());
+    value
+        .serialize_uncompressed(&mut bytes)
+        .expect("serializing into a vector never fails");
+    bytes
+}
+
+/// A knowledge-of-exponent proof for a contributed scalar `x`: a random
+/// `s` in G1, `s * x`, and `r * x`, where `r` in G2 is derived by hashing the
+/// transcript so far together with `s` and `s * x`.
+pub(crate) fn prove_knowledge<E: PairingEngine, R: Rng>(
+    x: E::Fr,
+    transcript: &Digest64,
+    personalization: u8,
+    rng: &mut R,
+) -> (E::G1Affine, E::G1Affine, E::G2Affine) {
+    let s = E::G1Projective::rand(rng).into_affine();
+    let s_x = s.mul(x).into_affine();
+    let r = knowledge_base::<E>(transcript, personalization, s, s_x);
+    (s, s_x, r.mul(x).into_affine())
+}
+
+/// Recomputes the G2 base `r` of a knowledge-of-exponent proof.
+pub(crate) fn knowledge_base<E: PairingEngine>(
+    transcript: &Digest64,
+    personalization: u8,
+    s: E::G1Affine,
+    s_x: E::G1Affine,
+) -> E::G2Affine {
+    let digest = hash(&[
+        &[personalization],
+        &transcript[..],
+        &to_bytes(&s),
+        &to_bytes(&s_x),
+    ]);
+    hash_to_g2::<E>(&digest)
+}
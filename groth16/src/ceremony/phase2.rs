@@ -0,0 +1,344 @@
+use algebra_core::{
+    io, serialize::*, AffineCurve, Field, One, PairingEngine, ProjectiveCurve, UniformRand, Zero,
+};
+use ff_fft::{EvaluationDomain, GeneralEvaluationDomain};
+use r1cs_core::{ConstraintSynthesizer, ConstraintSystem, Index, SynthesisError};
+use rand::Rng;
+
+use super::{
+    batch_mul, hash, knowledge_base, merge_pairs, prove_knowledge, same_ratio, to_bytes,
+    Accumulator, CeremonyError, Digest64,
+};
+use crate::{
+    circuit_digest, generator::KeypairAssembly, read_versioned, write_versioned, Parameters, Vec,
+    VerifyingKey,
+};
+
+const DELTA_PERSONALIZATION: u8 = 3;
+
+/// The public part of a phase 2 contribution: the new `delta * G1`, and a
+/// proof of knowledge `(s, s * delta', r * delta')` of the contributed
+/// `delta'`, where `r` is derived from the transcript so far.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PublicKey<E: PairingEngine> {
+    pub delta_after: E::G1Affine,
+    pub s: E::G1Affine,
+    pub s_delta: E::G1Affine,
+    pub r_delta: E::G2Affine,
+}
+
+/// Groth16 parameters under construction in the circuit-specific phase of the
+/// ceremony, together with the record of every contribution so far.
+///
+/// The parameters are initialized from a powers of tau [`Accumulator`] with
+/// `gamma = delta = 1`, and each contribution multiplies `delta` by fresh
+/// randomness.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MPCParameters<E: PairingEngine> {
+    pub params: Parameters<E>,
+    /// The digest of the initial parameters, which commits to the circuit and
+    /// to the accumulator.
+    pub cs_hash: Vec<u8>,
+    pub contributions: Vec<PublicKey<E>>,
+}
+
+impl<E: PairingEngine> MPCParameters<E> {
+    /// Specializes `accumulator` to `circuit`.
+    pub fn new<C: ConstraintSynthesizer<E::Fr>>(
+        circuit: C,
+        accumulator: &Accumulator<E>,
+    ) -> Result<Self, CeremonyError> {
+        let params = initial_parameters(circuit, accumulator)?;
+        let cs_hash = hash(&[&to_bytes(&params)]).to_vec();
+        Ok(Self {
+            params,
+            cs_hash,
+            contributions: Vec::new(),
+        })
+    }
+
+    /// The parameters produced by the ceremony so far.
+    pub fn get_params(&self) -> &Parameters<E> {
+        &self.params
+    }
+
+    /// Multiplies `delta` by fresh randomness, and returns the digest of the
+    /// contribution, which the contributor can later look for in the output of
+    /// [`MPCParameters::verify`]. The randomness is dropped as soon as this
+    /// returns.
+    pub fn contribute<R: Rng>(&mut self, rng: &mut R) -> Result<Digest64, CeremonyError> {
+        let contribution_time = start_timer!(|| "Phase 2 contribution");
+        let delta = E::Fr::rand(rng);
+        let delta_inverse = delta.inverse().ok_or(SynthesisError::UnexpectedIdentity)?;
+
+        let transcript = self.transcript(self.contributions.len());
+        let (s, s_delta, r_delta) =
+            prove_knowledge::<E, _>(delta, &transcript, DELTA_PERSONALIZATION, rng);
+
+        let params = &mut self.params;
+        params.delta_g1 = params.delta_g1.mul(delta).into_affine();
+        params.vk.delta_g2 = params.vk.delta_g2.mul(delta).into_affine();
+        let h_scalars = vec![delta_inverse; params.h_query.len()];
+        batch_mul(&mut params.h_query, &h_scalars);
+        let l_scalars = vec![delta_inverse; params.l_query.len()];
+        batch_mul(&mut params.l_query, &l_scalars);
+
+        let key = PublicKey {
+            delta_after: params.delta_g1,
+            s,
+            s_delta,
+            r_delta,
+        };
+        let digest = hash(&[&to_bytes(&key)]);
+        self.contributions.push(key);
+        end_timer!(contribution_time);
+
+        Ok(digest)
+    }
+
+    /// Checks that the parameters were derived from `circuit` and
+    /// `accumulator` by the recorded chain of contributions, and returns the
+    /// digests of the contributions in order. Parameters without
+    /// contributions are rejected, since their `delta` is one.
+    pub fn verify<C: ConstraintSynthesizer<E::Fr>, R: Rng>(
+        &self,
+        circuit: C,
+        accumulator: &Accumulator<E>,
+        rng: &mut R,
+    ) -> Result<Vec<Digest64>, CeremonyError> {
+        if self.contributions.is_empty() {
+            return Err(CeremonyError::NoContributions);
+        }
+
+        let verification_time = start_timer!(|| "Verify phase 2 contributions");
+        let initial = initial_parameters(circuit, accumulator)?;
+        if self.cs_hash[..] != hash(&[&to_bytes(&initial)])[..] {
+            return Err(CeremonyError::ParametersMismatch);
+        }
+
+        let params = &self.params;
+        if params.vk.alpha_g1 != initial.vk.alpha_g1
+            || params.vk.beta_g2 != initial.vk.beta_g2
+            || params.vk.gamma_g2 != initial.vk.gamma_g2
+            || params.vk.gamma_abc_g1 != initial.vk.gamma_abc_g1
+            || params.beta_g1 != initial.beta_g1
+            || params.a_query != initial.a_query
+            || params.b_g1_query != initial.b_g1_query
+            || params.b_g2_query != initial.b_g2_query
+            || params.h_query.len() != initial.h_query.len()
+            || params.l_query.len() != initial.l_query.len()
+        {
+            return Err(CeremonyError::ParametersMismatch);
+        }
+
+        // Walk the chain of contributions, checking that each one multiplied
+        // `delta` by the value it proves knowledge of.
+        let mut digests = Vec::with_capacity(self.contributions.len());
+        let mut delta = initial.delta_g1;
+        for (i, key) in self.contributions.iter().enumerate() {
+            let transcript = self.transcript(i);
+            let r = knowledge_base::<E>(&transcript, DELTA_PERSONALIZATION, key.s, key.s_delta);
+            if !same_ratio::<E>((key.s, key.s_delta), (r, key.r_delta)) {
+                return Err(CeremonyError::InvalidProofOfKnowledge);
+            }
+            if !same_ratio::<E>((delta, key.delta_after), (r, key.r_delta)) {
+                return Err(CeremonyError::InvalidDelta);
+            }
+            delta = key.delta_after;
+            digests.push(hash(&[&to_bytes(key)]));
+        }
+
+        // Check that `delta` was applied consistently to the parameters.
+        let g1 = E::G1Affine::prime_subgroup_generator();
+        let g2 = E::G2Affine::prime_subgroup_generator();
+        if params.delta_g1 != delta
+            || !same_ratio::<E>((g1, params.delta_g1), (g2, params.vk.delta_g2))
+        {
+            return Err(CeremonyError::InvalidDelta);
+        }
+        if !params.h_query.is_empty()
+            && !same_ratio::<E>(
+                merge_pairs(&params.h_query, &initial.h_query, rng),
+                (g2, params.vk.delta_g2),
+            )
+        {
+            return Err(CeremonyError::InvalidDelta);
+        }
+        if !params.l_query.is_empty()
+            && !same_ratio::<E>(
+                merge_pairs(&params.l_query, &initial.l_query, rng),
+                (g2, params.vk.delta_g2),
+            )
+        {
+            return Err(CeremonyError::InvalidDelta);
+        }
+        end_timer!(verification_time);
+
+        Ok(digests)
+    }
+
+    /// Serialize the ceremony state to bytes. Group elements are written in
+    /// compressed form.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_versioned(self, &mut writer, true)
+    }
+
+    /// Serialize the ceremony state to bytes, writing group elements in
+    /// uncompressed form.
+    pub fn write_uncompressed<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_versioned(self, &mut writer, false)
+    }
+
+    /// Deserialize the ceremony state from bytes, checking that every group
+    /// element is on the curve and in the prime order subgroup.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        read_versioned(&mut reader, true)
+    }
+
+    /// The transcript that the `i`-th contribution is bound to: the circuit
+    /// hash followed by all previous contributions.
+    fn transcript(&self, i: usize) -> Digest64 {
+        let mut bytes = self.cs_hash.clone();
+        for key in &self.contributions[..i] {
+            bytes.extend_from_slice(&to_bytes(key));
+        }
+        hash(&[&bytes])
+    }
+}
+
+/// Computes the parameters for `circuit` with `gamma = delta = 1`, by
+/// evaluating the QAP polynomials at `tau` in the exponent. The Lagrange basis
+/// is obtained by an inverse FFT of the powers of tau in the accumulator.
+fn initial_parameters<E, C>(
+    circuit: C,
+    accumulator: &Accumulator<E>,
+) -> Result<Parameters<E>, CeremonyError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+{
+    let mut assembly = KeypairAssembly::<E> {
+        num_inputs: 0,
+        num_aux: 0,
+        num_constraints: 0,
+        at: vec![],
+        bt: vec![],
+        ct: vec![],
+    };
+
+    // Allocate the "one" input variable
+    assembly.alloc_input(|| "", || Ok(E::Fr::one()))?;
+
+    // Synthesize the circuit.
+    let synthesis_time = start_timer!(|| "Constraint synthesis");
+    circuit.generate_constraints(&mut assembly)?;
+    end_timer!(synthesis_time);
+
+    let domain_size = assembly.num_constraints + (assembly.num_inputs - 1) + 1;
+    let domain = GeneralEvaluationDomain::<E::Fr>::new(domain_size)
+        .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+    let m = domain.size();
+    if accumulator.degree() < m {
+        return Err(CeremonyError::AccumulatorTooSmall);
+    }
+
+    let lagrange_time = start_timer!(|| "Compute Lagrange basis in the exponent");
+    let lagrange = |powers: &[E::G1Affine]| {
+        let mut evals = powers[..m]
+            .iter()
+            .map(|p| p.into_projective())
+            .collect::<Vec<_>>();
+        domain.ifft_in_place(&mut evals);
+        evals
+    };
+    let tau_lagrange_g1 = lagrange(&accumulator.tau_powers_g1);
+    let alpha_lagrange_g1 = lagrange(&accumulator.alpha_tau_powers_g1);
+    let beta_lagrange_g1 = lagrange(&accumulator.beta_tau_powers_g1);
+    let mut tau_lagrange_g2 = accumulator.tau_powers_g2[..m]
+        .iter()
+        .map(|p| p.into_projective())
+        .collect::<Vec<_>>();
+    domain.ifft_in_place(&mut tau_lagrange_g2);
+    end_timer!(lagrange_time);
+
+    let evaluation_time = start_timer!(|| "Evaluate QAP polynomials in the exponent");
+    let num_inputs = assembly.num_inputs;
+    let num_variables = assembly.num_inputs + assembly.num_aux;
+    let mut a_g1 = vec![E::G1Projective::zero(); num_variables];
+    let mut b_g1 = vec![E::G1Projective::zero(); num_variables];
+    let mut b_g2 = vec![E::G2Projective::zero(); num_variables];
+    // beta * a_i(tau) + alpha * b_i(tau) + c_i(tau)
+    let mut ext = vec![E::G1Projective::zero(); num_variables];
+
+    let index = |index: Index| match index {
+        Index::Input(i) => i,
+        Index::Aux(i) => num_inputs + i,
+    };
+    // Each input is also constrained by `input * 0 = 0`, which keeps the
+    // input polynomials linearly independent.
+    for i in 0..num_inputs {
+        a_g1[i] += &tau_lagrange_g1[assembly.num_constraints + i];
+        ext[i] += &beta_lagrange_g1[assembly.num_constraints + i];
+    }
+    for j in 0..assembly.num_constraints {
+        for &(coeff, var) in &assembly.at[j] {
+            let var = index(var);
+            a_g1[var] += &mul_by_coeff(tau_lagrange_g1[j], coeff);
+            ext[var] += &mul_by_coeff(beta_lagrange_g1[j], coeff);
+        }
+        for &(coeff, var) in &assembly.bt[j] {
+            let var = index(var);
+            b_g1[var] += &mul_by_coeff(tau_lagrange_g1[j], coeff);
+            b_g2[var] += &mul_by_coeff(tau_lagrange_g2[j], coeff);
+            ext[var] += &mul_by_coeff(alpha_lagrange_g1[j], coeff);
+        }
+        for &(coeff, var) in &assembly.ct[j] {
+            ext[index(var)] += &mul_by_coeff(tau_lagrange_g1[j], coeff);
+        }
+    }
+
+    // t(tau) * tau^i = tau^{m + i} - tau^i, since t(X) = X^m - 1.
+    let h_g1 = (0..(m - 1))
+        .map(|i| {
+            accumulator.tau_powers_g1[m + i].into_projective()
+                - accumulator.tau_powers_g1[i].into_projective()
+        })
+        .collect::<Vec<_>>();
+    end_timer!(evaluation_time);
+
+    let ext = E::G1Projective::batch_normalization_into_affine(&ext);
+    let g1 = E::G1Affine::prime_subgroup_generator();
+    let g2 = E::G2Affine::prime_subgroup_generator();
+
+    Ok(Parameters {
+        vk: VerifyingKey {
+            alpha_g1: accumulator.alpha_tau_powers_g1[0],
+            beta_g2: accumulator.beta_g2,
+            gamma_g2: g2,
+            delta_g2: g2,
+            gamma_abc_g1: ext[..num_inputs].to_vec(),
+        },
+        beta_g1: accumulator.beta_tau_powers_g1[0],
+        delta_g1: g1,
+        a_query: E::G1Projective::batch_normalization_into_affine(&a_g1),
+        b_g1_query: E::G1Projective::batch_normalization_into_affine(&b_g1),
+        b_g2_query: E::G2Projective::batch_normalization_into_affine(&b_g2),
+        h_query: E::G1Projective::batch_normalization_into_affine(&h_g1),
+        l_query: ext[num_inputs..].to_vec(),
+        circuit_digest: circuit_digest(
+            num_inputs,
+            assembly.num_aux,
+            &assembly.at,
+            &assembly.bt,
+            &assembly.ct,
+        ),
+    })
+}
+
+fn mul_by_coeff<G: ProjectiveCurve>(base: G, coeff: G::ScalarField) -> G {
+    if coeff.is_one() {
+        base
+    } else {
+        base.mul(coeff)
+    }
+}
@@ -0,0 +1,303 @@
+use algebra_core::{
+    io, serialize::*, AffineCurve, One, PairingEngine, ProjectiveCurve, UniformRand, Zero,
+};
+use rand::Rng;
+
+use super::{
+    batch_mul, hash, knowledge_base, power_pairs, powers, prove_knowledge, same_ratio, to_bytes,
+    CeremonyError, Digest64,
+};
+use crate::{read_versioned, write_versioned, Vec};
+
+const TAU_PERSONALIZATION: u8 = 0;
+const ALPHA_PERSONALIZATION: u8 = 1;
+const BETA_PERSONALIZATION: u8 = 2;
+
+/// The largest degree of a transcript. It bounds the memory allocated for
+/// the initial accumulator of a transcript read from an untrusted source.
+pub const MAX_DEGREE: usize = 1 << 28;
+
+/// The state of a powers of tau ceremony of degree `n`. It holds
+/// `tau^i * G1` for `0 <= i < 2n - 1`, and `tau^i * G2`, `alpha * tau^i * G1`
+/// and `beta * tau^i * G1` for `0 <= i < n`, together with `beta * G2`.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Accumulator<E: PairingEngine> {
+    pub tau_powers_g1: Vec<E::G1Affine>,
+    pub tau_powers_g2: Vec<E::G2Affine>,
+    pub alpha_tau_powers_g1: Vec<E::G1Affine>,
+    pub beta_tau_powers_g1: Vec<E::G1Affine>,
+    pub beta_g2: E::G2Affine,
+}
+
+impl<E: PairingEngine> Accumulator<E> {
+    /// Creates the initial accumulator of degree `degree`, in which `tau`,
+    /// `alpha` and `beta` are all one.
+    pub fn new(degree: usize) -> Self {
+        assert!(degree >= 2, "the accumulator must hold at least two powers");
+        let g1 = E::G1Affine::prime_subgroup_generator();
+        let g2 = E::G2Affine::prime_subgroup_generator();
+        Self {
+            tau_powers_g1: vec![g1; 2 * degree - 1],
+            tau_powers_g2: vec![g2; degree],
+            alpha_tau_powers_g1: vec![g1; degree],
+            beta_tau_powers_g1: vec![g1; degree],
+            beta_g2: g2,
+        }
+    }
+
+    /// The number of powers of tau available in G2, which bounds the size of
+    /// the evaluation domain of circuits that can use this accumulator.
+    pub fn degree(&self) -> usize {
+        self.tau_powers_g2.len()
+    }
+
+    /// The BLAKE2b digest of the accumulator, which binds contributions to
+    /// the state they were applied to.
+    pub fn digest(&self) -> Digest64 {
+        hash(&[&to_bytes(self)])
+    }
+
+    /// Mixes fresh randomness into the accumulator, and returns the public
+    /// key that proves knowledge of it. The randomness is dropped as soon as
+    /// this returns.
+    pub fn contribute<R: Rng>(&mut self, rng: &mut R) -> PublicKey<E> {
+        let contribution_time = start_timer!(|| "Powers of tau contribution");
+        let tau = E::Fr::rand(rng);
+        let alpha = E::Fr::rand(rng);
+        let beta = E::Fr::rand(rng);
+
+        let digest = self.digest();
+        let (tau_g1_s, tau_g1_s_x, tau_g2_r_x) =
+            prove_knowledge::<E, _>(tau, &digest, TAU_PERSONALIZATION, rng);
+        let (alpha_g1_s, alpha_g1_s_x, alpha_g2_r_x) =
+            prove_knowledge::<E, _>(alpha, &digest, ALPHA_PERSONALIZATION, rng);
+        let (beta_g1_s, beta_g1_s_x, beta_g2_r_x) =
+            prove_knowledge::<E, _>(beta, &digest, BETA_PERSONALIZATION, rng);
+
+        let degree = self.degree();
+        let tau_powers = powers(tau, E::Fr::one(), 2 * degree - 1);
+        batch_mul(&mut self.tau_powers_g1, &tau_powers);
+        batch_mul(&mut self.tau_powers_g2, &tau_powers[..degree]);
+        batch_mul(&mut self.alpha_tau_powers_g1, &powers(tau, alpha, degree));
+        batch_mul(&mut self.beta_tau_powers_g1, &powers(tau, beta, degree));
+        self.beta_g2 = self.beta_g2.mul(beta).into_affine();
+        end_timer!(contribution_time);
+
+        PublicKey {
+            tau_g1: (tau_g1_s, tau_g1_s_x),
+            alpha_g1: (alpha_g1_s, alpha_g1_s_x),
+            beta_g1: (beta_g1_s, beta_g1_s_x),
+            tau_g2: tau_g2_r_x,
+            alpha_g2: alpha_g2_r_x,
+            beta_g2: beta_g2_r_x,
+        }
+    }
+
+    /// Checks that `after` was obtained from `self` by the contribution
+    /// described by `key`, and that `after` is a well-formed accumulator.
+    pub fn verify_contribution<R: Rng>(
+        &self,
+        after: &Self,
+        key: &PublicKey<E>,
+        rng: &mut R,
+    ) -> Result<(), CeremonyError> {
+        let verification_time = start_timer!(|| "Verify powers of tau contribution");
+        if after.tau_powers_g1.len() != self.tau_powers_g1.len()
+            || after.tau_powers_g2.len() != self.tau_powers_g2.len()
+            || after.alpha_tau_powers_g1.len() != self.alpha_tau_powers_g1.len()
+            || after.beta_tau_powers_g1.len() != self.beta_tau_powers_g1.len()
+        {
+            return Err(CeremonyError::ParametersMismatch);
+        }
+
+        // Check the proofs of knowledge, and recover the G2 bases that they
+        // are relative to.
+        let digest = self.digest();
+        let tau_r = knowledge_base::<E>(&digest, TAU_PERSONALIZATION, key.tau_g1.0, key.tau_g1.1);
+        let alpha_r = knowledge_base::<E>(
+            &digest,
+            ALPHA_PERSONALIZATION,
+            key.alpha_g1.0,
+            key.alpha_g1.1,
+        );
+        let beta_r =
+            knowledge_base::<E>(&digest, BETA_PERSONALIZATION, key.beta_g1.0, key.beta_g1.1);
+        if !same_ratio::<E>(key.tau_g1, (tau_r, key.tau_g2))
+            || !same_ratio::<E>(key.alpha_g1, (alpha_r, key.alpha_g2))
+            || !same_ratio::<E>(key.beta_g1, (beta_r, key.beta_g2))
+        {
+            return Err(CeremonyError::InvalidProofOfKnowledge);
+        }
+
+        // Check that the accumulator was updated by the contributed values.
+        let g1 = E::G1Affine::prime_subgroup_generator();
+        let g2 = E::G2Affine::prime_subgroup_generator();
+        if after.tau_powers_g1[0] != g1 || after.tau_powers_g2[0] != g2 {
+            return Err(CeremonyError::InvalidAccumulatorUpdate);
+        }
+        if !same_ratio::<E>(
+            (self.tau_powers_g1[1], after.tau_powers_g1[1]),
+            (tau_r, key.tau_g2),
+        ) || !same_ratio::<E>(
+            (self.alpha_tau_powers_g1[0], after.alpha_tau_powers_g1[0]),
+            (alpha_r, key.alpha_g2),
+        ) || !same_ratio::<E>(
+            (self.beta_tau_powers_g1[0], after.beta_tau_powers_g1[0]),
+            (beta_r, key.beta_g2),
+        ) || !same_ratio::<E>(key.beta_g1, (self.beta_g2, after.beta_g2))
+        {
+            return Err(CeremonyError::InvalidAccumulatorUpdate);
+        }
+
+        after.verify_powers(rng)?;
+        end_timer!(verification_time);
+        Ok(())
+    }
+
+    /// Checks that the accumulator holds consecutive powers of a single `tau`,
+    /// and that the `alpha` and `beta` multiples are consistent with them.
+    pub fn verify_powers<R: Rng>(&self, rng: &mut R) -> Result<(), CeremonyError> {
+        let g1 = E::G1Affine::prime_subgroup_generator();
+        let g2 = E::G2Affine::prime_subgroup_generator();
+        let tau_g1 = self.tau_powers_g1[1];
+        let tau_g2 = self.tau_powers_g2[1];
+
+        if self.tau_powers_g1.iter().any(Zero::is_zero)
+            || self.tau_powers_g2.iter().any(Zero::is_zero)
+            || self.alpha_tau_powers_g1.iter().any(Zero::is_zero)
+            || self.beta_tau_powers_g1.iter().any(Zero::is_zero)
+        {
+            return Err(CeremonyError::InconsistentPowers);
+        }
+
+        if !same_ratio::<E>((g1, tau_g1), (g2, tau_g2))
+            || !same_ratio::<E>(power_pairs(&self.tau_powers_g1, rng), (g2, tau_g2))
+            || !same_ratio::<E>((g1, tau_g1), power_pairs(&self.tau_powers_g2, rng))
+            || !same_ratio::<E>(power_pairs(&self.alpha_tau_powers_g1, rng), (g2, tau_g2))
+            || !same_ratio::<E>(power_pairs(&self.beta_tau_powers_g1, rng), (g2, tau_g2))
+            || !same_ratio::<E>((g1, self.beta_tau_powers_g1[0]), (g2, self.beta_g2))
+        {
+            return Err(CeremonyError::InconsistentPowers);
+        }
+        Ok(())
+    }
+}
+
+/// The public part of a powers of tau contribution. For each of `tau`,
+/// `alpha` and `beta` it holds a pair `(s, s * x)` in G1 and `r * x` in G2,
+/// where `r` is derived from the accumulator digest, `s` and `s * x`.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PublicKey<E: PairingEngine> {
+    pub tau_g1: (E::G1Affine, E::G1Affine),
+    pub alpha_g1: (E::G1Affine, E::G1Affine),
+    pub beta_g1: (E::G1Affine, E::G1Affine),
+    pub tau_g2: E::G2Affine,
+    pub alpha_g2: E::G2Affine,
+    pub beta_g2: E::G2Affine,
+}
+
+/// A single step of a powers of tau ceremony: the public key of the
+/// contribution and the accumulator it produced.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Contribution<E: PairingEngine> {
+    pub public_key: PublicKey<E>,
+    pub accumulator: Accumulator<E>,
+}
+
+/// The full record of a powers of tau ceremony, from which anyone can
+/// re-verify every contribution.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Transcript<E: PairingEngine> {
+    pub degree: u64,
+    pub contributions: Vec<Contribution<E>>,
+}
+
+impl<E: PairingEngine> Transcript<E> {
+    /// Starts a new ceremony for accumulators of degree `degree`, which must
+    /// be between 2 and [`MAX_DEGREE`].
+    pub fn new(degree: usize) -> Self {
+        assert!(degree >= 2, "the accumulator must hold at least two powers");
+        assert!(degree <= MAX_DEGREE, "the accumulator is too large");
+        Self {
+            degree: degree as u64,
+            contributions: Vec::new(),
+        }
+    }
+
+    /// The latest accumulator of the ceremony, or the initial one if nobody
+    /// has contributed yet.
+    pub fn accumulator(&self) -> Result<Accumulator<E>, CeremonyError> {
+        match self.contributions.last() {
+            Some(contribution) => Ok(contribution.accumulator.clone()),
+            None => Ok(Accumulator::new(self.checked_degree()?)),
+        }
+    }
+
+    /// Adds a contribution on top of the latest accumulator, and returns the
+    /// digest of the resulting accumulator, which the contributor can later
+    /// look for in the transcript.
+    pub fn contribute<R: Rng>(&mut self, rng: &mut R) -> Result<Digest64, CeremonyError> {
+        let mut accumulator = self.accumulator()?;
+        let public_key = accumulator.contribute(rng);
+        let digest = accumulator.digest();
+        self.contributions.push(Contribution {
+            public_key,
+            accumulator,
+        });
+        Ok(digest)
+    }
+
+    /// Replays and checks every contribution of the ceremony, returning the
+    /// final accumulator. A ceremony without contributions is rejected, since
+    /// its `tau`, `alpha` and `beta` are all one.
+    pub fn verify<R: Rng>(&self, rng: &mut R) -> Result<Accumulator<E>, CeremonyError> {
+        let degree = self.checked_degree()?;
+        let first = self
+            .contributions
+            .first()
+            .ok_or(CeremonyError::NoContributions)?;
+        // Only allocate the initial accumulator once the transcript is known
+        // to hold one of the same size.
+        if first.accumulator.degree() != degree {
+            return Err(CeremonyError::InvalidDegree);
+        }
+
+        let mut current = Accumulator::new(degree);
+        for contribution in &self.contributions {
+            current.verify_contribution(
+                &contribution.accumulator,
+                &contribution.public_key,
+                rng,
+            )?;
+            current = contribution.accumulator.clone();
+        }
+        Ok(current)
+    }
+
+    /// Serialize the transcript to bytes. Group elements are written in
+    /// compressed form.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_versioned(self, &mut writer, true)
+    }
+
+    /// Serialize the transcript to bytes, writing group elements in
+    /// uncompressed form.
+    pub fn write_uncompressed<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_versioned(self, &mut writer, false)
+    }
+
+    /// Deserialize a transcript from bytes, checking that every group element
+    /// is on the curve and in the prime order subgroup.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        read_versioned(&mut reader, true)
+    }
+
+    /// The degree of the transcript, which may have been read from an
+    /// untrusted source, checked to be one that an accumulator can have.
+    fn checked_degree(&self) -> Result<usize, CeremonyError> {
+        if self.degree < 2 || self.degree > MAX_DEGREE as u64 {
+            return Err(CeremonyError::InvalidDegree);
+        }
+        Ok(self.degree as usize)
+    }
+}
@@ -0,0 +1,273 @@
+use algebra_core::{
+    msm::VariableBaseMSM, AffineCurve, One, PairingEngine, PrimeField, ProjectiveCurve,
+    UniformRand, Zero,
+};
+use core::{fmt, ops::Neg};
+use ff_fft::GeneralEvaluationDomain;
+use r1cs_core::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
+use rand::Rng;
+
+use crate::{generator::KeypairAssembly, r1cs_to_qap::R1CStoQAP, Parameters, Vec};
+
+/// This is an error that could occur while checking that parameters are
+/// consistent with a circuit and with their verifying key.
+#[derive(Debug)]
+pub enum ParameterError {
+    /// A query does not have the length that the circuit requires.
+    QueryLength {
+        query: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    /// An element is the identity, although the circuit requires it not to be.
+    UnexpectedIdentity { query: &'static str, index: usize },
+    /// An element is not the identity, although the circuit requires it to be.
+    ExpectedIdentity { query: &'static str, index: usize },
+    /// A pairing check relating the named elements failed.
+    Inconsistent(&'static str),
+    /// The circuit could not be synthesized.
+    Synthesis(SynthesisError),
+}
+
+impl From<SynthesisError> for ParameterError {
+    fn from(e: SynthesisError) -> ParameterError {
+        ParameterError::Synthesis(e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParameterError {}
+
+impl fmt::Display for ParameterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            ParameterError::QueryLength {
+                query,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} has {} elements, but the circuit requires {}",
+                query, found, expected
+            ),
+            ParameterError::UnexpectedIdentity { query, index } => {
+                write!(f, "{}[{}] is unexpectedly the identity", query, index)
+            },
+            ParameterError::ExpectedIdentity { query, index } => {
+                write!(f, "{}[{}] should be the identity", query, index)
+            },
+            ParameterError::Inconsistent(elements) => write!(f, "inconsistent {}", elements),
+            ParameterError::Synthesis(err) => write!(f, "synthesis error: {}", err),
+        }
+    }
+}
+
+/// Checks that `params` are consistent with `circuit` and with their own
+/// verifying key.
+///
+/// The circuit is reduced to a QAP, whose polynomials are evaluated at a
+/// random point to determine which query elements must be the identity. The
+/// queries are then related to the verifying key with pairing checks on random
+/// linear combinations of their elements:
+/// * `beta_g1` and `delta_g1` against `vk.beta_g2` and `vk.delta_g2`;
+/// * the B query in G1 against the B query in G2;
+/// * `l_query` and `vk.gamma_abc_g1` against the A and B queries, for the
+///   variables that do not appear in the C matrix.
+///
+/// These checks cannot detect every malformed parameter set: without the
+/// trapdoor, nothing relates the H query, or the contribution of the C matrix
+/// to the L query, to the rest of the parameters. They do catch parameters
+/// generated for another circuit, and queries mixed from different setups.
+pub fn check_parameters<E, C, R>(
+    params: &Parameters<E>,
+    circuit: C,
+    rng: &mut R,
+) -> Result<(), ParameterError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    R: Rng,
+{
+    let check_time = start_timer!(|| "Check parameters");
+    let mut assembly = KeypairAssembly::<E> {
+        num_inputs: 0,
+        num_aux: 0,
+        num_constraints: 0,
+        at: vec![],
+        bt: vec![],
+        ct: vec![],
+    };
+
+    // Allocate the "one" input variable
+    assembly.alloc_input(|| "", || Ok(E::Fr::one()))?;
+
+    // Synthesize the circuit.
+    let synthesis_time = start_timer!(|| "Constraint synthesis");
+    circuit.generate_constraints(&mut assembly)?;
+    end_timer!(synthesis_time);
+
+    let reduction_time = start_timer!(|| "R1CS to QAP Instance Map with Evaluation");
+    let t = E::Fr::rand(rng);
+    let (a, b, c, _, qap_num_variables, m_raw) = R1CStoQAP::instance_map_with_evaluation::<
+        E,
+        GeneralEvaluationDomain<E::Fr>,
+    >(&assembly, &t)?;
+    end_timer!(reduction_time);
+
+    let num_inputs = assembly.num_inputs;
+    let num_variables = qap_num_variables + 1;
+    check_length("a_query", &params.a_query, num_variables)?;
+    check_length("b_g1_query", &params.b_g1_query, num_variables)?;
+    check_length("b_g2_query", &params.b_g2_query, num_variables)?;
+    check_length("h_query", &params.h_query, m_raw - 1)?;
+    check_length("l_query", &params.l_query, assembly.num_aux)?;
+    check_length("vk.gamma_abc_g1", &params.vk.gamma_abc_g1, num_inputs)?;
+
+    // Elements that are never the identity.
+    let vk = &params.vk;
+    check_non_zero("vk.alpha_g1", &[vk.alpha_g1])?;
+    check_non_zero("vk.beta_g2", &[vk.beta_g2])?;
+    check_non_zero("vk.gamma_g2", &[vk.gamma_g2])?;
+    check_non_zero("vk.delta_g2", &[vk.delta_g2])?;
+    check_non_zero("beta_g1", &[params.beta_g1])?;
+    check_non_zero("delta_g1", &[params.delta_g1])?;
+    check_non_zero("h_query", &params.h_query)?;
+
+    // The identity elements of each query must match the zero polynomials of
+    // the QAP.
+    let abc_zero = (0..num_variables)
+        .map(|i| a[i].is_zero() && b[i].is_zero() && c[i].is_zero())
+        .collect::<Vec<_>>();
+    check_zero_pattern("a_query", &params.a_query, |i| a[i].is_zero())?;
+    check_zero_pattern("b_g1_query", &params.b_g1_query, |i| b[i].is_zero())?;
+    check_zero_pattern("b_g2_query", &params.b_g2_query, |i| b[i].is_zero())?;
+    check_zero_pattern("vk.gamma_abc_g1", &vk.gamma_abc_g1, |i| abc_zero[i])?;
+    check_zero_pattern("l_query", &params.l_query, |i| abc_zero[num_inputs + i])?;
+
+    let pairing_time = start_timer!(|| "Pairing checks");
+    let delta_g2_neg = vk.delta_g2.neg();
+
+    // e(beta_g1, delta_g2) = e(delta_g1, beta_g2)
+    if !is_one::<E>(&[
+        (params.beta_g1, delta_g2_neg),
+        (params.delta_g1, vk.beta_g2),
+    ]) {
+        return Err(ParameterError::Inconsistent(
+            "beta_g1 and delta_g1 with the verifying key",
+        ));
+    }
+
+    // e(b_g1, delta_g2) = e(delta_g1, b_g2) for a random combination of the
+    // B queries.
+    let r = random_scalars::<E::Fr, _>(num_variables, rng);
+    let b_g1 = msm(&params.b_g1_query, &r);
+    let b_g2 = msm(&params.b_g2_query, &r);
+    if !is_one::<E>(&[(b_g1, delta_g2_neg), (params.delta_g1, b_g2)]) {
+        return Err(ParameterError::Inconsistent("b_g1_query and b_g2_query"));
+    }
+
+    // For the variables that do not appear in the C matrix,
+    //   e(l_i, delta_g2) = e(a_i, beta_g2) * e(alpha_g1, b_i),
+    // and similarly for gamma_abc_g1 with gamma_g2.
+    let no_c = |i: usize| c[i].is_zero();
+    let inputs = (0..num_inputs).filter(|&i| no_c(i)).collect::<Vec<_>>();
+    let aux = (num_inputs..num_variables)
+        .filter(|&i| no_c(i))
+        .collect::<Vec<_>>();
+    let checks = [
+        (
+            inputs,
+            &vk.gamma_abc_g1,
+            0,
+            vk.gamma_g2,
+            "vk.gamma_abc_g1 with the A and B queries",
+        ),
+        (
+            aux,
+            &params.l_query,
+            num_inputs,
+            vk.delta_g2,
+            "l_query with the A and B queries",
+        ),
+    ];
+    for (indices, query, offset, divisor, elements) in checks.iter() {
+        if indices.is_empty() {
+            continue;
+        }
+        let r = random_scalars::<E::Fr, _>(indices.len(), rng);
+        let select_g1 = |v: &[E::G1Affine], offset: usize| {
+            indices.iter().map(|&i| v[i - offset]).collect::<Vec<_>>()
+        };
+        let query = msm(&select_g1(query, *offset), &r);
+        let a = msm(&select_g1(&params.a_query, 0), &r);
+        let b = msm(
+            &indices
+                .iter()
+                .map(|&i| params.b_g2_query[i])
+                .collect::<Vec<_>>(),
+            &r,
+        );
+        if !is_one::<E>(&[
+            (query, *divisor),
+            (a.neg(), vk.beta_g2),
+            (vk.alpha_g1.neg(), b),
+        ]) {
+            return Err(ParameterError::Inconsistent(elements));
+        }
+    }
+    end_timer!(pairing_time);
+    end_timer!(check_time);
+
+    Ok(())
+}
+
+fn check_length<T>(query: &'static str, v: &[T], expected: usize) -> Result<(), ParameterError> {
+    if v.len() != expected {
+        return Err(ParameterError::QueryLength {
+            query,
+            expected,
+            found: v.len(),
+        });
+    }
+    Ok(())
+}
+
+fn check_non_zero<G: AffineCurve>(query: &'static str, v: &[G]) -> Result<(), ParameterError> {
+    match v.iter().position(|g| g.is_zero()) {
+        Some(index) => Err(ParameterError::UnexpectedIdentity { query, index }),
+        None => Ok(()),
+    }
+}
+
+fn check_zero_pattern<G: AffineCurve>(
+    query: &'static str,
+    v: &[G],
+    is_zero: impl Fn(usize) -> bool,
+) -> Result<(), ParameterError> {
+    for (index, g) in v.iter().enumerate() {
+        match (g.is_zero(), is_zero(index)) {
+            (true, false) => return Err(ParameterError::UnexpectedIdentity { query, index }),
+            (false, true) => return Err(ParameterError::ExpectedIdentity { query, index }),
+            _ => {},
+        }
+    }
+    Ok(())
+}
+
+fn random_scalars<F: PrimeField, R: Rng>(n: usize, rng: &mut R) -> Vec<F::BigInt> {
+    (0..n)
+        .map(|_| F::from(rng.gen::<u128>()).into_repr())
+        .collect()
+}
+
+fn msm<G: AffineCurve>(bases: &[G], scalars: &[<G::ScalarField as PrimeField>::BigInt]) -> G {
+    VariableBaseMSM::multi_scalar_mul(bases, scalars).into_affine()
+}
+
+fn is_one<E: PairingEngine>(pairs: &[(E::G1Affine, E::G2Affine)]) -> bool {
+    let pairs = pairs
+        .iter()
+        .map(|(g1, g2)| ((*g1).into(), (*g2).into()))
+        .collect::<Vec<_>>();
+    E::product_of_pairings(&pairs).is_one()
+}
@@ -6,12 +6,16 @@ use ff_fft::{cfg_into_iter, cfg_iter, EvaluationDomain};
 use r1cs_core::{
     ConstraintSynthesizer, ConstraintSystem, Index, LinearCombination, SynthesisError, Variable,
 };
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaChaRng;
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
-use crate::{push_constraints, r1cs_to_qap::R1CStoQAP, Parameters, String, Vec, VerifyingKey};
+use crate::{
+    circuit_digest, push_constraints, r1cs_to_qap::R1CStoQAP, Parameters, String, Trapdoor, Vec,
+    VerifyingKey,
+};
 
 /// Generates a random common reference string for
 /// a circuit.
@@ -33,6 +37,44 @@ where
     generate_parameters::<E, C, D, R>(circuit, alpha, beta, gamma, delta, rng)
 }
 
+/// Generates a common reference string for a circuit deterministically
+/// from `seed`.
+///
+/// Anyone who knows the seed can recompute the trapdoor and forge proofs, so
+/// this is only suitable for development and testing.
+pub fn generate_parameters_from_seed<E, C, D>(
+    circuit: C,
+    seed: [u8; 32],
+) -> Result<Parameters<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+{
+    let rng = &mut ChaChaRng::from_seed(seed);
+    generate_random_parameters::<E, C, D, _>(circuit, rng)
+}
+
+/// Generates a random common reference string for
+/// a circuit, and returns it together with its trapdoor.
+pub fn generate_random_parameters_with_trapdoor<E, C, D, R>(
+    circuit: C,
+    rng: &mut R,
+) -> Result<(Parameters<E>, Trapdoor<E>), SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+{
+    let alpha = E::Fr::rand(rng);
+    let beta = E::Fr::rand(rng);
+    let gamma = E::Fr::rand(rng);
+    let delta = E::Fr::rand(rng);
+
+    generate_parameters_with_trapdoor::<E, C, D, R>(circuit, alpha, beta, gamma, delta, rng)
+}
+
 /// This is our assembly structure that we'll use to synthesize the
 /// circuit into a QAP.
 pub struct KeypairAssembly<E: PairingEngine> {
@@ -140,6 +182,26 @@ pub fn generate_parameters<E, C, D, R>(
     delta: E::Fr,
     rng: &mut R,
 ) -> Result<Parameters<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+{
+    generate_parameters_with_trapdoor::<E, C, D, R>(circuit, alpha, beta, gamma, delta, rng)
+        .map(|(params, _)| params)
+}
+
+/// Create parameters for a circuit, given some toxic waste, and return them
+/// together with the full trapdoor.
+pub fn generate_parameters_with_trapdoor<E, C, D, R>(
+    circuit: C,
+    alpha: E::Fr,
+    beta: E::Fr,
+    gamma: E::Fr,
+    delta: E::Fr,
+    rng: &mut R,
+) -> Result<(Parameters<E>, Trapdoor<E>), SynthesisError>
 where
     E: PairingEngine,
     C: ConstraintSynthesizer<E::Fr>,
@@ -303,7 +365,7 @@ where
     E::G1Projective::batch_normalization(l_query.as_mut_slice());
     end_timer!(batch_normalization_time);
 
-    Ok(Parameters {
+    let params = Parameters {
         vk,
         beta_g1: beta_g1.into_affine(),
         delta_g1: delta_g1.into_affine(),
@@ -312,5 +374,24 @@ where
         b_g2_query: b_g2_query.into_iter().map(Into::into).collect(),
         h_query: h_query.into_iter().map(Into::into).collect(),
         l_query: l_query.into_iter().map(Into::into).collect(),
-    })
+        circuit_digest: circuit_digest(
+            assembly.num_inputs,
+            assembly.num_aux,
+            &assembly.at,
+            &assembly.bt,
+            &assembly.ct,
+        ),
+    };
+    let trapdoor = Trapdoor {
+        alpha,
+        beta,
+        gamma,
+        delta,
+        tau: t,
+        g1_generator: g1_generator.into_affine(),
+        g2_generator: g2_generator.into_affine(),
+        input_base_logs: gamma_abc,
+    };
+
+    Ok((params, trapdoor))
 }
@@ -0,0 +1,109 @@
+use algebra_core::{AffineCurve, Field, PairingEngine, ProjectiveCurve, UniformRand, Zero};
+use ff_fft::GeneralEvaluationDomain;
+use r1cs_core::{ConstraintSynthesizer, SynthesisError};
+use rand::Rng;
+
+use super::{CommitmentKey, Parameters, Recorded, VerifyingKey};
+use crate::{generator::generic::generate_parameters_with_trapdoor, Vec};
+
+/// Generates a random common reference string for a circuit, whose
+/// commitments `link_d` are made under `link_key`.
+///
+/// Returns `SynthesisError::CommitmentKeyTooShort` if `link_key` has fewer
+/// bases than the circuit has committed variables.
+pub fn generate_random_parameters<E, C, R>(
+    circuit: C,
+    link_key: &CommitmentKey<E>,
+    rng: &mut R,
+) -> Result<Parameters<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    R: Rng,
+{
+    let alpha = E::Fr::rand(rng);
+    let beta = E::Fr::rand(rng);
+    let gamma = E::Fr::rand(rng);
+    let delta = E::Fr::rand(rng);
+    let eta = E::Fr::rand(rng);
+
+    let mut committed = Vec::new();
+    let circuit = Recorded {
+        circuit,
+        committed: &mut committed,
+    };
+    let (mut params, trapdoor) =
+        generate_parameters_with_trapdoor::<E, _, GeneralEvaluationDomain<E::Fr>, R>(
+            circuit, alpha, beta, gamma, delta, rng,
+        )?;
+
+    if committed.len() > link_key.bases.len() {
+        return Err(SynthesisError::CommitmentKeyTooShort);
+    }
+
+    let setup_time = start_timer!(|| "Generate the commitment keys");
+    let gamma_inverse = gamma.inverse().ok_or(SynthesisError::UnexpectedIdentity)?;
+    let delta_inverse = delta.inverse().ok_or(SynthesisError::UnexpectedIdentity)?;
+
+    // The L query holds `(beta * u_i + alpha * v_i + w_i) / delta`, while the
+    // commitment bases divide the same values by `gamma` instead.
+    let delta_gamma_inverse = delta * &gamma_inverse;
+    let mut commitment_bases = Vec::with_capacity(committed.len());
+    for (index, _) in &committed {
+        commitment_bases.push(params.l_query[*index].mul(delta_gamma_inverse));
+        params.l_query[*index] = E::G1Affine::zero();
+    }
+    let commitment_bases = E::G1Projective::batch_normalization_into_affine(&commitment_bases);
+
+    let g1_generator = trapdoor.g1_generator;
+    let g2_generator = trapdoor.g2_generator;
+    let eta_gamma_inv_g1 = g1_generator.mul(eta * &gamma_inverse).into_affine();
+    let eta_delta_inv_g1 = g1_generator.mul(eta * &delta_inverse).into_affine();
+
+    let link_key = CommitmentKey {
+        bases: link_key.bases[..committed.len()].to_vec(),
+        blinding_base: link_key.blinding_base,
+    };
+
+    // `link_pi` is a quasi-adaptive NIZK (after Kiltz and Wee) that
+    // `(d, link_d)` lies in the span of the columns of
+    //     [ commitment_bases  0              eta_gamma_inv_g1 ]
+    //     [ link_key.bases    blinding_base  0                ].
+    // Its evaluation key is `(k_0, k_1)` times that matrix, and its
+    // verification key is `(a, k_0 * a, k_1 * a)` in G2.
+    let k0 = E::Fr::rand(rng);
+    let k1 = E::Fr::rand(rng);
+    let a = E::Fr::rand(rng);
+    let mut link_ek = commitment_bases
+        .iter()
+        .zip(&link_key.bases)
+        .map(|(d_base, link_base)| {
+            let mut p = d_base.mul(k0);
+            p += &link_base.mul(k1);
+            p
+        })
+        .collect::<Vec<_>>();
+    link_ek.push(link_key.blinding_base.mul(k1));
+    link_ek.push(eta_gamma_inv_g1.mul(k0));
+    let link_ek = E::G1Projective::batch_normalization_into_affine(&link_ek);
+
+    let link_a_g2 = g2_generator.mul(a).into_affine();
+    let link_c0_g2 = g2_generator.mul(k0 * &a).into_affine();
+    let link_c1_g2 = g2_generator.mul(k1 * &a).into_affine();
+    end_timer!(setup_time);
+
+    Ok(Parameters {
+        vk: VerifyingKey {
+            vk: params.vk.clone(),
+            commitment_bases,
+            eta_gamma_inv_g1,
+            link_key,
+            link_a_g2,
+            link_c0_g2,
+            link_c1_g2,
+        },
+        params,
+        eta_delta_inv_g1,
+        link_ek,
+    })
+}
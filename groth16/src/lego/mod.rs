@@ -0,0 +1,253 @@
+//! A commit-and-prove variant of Groth16, after the LegoGroth16 construction
+//! of [LegoSNARK](https://eprint.iacr.org/2019/142.pdf).
+//!
+//! Circuits mark the witness variables to commit to by allocating them with
+//! [`ConstraintSystem::alloc_committed`]. Each proof then carries
+//! * `d`, a commitment to the committed variables under bases specific to
+//!   the circuit, which enters the Groth16 verification equation in place of
+//!   public inputs;
+//! * `link_d`, a Pedersen commitment to the same values under a
+//!   [`CommitmentKey`] chosen when generating the parameters, together with
+//!   `link_pi`, a proof that `d` and `link_d` commit to the same values.
+//!
+//! Proofs for different circuits whose parameters share a commitment key,
+//! and whose provers use the same blinding factor for `link_d`, yield equal
+//! `link_d` exactly when they commit to the same values.
+
+use crate::{Vec, VerifyingKey as Groth16VerifyingKey};
+use algebra_core::{
+    msm::VariableBaseMSM, serialize::*, AffineCurve, PairingEngine, PrimeField, ProjectiveCurve,
+    UniformRand,
+};
+use r1cs_core::{ConstraintSynthesizer, ConstraintSystem, Index, SynthesisError, Variable};
+use rand::Rng;
+
+mod generator;
+mod prover;
+mod verifier;
+
+pub use self::{generator::*, prover::*, verifier::*};
+
+/// A Pedersen commitment key, for committing to vectors of scalars.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CommitmentKey<E: PairingEngine> {
+    /// One base for each committed value.
+    pub bases: Vec<E::G1Affine>,
+    /// The base of the blinding factor.
+    pub blinding_base: E::G1Affine,
+}
+
+impl<E: PairingEngine> CommitmentKey<E> {
+    /// Samples a key for committing to up to `len` values.
+    ///
+    /// Nobody may know discrete logarithms relating the bases, so keys that
+    /// are shared between distrustful parties should instead be derived
+    /// from public randomness.
+    pub fn rand<R: Rng>(len: usize, rng: &mut R) -> Self {
+        Self {
+            bases: (0..len)
+                .map(|_| E::G1Projective::rand(rng).into_affine())
+                .collect(),
+            blinding_base: E::G1Projective::rand(rng).into_affine(),
+        }
+    }
+
+    /// Commits to `values` with the blinding factor `blinding`.
+    ///
+    /// Returns `SynthesisError::CommitmentKeyTooShort` if the key has fewer
+    /// bases than there are values.
+    pub fn commit(&self, values: &[E::Fr], blinding: E::Fr) -> Result<E::G1Affine, SynthesisError> {
+        if values.len() > self.bases.len() {
+            return Err(SynthesisError::CommitmentKeyTooShort);
+        }
+        let mut commitment = msm::<E::G1Affine>(&self.bases[..values.len()], values);
+        commitment += &self.blinding_base.mul(blinding);
+        Ok(commitment.into_affine())
+    }
+}
+
+/// A verification key for the commit-and-prove variant of Groth16.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerifyingKey<E: PairingEngine> {
+    /// The Groth16 verification key. Its `gamma_abc_g1` only covers the
+    /// public inputs.
+    pub vk: Groth16VerifyingKey<E>,
+    /// The bases of the commitment `d`, one for each committed variable, in
+    /// the order in which they were allocated.
+    pub commitment_bases: Vec<E::G1Affine>,
+    /// `eta / gamma` in G1, the base of the blinding factor of `d`.
+    pub eta_gamma_inv_g1: E::G1Affine,
+    /// The key of the commitment `link_d`, truncated to the number of
+    /// committed variables.
+    pub link_key: CommitmentKey<E>,
+    /// The verification key of the proof `link_pi`.
+    pub link_a_g2: E::G2Affine,
+    pub link_c0_g2: E::G2Affine,
+    pub link_c1_g2: E::G2Affine,
+}
+
+/// Preprocessed verification key parameters for the commit-and-prove
+/// variant of Groth16, that enable faster verification at the expense of
+/// larger size in memory.
+#[derive(Clone, Debug)]
+pub struct PreparedVerifyingKey<E: PairingEngine> {
+    pub vk: VerifyingKey<E>,
+    pub pvk: crate::PreparedVerifyingKey<E>,
+    pub link_a_g2_neg_pc: E::G2Prepared,
+    pub link_c0_g2_pc: E::G2Prepared,
+    pub link_c1_g2_pc: E::G2Prepared,
+}
+
+impl<E: PairingEngine> From<VerifyingKey<E>> for PreparedVerifyingKey<E> {
+    fn from(other: VerifyingKey<E>) -> Self {
+        prepare_verifying_key(&other)
+    }
+}
+
+/// Full public (prover and verifier) parameters for the commit-and-prove
+/// variant of Groth16.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Parameters<E: PairingEngine> {
+    pub vk: VerifyingKey<E>,
+    /// Groth16 parameters for the circuit, whose L query is the identity at
+    /// the committed variables, so that they only enter the proof through
+    /// `d`.
+    pub params: crate::Parameters<E>,
+    /// `eta / delta` in G1.
+    pub eta_delta_inv_g1: E::G1Affine,
+    /// The evaluation key of the proof `link_pi`, with one element for each
+    /// committed variable, followed by one for each of the blinding factors
+    /// of `link_d` and `d`.
+    pub link_ek: Vec<E::G1Affine>,
+}
+
+/// A proof in the commit-and-prove variant of Groth16.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Proof<E: PairingEngine> {
+    pub a: E::G1Affine,
+    pub b: E::G2Affine,
+    pub c: E::G1Affine,
+    /// The commitment to the committed variables under
+    /// `vk.commitment_bases`.
+    pub d: E::G1Affine,
+    /// The commitment to the committed variables under `vk.link_key`.
+    pub link_d: E::G1Affine,
+    /// The proof that `d` and `link_d` commit to the same values.
+    pub link_pi: E::G1Affine,
+}
+
+/// A circuit that records the variables allocated with
+/// [`ConstraintSystem::alloc_committed`] while it is synthesized, along with
+/// their assignments if they are known.
+struct Recorded<'a, F: PrimeField, C> {
+    circuit: C,
+    committed: &'a mut Vec<(usize, Option<F>)>,
+}
+
+impl<'a, F: PrimeField, C: ConstraintSynthesizer<F>> ConstraintSynthesizer<F>
+    for Recorded<'a, F, C>
+{
+    fn generate_constraints<CS: ConstraintSystem<F>>(
+        self,
+        cs: &mut CS,
+    ) -> Result<(), SynthesisError> {
+        self.circuit.generate_constraints(&mut Recorder {
+            cs,
+            committed: self.committed,
+        })
+    }
+}
+
+struct Recorder<'a, F: PrimeField, CS: ConstraintSystem<F>> {
+    cs: &'a mut CS,
+    committed: &'a mut Vec<(usize, Option<F>)>,
+}
+
+impl<'a, F: PrimeField, CS: ConstraintSystem<F>> ConstraintSystem<F> for Recorder<'a, F, CS> {
+    type Root = Self;
+
+    fn alloc<FN, A, AR>(&mut self, annotation: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<crate::String>,
+    {
+        self.cs.alloc(annotation, f)
+    }
+
+    fn alloc_input<FN, A, AR>(&mut self, annotation: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<crate::String>,
+    {
+        self.cs.alloc_input(annotation, f)
+    }
+
+    fn alloc_committed<FN, A, AR>(
+        &mut self,
+        annotation: A,
+        f: FN,
+    ) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<crate::String>,
+    {
+        let mut value = None;
+        let variable = self.cs.alloc(annotation, || {
+            let v = f()?;
+            value = Some(v);
+            Ok(v)
+        })?;
+        match variable.get_unchecked() {
+            Index::Aux(index) => self.committed.push((index, value)),
+            Index::Input(_) => unreachable!("private variables are auxiliary"),
+        }
+        // Like public inputs, committed variables need linearly independent
+        // polynomials in the QAP for the proof to be sound.
+        self.cs.enforce(
+            || "commitment independence",
+            |lc| lc + variable,
+            |lc| lc,
+            |lc| lc,
+        );
+        Ok(variable)
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, annotation: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<crate::String>,
+        LA: FnOnce(r1cs_core::LinearCombination<F>) -> r1cs_core::LinearCombination<F>,
+        LB: FnOnce(r1cs_core::LinearCombination<F>) -> r1cs_core::LinearCombination<F>,
+        LC: FnOnce(r1cs_core::LinearCombination<F>) -> r1cs_core::LinearCombination<F>,
+    {
+        self.cs.enforce(annotation, a, b, c)
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<crate::String>,
+        N: FnOnce() -> NR,
+    {
+        self.cs.get_root().push_namespace(name_fn)
+    }
+
+    fn pop_namespace(&mut self) {
+        self.cs.get_root().pop_namespace()
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.cs.num_constraints()
+    }
+}
+
+fn msm<G: AffineCurve>(bases: &[G], scalars: &[G::ScalarField]) -> G::Projective {
+    let scalars = scalars.iter().map(|s| s.into_repr()).collect::<Vec<_>>();
+    VariableBaseMSM::multi_scalar_mul(bases, &scalars)
+}
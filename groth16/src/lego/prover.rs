@@ -0,0 +1,99 @@
+use algebra_core::{AffineCurve, PairingEngine, ProjectiveCurve, UniformRand};
+use ff_fft::GeneralEvaluationDomain;
+use r1cs_core::{ConstraintSynthesizer, SynthesisError};
+use rand::Rng;
+
+use super::{msm, Parameters, Proof, Recorded};
+use crate::{prover::generic, Vec};
+
+/// Creates a proof, committing to the committed variables in `link_d` with
+/// the blinding factor `link_blinding`.
+///
+/// Provers that want to show that proofs for different circuits commit to
+/// the same values should use the same `link_blinding` for each of them.
+#[inline]
+pub fn create_random_proof<E, C, R>(
+    circuit: C,
+    params: &Parameters<E>,
+    link_blinding: E::Fr,
+    rng: &mut R,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    R: Rng,
+{
+    let r = E::Fr::rand(rng);
+    let s = E::Fr::rand(rng);
+    let v = E::Fr::rand(rng);
+
+    create_proof::<E, C>(circuit, params, r, s, v, link_blinding)
+}
+
+/// Creates a proof given the randomness `r` and `s` of the Groth16 proof,
+/// the blinding factor `v` of `d` and the blinding factor `link_blinding` of
+/// `link_d`.
+///
+/// Fails with `SynthesisError::CircuitMismatch` if `circuit` commits to a
+/// different number of variables than the one `params` were generated for.
+pub fn create_proof<E, C>(
+    circuit: C,
+    params: &Parameters<E>,
+    r: E::Fr,
+    s: E::Fr,
+    v: E::Fr,
+    link_blinding: E::Fr,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+{
+    let prover_time = start_timer!(|| "Commit-and-prove prover");
+    let mut committed = Vec::new();
+    let circuit = Recorded {
+        circuit,
+        committed: &mut committed,
+    };
+    let proof = generic::create_proof::<E, _, GeneralEvaluationDomain<E::Fr>>(
+        circuit,
+        &params.params,
+        r,
+        s,
+    )?;
+
+    let vk = &params.vk;
+    if committed.len() != vk.commitment_bases.len() {
+        return Err(SynthesisError::CircuitMismatch);
+    }
+    let values = committed
+        .into_iter()
+        .map(|(_, value)| value.ok_or(SynthesisError::AssignmentMissing))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let commitment_time = start_timer!(|| "Compute commitments");
+    // The committed variables are left out of C by the L query, and enter
+    // the verification equation through d instead.
+    let mut d = msm::<E::G1Affine>(&vk.commitment_bases, &values);
+    d += &vk.eta_gamma_inv_g1.mul(v);
+
+    let mut c = proof.c.into_projective();
+    c -= &params.eta_delta_inv_g1.mul(v);
+
+    let link_d = vk.link_key.commit(&values, link_blinding)?;
+
+    let mut witness = values;
+    witness.push(link_blinding);
+    witness.push(v);
+    let link_pi = msm::<E::G1Affine>(&params.link_ek, &witness);
+    end_timer!(commitment_time);
+    end_timer!(prover_time);
+
+    Ok(Proof {
+        a: proof.a,
+        b: proof.b,
+        c: c.into_affine(),
+        d: d.into_affine(),
+        link_d,
+        link_pi: link_pi.into_affine(),
+    })
+}
@@ -0,0 +1,74 @@
+use algebra_core::{AffineCurve, One, PairingEngine, PrimeField, ProjectiveCurve};
+use core::ops::{AddAssign, Neg};
+use r1cs_core::SynthesisError;
+
+use super::{PreparedVerifyingKey, Proof, VerifyingKey};
+
+pub fn prepare_verifying_key<E: PairingEngine>(vk: &VerifyingKey<E>) -> PreparedVerifyingKey<E> {
+    PreparedVerifyingKey {
+        vk: vk.clone(),
+        pvk: crate::prepare_verifying_key(&vk.vk),
+        link_a_g2_neg_pc: vk.link_a_g2.neg().into(),
+        link_c0_g2_pc: vk.link_c0_g2.into(),
+        link_c1_g2_pc: vk.link_c1_g2.into(),
+    }
+}
+
+/// Verifies a proof, and that its commitments `d` and `link_d` commit to the
+/// same values.
+pub fn verify_proof<E: PairingEngine>(
+    pvk: &PreparedVerifyingKey<E>,
+    proof: &Proof<E>,
+    public_inputs: &[E::Fr],
+) -> Result<bool, SynthesisError> {
+    let gamma_abc_g1 = &pvk.pvk.gamma_abc_g1;
+    if (public_inputs.len() + 1) != gamma_abc_g1.len() {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+
+    let link = E::miller_loop(
+        [
+            (proof.link_pi.into(), pvk.link_a_g2_neg_pc.clone()),
+            (proof.d.into(), pvk.link_c0_g2_pc.clone()),
+            (proof.link_d.into(), pvk.link_c1_g2_pc.clone()),
+        ]
+        .iter(),
+    );
+    let link = E::final_exponentiation(&link).ok_or(SynthesisError::UnexpectedIdentity)?;
+    if link != E::Fqk::one() {
+        return Ok(false);
+    }
+
+    let mut g_ic = gamma_abc_g1[0].into_projective();
+    for (i, b) in public_inputs.iter().zip(gamma_abc_g1.iter().skip(1)) {
+        g_ic.add_assign(&b.mul(i.into_repr()));
+    }
+    g_ic.add_assign_mixed(&proof.d);
+
+    let qap = E::miller_loop(
+        [
+            (proof.a.into(), proof.b.into()),
+            (g_ic.into_affine().into(), pvk.pvk.gamma_g2_neg_pc.clone()),
+            (proof.c.into(), pvk.pvk.delta_g2_neg_pc.clone()),
+        ]
+        .iter(),
+    );
+
+    let test = E::final_exponentiation(&qap).ok_or(SynthesisError::UnexpectedIdentity)?;
+
+    Ok(test == pvk.pvk.alpha_g1_beta_g2)
+}
+
+/// Checks that `link_d` of `proof` opens to `values` with the blinding
+/// factor `link_blinding`.
+pub fn verify_commitment<E: PairingEngine>(
+    vk: &VerifyingKey<E>,
+    proof: &Proof<E>,
+    values: &[E::Fr],
+    link_blinding: E::Fr,
+) -> Result<bool, SynthesisError> {
+    if values.len() != vk.link_key.bases.len() {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+    Ok(vk.link_key.commit(values, link_blinding)? == proof.link_d)
+}
@@ -18,10 +18,10 @@ extern crate bench_utils;
 extern crate alloc;
 
 #[cfg(not(feature = "std"))]
-use alloc::{string::String, vec::Vec};
+use alloc::{borrow::Cow, string::String, vec::Vec};
 
 #[cfg(feature = "std")]
-use std::{string::String, vec::Vec};
+use std::{borrow::Cow, string::String, vec::Vec};
 
 use algebra_core::{
     bytes::ToBytes,
@@ -29,7 +29,8 @@ use algebra_core::{
     serialize::*,
     Field, PairingEngine,
 };
-use r1cs_core::{Index, LinearCombination, SynthesisError};
+use blake2::{Blake2s, Digest};
+use r1cs_core::{CircuitDigest, CircuitHasher, Index, LinearCombination, SynthesisError};
 
 /// Reduce an R1CS instance to a *Quadratic Arithmetic Program* instance.
 pub mod r1cs_to_qap;
@@ -43,10 +44,32 @@ pub mod prover;
 /// Verify proofs for the Groth16 zkSNARK construction.
 pub mod verifier;
 
+/// Simulate proofs for the Groth16 zkSNARK construction, given the trapdoor.
+pub mod simulator;
+
+/// Check parameters for the Groth16 zkSNARK construction against a circuit.
+pub mod check;
+
+/// A commit-and-prove variant of the Groth16 zkSNARK construction, whose
+/// proofs carry commitments to designated witness variables.
+pub mod lego;
+
+/// Multi-party generation of parameters for the Groth16 zkSNARK construction.
+pub mod ceremony;
+
+/// Aggregate many proofs for the Groth16 zkSNARK construction that share a
+/// verifying key into one logarithmic-size proof.
+pub mod aggregate;
+
+/// Export verifying keys as Solidity contracts that verify Groth16 proofs on
+/// the EVM.
+#[cfg(feature = "solidity")]
+pub mod solidity;
+
 #[cfg(test)]
 mod test;
 
-pub use self::{generator::*, prover::*, verifier::*};
+pub use self::{check::*, generator::*, prover::*, simulator::*, verifier::*};
 
 /// A proof in the Groth16 SNARK.
 #[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
@@ -77,16 +100,22 @@ impl<E: PairingEngine> Default for Proof<E> {
 
 impl<E: PairingEngine> Proof<E> {
     /// Serialize the proof into bytes, for storage on disk or transmission
-    /// over the network.
-    pub fn write<W: Write>(&self, mut _writer: W) -> io::Result<()> {
-        // TODO: implement serialization
-        unimplemented!()
+    /// over the network. Group elements are written in compressed form.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_versioned(self, &mut writer, true)
+    }
+
+    /// Serialize the proof into bytes, writing group elements in
+    /// uncompressed form.
+    pub fn write_uncompressed<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_versioned(self, &mut writer, false)
     }
 
-    /// Deserialize the proof from bytes.
-    pub fn read<R: Read>(mut _reader: R) -> io::Result<Self> {
-        // TODO: implement serialization
-        unimplemented!()
+    /// Deserialize the proof from bytes. Proofs usually come from untrusted
+    /// parties, so every group element is checked to be on the curve and in
+    /// the prime order subgroup.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        read_versioned(&mut reader, true)
     }
 }
 
@@ -127,16 +156,28 @@ impl<E: PairingEngine> Default for VerifyingKey<E> {
 
 impl<E: PairingEngine> VerifyingKey<E> {
     /// Serialize the verification key into bytes, for storage on disk
-    /// or transmission over the network.
-    pub fn write<W: Write>(&self, mut _writer: W) -> io::Result<()> {
-        // TODO: implement serialization
-        unimplemented!()
+    /// or transmission over the network. Group elements are written in
+    /// compressed form.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_versioned(self, &mut writer, true)
+    }
+
+    /// Serialize the verification key into bytes, writing group elements in
+    /// uncompressed form.
+    pub fn write_uncompressed<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_versioned(self, &mut writer, false)
+    }
+
+    /// Deserialize the verification key from bytes, checking that every
+    /// group element is on the curve and in the prime order subgroup.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        read_versioned(&mut reader, true)
     }
 
-    /// Deserialize the verification key from bytes.
-    pub fn read<R: Read>(mut _reader: R) -> io::Result<Self> {
-        // TODO: implement serialization
-        unimplemented!()
+    /// A stable fingerprint of the verification key: the BLAKE2s hash of its
+    /// compressed canonical serialization.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        fingerprint(self)
     }
 }
 
@@ -151,22 +192,136 @@ pub struct Parameters<E: PairingEngine> {
     pub b_g2_query: Vec<E::G2Affine>,
     pub h_query: Vec<E::G1Affine>,
     pub l_query: Vec<E::G1Affine>,
+    /// The digest of the circuit that the parameters were generated for,
+    /// against which the prover checks the circuit it is given.
+    pub circuit_digest: CircuitDigest,
+}
+
+/// The secret randomness from which [`Parameters`] were generated.
+///
+/// Anyone who knows the trapdoor can forge proofs for any statement with
+/// [`simulate_proof`], so it must be destroyed once the parameters are
+/// generated. It is only useful for testing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trapdoor<E: PairingEngine> {
+    pub alpha: E::Fr,
+    pub beta: E::Fr,
+    pub gamma: E::Fr,
+    pub delta: E::Fr,
+    pub tau: E::Fr,
+    pub g1_generator: E::G1Affine,
+    pub g2_generator: E::G2Affine,
+    /// The discrete logarithms of `vk.gamma_abc_g1`, the bases of the public
+    /// inputs, to the base `g1_generator`.
+    pub input_base_logs: Vec<E::Fr>,
 }
 
 impl<E: PairingEngine> Parameters<E> {
-    /// Serialize the parameters to bytes.
-    pub fn write<W: Write>(&self, mut _writer: W) -> io::Result<()> {
-        // TODO: implement serialization
-        unimplemented!()
+    /// Serialize the parameters to bytes. Group elements are written in
+    /// compressed form.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_versioned(self, &mut writer, true)
+    }
+
+    /// Serialize the parameters to bytes, writing group elements in
+    /// uncompressed form. This is larger, but much faster to read back.
+    pub fn write_uncompressed<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_versioned(self, &mut writer, false)
     }
 
     /// Deserialize the public parameters from bytes.
-    pub fn read<R: Read>(mut _reader: R, _checked: bool) -> io::Result<Self> {
-        // TODO: implement serialization
-        unimplemented!()
+    ///
+    /// If `checked` is set, every group element is checked to be on the curve
+    /// and in the prime order subgroup. Unchecked reads of uncompressed
+    /// parameters skip these checks, and should only be used for trusted
+    /// input. Compressed points are always validated while decompressing.
+    pub fn read<R: Read>(mut reader: R, checked: bool) -> io::Result<Self> {
+        read_versioned(&mut reader, checked)
+    }
+
+    /// A stable fingerprint of the parameters, including the verification
+    /// key: the BLAKE2s hash of their compressed canonical serialization.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        fingerprint(self)
+    }
+}
+
+/// Version of the binary format produced by the `write` methods of
+/// [`Proof`], [`VerifyingKey`] and [`Parameters`].
+///
+/// Every encoding starts with this version byte, followed by a byte
+/// indicating whether group elements are compressed, followed by the
+/// canonical serialization of the value.
+///
+/// Version 2 added the circuit digest to the end of [`Parameters`].
+pub const SERIALIZATION_VERSION: u8 = 2;
+
+const COMPRESSED: u8 = 0;
+const UNCOMPRESSED: u8 = 1;
+
+pub(crate) fn write_versioned<T: CanonicalSerialize, W: Write>(
+    value: &T,
+    writer: &mut W,
+    compressed: bool,
+) -> io::Result<()> {
+    let mode = if compressed { COMPRESSED } else { UNCOMPRESSED };
+    writer.write_all(&[SERIALIZATION_VERSION, mode])?;
+    if compressed {
+        value.serialize(writer)?;
+    } else {
+        value.serialize_uncompressed(writer)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn read_versioned<T: CanonicalDeserialize, R: Read>(
+    reader: &mut R,
+    checked: bool,
+) -> io::Result<T> {
+    let compressed = read_header(reader)?;
+    Ok(deserialize_with_mode(reader, compressed, checked)?)
+}
+
+/// Reads the header written by `write_versioned`, and returns whether the
+/// value that follows it is compressed.
+pub(crate) fn read_header<R: Read>(reader: &mut R) -> io::Result<bool> {
+    let mut header = [0u8; 2];
+    reader.read_exact(&mut header)?;
+    if header[0] != SERIALIZATION_VERSION {
+        return Err(SerializationError::InvalidData.into());
+    }
+    match header[1] {
+        COMPRESSED => Ok(true),
+        UNCOMPRESSED => Ok(false),
+        _ => Err(SerializationError::InvalidData.into()),
     }
 }
 
+pub(crate) fn deserialize_with_mode<T: CanonicalDeserialize, R: Read>(
+    reader: &mut R,
+    compressed: bool,
+    checked: bool,
+) -> Result<T, SerializationError> {
+    if compressed {
+        T::deserialize(reader)
+    } else if checked {
+        T::deserialize_uncompressed(reader)
+    } else {
+        T::deserialize_unchecked(reader)
+    }
+}
+
+/// Hashes the compressed canonical serialization of `value` with BLAKE2s.
+fn fingerprint<T: CanonicalSerialize>(value: &T) -> [u8; 32] {
+    let mut bytes = Vec::with_capacity(value.serialized_size());
+    value
+        .serialize(&mut bytes)
+        .expect("serializing into a vector never fails");
+    let mut fingerprint = [0u8; 32];
+    fingerprint.copy_from_slice(Blake2s::digest(&bytes).as_slice());
+    fingerprint
+}
+
 /// Preprocessed verification key parameters that enable faster verification
 /// at the expense of larger size in memory.
 #[derive(Clone, Debug, PartialEq)]
@@ -204,7 +359,7 @@ impl<E: PairingEngine> Default for PreparedVerifyingKey<E> {
 
 impl<E: PairingEngine> ToBytes for PreparedVerifyingKey<E> {
     fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
-        self.vk.write(&mut writer)?;
+        ToBytes::write(&self.vk, &mut writer)?;
         self.alpha_g1_beta_g2.write(&mut writer)?;
         self.gamma_g2_neg_pc.write(&mut writer)?;
         self.delta_g2_neg_pc.write(&mut writer)?;
@@ -228,6 +383,23 @@ fn push_constraints<F: Field>(
     }
 }
 
+/// Computes the digest of a circuit that was synthesized into the matrices
+/// `at`, `bt` and `ct` over `num_inputs` input variables, including the "one"
+/// variable, and `num_aux` auxiliary variables.
+pub(crate) fn circuit_digest<F: Field>(
+    num_inputs: usize,
+    num_aux: usize,
+    at: &[Vec<(F, Index)>],
+    bt: &[Vec<(F, Index)>],
+    ct: &[Vec<(F, Index)>],
+) -> CircuitDigest {
+    let mut hasher = CircuitHasher::with_variables(num_inputs, num_aux);
+    for ((a, b), c) in at.iter().zip(bt).zip(ct) {
+        hasher.hash_constraint(a, b, c);
+    }
+    hasher.finish()
+}
+
 impl<E: PairingEngine> Parameters<E> {
     pub fn get_vk(&self, _: usize) -> Result<VerifyingKey<E>, SynthesisError> {
         Ok(self.vk.clone())
@@ -5,12 +5,18 @@ use algebra_core::{
     UniformRand, Zero,
 };
 
-use crate::{push_constraints, r1cs_to_qap::R1CStoQAP, Parameters, Proof, String, Vec};
+use crate::{
+    circuit_digest, prover::ParameterSource, push_constraints, r1cs_to_qap::R1CStoQAP, Cow,
+    Parameters, Proof, String, Vec,
+};
 
 use r1cs_core::{
-    ConstraintSynthesizer, ConstraintSystem, Index, LinearCombination, SynthesisError, Variable,
+    CancellationToken, CircuitDigest, ConstraintSynthesizer, ConstraintSystem, ForkSynthesizer,
+    Index, LinearCombination, ProverObserver, ProverPhase, R1CSRecorder, SynthesisError, Variable,
+    WitnessGenerator,
 };
 
+use core::ops::Range;
 use ff_fft::{cfg_into_iter, EvaluationDomain};
 
 #[cfg(feature = "parallel")]
@@ -95,6 +101,58 @@ impl<E: PairingEngine> ConstraintSystem<E::Fr> for ProvingAssignment<E> {
     fn num_constraints(&self) -> usize {
         self.at.len()
     }
+
+    fn fork<S: ForkSynthesizer<E::Fr>>(
+        &mut self,
+        forks: Vec<S>,
+    ) -> Result<Vec<S::Output>, SynthesisError> {
+        let synthesized = cfg_into_iter!(forks)
+            .map(|fork| -> Result<_, SynthesisError> {
+                let mut prover = ProvingAssignment {
+                    at: vec![],
+                    bt: vec![],
+                    ct: vec![],
+                    input_assignment: vec![E::Fr::one()],
+                    aux_assignment: vec![],
+                };
+                let output = fork.synthesize(&mut prover)?;
+                Ok((prover, output))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut outputs = Vec::with_capacity(synthesized.len());
+        for (prover, output) in synthesized {
+            self.append(prover);
+            outputs.push(output);
+        }
+        Ok(outputs)
+    }
+}
+
+impl<E: PairingEngine> ProvingAssignment<E> {
+    /// Appends the variables and constraints of `fork`, which was synthesized
+    /// on top of just the "one" input variable. Its other variables are
+    /// moved to the ranges that follow the variables of `self`, as if it had
+    /// been synthesized into `self`.
+    fn append(&mut self, fork: Self) {
+        let input_offset = self.input_assignment.len() - 1;
+        let aux_offset = self.aux_assignment.len();
+        let relocate = |row: Vec<(E::Fr, Index)>| {
+            row.into_iter()
+                .map(|(coeff, index)| match index {
+                    Index::Input(0) => (coeff, index),
+                    Index::Input(i) => (coeff, Index::Input(input_offset + i)),
+                    Index::Aux(i) => (coeff, Index::Aux(aux_offset + i)),
+                })
+                .collect::<Vec<_>>()
+        };
+        self.at.extend(fork.at.into_iter().map(relocate));
+        self.bt.extend(fork.bt.into_iter().map(relocate));
+        self.ct.extend(fork.ct.into_iter().map(relocate));
+        self.input_assignment
+            .extend(fork.input_assignment.into_iter().skip(1));
+        self.aux_assignment.extend(fork.aux_assignment);
+    }
 }
 
 pub fn create_random_proof<E, C, D, R>(
@@ -132,12 +190,301 @@ pub fn create_proof<E, C, D>(
     r: E::Fr,
     s: E::Fr,
 ) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+{
+    create_proof_with_source::<E, C, D, _>(circuit, params, r, s)
+}
+
+pub fn create_random_proof_with_source<E, C, D, R, P>(
+    circuit: C,
+    params: P,
+    rng: &mut R,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+    P: ParameterSource<E>,
+{
+    let r = E::Fr::rand(rng);
+    let s = E::Fr::rand(rng);
+
+    create_proof_with_source::<E, C, D, P>(circuit, params, r, s)
+}
+
+pub fn create_proof_with_source<E, C, D, P>(
+    circuit: C,
+    params: P,
+    r: E::Fr,
+    s: E::Fr,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+    P: ParameterSource<E>,
+{
+    create_proof_with_observer::<E, C, D, P, _>(
+        circuit,
+        params,
+        r,
+        s,
+        &|_: ProverPhase, _: f64| {},
+        &CancellationToken::new(),
+    )
+}
+
+pub fn create_random_proof_with_observer<E, C, D, R, P, O>(
+    circuit: C,
+    params: P,
+    rng: &mut R,
+    observer: &O,
+    cancel: &CancellationToken,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+    P: ParameterSource<E>,
+    O: ProverObserver,
+{
+    let r = E::Fr::rand(rng);
+    let s = E::Fr::rand(rng);
+
+    create_proof_with_observer::<E, C, D, P, O>(circuit, params, r, s, observer, cancel)
+}
+
+/// Creates a proof, reporting progress to `observer` and stopping with
+/// `SynthesisError::Cancelled` as soon as possible once `cancel` is
+/// cancelled.
+pub fn create_proof_with_observer<E, C, D, P, O>(
+    circuit: C,
+    params: P,
+    r: E::Fr,
+    s: E::Fr,
+    observer: &O,
+    cancel: &CancellationToken,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+    P: ParameterSource<E>,
+    O: ProverObserver,
+{
+    let prover_time = start_timer!(|| "Prover");
+    let progress = Progress {
+        observer,
+        cancel,
+        phase: ProverPhase::Synthesis,
+    };
+    let prover = synthesize(circuit, &progress)?;
+    check_circuit_digest(&prover, params.circuit_digest())?;
+
+    let witness_map_time = start_timer!(|| "R1CS to QAP witness map");
+    let progress = progress.phase(ProverPhase::WitnessMap);
+    progress.report(0.0)?;
+    let h = R1CStoQAP::witness_map::<E, D>(&prover)?;
+    progress.report(1.0)?;
+    end_timer!(witness_map_time);
+
+    let proof = prove_assignment(prover, h, params, r, s, &progress)?;
+    end_timer!(prover_time);
+
+    Ok(proof)
+}
+
+/// Proves many instances of the same circuit at once, sharing the evaluation
+/// domain between them and computing the witness maps and multi-scalar
+/// multiplications of the instances in parallel.
+///
+/// The circuits are synthesized and proven a few at a time, one per thread,
+/// so that only that many witnesses are held in memory at once.
+///
+/// The randomness of each proof is sampled from `rng` in order, so the
+/// proofs are exactly those that calling `create_random_proof` on each
+/// circuit in turn would produce.
+pub fn create_random_proofs_batch<E, C, D, R>(
+    circuits: Vec<C>,
+    params: &Parameters<E>,
+    rng: &mut R,
+) -> Result<Vec<Proof<E>>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr> + Sync,
+    R: Rng,
+{
+    let prover_time = start_timer!(|| format!("Batch prover ({} proofs)", circuits.len()));
+    let mut randomness = circuits
+        .iter()
+        .map(|_| {
+            let r = E::Fr::rand(rng);
+            let s = E::Fr::rand(rng);
+            (r, s)
+        })
+        .collect::<Vec<_>>()
+        .into_iter();
+
+    let cancel = CancellationToken::new();
+    let progress = Progress {
+        observer: &|_: ProverPhase, _: f64| {},
+        cancel: &cancel,
+        phase: ProverPhase::Synthesis,
+    };
+
+    #[cfg(feature = "parallel")]
+    let chunk_size = rayon::current_num_threads();
+    #[cfg(not(feature = "parallel"))]
+    let chunk_size = 1;
+
+    // Every instance of one circuit has the same size, and so the same
+    // domain. An instance of a different size gets a domain of its own.
+    let domain_size =
+        |prover: &ProvingAssignment<E>| prover.num_constraints() + prover.input_assignment.len();
+    let mut shared_domain = None;
+
+    let mut proofs = Vec::with_capacity(circuits.len());
+    let mut circuits = circuits.into_iter();
+    loop {
+        let provers = circuits
+            .by_ref()
+            .take(chunk_size)
+            .map(|circuit| -> Result<_, SynthesisError> {
+                let prover = synthesize(circuit, &progress)?;
+                check_circuit_digest(&prover, params.circuit_digest)?;
+                Ok(prover)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if provers.is_empty() {
+            break;
+        }
+        if shared_domain.is_none() {
+            let size = domain_size(&provers[0]);
+            let domain = D::new(size).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+            shared_domain = Some((size, domain));
+        }
+        let (shared_size, domain) = shared_domain.as_ref().unwrap();
+        let randomness = randomness.by_ref().take(provers.len()).collect::<Vec<_>>();
+
+        let chunk = cfg_into_iter!(provers)
+            .zip(randomness)
+            .map(|(prover, (r, s))| {
+                let h = if domain_size(&prover) == *shared_size {
+                    R1CStoQAP::witness_map_with_domain::<E, D>(&prover, domain)
+                } else {
+                    R1CStoQAP::witness_map::<E, D>(&prover)
+                }?;
+                prove_assignment(prover, h, params, r, s, &progress)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        proofs.extend(chunk);
+    }
+    end_timer!(prover_time);
+
+    Ok(proofs)
+}
+
+pub fn create_random_proof_with_matrices<E, C, D, R>(
+    circuit: C,
+    matrices: &R1CSRecorder<E::Fr>,
+    params: &Parameters<E>,
+    rng: &mut R,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    D: EvaluationDomain<E::Fr>,
+    R: Rng,
+{
+    let r = E::Fr::rand(rng);
+    let s = E::Fr::rand(rng);
+
+    create_proof_with_matrices::<E, C, D>(circuit, matrices, params, r, s)
+}
+
+/// Creates a proof by synthesizing only the witness of `circuit`, and
+/// taking its constraints from `matrices`, which recorded them beforehand.
+/// The closures that build the linear combinations of its constraints are
+/// never invoked, although gadgets may still build the linear combinations
+/// they hold outside of them; see [`WitnessGenerator`].
+///
+/// Fails with `SynthesisError::CircuitMismatch` if the numbers of variables
+/// and constraints of `circuit` differ from those recorded in `matrices`, or
+/// if `matrices` were not recorded from the circuit that `params` were
+/// generated for.
+pub fn create_proof_with_matrices<E, C, D>(
+    circuit: C,
+    matrices: &R1CSRecorder<E::Fr>,
+    params: &Parameters<E>,
+    r: E::Fr,
+    s: E::Fr,
+) -> Result<Proof<E>, SynthesisError>
 where
     E: PairingEngine,
     C: ConstraintSynthesizer<E::Fr>,
     D: EvaluationDomain<E::Fr>,
 {
     let prover_time = start_timer!(|| "Prover");
+
+    let synthesis_time = start_timer!(|| "Witness generation");
+    let mut witness = WitnessGenerator::new();
+    circuit.generate_constraints(&mut witness)?;
+    end_timer!(synthesis_time);
+    if witness.input_assignment.len() != matrices.num_inputs()
+        || witness.aux_assignment.len() != matrices.num_aux()
+        || witness.num_constraints() != matrices.num_constraints()
+    {
+        return Err(SynthesisError::CircuitMismatch);
+    }
+    if matrices.digest() != params.circuit_digest {
+        return Err(SynthesisError::CircuitMismatch);
+    }
+
+    let witness_map_time = start_timer!(|| "R1CS to QAP witness map");
+    let domain = D::new(matrices.num_constraints() + matrices.num_inputs())
+        .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+    let h = R1CStoQAP::witness_map_with_matrices::<E, D>(
+        [&matrices.a, &matrices.b, &matrices.c],
+        &witness.input_assignment,
+        &witness.aux_assignment,
+        &domain,
+    )?;
+    end_timer!(witness_map_time);
+
+    let prover = ProvingAssignment {
+        at: vec![],
+        bt: vec![],
+        ct: vec![],
+        input_assignment: witness.input_assignment,
+        aux_assignment: witness.aux_assignment,
+    };
+    let cancel = CancellationToken::new();
+    let progress = Progress {
+        observer: &|_: ProverPhase, _: f64| {},
+        cancel: &cancel,
+        phase: ProverPhase::Synthesis,
+    };
+    let proof = prove_assignment(prover, h, params, r, s, &progress)?;
+    end_timer!(prover_time);
+
+    Ok(proof)
+}
+
+fn synthesize<E, C, O>(
+    circuit: C,
+    progress: &Progress<'_, O>,
+) -> Result<ProvingAssignment<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    O: ProverObserver,
+{
     let mut prover = ProvingAssignment {
         at: vec![],
         bt: vec![],
@@ -151,13 +498,50 @@ where
 
     // Synthesize the circuit.
     let synthesis_time = start_timer!(|| "Constraint synthesis");
+    let progress = progress.phase(ProverPhase::Synthesis);
+    progress.report(0.0)?;
     circuit.generate_constraints(&mut prover)?;
+    progress.report(1.0)?;
     end_timer!(synthesis_time);
 
-    let witness_map_time = start_timer!(|| "R1CS to QAP witness map");
-    let h = R1CStoQAP::witness_map::<E, D>(&prover)?;
-    end_timer!(witness_map_time);
+    Ok(prover)
+}
 
+/// Fails with `SynthesisError::CircuitMismatch` unless `prover` synthesized
+/// the circuit whose digest is `expected`, as a proof for any other circuit
+/// would not verify.
+fn check_circuit_digest<E: PairingEngine>(
+    prover: &ProvingAssignment<E>,
+    expected: CircuitDigest,
+) -> Result<(), SynthesisError> {
+    let digest = circuit_digest(
+        prover.input_assignment.len(),
+        prover.aux_assignment.len(),
+        &prover.at,
+        &prover.bt,
+        &prover.ct,
+    );
+    if digest != expected {
+        return Err(SynthesisError::CircuitMismatch);
+    }
+    Ok(())
+}
+
+/// Computes the proof elements from a synthesized circuit and its QAP
+/// witness `h`.
+fn prove_assignment<E, P, O>(
+    prover: ProvingAssignment<E>,
+    h: Vec<E::Fr>,
+    mut params: P,
+    r: E::Fr,
+    s: E::Fr,
+    progress: &Progress<'_, O>,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    P: ParameterSource<E>,
+    O: ProverObserver,
+{
     let input_assignment = prover.input_assignment[1..]
         .into_iter()
         .map(|s| s.into_repr())
@@ -171,22 +555,41 @@ where
 
     let h_assignment = cfg_into_iter!(h).map(|s| s.into_repr()).collect::<Vec<_>>();
 
+    let chunk_size = params.chunk_size();
+    let delta_g1 = params.delta_g1();
+
     // Compute A
     let a_acc_time = start_timer!(|| "Compute A");
-    let a_query = params.get_a_query_full()?;
-    let r_g1 = params.delta_g1.mul(r);
-
-    let g_a = calculate_coeff(r_g1, a_query, params.vk.alpha_g1, &assignment);
+    let r_g1 = delta_g1.mul(r);
+    let alpha_g1 = params.vk().alpha_g1;
+
+    let g_a = calculate_coeff(
+        r_g1,
+        &mut params,
+        P::a_query,
+        chunk_size,
+        alpha_g1,
+        &assignment,
+        progress.phase(ProverPhase::Msm("A")),
+    )?;
 
     end_timer!(a_acc_time);
 
     // Compute B in G1 if needed
     let g1_b = if r != E::Fr::zero() {
         let b_g1_acc_time = start_timer!(|| "Compute B in G1");
-        let s_g1 = params.delta_g1.mul(s);
-        let b_query = params.get_b_g1_query_full()?;
-
-        let g1_b = calculate_coeff(s_g1, b_query, params.beta_g1, &assignment);
+        let s_g1 = delta_g1.mul(s);
+        let beta_g1 = params.beta_g1();
+
+        let g1_b = calculate_coeff(
+            s_g1,
+            &mut params,
+            P::b_g1_query,
+            chunk_size,
+            beta_g1,
+            &assignment,
+            progress.phase(ProverPhase::Msm("B in G1")),
+        )?;
 
         end_timer!(b_g1_acc_time);
 
@@ -197,24 +600,44 @@ where
 
     // Compute B in G2
     let b_g2_acc_time = start_timer!(|| "Compute B in G2");
-    let b_query = params.get_b_g2_query_full()?;
-    let s_g2 = params.vk.delta_g2.mul(s);
-    let g2_b = calculate_coeff(s_g2, b_query, params.vk.beta_g2, &assignment);
+    let s_g2 = params.vk().delta_g2.mul(s);
+    let beta_g2 = params.vk().beta_g2;
+    let g2_b = calculate_coeff(
+        s_g2,
+        &mut params,
+        P::b_g2_query,
+        chunk_size,
+        beta_g2,
+        &assignment,
+        progress.phase(ProverPhase::Msm("B in G2")),
+    )?;
 
     end_timer!(b_g2_acc_time);
 
     // Compute C
     let c_acc_time = start_timer!(|| "Compute C");
 
-    let h_query = params.get_h_query_full()?;
-    let h_acc = VariableBaseMSM::multi_scalar_mul(&h_query, &h_assignment);
-
-    let l_aux_source = params.get_l_query_full()?;
-    let l_aux_acc = VariableBaseMSM::multi_scalar_mul(l_aux_source, &aux_assignment);
+    let h_acc = chunked_msm(
+        &mut params,
+        P::h_query,
+        chunk_size,
+        0,
+        &h_assignment,
+        progress.phase(ProverPhase::Msm("H")),
+    )?;
+
+    let l_aux_acc = chunked_msm(
+        &mut params,
+        P::l_query,
+        chunk_size,
+        0,
+        &aux_assignment,
+        progress.phase(ProverPhase::Msm("L")),
+    )?;
 
     let s_g_a = g_a.mul(s);
     let r_g1_b = g1_b.mul(r);
-    let r_s_delta_g1 = params.delta_g1.into_projective().mul(r).mul(s);
+    let r_s_delta_g1 = delta_g1.into_projective().mul(r).mul(s);
 
     let mut g_c = s_g_a;
     g_c += &r_g1_b;
@@ -223,8 +646,6 @@ where
     g_c += &h_acc;
     end_timer!(c_acc_time);
 
-    end_timer!(prover_time);
-
     Ok(Proof {
         a: g_a.into_affine(),
         b: g2_b.into_affine(),
@@ -232,19 +653,89 @@ where
     })
 }
 
-fn calculate_coeff<G: AffineCurve>(
+/// Reports progress through a phase to an observer, and checks for
+/// cancellation every time that it does.
+struct Progress<'a, O: ProverObserver> {
+    observer: &'a O,
+    cancel: &'a CancellationToken,
+    phase: ProverPhase,
+}
+
+impl<'a, O: ProverObserver> Progress<'a, O> {
+    fn phase(&self, phase: ProverPhase) -> Self {
+        Progress {
+            observer: self.observer,
+            cancel: self.cancel,
+            phase,
+        }
+    }
+
+    fn report(&self, fraction: f64) -> Result<(), SynthesisError> {
+        self.cancel.check()?;
+        self.observer.on_progress(self.phase, fraction);
+        Ok(())
+    }
+}
+
+fn calculate_coeff<G, P, F, O>(
     initial: G::Projective,
-    query: &[G],
+    params: &mut P,
+    query: F,
+    chunk_size: usize,
     vk_param: G,
     assignment: &[<G::ScalarField as PrimeField>::BigInt],
-) -> G::Projective {
-    let el = query[0];
-    let acc = VariableBaseMSM::multi_scalar_mul(&query[1..], assignment);
+    progress: Progress<'_, O>,
+) -> Result<G::Projective, SynthesisError>
+where
+    G: AffineCurve,
+    F: Fn(&mut P, Range<usize>) -> Result<Cow<'_, [G]>, SynthesisError>,
+    O: ProverObserver,
+{
+    let el = query(params, 0..1)?[0];
+    let acc = chunked_msm(params, query, chunk_size, 1, assignment, progress)?;
 
     let mut res = initial;
     res.add_assign_mixed(&el);
     res += &acc;
     res.add_assign_mixed(&vk_param);
 
-    res
+    Ok(res)
+}
+
+/// Computes the multi-scalar multiplication of `scalars` with the elements of
+/// `query` starting at `offset`, fetching at most `chunk_size` of them at a
+/// time. As with `VariableBaseMSM::multi_scalar_mul`, any scalars beyond the
+/// end of the query are ignored.
+///
+/// Progress is reported after every chunk.
+fn chunked_msm<G, P, F, O>(
+    params: &mut P,
+    query: F,
+    chunk_size: usize,
+    offset: usize,
+    scalars: &[<G::ScalarField as PrimeField>::BigInt],
+    progress: Progress<'_, O>,
+) -> Result<G::Projective, SynthesisError>
+where
+    G: AffineCurve,
+    F: Fn(&mut P, Range<usize>) -> Result<Cow<'_, [G]>, SynthesisError>,
+    O: ProverObserver,
+{
+    progress.report(0.0)?;
+    let mut acc = G::Projective::zero();
+    let mut start = 0;
+    while start < scalars.len() {
+        let end = start + chunk_size.min(scalars.len() - start);
+        let bases = query(params, (offset + start)..(offset + end))?;
+        if bases.is_empty() {
+            break;
+        }
+        acc += &VariableBaseMSM::multi_scalar_mul(&bases, &scalars[start..end]);
+        start = end;
+        if start < scalars.len() {
+            progress.report(start as f64 / scalars.len() as f64)?;
+        }
+    }
+    progress.report(1.0)?;
+    Ok(acc)
 }
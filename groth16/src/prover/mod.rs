@@ -1,13 +1,24 @@
-use crate::{Parameters, Proof};
-use algebra_core::PairingEngine;
+use crate::{Parameters, Proof, Vec, VerifyingKey};
+use algebra_core::{AffineCurve, Field, PairingEngine, ProjectiveCurve, UniformRand};
 use ff_fft::GeneralEvaluationDomain;
-use r1cs_core::{ConstraintSynthesizer, SynthesisError};
+use r1cs_core::{
+    CancellationToken, ConstraintSynthesizer, ProverObserver, R1CSRecorder, SynthesisError,
+};
 use rand::Rng;
 
 pub mod generic;
 
+/// Sources of proving parameters, which need not be held in memory.
+pub mod source;
+
 pub use generic::ProvingAssignment;
+#[cfg(feature = "std")]
+pub use source::FileParameters;
+pub use source::ParameterSource;
 
+/// Creates a proof for `circuit` under `params`, which fails with
+/// `SynthesisError::CircuitMismatch` if the structure of `circuit` differs from
+/// that of the circuit that `params` were generated for.
 #[inline]
 pub fn create_random_proof<E, C, R>(
     circuit: C,
@@ -24,6 +35,47 @@ where
     )
 }
 
+/// Proves many instances of the same circuit under `params`. The proofs are
+/// identical to those of calling [`create_random_proof`] on each circuit in
+/// turn with the same `rng`, but the instances share their evaluation domain
+/// and are proven in parallel.
+#[inline]
+pub fn create_random_proofs_batch<E, C, R>(
+    circuits: Vec<C>,
+    params: &Parameters<E>,
+    rng: &mut R,
+) -> Result<Vec<Proof<E>>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    R: Rng,
+{
+    self::generic::create_random_proofs_batch::<E, C, GeneralEvaluationDomain<E::Fr>, R>(
+        circuits, params, rng,
+    )
+}
+
+/// Like [`create_random_proof`], but only synthesizes the witness of
+/// `circuit`, taking its constraints from `matrices`. Recording the
+/// constraints once with an [`R1CSRecorder`] and reusing them saves building
+/// the linear combinations of the constraints for every proof.
+#[inline]
+pub fn create_random_proof_with_matrices<E, C, R>(
+    circuit: C,
+    matrices: &R1CSRecorder<E::Fr>,
+    params: &Parameters<E>,
+    rng: &mut R,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    R: Rng,
+{
+    self::generic::create_random_proof_with_matrices::<E, C, GeneralEvaluationDomain<E::Fr>, R>(
+        circuit, matrices, params, rng,
+    )
+}
+
 #[inline]
 pub fn create_proof_no_zk<E, C>(
     circuit: C,
@@ -49,3 +101,122 @@ where
 {
     self::generic::create_proof::<E, C, GeneralEvaluationDomain<E::Fr>>(circuit, params, r, s)
 }
+
+/// Like [`create_random_proof`], but reads the proving parameters from
+/// `params`, which need not be held in memory.
+#[inline]
+pub fn create_random_proof_with_source<E, C, R, P>(
+    circuit: C,
+    params: P,
+    rng: &mut R,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    R: Rng,
+    P: ParameterSource<E>,
+{
+    self::generic::create_random_proof_with_source::<E, C, GeneralEvaluationDomain<E::Fr>, R, P>(
+        circuit, params, rng,
+    )
+}
+
+/// Like [`create_proof`], but reads the proving parameters from `params`,
+/// which need not be held in memory.
+#[inline]
+pub fn create_proof_with_source<E, C, P>(
+    circuit: C,
+    params: P,
+    r: E::Fr,
+    s: E::Fr,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    P: ParameterSource<E>,
+{
+    self::generic::create_proof_with_source::<E, C, GeneralEvaluationDomain<E::Fr>, P>(
+        circuit, params, r, s,
+    )
+}
+
+/// Like [`create_random_proof`], but reports the progress of each phase of
+/// the prover to `observer`, and fails with `SynthesisError::Cancelled` once
+/// `cancel` is cancelled.
+#[inline]
+pub fn create_random_proof_with_observer<E, C, R, O>(
+    circuit: C,
+    params: &Parameters<E>,
+    rng: &mut R,
+    observer: &O,
+    cancel: &CancellationToken,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    R: Rng,
+    O: ProverObserver,
+{
+    self::generic::create_random_proof_with_observer::<E, C, GeneralEvaluationDomain<E::Fr>, R, _, O>(
+        circuit, params, rng, observer, cancel,
+    )
+}
+
+/// Like [`create_proof`], but reports the progress of each phase of the
+/// prover to `observer`, and fails with `SynthesisError::Cancelled` once
+/// `cancel` is cancelled.
+#[inline]
+pub fn create_proof_with_observer<E, C, O>(
+    circuit: C,
+    params: &Parameters<E>,
+    r: E::Fr,
+    s: E::Fr,
+    observer: &O,
+    cancel: &CancellationToken,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    O: ProverObserver,
+{
+    self::generic::create_proof_with_observer::<E, C, GeneralEvaluationDomain<E::Fr>, _, O>(
+        circuit, params, r, s, observer, cancel,
+    )
+}
+
+/// Given a proof, produces a new proof for the same statement that is
+/// distributed identically to a fresh proof, and so cannot be linked to the
+/// original.
+///
+/// For random `r1 != 0` and `r2`, the new proof is
+/// `(A / r1, r1 * B + r1 * r2 * delta, C + r2 * A)`, which satisfies the
+/// verification equation whenever the original proof does.
+pub fn rerandomize_proof<E, R>(vk: &VerifyingKey<E>, proof: &Proof<E>, rng: &mut R) -> Proof<E>
+where
+    E: PairingEngine,
+    R: Rng,
+{
+    let rerandomize_time = start_timer!(|| "Rerandomize proof");
+    let (r1, r1_inverse) = loop {
+        let r1 = E::Fr::rand(rng);
+        if let Some(r1_inverse) = r1.inverse() {
+            break (r1, r1_inverse);
+        }
+    };
+    let r2 = E::Fr::rand(rng);
+
+    let a = proof.a.mul(r1_inverse);
+
+    let mut b = proof.b.mul(r1);
+    b += &vk.delta_g2.mul(r1 * &r2);
+
+    let mut c = proof.a.mul(r2);
+    c.add_assign_mixed(&proof.c);
+    end_timer!(rerandomize_time);
+
+    Proof {
+        a: a.into_affine(),
+        b: b.into_affine(),
+        c: c.into_affine(),
+    }
+}
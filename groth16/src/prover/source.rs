@@ -0,0 +1,316 @@
+use crate::{Cow, Parameters, VerifyingKey};
+use algebra_core::PairingEngine;
+use core::ops::Range;
+use r1cs_core::{CircuitDigest, SynthesisError};
+
+#[cfg(feature = "std")]
+pub use self::file::FileParameters;
+
+/// The default number of query elements that the prover requests at once.
+/// The prover reports progress and checks for cancellation between chunks,
+/// so this bounds the work done between two reports.
+pub const DEFAULT_CHUNK_SIZE: usize = 1 << 16;
+
+/// A source of proving parameters for the prover.
+///
+/// The prover only needs the query vectors of the parameters one slice at a
+/// time, so they need not be held in memory: implementations are free to
+/// fetch each slice on demand. The prover requests at most
+/// [`chunk_size`](ParameterSource::chunk_size) elements of a query at once.
+pub trait ParameterSource<E: PairingEngine> {
+    /// The verification key.
+    fn vk(&self) -> &VerifyingKey<E>;
+
+    /// `beta` in G1.
+    fn beta_g1(&self) -> E::G1Affine;
+
+    /// `delta` in G1.
+    fn delta_g1(&self) -> E::G1Affine;
+
+    /// The digest of the circuit that the parameters were generated for.
+    fn circuit_digest(&self) -> CircuitDigest;
+
+    /// The maximum number of query elements that the prover requests at once.
+    fn chunk_size(&self) -> usize;
+
+    /// The elements of the A query in `range`, truncated to the length of the
+    /// query.
+    fn a_query(&mut self, range: Range<usize>) -> Result<Cow<'_, [E::G1Affine]>, SynthesisError>;
+
+    /// The elements of the B query in G1 in `range`, truncated to the length
+    /// of the query.
+    fn b_g1_query(&mut self, range: Range<usize>)
+        -> Result<Cow<'_, [E::G1Affine]>, SynthesisError>;
+
+    /// The elements of the B query in G2 in `range`, truncated to the length
+    /// of the query.
+    fn b_g2_query(&mut self, range: Range<usize>)
+        -> Result<Cow<'_, [E::G2Affine]>, SynthesisError>;
+
+    /// The elements of the H query in `range`, truncated to the length of the
+    /// query.
+    fn h_query(&mut self, range: Range<usize>) -> Result<Cow<'_, [E::G1Affine]>, SynthesisError>;
+
+    /// The elements of the L query in `range`, truncated to the length of the
+    /// query.
+    fn l_query(&mut self, range: Range<usize>) -> Result<Cow<'_, [E::G1Affine]>, SynthesisError>;
+}
+
+fn truncate<T>(query: &[T], range: Range<usize>) -> &[T] {
+    let end = range.end.min(query.len());
+    &query[range.start.min(end)..end]
+}
+
+/// Parameters held in memory are handed to the prover in chunks of
+/// [`DEFAULT_CHUNK_SIZE`] elements.
+impl<'a, E: PairingEngine> ParameterSource<E> for &'a Parameters<E> {
+    fn vk(&self) -> &VerifyingKey<E> {
+        &self.vk
+    }
+
+    fn beta_g1(&self) -> E::G1Affine {
+        self.beta_g1
+    }
+
+    fn delta_g1(&self) -> E::G1Affine {
+        self.delta_g1
+    }
+
+    fn circuit_digest(&self) -> CircuitDigest {
+        self.circuit_digest
+    }
+
+    fn chunk_size(&self) -> usize {
+        DEFAULT_CHUNK_SIZE
+    }
+
+    fn a_query(&mut self, range: Range<usize>) -> Result<Cow<'_, [E::G1Affine]>, SynthesisError> {
+        Ok(Cow::Borrowed(truncate(&self.a_query, range)))
+    }
+
+    fn b_g1_query(
+        &mut self,
+        range: Range<usize>,
+    ) -> Result<Cow<'_, [E::G1Affine]>, SynthesisError> {
+        Ok(Cow::Borrowed(truncate(&self.b_g1_query, range)))
+    }
+
+    fn b_g2_query(
+        &mut self,
+        range: Range<usize>,
+    ) -> Result<Cow<'_, [E::G2Affine]>, SynthesisError> {
+        Ok(Cow::Borrowed(truncate(&self.b_g2_query, range)))
+    }
+
+    fn h_query(&mut self, range: Range<usize>) -> Result<Cow<'_, [E::G1Affine]>, SynthesisError> {
+        Ok(Cow::Borrowed(truncate(&self.h_query, range)))
+    }
+
+    fn l_query(&mut self, range: Range<usize>) -> Result<Cow<'_, [E::G1Affine]>, SynthesisError> {
+        Ok(Cow::Borrowed(truncate(&self.l_query, range)))
+    }
+}
+
+#[cfg(feature = "std")]
+mod file {
+    use super::{ParameterSource, DEFAULT_CHUNK_SIZE};
+    use crate::{deserialize_with_mode, read_header, Cow, Vec, VerifyingKey};
+    use algebra_core::{
+        serialize::{CanonicalDeserialize, ConstantSerializedSize},
+        AffineCurve, PairingEngine,
+    };
+    use core::{convert::TryFrom, ops::Range};
+    use r1cs_core::{CircuitDigest, SynthesisError};
+    use std::{
+        fs::File,
+        io::{self, BufReader, Read, Seek, SeekFrom},
+        path::Path,
+    };
+
+    /// The position of a query vector within the serialized parameters.
+    #[derive(Clone, Copy, Debug)]
+    struct QueryLocation {
+        offset: u64,
+        len: usize,
+    }
+
+    /// Parameters serialized with [`Parameters::write`] or
+    /// [`Parameters::write_uncompressed`], whose query vectors are read
+    /// lazily, one chunk at a time.
+    ///
+    /// Only the verification key, `beta_g1`, `delta_g1` and the circuit digest
+    /// are kept in memory.
+    ///
+    /// [`Parameters::write`]: crate::Parameters::write
+    /// [`Parameters::write_uncompressed`]: crate::Parameters::write_uncompressed
+    pub struct FileParameters<E: PairingEngine, R = BufReader<File>> {
+        reader: R,
+        compressed: bool,
+        checked: bool,
+        chunk_size: usize,
+        vk: VerifyingKey<E>,
+        beta_g1: E::G1Affine,
+        delta_g1: E::G1Affine,
+        circuit_digest: CircuitDigest,
+        a_query: QueryLocation,
+        b_g1_query: QueryLocation,
+        b_g2_query: QueryLocation,
+        h_query: QueryLocation,
+        l_query: QueryLocation,
+    }
+
+    impl<E: PairingEngine> FileParameters<E> {
+        /// Opens the parameters stored in the file at `path`.
+        ///
+        /// If `checked` is set, every group element is checked to be on the
+        /// curve and in the prime order subgroup as it is read, as in
+        /// [`Parameters::read`](crate::Parameters::read).
+        pub fn open<P: AsRef<Path>>(path: P, checked: bool) -> io::Result<Self> {
+            Self::new(BufReader::new(File::open(path)?), checked)
+        }
+    }
+
+    impl<E: PairingEngine, R: Read + Seek> FileParameters<E, R> {
+        /// Reads the verification key and the layout of the query vectors
+        /// from `reader`, which must be positioned at the start of the
+        /// serialized parameters.
+        pub fn new(mut reader: R, checked: bool) -> io::Result<Self> {
+            let compressed = read_header(&mut reader)?;
+            let vk = deserialize_with_mode(&mut reader, compressed, checked)?;
+            let beta_g1 = deserialize_with_mode(&mut reader, compressed, checked)?;
+            let delta_g1 = deserialize_with_mode(&mut reader, compressed, checked)?;
+            let a_query = skip_query::<E::G1Affine, _>(&mut reader, compressed)?;
+            let b_g1_query = skip_query::<E::G1Affine, _>(&mut reader, compressed)?;
+            let b_g2_query = skip_query::<E::G2Affine, _>(&mut reader, compressed)?;
+            let h_query = skip_query::<E::G1Affine, _>(&mut reader, compressed)?;
+            let l_query = skip_query::<E::G1Affine, _>(&mut reader, compressed)?;
+            let circuit_digest = deserialize_with_mode(&mut reader, compressed, checked)?;
+
+            Ok(Self {
+                reader,
+                compressed,
+                checked,
+                chunk_size: DEFAULT_CHUNK_SIZE,
+                vk,
+                beta_g1,
+                delta_g1,
+                circuit_digest,
+                a_query,
+                b_g1_query,
+                b_g2_query,
+                h_query,
+                l_query,
+            })
+        }
+
+        /// Sets the number of query elements read from disk at once, which
+        /// bounds the memory used by the prover for the queries.
+        pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+            assert!(chunk_size > 0, "chunk size must be positive");
+            self.chunk_size = chunk_size;
+            self
+        }
+
+        fn read_query<G: AffineCurve>(
+            &mut self,
+            location: QueryLocation,
+            range: Range<usize>,
+        ) -> Result<Cow<'_, [G]>, SynthesisError> {
+            let end = range.end.min(location.len);
+            let start = range.start.min(end);
+            let offset = location.offset + (start * element_size::<G>(self.compressed)) as u64;
+            self.reader.seek(SeekFrom::Start(offset))?;
+
+            let mut elements = Vec::with_capacity(end - start);
+            for _ in start..end {
+                let element =
+                    deserialize_with_mode(&mut self.reader, self.compressed, self.checked)
+                        .map_err(io::Error::from)?;
+                elements.push(element);
+            }
+            Ok(Cow::Owned(elements))
+        }
+    }
+
+    fn element_size<G: ConstantSerializedSize>(compressed: bool) -> usize {
+        if compressed {
+            G::SERIALIZED_SIZE
+        } else {
+            G::UNCOMPRESSED_SIZE
+        }
+    }
+
+    /// Records the position of the query vector at the current position of
+    /// `reader`, and moves past it.
+    fn skip_query<G: AffineCurve, R: Read + Seek>(
+        reader: &mut R,
+        compressed: bool,
+    ) -> io::Result<QueryLocation> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid query length");
+        let len = usize::try_from(u64::deserialize(reader)?).map_err(|_| invalid())?;
+        let offset = reader.seek(SeekFrom::Current(0))?;
+        let size = len
+            .checked_mul(element_size::<G>(compressed))
+            .and_then(|size| i64::try_from(size).ok())
+            .ok_or_else(invalid)?;
+        reader.seek(SeekFrom::Current(size))?;
+        Ok(QueryLocation { offset, len })
+    }
+
+    impl<E: PairingEngine, R: Read + Seek> ParameterSource<E> for FileParameters<E, R> {
+        fn vk(&self) -> &VerifyingKey<E> {
+            &self.vk
+        }
+
+        fn beta_g1(&self) -> E::G1Affine {
+            self.beta_g1
+        }
+
+        fn delta_g1(&self) -> E::G1Affine {
+            self.delta_g1
+        }
+
+        fn circuit_digest(&self) -> CircuitDigest {
+            self.circuit_digest
+        }
+
+        fn chunk_size(&self) -> usize {
+            self.chunk_size
+        }
+
+        fn a_query(
+            &mut self,
+            range: Range<usize>,
+        ) -> Result<Cow<'_, [E::G1Affine]>, SynthesisError> {
+            self.read_query(self.a_query, range)
+        }
+
+        fn b_g1_query(
+            &mut self,
+            range: Range<usize>,
+        ) -> Result<Cow<'_, [E::G1Affine]>, SynthesisError> {
+            self.read_query(self.b_g1_query, range)
+        }
+
+        fn b_g2_query(
+            &mut self,
+            range: Range<usize>,
+        ) -> Result<Cow<'_, [E::G2Affine]>, SynthesisError> {
+            self.read_query(self.b_g2_query, range)
+        }
+
+        fn h_query(
+            &mut self,
+            range: Range<usize>,
+        ) -> Result<Cow<'_, [E::G1Affine]>, SynthesisError> {
+            self.read_query(self.h_query, range)
+        }
+
+        fn l_query(
+            &mut self,
+            range: Range<usize>,
+        ) -> Result<Cow<'_, [E::G1Affine]>, SynthesisError> {
+            self.read_query(self.l_query, range)
+        }
+    }
+}
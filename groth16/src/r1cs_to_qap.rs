@@ -109,16 +109,40 @@ impl R1CStoQAP {
     #[inline]
     pub(crate) fn witness_map<E: PairingEngine, D: EvaluationDomain<E::Fr>>(
         prover: &ProvingAssignment<E>,
+    ) -> Result<Vec<E::Fr>, SynthesisError> {
+        let domain = D::new(prover.num_constraints() + prover.input_assignment.len())
+            .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+        Self::witness_map_with_domain::<E, D>(prover, &domain)
+    }
+
+    /// Like `witness_map`, but over a `domain` that was already constructed
+    /// for a circuit of the same size.
+    pub(crate) fn witness_map_with_domain<E: PairingEngine, D: EvaluationDomain<E::Fr>>(
+        prover: &ProvingAssignment<E>,
+        domain: &D,
+    ) -> Result<Vec<E::Fr>, SynthesisError> {
+        Self::witness_map_with_matrices::<E, D>(
+            [&prover.at, &prover.bt, &prover.ct],
+            &prover.input_assignment,
+            &prover.aux_assignment,
+            domain,
+        )
+    }
+
+    /// Like `witness_map_with_domain`, but with the A, B and C matrices of
+    /// the constraints given apart from the assignment.
+    pub(crate) fn witness_map_with_matrices<E: PairingEngine, D: EvaluationDomain<E::Fr>>(
+        [at, bt, ct]: [&[Vec<(E::Fr, Index)>]; 3],
+        input_assignment: &[E::Fr],
+        aux_assignment: &[E::Fr],
+        domain: &D,
     ) -> Result<Vec<E::Fr>, SynthesisError> {
         let zero = E::Fr::zero();
-        let num_inputs = prover.input_assignment.len();
-        let num_constraints = prover.num_constraints();
+        let num_inputs = input_assignment.len();
+        let num_constraints = at.len();
 
-        let full_input_assignment =
-            [&prover.input_assignment[..], &prover.aux_assignment[..]].concat();
+        let full_input_assignment = [input_assignment, aux_assignment].concat();
 
-        let domain =
-            D::new(num_constraints + num_inputs).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
         let domain_size = domain.size();
 
         let mut a = vec![zero; domain_size];
@@ -126,8 +150,8 @@ impl R1CStoQAP {
 
         cfg_iter_mut!(a[..num_constraints])
             .zip(cfg_iter_mut!(b[..num_constraints]))
-            .zip(cfg_iter!(&prover.at))
-            .zip(cfg_iter!(&prover.bt))
+            .zip(cfg_iter!(at))
+            .zip(cfg_iter!(bt))
             .for_each(|(((a, b), at_i), bt_i)| {
                 *a = evaluate_constraint(&at_i, &full_input_assignment, num_inputs);
                 *b = evaluate_constraint(&bt_i, &full_input_assignment, num_inputs);
@@ -148,10 +172,10 @@ impl R1CStoQAP {
         drop(b);
 
         let mut c = vec![zero; domain_size];
-        cfg_iter_mut!(c[..prover.num_constraints()])
+        cfg_iter_mut!(c[..num_constraints])
             .enumerate()
             .for_each(|(i, c)| {
-                *c = evaluate_constraint(&prover.ct[i], &full_input_assignment, num_inputs);
+                *c = evaluate_constraint(&ct[i], &full_input_assignment, num_inputs);
             });
 
         domain.ifft_in_place(&mut c);
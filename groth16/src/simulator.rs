@@ -0,0 +1,46 @@
+use crate::{Proof, Trapdoor};
+use algebra_core::{AffineCurve, Field, PairingEngine, UniformRand};
+use r1cs_core::SynthesisError;
+use rand::Rng;
+
+/// Forges a proof that `public_inputs` is a valid statement, using the
+/// trapdoor of the parameters instead of a witness.
+///
+/// For random `a` and `b`, the proof is `(a * G, b * H, c * G)` where
+/// `c = (a * b - alpha * beta - gamma * sum_i x_i * gamma_abc_i) / delta`,
+/// which satisfies the verification equation. Simulated proofs are distributed
+/// identically to honestly generated ones.
+pub fn simulate_proof<E, R>(
+    trapdoor: &Trapdoor<E>,
+    public_inputs: &[E::Fr],
+    rng: &mut R,
+) -> Result<Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    R: Rng,
+{
+    if (public_inputs.len() + 1) != trapdoor.input_base_logs.len() {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+    let delta_inverse = trapdoor
+        .delta
+        .inverse()
+        .ok_or(SynthesisError::UnexpectedIdentity)?;
+
+    let a = E::Fr::rand(rng);
+    let b = E::Fr::rand(rng);
+
+    let mut inputs = trapdoor.input_base_logs[0];
+    for (input, log) in public_inputs.iter().zip(&trapdoor.input_base_logs[1..]) {
+        inputs += &(*input * log);
+    }
+
+    let c = (a * &b - &(trapdoor.alpha * &trapdoor.beta) - &(trapdoor.gamma * &inputs))
+        * &delta_inverse;
+
+    Ok(Proof {
+        a: trapdoor.g1_generator.mul(a).into(),
+        b: trapdoor.g2_generator.mul(b).into(),
+        c: trapdoor.g1_generator.mul(c).into(),
+    })
+}
@@ -0,0 +1,355 @@
+use crate::{Proof, String, Vec, VerifyingKey};
+use algebra::{
+    bls12_381::{self, Bls12_381},
+    bn254::{self, Bn254},
+};
+use algebra_core::{Field, PairingEngine, PrimeField};
+use core::ops::Neg;
+
+/// A big-endian 256-bit EVM word.
+pub type Word = [u8; 32];
+
+/// The precompiled contracts through which a generated verifier computes on
+/// the curve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Precompiles {
+    /// The BN254 precompiles of EIP-196 and EIP-197: `ecAdd` at `0x06`,
+    /// `ecMul` at `0x07` and `ecPairing` at `0x08`.
+    Eip197,
+    /// The BLS12-381 precompiles of EIP-2537: `G1MSM` at `0x0c` and
+    /// `PAIRING_CHECK` at `0x0f`.
+    Eip2537,
+}
+
+/// A pairing engine whose curve operations are available to EVM contracts
+/// through precompiles.
+pub trait EvmEngine: PairingEngine {
+    /// The name of the curve, as it appears in generated contracts.
+    const CURVE: &'static str;
+
+    /// The precompiles that generated contracts call.
+    const PRECOMPILES: Precompiles;
+
+    /// Encodes a point of G1 as the precompiles expect it.
+    fn encode_g1(p: &Self::G1Affine) -> Vec<Word>;
+
+    /// Encodes a point of G2 as the precompiles expect it.
+    fn encode_g2(p: &Self::G2Affine) -> Vec<Word>;
+}
+
+impl EvmEngine for Bn254 {
+    const CURVE: &'static str = "BN254";
+    const PRECOMPILES: Precompiles = Precompiles::Eip197;
+
+    fn encode_g1(p: &bn254::G1Affine) -> Vec<Word> {
+        if p.infinity {
+            return vec![[0u8; 32]; 2];
+        }
+        [p.x, p.y].iter().flat_map(|c| field_words(c, 1)).collect()
+    }
+
+    fn encode_g2(p: &bn254::G2Affine) -> Vec<Word> {
+        if p.infinity {
+            return vec![[0u8; 32]; 4];
+        }
+        // EIP-197 expects the imaginary part of each coordinate first.
+        [p.x.c1, p.x.c0, p.y.c1, p.y.c0]
+            .iter()
+            .flat_map(|c| field_words(c, 1))
+            .collect()
+    }
+}
+
+impl EvmEngine for Bls12_381 {
+    const CURVE: &'static str = "BLS12-381";
+    const PRECOMPILES: Precompiles = Precompiles::Eip2537;
+
+    fn encode_g1(p: &bls12_381::G1Affine) -> Vec<Word> {
+        if p.infinity {
+            return vec![[0u8; 32]; 4];
+        }
+        // EIP-2537 pads each base field element to 64 bytes.
+        [p.x, p.y].iter().flat_map(|c| field_words(c, 2)).collect()
+    }
+
+    fn encode_g2(p: &bls12_381::G2Affine) -> Vec<Word> {
+        if p.infinity {
+            return vec![[0u8; 32]; 8];
+        }
+        [p.x.c0, p.x.c1, p.y.c0, p.y.c1]
+            .iter()
+            .flat_map(|c| field_words(c, 2))
+            .collect()
+    }
+}
+
+/// The selector of `verifyProof(bytes,uint256[])`, that is, the first four
+/// bytes of the Keccak-256 hash of the signature.
+const VERIFY_PROOF_SELECTOR: [u8; 4] = [0x1e, 0x8e, 0x1e, 0x13];
+
+/// Encodes `proof` as the `proof` argument of the `verifyProof` function of
+/// a contract generated by [`verifier_contract`]: the points A, B and C,
+/// encoded as the precompiles expect them.
+pub fn encode_proof<E: EvmEngine>(proof: &Proof<E>) -> Vec<u8> {
+    let mut words = E::encode_g1(&proof.a);
+    words.extend(E::encode_g2(&proof.b));
+    words.extend(E::encode_g1(&proof.c));
+    words.concat()
+}
+
+/// Encodes a call to the `verifyProof` function of a contract generated by
+/// [`verifier_contract`], with `proof` and `public_inputs` as arguments.
+pub fn encode_calldata<E: EvmEngine>(proof: &Proof<E>, public_inputs: &[E::Fr]) -> Vec<u8> {
+    // The proof is a whole number of words, so it needs no padding.
+    let proof = encode_proof(proof);
+
+    let mut calldata = VERIFY_PROOF_SELECTOR.to_vec();
+    // The head holds the offsets of the two dynamic arguments.
+    calldata.extend_from_slice(&uint_word(64));
+    calldata.extend_from_slice(&uint_word(64 + 32 + proof.len()));
+    calldata.extend_from_slice(&uint_word(proof.len()));
+    calldata.extend_from_slice(&proof);
+    calldata.extend_from_slice(&uint_word(public_inputs.len()));
+    for input in public_inputs {
+        calldata.extend_from_slice(&field_words(input, 1)[0]);
+    }
+    calldata
+}
+
+/// Generates the source of a Solidity contract that verifies proofs against
+/// `vk`.
+///
+/// The contract exposes
+/// `verifyProof(bytes calldata proof, uint256[] calldata input)`, whose
+/// arguments are produced by [`encode_proof`] and [`encode_calldata`]. It
+/// checks that
+/// `e(A, B) * e(alpha, -beta) * e(vk_x, -gamma) * e(C, -delta) = 1`
+/// with the pairing precompile, after computing `vk_x` from the public
+/// inputs with the curve arithmetic precompiles.
+pub fn verifier_contract<E: EvmEngine>(vk: &VerifyingKey<E>) -> String {
+    let (eips, precompiles, input_commitment) = match E::PRECOMPILES {
+        Precompiles::Eip197 => (
+            "EIP-196 and EIP-197",
+            EIP197_PRECOMPILES,
+            EIP197_INPUT_COMMITMENT,
+        ),
+        Precompiles::Eip2537 => ("EIP-2537", EIP2537_PRECOMPILES, EIP2537_INPUT_COMMITMENT),
+    };
+
+    let mut alpha_g1_neg_beta_g2 = E::encode_g1(&vk.alpha_g1);
+    alpha_g1_neg_beta_g2.extend(E::encode_g2(&vk.beta_g2.neg()));
+    let gamma_abc_g1 = vk
+        .gamma_abc_g1
+        .iter()
+        .flat_map(E::encode_g1)
+        .collect::<Vec<_>>();
+
+    CONTRACT
+        .replace("$INPUT_COMMITMENT", input_commitment)
+        .replace("$PRECOMPILES", precompiles)
+        .replace("$CURVE", E::CURVE)
+        .replace("$EIPS", eips)
+        .replace(
+            "$SCALAR_MODULUS",
+            &hex_uint(&words(E::Fr::characteristic(), 1)[0]),
+        )
+        .replace("$NUM_INPUTS", &(vk.gamma_abc_g1.len() - 1).to_string())
+        .replace(
+            "$G1_LENGTH",
+            &(32 * E::encode_g1(&vk.alpha_g1).len()).to_string(),
+        )
+        .replace(
+            "$G2_LENGTH",
+            &(32 * E::encode_g2(&vk.beta_g2).len()).to_string(),
+        )
+        .replace("$ALPHA_G1_NEG_BETA_G2", &hex_bytes(&alpha_g1_neg_beta_g2))
+        .replace(
+            "$NEG_GAMMA_G2",
+            &hex_bytes(&E::encode_g2(&vk.gamma_g2.neg())),
+        )
+        .replace(
+            "$NEG_DELTA_G2",
+            &hex_bytes(&E::encode_g2(&vk.delta_g2.neg())),
+        )
+        .replace("$GAMMA_ABC_G1", &hex_bytes(&gamma_abc_g1))
+}
+
+/// Encodes `f` as `len` words.
+fn field_words<F: PrimeField>(f: &F, len: usize) -> Vec<Word> {
+    words(f.into_repr().as_ref(), len)
+}
+
+/// Encodes the integer with little-endian `limbs` as `len` words.
+fn words(limbs: &[u64], len: usize) -> Vec<Word> {
+    let mut bytes = vec![0u8; 32 * len];
+    for (i, limb) in limbs.iter().enumerate() {
+        let end = bytes.len() - 8 * i;
+        bytes[end - 8..end].copy_from_slice(&limb.to_be_bytes());
+    }
+    bytes
+        .chunks(32)
+        .map(|chunk| {
+            let mut word = [0u8; 32];
+            word.copy_from_slice(chunk);
+            word
+        })
+        .collect()
+}
+
+fn uint_word(n: usize) -> Word {
+    words(&[n as u64], 1)[0]
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hex_uint(word: &Word) -> String {
+    format!("0x{}", hex(word))
+}
+
+/// Formats `words` as a Solidity hex literal, one word per line.
+fn hex_bytes(words: &[Word]) -> String {
+    words
+        .iter()
+        .map(|word| format!("        hex\"{}\"", hex(word)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+const CONTRACT: &str = r#"// SPDX-License-Identifier: MIT OR Apache-2.0
+// This file was generated by the groth16 crate. Do not edit it by hand.
+pragma solidity ^0.8.0;
+
+/// @title Groth16 verifier over $CURVE
+/// @notice Verifies proofs against a fixed verifying key, using the
+/// precompiles of $EIPS.
+contract Groth16Verifier {
+    /// The modulus of the scalar field, which bounds the public inputs.
+    uint256 internal constant SCALAR_MODULUS = $SCALAR_MODULUS;
+    uint256 internal constant NUM_INPUTS = $NUM_INPUTS;
+
+    /// The lengths in bytes of the encodings of points of G1 and G2.
+    uint256 internal constant G1_LENGTH = $G1_LENGTH;
+    uint256 internal constant G2_LENGTH = $G2_LENGTH;
+
+$PRECOMPILES
+
+    /// alpha in G1, followed by -beta in G2.
+    bytes internal constant ALPHA_G1_NEG_BETA_G2 =
+$ALPHA_G1_NEG_BETA_G2;
+
+    /// -gamma in G2.
+    bytes internal constant NEG_GAMMA_G2 =
+$NEG_GAMMA_G2;
+
+    /// -delta in G2.
+    bytes internal constant NEG_DELTA_G2 =
+$NEG_DELTA_G2;
+
+    /// The points of G1 that the public inputs are committed to.
+    bytes internal constant GAMMA_ABC_G1 =
+$GAMMA_ABC_G1;
+
+    /// @notice Checks a proof against its public inputs.
+    /// @param proof The points A, B and C of the proof, encoded as the
+    /// precompiles expect them.
+    /// @param input The public inputs, each less than the scalar field modulus.
+    /// @return Whether the proof is valid.
+    function verifyProof(bytes calldata proof, uint256[] calldata input)
+        external
+        view
+        returns (bool)
+    {
+        require(proof.length == 2 * G1_LENGTH + G2_LENGTH, "invalid proof length");
+        require(input.length == NUM_INPUTS, "invalid number of public inputs");
+
+        // e(A, B) * e(alpha, -beta) * e(vk_x, -gamma) * e(C, -delta) == 1
+        bytes memory pairingInput = abi.encodePacked(
+            proof[:G1_LENGTH + G2_LENGTH],
+            ALPHA_G1_NEG_BETA_G2,
+            inputCommitment(input),
+            NEG_GAMMA_G2,
+            proof[G1_LENGTH + G2_LENGTH:],
+            NEG_DELTA_G2
+        );
+        (bool success, bytes memory output) = PAIRING.staticcall(pairingInput);
+        return success && output.length == 32 && abi.decode(output, (uint256)) == 1;
+    }
+
+$INPUT_COMMITMENT
+
+    /// Calls `precompile`, and checks that it succeeds with `outputLength`
+    /// bytes of output.
+    function callPrecompile(address precompile, bytes memory input, uint256 outputLength)
+        private
+        view
+        returns (bytes memory output)
+    {
+        bool success;
+        (success, output) = precompile.staticcall(input);
+        require(success && output.length == outputLength, "precompile call failed");
+    }
+
+    /// Reads the word at `index` of `data`.
+    function word(bytes memory data, uint256 index) private pure returns (uint256 value) {
+        assembly {
+            value := mload(add(data, mul(add(index, 1), 32)))
+        }
+    }
+}
+"#;
+
+const EIP197_PRECOMPILES: &str = r#"    address internal constant EC_ADD = address(0x06);
+    address internal constant EC_MUL = address(0x07);
+    address internal constant PAIRING = address(0x08);"#;
+
+const EIP197_INPUT_COMMITMENT: &str = r#"    /// Computes vk_x = gamma_abc[0] + sum(input[i] * gamma_abc[i + 1]) with
+    /// ecMul and ecAdd.
+    function inputCommitment(uint256[] calldata input)
+        private
+        view
+        returns (bytes memory commitment)
+    {
+        bytes memory gammaAbc = GAMMA_ABC_G1;
+        commitment = abi.encodePacked(word(gammaAbc, 0), word(gammaAbc, 1));
+        for (uint256 i = 0; i < input.length; i++) {
+            require(input[i] < SCALAR_MODULUS, "public input is not a field element");
+            uint256 j = 2 * i + 2;
+            bytes memory term = callPrecompile(
+                EC_MUL,
+                abi.encodePacked(word(gammaAbc, j), word(gammaAbc, j + 1), input[i]),
+                G1_LENGTH
+            );
+            commitment = callPrecompile(EC_ADD, abi.encodePacked(commitment, term), G1_LENGTH);
+        }
+    }"#;
+
+const EIP2537_PRECOMPILES: &str = r#"    address internal constant G1_MSM = address(0x0c);
+    address internal constant PAIRING = address(0x0f);"#;
+
+const EIP2537_INPUT_COMMITMENT: &str = r#"    /// Computes vk_x = gamma_abc[0] + sum(input[i] * gamma_abc[i + 1]) with
+    /// a single call to G1MSM.
+    function inputCommitment(uint256[] calldata input) private view returns (bytes memory) {
+        bytes memory gammaAbc = GAMMA_ABC_G1;
+        bytes memory msmInput = abi.encodePacked(
+            word(gammaAbc, 0),
+            word(gammaAbc, 1),
+            word(gammaAbc, 2),
+            word(gammaAbc, 3),
+            uint256(1)
+        );
+        for (uint256 i = 0; i < input.length; i++) {
+            require(input[i] < SCALAR_MODULUS, "public input is not a field element");
+            uint256 j = 4 * i + 4;
+            msmInput = abi.encodePacked(
+                msmInput,
+                word(gammaAbc, j),
+                word(gammaAbc, j + 1),
+                word(gammaAbc, j + 2),
+                word(gammaAbc, j + 3),
+                input[i]
+            );
+        }
+        return callPrecompile(G1_MSM, msmInput, G1_LENGTH);
+    }"#;
@@ -113,3 +113,1962 @@ mod sw6 {
         assert!(!verify_proof(&pvk, &proof, &[Fr::zero()]).unwrap());
     }
 }
+
+mod serialization {
+    use super::*;
+    use crate::{
+        create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof,
+        Parameters, Proof, VerifyingKey, SERIALIZATION_VERSION,
+    };
+    use algebra_core::{test_rng, PairingEngine, UniformRand};
+
+    fn serialization_round_trip<E: PairingEngine>() {
+        let rng = &mut test_rng();
+
+        let params =
+            generate_random_parameters::<E, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+
+        let a = E::Fr::rand(rng);
+        let b = E::Fr::rand(rng);
+        let c = a * &b;
+        let proof = create_random_proof(
+            MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            },
+            &params,
+            rng,
+        )
+        .unwrap();
+
+        for &compressed in &[true, false] {
+            let write_params = |params: &Parameters<E>, bytes: &mut Vec<u8>| {
+                if compressed {
+                    params.write(bytes).unwrap();
+                } else {
+                    params.write_uncompressed(bytes).unwrap();
+                }
+            };
+            let mut bytes = Vec::new();
+            write_params(&params, &mut bytes);
+            assert_eq!(bytes[0], SERIALIZATION_VERSION);
+            for &checked in &[true, false] {
+                let read = Parameters::<E>::read(&bytes[..], checked).unwrap();
+                let mut read_bytes = Vec::new();
+                write_params(&read, &mut read_bytes);
+                assert_eq!(bytes, read_bytes);
+            }
+
+            let write_vk = |vk: &VerifyingKey<E>, bytes: &mut Vec<u8>| {
+                if compressed {
+                    vk.write(bytes).unwrap();
+                } else {
+                    vk.write_uncompressed(bytes).unwrap();
+                }
+            };
+            let mut bytes = Vec::new();
+            write_vk(&params.vk, &mut bytes);
+            let vk = VerifyingKey::<E>::read(&bytes[..]).unwrap();
+            let mut read_bytes = Vec::new();
+            write_vk(&vk, &mut read_bytes);
+            assert_eq!(bytes, read_bytes);
+
+            let write_proof = |proof: &Proof<E>, bytes: &mut Vec<u8>| {
+                if compressed {
+                    proof.write(bytes).unwrap();
+                } else {
+                    proof.write_uncompressed(bytes).unwrap();
+                }
+            };
+            let mut bytes = Vec::new();
+            write_proof(&proof, &mut bytes);
+            let read_proof = Proof::<E>::read(&bytes[..]).unwrap();
+            let mut read_bytes = Vec::new();
+            write_proof(&read_proof, &mut read_bytes);
+            assert_eq!(bytes, read_bytes);
+
+            let pvk = prepare_verifying_key(&vk);
+            assert!(verify_proof(&pvk, &read_proof, &[c]).unwrap());
+        }
+    }
+
+    fn serialization_rejects_malformed<E: PairingEngine>() {
+        let rng = &mut test_rng();
+
+        let params =
+            generate_random_parameters::<E, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+
+        // Unknown version.
+        let mut bytes = Vec::new();
+        params.vk.write(&mut bytes).unwrap();
+        bytes[0] = SERIALIZATION_VERSION + 1;
+        assert!(VerifyingKey::<E>::read(&bytes[..]).is_err());
+
+        // Truncated input.
+        let mut bytes = Vec::new();
+        params.vk.write(&mut bytes).unwrap();
+        bytes.pop();
+        assert!(VerifyingKey::<E>::read(&bytes[..]).is_err());
+
+        // A point that is moved off the curve is rejected by a checked read,
+        // but accepted by an unchecked one.
+        let mut bytes = Vec::new();
+        params.write_uncompressed(&mut bytes).unwrap();
+        bytes[2] ^= 1;
+        assert!(Parameters::<E>::read(&bytes[..], true).is_err());
+        assert!(Parameters::<E>::read(&bytes[..], false).is_ok());
+    }
+
+    macro_rules! serialization_tests {
+        ($($name:ident => $engine:ty),*) => {
+            $(
+                mod $name {
+                    #[test]
+                    fn round_trip() {
+                        super::serialization_round_trip::<$engine>();
+                    }
+
+                    #[test]
+                    fn rejects_malformed() {
+                        super::serialization_rejects_malformed::<$engine>();
+                    }
+                }
+            )*
+        };
+    }
+
+    serialization_tests!(
+        bls12_377 => algebra::bls12_377::Bls12_377,
+        bls12_381 => algebra::bls12_381::Bls12_381,
+        bn_382 => algebra::bn_382::Bn_382,
+        sw6 => algebra::sw6::SW6,
+        mnt4_298 => algebra::mnt4_298::MNT4_298,
+        mnt4_753 => algebra::mnt4_753::MNT4_753,
+        mnt6_298 => algebra::mnt6_298::MNT6_298,
+        mnt6_753 => algebra::mnt6_753::MNT6_753
+    );
+}
+
+mod batch_verification {
+    use super::*;
+    use crate::{
+        batch_verify_proofs, create_random_proof, generate_random_parameters, prepare_verifying_key,
+    };
+    use algebra_core::{test_rng, PairingEngine, UniformRand};
+
+    fn batch_verify<E: PairingEngine>() {
+        let rng = &mut test_rng();
+
+        let params =
+            generate_random_parameters::<E, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+        let pvk = prepare_verifying_key::<E>(&params.vk);
+
+        let mut proofs = Vec::new();
+        let mut inputs = Vec::new();
+        for _ in 0..10 {
+            let a = E::Fr::rand(rng);
+            let b = E::Fr::rand(rng);
+            let proof = create_random_proof(
+                MySillyCircuit {
+                    a: Some(a),
+                    b: Some(b),
+                },
+                &params,
+                rng,
+            )
+            .unwrap();
+            proofs.push(proof);
+            inputs.push(vec![a * &b]);
+        }
+
+        assert!(batch_verify_proofs(&pvk, &proofs, &inputs, rng)
+            .unwrap()
+            .is_empty());
+        assert!(batch_verify_proofs(&pvk, &proofs[..1], &inputs[..1], rng)
+            .unwrap()
+            .is_empty());
+        assert!(batch_verify_proofs::<E, Vec<E::Fr>, _>(&pvk, &[], &[], rng)
+            .unwrap()
+            .is_empty());
+
+        // Invalidate two of the statements and check that the bisection finds them.
+        inputs[3][0] = E::Fr::rand(rng);
+        inputs[7][0] = E::Fr::rand(rng);
+        assert_eq!(
+            batch_verify_proofs(&pvk, &proofs, &inputs, rng).unwrap(),
+            vec![3, 7]
+        );
+
+        // Swapping proofs between valid statements must also be caught.
+        let mut swapped = proofs.clone();
+        swapped.swap(0, 1);
+        assert_eq!(
+            batch_verify_proofs(&pvk, &swapped[..3], &inputs[..3], rng).unwrap(),
+            vec![0, 1]
+        );
+
+        // Public inputs of the wrong length are rejected.
+        assert!(batch_verify_proofs(&pvk, &proofs[..1], &[vec![]], rng).is_err());
+        // So is a number of public inputs that differs from the number of proofs.
+        assert!(matches!(
+            batch_verify_proofs(&pvk, &proofs[..2], &inputs[..1], rng),
+            Err(SynthesisError::ProofCountMismatch)
+        ));
+    }
+
+    #[test]
+    fn bls12_377_batch_verify() {
+        batch_verify::<algebra::bls12_377::Bls12_377>();
+    }
+
+    #[test]
+    fn mnt4_298_batch_verify() {
+        batch_verify::<algebra::mnt4_298::MNT4_298>();
+    }
+}
+
+mod rerandomization {
+    use super::*;
+    use crate::{
+        create_random_proof, generate_random_parameters, prepare_verifying_key, rerandomize_proof,
+        verify_proof, Proof,
+    };
+    use algebra_core::{test_rng, CanonicalSerialize, PairingEngine, UniformRand};
+
+    fn to_bytes<E: PairingEngine>(proof: &Proof<E>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        proof.serialize(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn rerandomize<E: PairingEngine>() {
+        let rng = &mut test_rng();
+
+        let params =
+            generate_random_parameters::<E, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+        let pvk = prepare_verifying_key::<E>(&params.vk);
+
+        let a = E::Fr::rand(rng);
+        let b = E::Fr::rand(rng);
+        let c = a * &b;
+        let proof = create_random_proof(
+            MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            },
+            &params,
+            rng,
+        )
+        .unwrap();
+
+        let rerandomized = rerandomize_proof(&params.vk, &proof, rng);
+        assert!(verify_proof(&pvk, &rerandomized, &[c]).unwrap());
+        assert!(!verify_proof(&pvk, &rerandomized, &[a]).unwrap());
+        assert!(proof.a != rerandomized.a);
+        assert!(proof.b != rerandomized.b);
+        assert!(proof.c != rerandomized.c);
+
+        // Re-randomizing again yields yet another valid proof.
+        let again = rerandomize_proof(&params.vk, &rerandomized, rng);
+        assert!(verify_proof(&pvk, &again, &[c]).unwrap());
+        assert!(to_bytes(&again) != to_bytes(&rerandomized));
+        assert!(to_bytes(&again) != to_bytes(&proof));
+    }
+
+    macro_rules! rerandomization_tests {
+        ($($name:ident => $engine:ty),*) => {
+            $(
+                #[test]
+                fn $name() {
+                    rerandomize::<$engine>();
+                }
+            )*
+        };
+    }
+
+    rerandomization_tests!(
+        bls12_377 => algebra::bls12_377::Bls12_377,
+        bls12_381 => algebra::bls12_381::Bls12_381,
+        bn_382 => algebra::bn_382::Bn_382,
+        sw6 => algebra::sw6::SW6,
+        mnt4_298 => algebra::mnt4_298::MNT4_298,
+        mnt4_753 => algebra::mnt4_753::MNT4_753,
+        mnt6_298 => algebra::mnt6_298::MNT6_298,
+        mnt6_753 => algebra::mnt6_753::MNT6_753
+    );
+}
+
+mod parameter_source {
+    use super::*;
+    use crate::{
+        create_proof, create_proof_with_source, generate_random_parameters, prepare_verifying_key,
+        verify_proof, FileParameters,
+    };
+
+    use algebra::bls12_377::{Bls12_377, Fr};
+    use algebra_core::{test_rng, CanonicalSerialize, UniformRand};
+    use std::{fs, io::Cursor};
+
+    #[test]
+    fn file_parameters_match_in_memory() {
+        let rng = &mut test_rng();
+
+        let params =
+            generate_random_parameters::<Bls12_377, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+        let pvk = prepare_verifying_key::<Bls12_377>(&params.vk);
+
+        let a = Fr::rand(rng);
+        let b = Fr::rand(rng);
+        let c = a * &b;
+        let r = Fr::rand(rng);
+        let s = Fr::rand(rng);
+        let circuit = || MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        };
+
+        let expected = create_proof(circuit(), &params, r, s).unwrap();
+        assert!(verify_proof(&pvk, &expected, &[c]).unwrap());
+        let mut expected_bytes = vec![];
+        expected.serialize(&mut expected_bytes).unwrap();
+
+        let mut compressed = vec![];
+        params.write(&mut compressed).unwrap();
+        let mut uncompressed = vec![];
+        params.write_uncompressed(&mut uncompressed).unwrap();
+
+        for bytes in &[&compressed, &uncompressed] {
+            for &checked in &[true, false] {
+                for &chunk_size in &[1, 2, 3, 1 << 10] {
+                    let source =
+                        FileParameters::<Bls12_377, _>::new(Cursor::new(&bytes[..]), checked)
+                            .unwrap()
+                            .with_chunk_size(chunk_size);
+                    let proof = create_proof_with_source(circuit(), source, r, s).unwrap();
+                    let mut proof_bytes = vec![];
+                    proof.serialize(&mut proof_bytes).unwrap();
+                    assert_eq!(proof_bytes, expected_bytes);
+                }
+            }
+        }
+
+        // The same, from an actual file.
+        let path = std::env::temp_dir().join("groth16-parameter-source-test.params");
+        fs::write(&path, &uncompressed).unwrap();
+        let source = FileParameters::<Bls12_377>::open(&path, true)
+            .unwrap()
+            .with_chunk_size(2);
+        let proof = create_proof_with_source(circuit(), source, r, s).unwrap();
+        fs::remove_file(&path).unwrap();
+        let mut proof_bytes = vec![];
+        proof.serialize(&mut proof_bytes).unwrap();
+        assert_eq!(proof_bytes, expected_bytes);
+    }
+
+    #[test]
+    fn truncated_file_is_rejected() {
+        let rng = &mut test_rng();
+
+        let params =
+            generate_random_parameters::<Bls12_377, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+        let mut bytes = vec![];
+        params.write_uncompressed(&mut bytes).unwrap();
+        bytes.truncate(bytes.len() - 1);
+
+        // The circuit digest follows the queries, so the truncation is
+        // detected when the file is opened.
+        assert!(FileParameters::<Bls12_377, _>::new(Cursor::new(&bytes), true).is_err());
+    }
+
+    #[test]
+    fn oversized_query_length_is_rejected() {
+        let rng = &mut test_rng();
+
+        let params =
+            generate_random_parameters::<Bls12_377, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+        let mut bytes = vec![];
+        params.write_uncompressed(&mut bytes).unwrap();
+
+        // The length of `a_query` follows the header, the verification key,
+        // `beta_g1` and `delta_g1`.
+        let offset = 2
+            + params.vk.uncompressed_size()
+            + params.beta_g1.uncompressed_size()
+            + params.delta_g1.uncompressed_size();
+        let element_size = params.beta_g1.uncompressed_size() as u64;
+        // Lengths whose size in bytes overflows, or does not fit a seek.
+        for &len in &[
+            u64::max_value(),
+            (i64::max_value() as u64) / element_size + 1,
+        ] {
+            bytes[offset..(offset + 8)].copy_from_slice(&len.to_le_bytes());
+            let err = FileParameters::<Bls12_377, _>::new(Cursor::new(&bytes), true)
+                .err()
+                .unwrap();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        }
+    }
+}
+
+mod simulation {
+    use super::*;
+    use crate::{
+        generate_random_parameters_with_trapdoor, prepare_verifying_key, simulate_proof,
+        verify_proof,
+    };
+    use algebra_core::{test_rng, AffineCurve, PairingEngine, UniformRand};
+
+    fn simulate<E: PairingEngine>() {
+        let rng = &mut test_rng();
+
+        let (params, trapdoor) = generate_random_parameters_with_trapdoor::<E, _, _>(
+            MySillyCircuit { a: None, b: None },
+            rng,
+        )
+        .unwrap();
+        assert!(params.vk.alpha_g1 == trapdoor.g1_generator.mul(trapdoor.alpha).into());
+        assert!(params.vk.delta_g2 == trapdoor.g2_generator.mul(trapdoor.delta).into());
+
+        let pvk = prepare_verifying_key::<E>(&params.vk);
+        for _ in 0..5 {
+            // No witness is needed: `c` need not even be a product.
+            let c = E::Fr::rand(rng);
+            let proof = simulate_proof(&trapdoor, &[c], rng).unwrap();
+            assert!(verify_proof(&pvk, &proof, &[c]).unwrap());
+            assert!(!verify_proof(&pvk, &proof, &[c.double()]).unwrap());
+        }
+        assert!(simulate_proof(&trapdoor, &[], rng).is_err());
+    }
+
+    #[test]
+    fn bls12_377() {
+        simulate::<algebra::bls12_377::Bls12_377>();
+    }
+
+    #[test]
+    fn mnt4_298() {
+        simulate::<algebra::mnt4_298::MNT4_298>();
+    }
+}
+
+mod parameter_check {
+    use super::*;
+    use crate::{check_parameters, generate_random_parameters, ParameterError};
+
+    use algebra::bls12_377::{Bls12_377, Fr};
+    use algebra_core::{test_rng, AffineCurve, Zero};
+
+    struct SquareCircuit;
+
+    impl<F: Field> ConstraintSynthesizer<F> for SquareCircuit {
+        fn generate_constraints<CS: ConstraintSystem<F>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let a = cs.alloc(|| "a", || Err(SynthesisError::AssignmentMissing))?;
+            let c = cs.alloc_input(|| "c", || Err(SynthesisError::AssignmentMissing))?;
+            cs.enforce(|| "a*a=c", |lc| lc + a, |lc| lc + a, |lc| lc + c);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn check() {
+        let rng = &mut test_rng();
+        let circuit = || MySillyCircuit::<Fr> { a: None, b: None };
+
+        let params = generate_random_parameters::<Bls12_377, _, _>(circuit(), rng).unwrap();
+        let other = generate_random_parameters::<Bls12_377, _, _>(circuit(), rng).unwrap();
+        check_parameters(&params, circuit(), rng).unwrap();
+        check_parameters(&other, circuit(), rng).unwrap();
+
+        // Parameters for another circuit.
+        let square = generate_random_parameters::<Bls12_377, _, _>(SquareCircuit, rng).unwrap();
+        assert!(check_parameters(&square, circuit(), rng).is_err());
+        assert!(check_parameters(&params, SquareCircuit, rng).is_err());
+
+        // Queries mixed from two setups for the same circuit.
+        let mut mixed = params.clone();
+        mixed.b_g2_query = other.b_g2_query.clone();
+        assert!(matches!(
+            check_parameters(&mixed, circuit(), rng),
+            Err(ParameterError::Inconsistent(_))
+        ));
+
+        let mut mixed = params.clone();
+        mixed.l_query = other.l_query.clone();
+        assert!(matches!(
+            check_parameters(&mixed, circuit(), rng),
+            Err(ParameterError::Inconsistent(_))
+        ));
+
+        let mut mixed = params.clone();
+        mixed.delta_g1 = other.delta_g1;
+        assert!(matches!(
+            check_parameters(&mixed, circuit(), rng),
+            Err(ParameterError::Inconsistent(_))
+        ));
+
+        let mut mixed = params.clone();
+        mixed.vk.gamma_g2 = other.vk.gamma_g2;
+        assert!(matches!(
+            check_parameters(&mixed, circuit(), rng),
+            Err(ParameterError::Inconsistent(_))
+        ));
+
+        // Identity elements where the QAP has none, and vice versa.
+        let mut tampered = params.clone();
+        tampered.a_query[1] = <Bls12_377 as algebra_core::PairingEngine>::G1Affine::zero();
+        assert!(matches!(
+            check_parameters(&tampered, circuit(), rng),
+            Err(ParameterError::UnexpectedIdentity {
+                query: "a_query",
+                index: 1
+            })
+        ));
+
+        let mut tampered = params.clone();
+        tampered.h_query.pop();
+        assert!(matches!(
+            check_parameters(&tampered, circuit(), rng),
+            Err(ParameterError::QueryLength {
+                query: "h_query",
+                ..
+            })
+        ));
+
+        let mut tampered = params;
+        tampered.b_g1_query[3] = tampered.b_g1_query[3].mul(Fr::from(2u64)).into();
+        assert!(matches!(
+            check_parameters(&tampered, circuit(), rng),
+            Err(ParameterError::Inconsistent(_))
+        ));
+    }
+}
+
+mod ceremony {
+    use super::*;
+    use crate::{
+        ceremony::{hash, hash_to_g2, CeremonyError, MPCParameters, Transcript},
+        create_random_proof, prepare_verifying_key, verify_proof,
+    };
+
+    use algebra::bls12_377::{Bls12_377, Fr, G2Affine};
+    use algebra_core::{
+        test_rng, AffineCurve, FpParameters, PrimeField, ProjectiveCurve, UniformRand, Zero,
+    };
+    use core::ops::MulAssign;
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    #[test]
+    fn hash_to_g2_has_unknown_discrete_log() {
+        let digest = hash(&[&b"hash to G2"[..]]);
+        let point = hash_to_g2::<Bls12_377>(&digest);
+        assert_eq!(point, hash_to_g2::<Bls12_377>(&digest));
+        assert_ne!(
+            point,
+            hash_to_g2::<Bls12_377>(&hash(&[&b"another digest"[..]]))
+        );
+
+        // The point is in the prime order subgroup.
+        assert!(!point.is_zero());
+        assert!(point.mul(<Fr as PrimeField>::Params::MODULUS).is_zero());
+
+        // It is not the generator times a scalar derived from the digest,
+        // whose discrete logarithm anyone could compute.
+        let mut seed = [0u8; 32];
+        seed.copy_from_slice(&digest[..32]);
+        let scalar = Fr::rand(&mut ChaChaRng::from_seed(seed));
+        assert_ne!(
+            point,
+            G2Affine::prime_subgroup_generator()
+                .mul(scalar)
+                .into_affine()
+        );
+    }
+
+    #[test]
+    fn powers_of_tau() {
+        let rng = &mut test_rng();
+
+        let mut transcript = Transcript::<Bls12_377>::new(16);
+        let digests = (0..3)
+            .map(|_| transcript.contribute(rng).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(digests.len(), 3);
+        let accumulator = transcript.verify(rng).unwrap();
+        assert_eq!(accumulator.degree(), 16);
+
+        let mut bytes = vec![];
+        transcript.write(&mut bytes).unwrap();
+        let read = Transcript::<Bls12_377>::read(&bytes[..]).unwrap();
+        read.verify(rng).unwrap();
+
+        // Replace a contribution's accumulator with one that was updated by
+        // different randomness.
+        let mut tampered = transcript.clone();
+        let mut other = Transcript::<Bls12_377>::new(16);
+        other.contribute(rng).unwrap();
+        other.contribute(rng).unwrap();
+        tampered.contributions[1].accumulator = other.accumulator().unwrap();
+        assert!(tampered.verify(rng).is_err());
+
+        // A transcript without contributions has publicly known secrets.
+        assert!(matches!(
+            Transcript::<Bls12_377>::new(16).verify(rng),
+            Err(CeremonyError::NoContributions)
+        ));
+
+        // Degrees read from a transcript are checked before anything is
+        // allocated for them.
+        for &degree in &[0, 1, 1 << 40] {
+            let mut tampered = Transcript::<Bls12_377>::new(16);
+            tampered.degree = degree;
+            assert!(matches!(
+                tampered.accumulator(),
+                Err(CeremonyError::InvalidDegree)
+            ));
+            assert!(matches!(
+                tampered.contribute(rng),
+                Err(CeremonyError::InvalidDegree)
+            ));
+            let mut tampered = transcript.clone();
+            tampered.degree = degree;
+            assert!(matches!(
+                tampered.verify(rng),
+                Err(CeremonyError::InvalidDegree)
+            ));
+        }
+        let mut tampered = transcript.clone();
+        tampered.degree = 32;
+        assert!(matches!(
+            tampered.verify(rng),
+            Err(CeremonyError::InvalidDegree)
+        ));
+    }
+
+    #[test]
+    fn phase2() {
+        let rng = &mut test_rng();
+
+        let mut transcript = Transcript::<Bls12_377>::new(16);
+        transcript.contribute(rng).unwrap();
+        transcript.contribute(rng).unwrap();
+        let accumulator = transcript.verify(rng).unwrap();
+
+        let mut mpc =
+            MPCParameters::new(MySillyCircuit::<Fr> { a: None, b: None }, &accumulator).unwrap();
+        // Parameters without contributions have a publicly known delta.
+        assert!(matches!(
+            mpc.verify(MySillyCircuit { a: None, b: None }, &accumulator, rng),
+            Err(CeremonyError::NoContributions)
+        ));
+        let first = mpc.contribute(rng).unwrap();
+        let second = mpc.contribute(rng).unwrap();
+        let digests = mpc
+            .verify(MySillyCircuit { a: None, b: None }, &accumulator, rng)
+            .unwrap();
+        assert_eq!(digests, vec![first, second]);
+
+        let params = mpc.get_params();
+        let pvk = prepare_verifying_key::<Bls12_377>(&params.vk);
+        for _ in 0..10 {
+            let a = Fr::rand(rng);
+            let b = Fr::rand(rng);
+            let mut c = a;
+            c.mul_assign(&b);
+
+            let proof = create_random_proof(
+                MySillyCircuit {
+                    a: Some(a),
+                    b: Some(b),
+                },
+                params,
+                rng,
+            )
+            .unwrap();
+            assert!(verify_proof(&pvk, &proof, &[c]).unwrap());
+            assert!(!verify_proof(&pvk, &proof, &[a]).unwrap());
+        }
+
+        let mut bytes = vec![];
+        mpc.write(&mut bytes).unwrap();
+        let read = MPCParameters::<Bls12_377>::read(&bytes[..]).unwrap();
+        read.verify(MySillyCircuit { a: None, b: None }, &accumulator, rng)
+            .unwrap();
+
+        // Changing delta without a matching proof of knowledge is rejected.
+        let mut tampered = mpc.clone();
+        tampered.params.delta_g1 = tampered.params.vk.alpha_g1;
+        assert!(matches!(
+            tampered.verify(MySillyCircuit { a: None, b: None }, &accumulator, rng),
+            Err(CeremonyError::InvalidDelta)
+        ));
+
+        // So is a contribution that touches anything other than delta.
+        let mut tampered = mpc.clone();
+        tampered.params.a_query.swap(0, 1);
+        assert!(matches!(
+            tampered.verify(MySillyCircuit { a: None, b: None }, &accumulator, rng),
+            Err(CeremonyError::ParametersMismatch)
+        ));
+
+        // An accumulator that is too small for the circuit is rejected.
+        let small = Transcript::<Bls12_377>::new(4).accumulator().unwrap();
+        assert!(matches!(
+            MPCParameters::new(MySillyCircuit::<Fr> { a: None, b: None }, &small),
+            Err(CeremonyError::AccumulatorTooSmall)
+        ));
+    }
+}
+
+#[cfg(feature = "solidity")]
+mod solidity {
+    use crate::{
+        solidity::{encode_calldata, encode_proof, verifier_contract, EvmEngine},
+        Proof, VerifyingKey,
+    };
+    use algebra_core::{AffineCurve, PairingEngine, PrimeField, Zero};
+    use std::{fs, path::PathBuf};
+
+    /// Compares `actual` with the contents of the golden file `name`, or
+    /// overwrites the file if `UPDATE_GOLDEN_FILES` is set.
+    fn check_golden(name: &str, actual: &str) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("solidity")
+            .join(name);
+        if std::env::var_os("UPDATE_GOLDEN_FILES").is_some() {
+            fs::write(&path, actual).unwrap();
+        }
+        let expected = fs::read_to_string(&path).unwrap();
+        assert!(
+            expected == actual,
+            "{} is out of date; rerun with UPDATE_GOLDEN_FILES=1 to update it",
+            name
+        );
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn g1<E: PairingEngine>(k: u64) -> E::G1Affine {
+        E::G1Affine::prime_subgroup_generator()
+            .mul(E::Fr::from_repr(k.into()))
+            .into()
+    }
+
+    fn g2<E: PairingEngine>(k: u64) -> E::G2Affine {
+        E::G2Affine::prime_subgroup_generator()
+            .mul(E::Fr::from_repr(k.into()))
+            .into()
+    }
+
+    /// A verifying key for two public inputs whose elements are fixed
+    /// multiples of the generators.
+    fn fixed_vk<E: PairingEngine>() -> VerifyingKey<E> {
+        VerifyingKey {
+            alpha_g1: g1::<E>(2),
+            beta_g2: g2::<E>(3),
+            gamma_g2: g2::<E>(5),
+            delta_g2: g2::<E>(7),
+            gamma_abc_g1: vec![g1::<E>(11), g1::<E>(13), E::G1Affine::zero()],
+        }
+    }
+
+    fn fixed_proof<E: PairingEngine>() -> Proof<E> {
+        Proof {
+            a: g1::<E>(17),
+            b: g2::<E>(19),
+            c: g1::<E>(23),
+        }
+    }
+
+    fn golden_files<E: EvmEngine>(name: &str) {
+        check_golden(
+            &format!("{}_verifier.sol", name),
+            &verifier_contract(&fixed_vk::<E>()),
+        );
+
+        let inputs = [E::Fr::from_repr(29.into()), E::Fr::from_repr(31.into())];
+        let calldata = encode_calldata(&fixed_proof::<E>(), &inputs);
+        check_golden(
+            &format!("{}_calldata.hex", name),
+            &format!("{}\n", hex(&calldata)),
+        );
+    }
+
+    #[test]
+    fn bn254_golden_files() {
+        golden_files::<algebra::bn254::Bn254>("bn254");
+    }
+
+    #[test]
+    fn bls12_381_golden_files() {
+        golden_files::<algebra::bls12_381::Bls12_381>("bls12_381");
+    }
+
+    #[test]
+    fn bn254_generator_encoding() {
+        use algebra::bn254::{Bn254, G1Affine, G2Affine};
+
+        // The generators of EIP-197.
+        let g1 = hex(&Bn254::encode_g1(&G1Affine::prime_subgroup_generator()).concat());
+        assert_eq!(g1, format!("{:064x}{:064x}", 1, 2));
+        let g2 = hex(&Bn254::encode_g2(&G2Affine::prime_subgroup_generator()).concat());
+        assert_eq!(
+            g2,
+            concat!(
+                "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2",
+                "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed",
+                "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b",
+                "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+            )
+        );
+    }
+
+    #[test]
+    fn proof_encoding_length() {
+        use algebra::{bls12_381::Bls12_381, bn254::Bn254};
+
+        assert_eq!(encode_proof(&fixed_proof::<Bn254>()).len(), 256);
+        assert_eq!(encode_proof(&fixed_proof::<Bls12_381>()).len(), 512);
+    }
+}
+
+mod commit_and_prove {
+    use crate::lego::{
+        create_random_proof, generate_random_parameters, prepare_verifying_key, verify_commitment,
+        verify_proof, CommitmentKey,
+    };
+    use algebra_core::{test_rng, Field, PairingEngine, UniformRand};
+    use r1cs_core::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
+
+    /// Proves that the committed `a` and `b` multiply to the public `c`.
+    struct ProductCircuit<F: Field> {
+        a: Option<F>,
+        b: Option<F>,
+    }
+
+    impl<F: Field> ConstraintSynthesizer<F> for ProductCircuit<F> {
+        fn generate_constraints<CS: ConstraintSystem<F>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let a =
+                cs.alloc_committed(|| "a", || self.a.ok_or(SynthesisError::AssignmentMissing))?;
+            let b =
+                cs.alloc_committed(|| "b", || self.b.ok_or(SynthesisError::AssignmentMissing))?;
+            let c = cs.alloc_input(
+                || "c",
+                || {
+                    Ok(self.a.ok_or(SynthesisError::AssignmentMissing)?
+                        * &self.b.ok_or(SynthesisError::AssignmentMissing)?)
+                },
+            )?;
+            cs.enforce(|| "a*b=c", |lc| lc + a, |lc| lc + b, |lc| lc + c);
+            Ok(())
+        }
+    }
+
+    /// Proves that the committed `a` and `b` add up to the public `c`, with
+    /// a private intermediate variable that is not committed to.
+    struct SumCircuit<F: Field> {
+        a: Option<F>,
+        b: Option<F>,
+    }
+
+    impl<F: Field> ConstraintSynthesizer<F> for SumCircuit<F> {
+        fn generate_constraints<CS: ConstraintSystem<F>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let a =
+                cs.alloc_committed(|| "a", || self.a.ok_or(SynthesisError::AssignmentMissing))?;
+            let b =
+                cs.alloc_committed(|| "b", || self.b.ok_or(SynthesisError::AssignmentMissing))?;
+            let sum = self.a.and_then(|a| self.b.map(|b| a + &b));
+            let s = cs.alloc(|| "s", || sum.ok_or(SynthesisError::AssignmentMissing))?;
+            let c = cs.alloc_input(|| "c", || sum.ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce(
+                || "a+b=s",
+                |lc| lc + a + b,
+                |lc| lc + CS::one(),
+                |lc| lc + s,
+            );
+            cs.enforce(|| "s=c", |lc| lc + s, |lc| lc + CS::one(), |lc| lc + c);
+            Ok(())
+        }
+    }
+
+    fn commit_and_prove<E: PairingEngine>() {
+        let rng = &mut test_rng();
+        let link_key = CommitmentKey::<E>::rand(2, rng);
+
+        let product_params = generate_random_parameters::<E, _, _>(
+            ProductCircuit { a: None, b: None },
+            &link_key,
+            rng,
+        )
+        .unwrap();
+        let sum_params =
+            generate_random_parameters::<E, _, _>(SumCircuit { a: None, b: None }, &link_key, rng)
+                .unwrap();
+        assert_eq!(product_params.vk.commitment_bases.len(), 2);
+        let short_key = CommitmentKey::<E>::rand(1, rng);
+        assert!(matches!(
+            generate_random_parameters::<E, _, _>(
+                ProductCircuit { a: None, b: None },
+                &short_key,
+                rng
+            ),
+            Err(SynthesisError::CommitmentKeyTooShort)
+        ));
+        let product_pvk = prepare_verifying_key(&product_params.vk);
+        let sum_pvk = prepare_verifying_key(&sum_params.vk);
+
+        let a = E::Fr::rand(rng);
+        let b = E::Fr::rand(rng);
+        let link_blinding = E::Fr::rand(rng);
+
+        let product_proof = create_random_proof(
+            ProductCircuit {
+                a: Some(a),
+                b: Some(b),
+            },
+            &product_params,
+            link_blinding,
+            rng,
+        )
+        .unwrap();
+        let sum_proof = create_random_proof(
+            SumCircuit {
+                a: Some(a),
+                b: Some(b),
+            },
+            &sum_params,
+            link_blinding,
+            rng,
+        )
+        .unwrap();
+
+        assert!(verify_proof(&product_pvk, &product_proof, &[a * &b]).unwrap());
+        assert!(!verify_proof(&product_pvk, &product_proof, &[a + &b]).unwrap());
+        assert!(verify_proof(&sum_pvk, &sum_proof, &[a + &b]).unwrap());
+
+        // Both proofs commit to the same values under the shared key.
+        assert_eq!(product_proof.link_d, sum_proof.link_d);
+        assert!(
+            verify_commitment(&product_params.vk, &product_proof, &[a, b], link_blinding).unwrap()
+        );
+        assert!(
+            !verify_commitment(&product_params.vk, &product_proof, &[b, a], link_blinding).unwrap()
+        );
+
+        // A proof for different values commits to them differently.
+        let other_proof = create_random_proof(
+            SumCircuit {
+                a: Some(b),
+                b: Some(a),
+            },
+            &sum_params,
+            link_blinding,
+            rng,
+        )
+        .unwrap();
+        assert!(verify_proof(&sum_pvk, &other_proof, &[a + &b]).unwrap());
+        assert!(other_proof.link_d != sum_proof.link_d);
+
+        // Swapping in the commitment of another proof breaks the link proof.
+        let mut forged = sum_proof.clone();
+        forged.link_d = other_proof.link_d;
+        assert!(!verify_proof(&sum_pvk, &forged, &[a + &b]).unwrap());
+        let mut forged = sum_proof;
+        forged.d = other_proof.d;
+        assert!(!verify_proof(&sum_pvk, &forged, &[a + &b]).unwrap());
+    }
+
+    #[test]
+    fn bls12_377_commit_and_prove() {
+        commit_and_prove::<algebra::bls12_377::Bls12_377>();
+    }
+
+    #[test]
+    fn mnt4_298_commit_and_prove() {
+        commit_and_prove::<algebra::mnt4_298::MNT4_298>();
+    }
+}
+
+mod aggregation {
+    use super::*;
+    use crate::{
+        aggregate::{
+            aggregate_proofs, verify_aggregate_proof, AggregateProof, AggregationError, GenericSRS,
+        },
+        ceremony::Accumulator,
+        create_random_proof, generate_random_parameters, prepare_verifying_key,
+    };
+    use algebra_core::{test_rng, PairingEngine, UniformRand};
+
+    fn aggregate<E: PairingEngine>(log_num_proofs: u32) {
+        let rng = &mut test_rng();
+        let num_proofs = 1 << log_num_proofs;
+
+        let mut alpha = Accumulator::<E>::new(num_proofs + 1);
+        alpha.contribute(rng);
+        let mut beta = Accumulator::<E>::new(num_proofs + 1);
+        beta.contribute(rng);
+        let srs = GenericSRS::from_accumulators(&alpha, &beta).unwrap();
+        let (pk, vk) = srs.specialize(num_proofs).unwrap();
+
+        let params =
+            generate_random_parameters::<E, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+        let pvk = prepare_verifying_key::<E>(&params.vk);
+
+        let mut proofs = Vec::new();
+        let mut inputs = Vec::new();
+        for _ in 0..num_proofs {
+            let a = E::Fr::rand(rng);
+            let b = E::Fr::rand(rng);
+            let proof = create_random_proof(
+                MySillyCircuit {
+                    a: Some(a),
+                    b: Some(b),
+                },
+                &params,
+                rng,
+            )
+            .unwrap();
+            proofs.push(proof);
+            inputs.push(vec![a * &b]);
+        }
+
+        let aggregate = aggregate_proofs(&pk, &proofs, &inputs).unwrap();
+        assert!(verify_aggregate_proof(&vk, &pvk, &inputs, &aggregate).unwrap());
+
+        let mut bytes = Vec::new();
+        aggregate.write(&mut bytes).unwrap();
+        let read = AggregateProof::<E>::read(&bytes[..]).unwrap();
+        assert_eq!(read, aggregate);
+
+        // A wrong statement is rejected.
+        let mut wrong_inputs = inputs.clone();
+        wrong_inputs[num_proofs - 1][0] = E::Fr::rand(rng);
+        assert!(!verify_aggregate_proof(&vk, &pvk, &wrong_inputs, &aggregate).unwrap());
+
+        // Aggregating an invalid proof yields an invalid aggregate.
+        let mut swapped = proofs.clone();
+        swapped.swap(0, 1);
+        let invalid = aggregate_proofs(&pk, &swapped, &inputs).unwrap();
+        assert!(!verify_aggregate_proof(&vk, &pvk, &inputs, &invalid).unwrap());
+
+        // So does tampering with the aggregate proof.
+        let mut tampered = aggregate.clone();
+        tampered.z_c = proofs[0].c;
+        assert!(!verify_aggregate_proof(&vk, &pvk, &inputs, &tampered).unwrap());
+        let mut tampered = aggregate;
+        tampered.tmipp.final_c = proofs[0].c;
+        assert!(!verify_aggregate_proof(&vk, &pvk, &inputs, &tampered).unwrap());
+
+        // The number of statements must match the reference string.
+        assert!(verify_aggregate_proof(&vk, &pvk, &inputs[1..], &invalid).is_err());
+    }
+
+    #[test]
+    fn aggregate_two_proofs() {
+        aggregate::<algebra::bls12_381::Bls12_381>(1);
+    }
+
+    #[test]
+    fn aggregate_eight_proofs() {
+        aggregate::<algebra::bls12_381::Bls12_381>(3);
+    }
+
+    #[test]
+    fn aggregate_sixteen_proofs() {
+        aggregate::<algebra::bls12_377::Bls12_377>(4);
+    }
+
+    #[test]
+    fn rejects_bad_proof_counts() {
+        let rng = &mut test_rng();
+        let mut alpha = Accumulator::<algebra::bls12_381::Bls12_381>::new(5);
+        alpha.contribute(rng);
+        let mut beta = Accumulator::new(5);
+        beta.contribute(rng);
+        let srs = GenericSRS::from_accumulators(&alpha, &beta).unwrap();
+
+        assert!(srs.specialize(4).is_ok());
+        match srs.specialize(3) {
+            Err(AggregationError::InvalidProofCount) => {},
+            _ => panic!("three proofs cannot be aggregated"),
+        }
+        match srs.specialize(8) {
+            Err(AggregationError::SrsTooSmall) => {},
+            _ => panic!("the reference string only supports four proofs"),
+        }
+    }
+}
+
+mod progress {
+    use super::*;
+    use crate::{
+        create_random_proof_with_observer, generate_random_parameters, prepare_verifying_key,
+        prover::{generic, source::DEFAULT_CHUNK_SIZE},
+        verify_proof, Cow, ParameterSource, Parameters, VerifyingKey,
+    };
+    use algebra::bls12_377::{Bls12_377, Fr, G1Affine, G2Affine};
+    use algebra_core::{test_rng, UniformRand};
+    use core::ops::Range;
+    use ff_fft::GeneralEvaluationDomain;
+    use r1cs_core::{CancellationToken, CircuitDigest, ProverPhase};
+    use std::cell::RefCell;
+
+    #[test]
+    fn reports_every_phase() {
+        let rng = &mut test_rng();
+        let params =
+            generate_random_parameters::<Bls12_377, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+
+        let a = Fr::rand(rng);
+        let b = Fr::rand(rng);
+        let reports = RefCell::new(Vec::new());
+        let proof = create_random_proof_with_observer(
+            MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            },
+            &params,
+            rng,
+            &|phase: ProverPhase, fraction: f64| reports.borrow_mut().push((phase, fraction)),
+            &CancellationToken::new(),
+        )
+        .unwrap();
+        assert!(verify_proof(&pvk, &proof, &[a * &b]).unwrap());
+
+        let reports = reports.into_inner();
+        let phases = [
+            ProverPhase::Synthesis,
+            ProverPhase::WitnessMap,
+            ProverPhase::Msm("A"),
+            ProverPhase::Msm("B in G1"),
+            ProverPhase::Msm("B in G2"),
+            ProverPhase::Msm("H"),
+            ProverPhase::Msm("L"),
+        ];
+        for phase in &phases {
+            let fractions = reports
+                .iter()
+                .filter(|(p, _)| p == phase)
+                .map(|(_, fraction)| *fraction)
+                .collect::<Vec<_>>();
+            assert_eq!(fractions.first(), Some(&0.0));
+            assert_eq!(fractions.last(), Some(&1.0));
+            assert!(fractions.windows(2).all(|w| w[0] <= w[1]));
+        }
+    }
+
+    #[test]
+    fn cancellation_stops_the_prover() {
+        let rng = &mut test_rng();
+        let params =
+            generate_random_parameters::<Bls12_377, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+        let circuit = || MySillyCircuit {
+            a: Some(Fr::from(2u64)),
+            b: Some(Fr::from(3u64)),
+        };
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let result = create_random_proof_with_observer(
+            circuit(),
+            &params,
+            rng,
+            &|_: ProverPhase, _: f64| {},
+            &cancel,
+        );
+        assert!(matches!(result, Err(SynthesisError::Cancelled)));
+
+        // Cancelling part way through stops the prover at its next report.
+        let cancel = CancellationToken::new();
+        let last_phase = RefCell::new(None);
+        let observer = |phase: ProverPhase, _: f64| {
+            *last_phase.borrow_mut() = Some(phase);
+            if phase == ProverPhase::Msm("A") {
+                cancel.cancel();
+            }
+        };
+        let result = create_random_proof_with_observer(circuit(), &params, rng, &observer, &cancel);
+        assert!(matches!(result, Err(SynthesisError::Cancelled)));
+        assert_eq!(*last_phase.borrow(), Some(ProverPhase::Msm("A")));
+    }
+
+    /// In-memory parameters handed to the prover one element at a time.
+    struct OneByOne<'a>(&'a Parameters<Bls12_377>);
+
+    fn slice<T>(query: &[T], range: Range<usize>) -> Cow<'_, [T]> {
+        let end = range.end.min(query.len());
+        Cow::Borrowed(&query[range.start.min(end)..end])
+    }
+
+    impl<'a> ParameterSource<Bls12_377> for OneByOne<'a> {
+        fn vk(&self) -> &VerifyingKey<Bls12_377> {
+            &self.0.vk
+        }
+
+        fn beta_g1(&self) -> G1Affine {
+            self.0.beta_g1
+        }
+
+        fn delta_g1(&self) -> G1Affine {
+            self.0.delta_g1
+        }
+
+        fn circuit_digest(&self) -> CircuitDigest {
+            self.0.circuit_digest
+        }
+
+        fn chunk_size(&self) -> usize {
+            1
+        }
+
+        fn a_query(&mut self, range: Range<usize>) -> Result<Cow<'_, [G1Affine]>, SynthesisError> {
+            Ok(slice(&self.0.a_query, range))
+        }
+
+        fn b_g1_query(
+            &mut self,
+            range: Range<usize>,
+        ) -> Result<Cow<'_, [G1Affine]>, SynthesisError> {
+            Ok(slice(&self.0.b_g1_query, range))
+        }
+
+        fn b_g2_query(
+            &mut self,
+            range: Range<usize>,
+        ) -> Result<Cow<'_, [G2Affine]>, SynthesisError> {
+            Ok(slice(&self.0.b_g2_query, range))
+        }
+
+        fn h_query(&mut self, range: Range<usize>) -> Result<Cow<'_, [G1Affine]>, SynthesisError> {
+            Ok(slice(&self.0.h_query, range))
+        }
+
+        fn l_query(&mut self, range: Range<usize>) -> Result<Cow<'_, [G1Affine]>, SynthesisError> {
+            Ok(slice(&self.0.l_query, range))
+        }
+    }
+
+    #[test]
+    fn in_memory_parameters_are_chunked() {
+        let rng = &mut test_rng();
+        let params =
+            generate_random_parameters::<Bls12_377, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+        assert_eq!(
+            ParameterSource::<Bls12_377>::chunk_size(&&params),
+            DEFAULT_CHUNK_SIZE
+        );
+    }
+
+    #[test]
+    fn cancellation_stops_a_multi_scalar_multiplication() {
+        let rng = &mut test_rng();
+        let params =
+            generate_random_parameters::<Bls12_377, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+        let circuit = MySillyCircuit {
+            a: Some(Fr::from(2u64)),
+            b: Some(Fr::from(3u64)),
+        };
+
+        let cancel = CancellationToken::new();
+        let reports = RefCell::new(Vec::new());
+        let observer = |phase: ProverPhase, fraction: f64| {
+            reports.borrow_mut().push((phase, fraction));
+            if phase == ProverPhase::Msm("A") && fraction > 0.0 && fraction < 1.0 {
+                cancel.cancel();
+            }
+        };
+        let result = generic::create_random_proof_with_observer::<
+            _,
+            _,
+            GeneralEvaluationDomain<Fr>,
+            _,
+            _,
+            _,
+        >(circuit, OneByOne(&params), rng, &observer, &cancel);
+        assert!(matches!(result, Err(SynthesisError::Cancelled)));
+
+        let (phase, fraction) = *reports.borrow().last().unwrap();
+        assert_eq!(phase, ProverPhase::Msm("A"));
+        assert!(fraction > 0.0 && fraction < 1.0);
+    }
+}
+
+mod batch_proving {
+    use super::*;
+    use crate::{
+        create_random_proof, create_random_proofs_batch, generate_random_parameters,
+        prepare_verifying_key, verify_proof, Proof,
+    };
+    use algebra_core::{test_rng, CanonicalSerialize, PairingEngine, UniformRand};
+
+    fn to_bytes<E: PairingEngine>(proof: &Proof<E>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        proof.serialize(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn batch_matches_sequential<E: PairingEngine>() {
+        let rng = &mut test_rng();
+        let params =
+            generate_random_parameters::<E, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+        let pvk = prepare_verifying_key::<E>(&params.vk);
+
+        let witnesses = (0..6)
+            .map(|_| (E::Fr::rand(rng), E::Fr::rand(rng)))
+            .collect::<Vec<_>>();
+        let circuits = || {
+            witnesses
+                .iter()
+                .map(|(a, b)| MySillyCircuit {
+                    a: Some(*a),
+                    b: Some(*b),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut sequential_rng = rng.clone();
+        let proofs = create_random_proofs_batch(circuits(), &params, rng).unwrap();
+        assert_eq!(proofs.len(), witnesses.len());
+        for ((proof, circuit), (a, b)) in proofs.iter().zip(circuits()).zip(&witnesses) {
+            let expected = create_random_proof(circuit, &params, &mut sequential_rng).unwrap();
+            assert_eq!(to_bytes(proof), to_bytes(&expected));
+            assert!(verify_proof(&pvk, proof, &[*a * b]).unwrap());
+        }
+
+        let empty =
+            create_random_proofs_batch::<E, MySillyCircuit<E::Fr>, _>(Vec::new(), &params, rng)
+                .unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn bls12_377() {
+        batch_matches_sequential::<algebra::bls12_377::Bls12_377>();
+    }
+
+    #[test]
+    fn mnt4_298() {
+        batch_matches_sequential::<algebra::mnt4_298::MNT4_298>();
+    }
+}
+
+mod seeded_parameters {
+    use super::*;
+    use crate::{
+        create_random_proof, generate_parameters_from_seed, prepare_verifying_key, verify_proof,
+        Parameters, VerifyingKey,
+    };
+    use algebra::bls12_377::{Bls12_377, Fr};
+    use algebra_core::{test_rng, UniformRand};
+
+    struct SquareCircuit;
+
+    impl<F: Field> ConstraintSynthesizer<F> for SquareCircuit {
+        fn generate_constraints<CS: ConstraintSystem<F>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let a = cs.alloc(|| "a", || Err(SynthesisError::AssignmentMissing))?;
+            let c = cs.alloc_input(|| "c", || Err(SynthesisError::AssignmentMissing))?;
+            cs.enforce(|| "a*a=c", |lc| lc + a, |lc| lc + a, |lc| lc + c);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn same_seed_same_parameters() {
+        let circuit = || MySillyCircuit::<Fr> { a: None, b: None };
+        let params = generate_parameters_from_seed::<Bls12_377, _>(circuit(), [7u8; 32]).unwrap();
+        let again = generate_parameters_from_seed::<Bls12_377, _>(circuit(), [7u8; 32]).unwrap();
+        assert!(params == again);
+        assert_eq!(params.fingerprint(), again.fingerprint());
+        assert_eq!(params.vk.fingerprint(), again.vk.fingerprint());
+        assert_ne!(params.fingerprint(), params.vk.fingerprint());
+
+        // The fingerprints survive a round trip through serialization.
+        let mut bytes = Vec::new();
+        params.write(&mut bytes).unwrap();
+        let read = Parameters::<Bls12_377>::read(&bytes[..], true).unwrap();
+        assert_eq!(read.fingerprint(), params.fingerprint());
+        let mut bytes = Vec::new();
+        params.vk.write_uncompressed(&mut bytes).unwrap();
+        let read = VerifyingKey::<Bls12_377>::read(&bytes[..]).unwrap();
+        assert_eq!(read.fingerprint(), params.vk.fingerprint());
+
+        // The seeded parameters are ordinary parameters.
+        let rng = &mut test_rng();
+        let pvk = prepare_verifying_key(&params.vk);
+        let a = Fr::rand(rng);
+        let b = Fr::rand(rng);
+        let proof = create_random_proof(
+            MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            },
+            &params,
+            rng,
+        )
+        .unwrap();
+        assert!(verify_proof(&pvk, &proof, &[a * &b]).unwrap());
+    }
+
+    #[test]
+    fn fingerprints_detect_changes() {
+        let circuit = || MySillyCircuit::<Fr> { a: None, b: None };
+        let params = generate_parameters_from_seed::<Bls12_377, _>(circuit(), [7u8; 32]).unwrap();
+
+        let other_seed =
+            generate_parameters_from_seed::<Bls12_377, _>(circuit(), [8u8; 32]).unwrap();
+        assert_ne!(params.fingerprint(), other_seed.fingerprint());
+        assert_ne!(params.vk.fingerprint(), other_seed.vk.fingerprint());
+
+        let other_circuit =
+            generate_parameters_from_seed::<Bls12_377, _>(SquareCircuit, [7u8; 32]).unwrap();
+        assert_ne!(params.fingerprint(), other_circuit.fingerprint());
+    }
+}
+
+mod circom {
+    use super::*;
+    use crate::{
+        create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof,
+    };
+    use algebra::{
+        bls12_381,
+        bn254::{Bn254, Fr},
+    };
+    use algebra_core::{test_rng, FpParameters, PrimeField, ToBytes, UniformRand};
+    use r1cs_core::{CircomCircuit, CircomError, R1CSRecorder};
+    use std::{fs, path::PathBuf};
+
+    // `tests/circom/multiplier.r1cs` holds the circuit of `multiplier.circom`,
+    // and `multiplier.wtns` its witness for `a = 3` and `b = 11`, in the
+    // layout of circom and snarkjs, including the labels section that
+    // `R1CSRecorder` does not write.
+    fn fixture(name: &str) -> Vec<u8> {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("circom")
+            .join(name);
+        fs::read(path).unwrap()
+    }
+
+    // Writes the assignment of a recorded circuit as a version 2 `.wtns`
+    // file, in the order of circom's wires.
+    fn write_wtns(cs: &R1CSRecorder<Fr>) -> Vec<u8> {
+        let values = cs
+            .input_assignment
+            .iter()
+            .chain(&cs.aux_assignment)
+            .map(|value| value.unwrap())
+            .collect::<Vec<_>>();
+
+        let mut header = Vec::new();
+        32u32.write(&mut header).unwrap();
+        <Fr as PrimeField>::Params::MODULUS
+            .write(&mut header)
+            .unwrap();
+        (values.len() as u32).write(&mut header).unwrap();
+        let mut witness = Vec::new();
+        for value in &values {
+            value.into_repr().write(&mut witness).unwrap();
+        }
+
+        let mut bytes = b"wtns".to_vec();
+        2u32.write(&mut bytes).unwrap();
+        2u32.write(&mut bytes).unwrap();
+        for (section, data) in &[(1u32, header), (2u32, witness)] {
+            section.write(&mut bytes).unwrap();
+            (data.len() as u64).write(&mut bytes).unwrap();
+            bytes.extend_from_slice(data);
+        }
+        bytes
+    }
+
+    fn record(a: Fr, b: Fr) -> (Vec<u8>, Vec<u8>) {
+        let mut cs = R1CSRecorder::new();
+        MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        }
+        .generate_constraints(&mut cs)
+        .unwrap();
+        let mut r1cs = Vec::new();
+        cs.write_r1cs(&mut r1cs).unwrap();
+        (r1cs, write_wtns(&cs))
+    }
+
+    #[test]
+    fn prove_and_verify_loaded_circuit() {
+        let rng = &mut test_rng();
+        let a = Fr::rand(rng);
+        let b = Fr::rand(rng);
+        let (r1cs, wtns) = record(a, b);
+
+        let mut circuit = CircomCircuit::<Fr>::read_r1cs(&r1cs[..]).unwrap();
+        assert_eq!(circuit.num_wires, 4);
+        assert_eq!(circuit.num_public, 1);
+        assert_eq!(circuit.constraints.len(), 6);
+
+        let params = generate_random_parameters::<Bn254, _, _>(circuit.clone(), rng).unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+
+        circuit.read_witness(&wtns[..]).unwrap();
+        let public_inputs = circuit.public_inputs().unwrap();
+        assert_eq!(public_inputs, vec![a * &b]);
+
+        let proof = create_random_proof(circuit, &params, rng).unwrap();
+        assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
+        assert!(!verify_proof(&pvk, &proof, &[a]).unwrap());
+    }
+
+    #[test]
+    fn prove_and_verify_circom_files() {
+        let rng = &mut test_rng();
+        let mut circuit = CircomCircuit::<Fr>::read_r1cs(&fixture("multiplier.r1cs")[..]).unwrap();
+        assert_eq!(circuit.num_wires, 4);
+        assert_eq!(circuit.num_public, 1);
+        assert_eq!(circuit.constraints.len(), 1);
+
+        let params = generate_random_parameters::<Bn254, _, _>(circuit.clone(), rng).unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+
+        circuit
+            .read_witness(&fixture("multiplier.wtns")[..])
+            .unwrap();
+        let public_inputs = circuit.public_inputs().unwrap();
+        assert_eq!(public_inputs, vec![Fr::from(33u64)]);
+
+        let proof = create_random_proof(circuit, &params, rng).unwrap();
+        assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
+        assert!(!verify_proof(&pvk, &proof, &[Fr::from(34u64)]).unwrap());
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        let rng = &mut test_rng();
+        let (r1cs, wtns) = record(Fr::rand(rng), Fr::rand(rng));
+
+        // Sizes read from the file are not trusted: a section that claims to
+        // be longer than the file, or a prime that is wider than the field,
+        // is rejected without allocating that much. The size of the first
+        // section follows the magic number, the version, the number of
+        // sections and the section type, and the header starts with the size
+        // of field elements.
+        let circom_r1cs = fixture("multiplier.r1cs");
+        let mut oversized = circom_r1cs.clone();
+        oversized[16..24].copy_from_slice(&u64::max_value().to_le_bytes());
+        match CircomCircuit::<Fr>::read_r1cs(&oversized[..]) {
+            Err(CircomError::IoError(_)) => {},
+            result => panic!("unexpected result {:?}", result),
+        }
+        let mut wide = circom_r1cs.clone();
+        wide[24..28].copy_from_slice(&u32::max_value().to_le_bytes());
+        match CircomCircuit::<Fr>::read_r1cs(&wide[..]) {
+            Err(CircomError::PrimeMismatch) => {},
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        // The header must count the "one" wire and at most as many public
+        // wires as follow it. The prime is followed by the numbers of wires,
+        // public outputs and public inputs, and eventually by the number of
+        // constraints.
+        let set_u32 = |offset: usize, value: u32| {
+            let mut bytes = circom_r1cs.clone();
+            bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+            bytes
+        };
+        for bytes in &[set_u32(60, 0), set_u32(64, 4), set_u32(68, 3)] {
+            match CircomCircuit::<Fr>::read_r1cs(&bytes[..]) {
+                Err(CircomError::InvalidHeader) => {},
+                result => panic!("unexpected result {:?}", result),
+            }
+        }
+
+        // The number of constraints must account for the whole constraints
+        // section.
+        match CircomCircuit::<Fr>::read_r1cs(&set_u32(84, 0)[..]) {
+            Err(CircomError::TrailingBytes(2)) => {},
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        match CircomCircuit::<bls12_381::Fr>::read_r1cs(&r1cs[..]) {
+            Err(CircomError::PrimeMismatch) => {},
+            result => panic!("unexpected result {:?}", result),
+        }
+        match CircomCircuit::<Fr>::read_r1cs(&wtns[..]) {
+            Err(CircomError::InvalidMagic("r1cs")) => {},
+            result => panic!("unexpected result {:?}", result),
+        }
+        assert!(CircomCircuit::<Fr>::read_r1cs(&r1cs[..r1cs.len() - 1]).is_err());
+
+        // A witness of another circuit does not assign every wire.
+        let mut circuit = CircomCircuit::<Fr>::read_r1cs(&r1cs[..]).unwrap();
+        let mut cs = R1CSRecorder::new();
+        MySillyCircuit {
+            a: Some(Fr::rand(rng)),
+            b: Some(Fr::rand(rng)),
+        }
+        .generate_constraints(&mut cs)
+        .unwrap();
+        cs.alloc(|| "extra", || Ok(Fr::rand(rng))).unwrap();
+        match circuit.read_witness(&write_wtns(&cs)[..]) {
+            Err(CircomError::WitnessLength {
+                expected: 4,
+                found: 5,
+            }) => {},
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        // Coefficients must be reduced. The first one follows the file and
+        // header sections, and the term count and wire of its term.
+        let mut unreduced = r1cs.clone();
+        let mut modulus = Vec::new();
+        <Fr as PrimeField>::Params::MODULUS
+            .write(&mut modulus)
+            .unwrap();
+        unreduced[108..140].copy_from_slice(&modulus);
+        match CircomCircuit::<Fr>::read_r1cs(&unreduced[..]) {
+            Err(CircomError::InvalidFieldElement) => {},
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+}
+
+mod optimized_constraints {
+    use super::*;
+    use crate::{
+        create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof,
+    };
+    use algebra::bls12_377::{Bls12_377, Fr};
+    use algebra_core::{test_rng, UniformRand};
+    use r1cs_core::R1CSRecorder;
+
+    // Proves knowledge of `x` and `y` such that `(x + y)^2 = out`, with a
+    // linear constraint for the sum.
+    struct SquaredSum {
+        x: Option<Fr>,
+        y: Option<Fr>,
+    }
+
+    impl ConstraintSynthesizer<Fr> for SquaredSum {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let sum_value = self.x.and_then(|x| self.y.map(|y| x + &y));
+            let out = cs.alloc_input(
+                || "out",
+                || {
+                    sum_value
+                        .map(|sum| sum.square())
+                        .ok_or(SynthesisError::AssignmentMissing)
+                },
+            )?;
+            let x = cs.alloc(|| "x", || self.x.ok_or(SynthesisError::AssignmentMissing))?;
+            let y = cs.alloc(|| "y", || self.y.ok_or(SynthesisError::AssignmentMissing))?;
+            let sum = cs.alloc(
+                || "sum",
+                || sum_value.ok_or(SynthesisError::AssignmentMissing),
+            )?;
+            cs.enforce(
+                || "x+y=sum",
+                |lc| lc + x + y,
+                |lc| lc + CS::one(),
+                |lc| lc + sum,
+            );
+            cs.enforce(|| "sum^2=out", |lc| lc + sum, |lc| lc + sum, |lc| lc + out);
+            Ok(())
+        }
+    }
+
+    fn record(circuit: SquaredSum) -> R1CSRecorder<Fr> {
+        let mut cs = R1CSRecorder::new();
+        circuit.generate_constraints(&mut cs).unwrap();
+        cs.optimize()
+    }
+
+    #[test]
+    fn prove_and_verify_optimized_circuit() {
+        let rng = &mut test_rng();
+        let optimized = record(SquaredSum { x: None, y: None });
+        assert_eq!(optimized.num_constraints(), 1);
+        assert_eq!(optimized.num_aux(), 2);
+
+        let params = generate_random_parameters::<Bls12_377, _, _>(optimized, rng).unwrap();
+        let unoptimized =
+            generate_random_parameters::<Bls12_377, _, _>(SquaredSum { x: None, y: None }, rng)
+                .unwrap();
+        assert!(params.l_query.len() < unoptimized.l_query.len());
+        let pvk = prepare_verifying_key(&params.vk);
+
+        let x = Fr::rand(rng);
+        let y = Fr::rand(rng);
+        let witness = record(SquaredSum {
+            x: Some(x),
+            y: Some(y),
+        });
+        let proof = create_random_proof(witness, &params, rng).unwrap();
+        assert!(verify_proof(&pvk, &proof, &[(x + &y).square()]).unwrap());
+        assert!(!verify_proof(&pvk, &proof, &[x]).unwrap());
+    }
+}
+
+mod witness_generation {
+    use super::*;
+    use crate::{
+        create_random_proof_with_matrices, generate_random_parameters, prepare_verifying_key,
+        verify_proof,
+    };
+    use algebra::bls12_377::{Bls12_377, Fr};
+    use algebra_core::{test_rng, UniformRand};
+    use r1cs_core::R1CSRecorder;
+
+    #[test]
+    fn prove_with_recorded_matrices() {
+        let rng = &mut test_rng();
+        let mut matrices = R1CSRecorder::new();
+        MySillyCircuit::<Fr> { a: None, b: None }
+            .generate_constraints(&mut matrices)
+            .unwrap();
+        let params =
+            generate_random_parameters::<Bls12_377, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+
+        for _ in 0..5 {
+            let a = Fr::rand(rng);
+            let b = Fr::rand(rng);
+            let circuit = MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            };
+            let proof =
+                create_random_proof_with_matrices(circuit, &matrices, &params, rng).unwrap();
+            assert!(verify_proof(&pvk, &proof, &[a * &b]).unwrap());
+            assert!(!verify_proof(&pvk, &proof, &[a]).unwrap());
+        }
+    }
+
+    #[test]
+    fn rejects_mismatched_circuit() {
+        let rng = &mut test_rng();
+        let params =
+            generate_random_parameters::<Bls12_377, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+
+        // Matrices of a circuit without any constraints.
+        let matrices = R1CSRecorder::new();
+        let circuit = MySillyCircuit {
+            a: Some(Fr::rand(rng)),
+            b: Some(Fr::rand(rng)),
+        };
+        assert!(matches!(
+            create_random_proof_with_matrices(circuit, &matrices, &params, rng),
+            Err(SynthesisError::CircuitMismatch)
+        ));
+    }
+}
+
+mod circuit_digest {
+    use super::*;
+    use crate::{
+        create_random_proof, create_random_proof_with_matrices, create_random_proof_with_source,
+        create_random_proofs_batch, generate_random_parameters, prepare_verifying_key,
+        verify_proof, FileParameters, Parameters,
+    };
+    use algebra::bls12_377::{Bls12_377, Fr};
+    use algebra_core::{test_rng, UniformRand};
+    use r1cs_core::{CircuitDigest, R1CSRecorder};
+    use std::io::Cursor;
+
+    // Like `MySillyCircuit`, but with `a * b = c` enforced once, so that it
+    // has the same variables but a different structure.
+    struct SingleConstraint {
+        a: Option<Fr>,
+        b: Option<Fr>,
+    }
+
+    impl ConstraintSynthesizer<Fr> for SingleConstraint {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let a = cs.alloc(|| "a", || self.a.ok_or(SynthesisError::AssignmentMissing))?;
+            let b = cs.alloc(|| "b", || self.b.ok_or(SynthesisError::AssignmentMissing))?;
+            let c = cs.alloc_input(
+                || "c",
+                || {
+                    let a = self.a.ok_or(SynthesisError::AssignmentMissing)?;
+                    let b = self.b.ok_or(SynthesisError::AssignmentMissing)?;
+                    Ok(a * &b)
+                },
+            )?;
+            cs.enforce(|| "a*b=c", |lc| lc + a, |lc| lc + b, |lc| lc + c);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn parameters_record_circuit_digest() {
+        let rng = &mut test_rng();
+        let params =
+            generate_random_parameters::<Bls12_377, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+        let digest = CircuitDigest::of(MySillyCircuit::<Fr> { a: None, b: None }).unwrap();
+        assert_eq!(params.circuit_digest, digest);
+        assert_ne!(
+            CircuitDigest::of(SingleConstraint { a: None, b: None }).unwrap(),
+            digest
+        );
+
+        let mut bytes = vec![];
+        params.write(&mut bytes).unwrap();
+        let read = Parameters::<Bls12_377>::read(&bytes[..], true).unwrap();
+        assert_eq!(read.circuit_digest, digest);
+    }
+
+    #[test]
+    fn prover_rejects_other_circuit() {
+        let rng = &mut test_rng();
+        let params =
+            generate_random_parameters::<Bls12_377, _, _>(MySillyCircuit { a: None, b: None }, rng)
+                .unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+        let a = Fr::rand(rng);
+        let b = Fr::rand(rng);
+        let circuit = || SingleConstraint {
+            a: Some(a),
+            b: Some(b),
+        };
+
+        assert!(matches!(
+            create_random_proof(circuit(), &params, rng),
+            Err(SynthesisError::CircuitMismatch)
+        ));
+        assert!(matches!(
+            create_random_proofs_batch(vec![circuit()], &params, rng),
+            Err(SynthesisError::CircuitMismatch)
+        ));
+
+        let mut bytes = vec![];
+        params.write(&mut bytes).unwrap();
+        let source = FileParameters::<Bls12_377, _>::new(Cursor::new(&bytes), true).unwrap();
+        assert!(matches!(
+            create_random_proof_with_source(circuit(), source, rng),
+            Err(SynthesisError::CircuitMismatch)
+        ));
+
+        let mut matrices = R1CSRecorder::new();
+        SingleConstraint { a: None, b: None }
+            .generate_constraints(&mut matrices)
+            .unwrap();
+        assert!(matches!(
+            create_random_proof_with_matrices(circuit(), &matrices, &params, rng),
+            Err(SynthesisError::CircuitMismatch)
+        ));
+
+        // The circuit that the parameters were generated for is still proven.
+        let proof = create_random_proof(
+            MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            },
+            &params,
+            rng,
+        )
+        .unwrap();
+        assert!(verify_proof(&pvk, &proof, &[a * &b]).unwrap());
+    }
+}
+
+mod forked_synthesis {
+    use super::*;
+    use crate::{
+        create_random_proof, create_random_proof_with_matrices, generate_random_parameters,
+        prepare_verifying_key, verify_proof,
+    };
+    use algebra::bls12_377::{Bls12_377, Fr};
+    use algebra_core::{test_rng, UniformRand};
+    use r1cs_core::{ForkSynthesizer, R1CSRecorder};
+
+    impl ForkSynthesizer<Fr> for MySillyCircuit<Fr> {
+        type Output = ();
+
+        fn synthesize<CS: ConstraintSystem<Fr>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+            self.generate_constraints(cs)
+        }
+    }
+
+    // Proves several products at once, each in a fork of its own.
+    struct Products(Vec<(Option<Fr>, Option<Fr>)>);
+
+    impl ConstraintSynthesizer<Fr> for Products {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let forks = self
+                .0
+                .into_iter()
+                .map(|(a, b)| MySillyCircuit { a, b })
+                .collect();
+            cs.fork(forks)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn prove_forked_circuit() {
+        let rng = &mut test_rng();
+        let unknown = || Products(vec![(None, None); 4]);
+        let mut matrices = R1CSRecorder::new();
+        unknown().generate_constraints(&mut matrices).unwrap();
+        let params = generate_random_parameters::<Bls12_377, _, _>(unknown(), rng).unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+
+        let factors = (0..4)
+            .map(|_| (Fr::rand(rng), Fr::rand(rng)))
+            .collect::<Vec<_>>();
+        let products = factors.iter().map(|(a, b)| *a * b).collect::<Vec<_>>();
+        let circuit = || Products(factors.iter().map(|(a, b)| (Some(*a), Some(*b))).collect());
+
+        // Both provers synthesize the forks in parallel, and must append
+        // them to the same system as the parameters were generated for.
+        let proof = create_random_proof_with_matrices(circuit(), &matrices, &params, rng).unwrap();
+        assert!(verify_proof(&pvk, &proof, &products).unwrap());
+        let proof = create_random_proof(circuit(), &params, rng).unwrap();
+        assert!(verify_proof(&pvk, &proof, &products).unwrap());
+
+        let mut swapped = products.clone();
+        swapped.swap(0, 1);
+        assert!(!verify_proof(&pvk, &proof, &swapped).unwrap());
+    }
+}
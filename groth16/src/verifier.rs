@@ -1,11 +1,18 @@
-use algebra_core::{AffineCurve, PairingEngine, PrimeField, ProjectiveCurve};
+use algebra_core::{
+    msm::VariableBaseMSM, AffineCurve, Field, PairingEngine, PrimeField, ProjectiveCurve, Zero,
+};
+use ff_fft::cfg_iter;
+use rand::Rng;
 
 use super::{PreparedVerifyingKey, Proof, VerifyingKey};
 
-use crate::SynthesisError;
+use crate::{SynthesisError, Vec};
 
 use core::ops::{AddAssign, Neg};
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
 pub fn prepare_verifying_key<E: PairingEngine>(vk: &VerifyingKey<E>) -> PreparedVerifyingKey<E> {
     PreparedVerifyingKey {
         vk: vk.clone(),
@@ -43,3 +50,138 @@ pub fn verify_proof<E: PairingEngine>(
 
     Ok(test == pvk.alpha_g1_beta_g2)
 }
+
+/// Verify a batch of proofs that share the same verifying key.
+///
+/// The verification equations of all proofs are combined with random
+/// 128-bit coefficients, so that the whole batch costs a single multi-Miller
+/// loop and a single final exponentiation. If the combined check fails, the
+/// batch is bisected to locate the invalid proofs.
+///
+/// Returns the indices of the proofs that failed to verify; an empty vector
+/// means that every proof in the batch is valid. Fails with
+/// `SynthesisError::ProofCountMismatch` unless there are as many sets of
+/// public inputs as proofs.
+pub fn batch_verify_proofs<E, I, R>(
+    pvk: &PreparedVerifyingKey<E>,
+    proofs: &[Proof<E>],
+    public_inputs: &[I],
+    rng: &mut R,
+) -> Result<Vec<usize>, SynthesisError>
+where
+    E: PairingEngine,
+    I: AsRef<[E::Fr]>,
+    R: Rng,
+{
+    if proofs.len() != public_inputs.len() {
+        return Err(SynthesisError::ProofCountMismatch);
+    }
+    for inputs in public_inputs {
+        if (inputs.as_ref().len() + 1) != pvk.gamma_abc_g1.len() {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+    }
+
+    let batch_time = start_timer!(|| "Batch verification");
+    let mut failed = Vec::new();
+    bisect_batch(pvk, proofs, public_inputs, 0, rng, &mut failed)?;
+    end_timer!(batch_time);
+
+    Ok(failed)
+}
+
+fn bisect_batch<E, I, R>(
+    pvk: &PreparedVerifyingKey<E>,
+    proofs: &[Proof<E>],
+    public_inputs: &[I],
+    offset: usize,
+    rng: &mut R,
+    failed: &mut Vec<usize>,
+) -> Result<(), SynthesisError>
+where
+    E: PairingEngine,
+    I: AsRef<[E::Fr]>,
+    R: Rng,
+{
+    if proofs.is_empty() || verify_batch(pvk, proofs, public_inputs, rng)? {
+        return Ok(());
+    }
+    if proofs.len() == 1 {
+        failed.push(offset);
+        return Ok(());
+    }
+
+    let mid = proofs.len() / 2;
+    bisect_batch(
+        pvk,
+        &proofs[..mid],
+        &public_inputs[..mid],
+        offset,
+        rng,
+        failed,
+    )?;
+    bisect_batch(
+        pvk,
+        &proofs[mid..],
+        &public_inputs[mid..],
+        offset + mid,
+        rng,
+        failed,
+    )
+}
+
+/// Checks the random linear combination
+/// prod_i e(r_i * A_i, B_i) = e(alpha, beta)^{sum_i r_i}
+///     * e(sum_i r_i * IC_i, gamma) * e(sum_i r_i * C_i, delta).
+fn verify_batch<E, I, R>(
+    pvk: &PreparedVerifyingKey<E>,
+    proofs: &[Proof<E>],
+    public_inputs: &[I],
+    rng: &mut R,
+) -> Result<bool, SynthesisError>
+where
+    E: PairingEngine,
+    I: AsRef<[E::Fr]>,
+    R: Rng,
+{
+    let rs = (0..proofs.len())
+        .map(|_| E::Fr::from(rng.gen::<u128>()))
+        .collect::<Vec<_>>();
+    let r_sum: E::Fr = rs.iter().sum();
+
+    // Fold the public inputs of all proofs into a single MSM over `gamma_abc_g1`.
+    let mut input_scalars = vec![E::Fr::zero(); pvk.gamma_abc_g1.len()];
+    input_scalars[0] = r_sum;
+    for (r, inputs) in rs.iter().zip(public_inputs) {
+        for (acc, input) in input_scalars[1..].iter_mut().zip(inputs.as_ref()) {
+            *acc += &(*r * input);
+        }
+    }
+    let input_scalars = cfg_iter!(input_scalars)
+        .map(|s| s.into_repr())
+        .collect::<Vec<_>>();
+    let g_ic = VariableBaseMSM::multi_scalar_mul(&pvk.gamma_abc_g1, &input_scalars);
+
+    let r_reprs = cfg_iter!(rs).map(|r| r.into_repr()).collect::<Vec<_>>();
+    let c_bases = proofs.iter().map(|proof| proof.c).collect::<Vec<_>>();
+    let g_c = VariableBaseMSM::multi_scalar_mul(&c_bases, &r_reprs);
+
+    let r_a = cfg_iter!(proofs)
+        .zip(&r_reprs)
+        .map(|(proof, r)| proof.a.mul(*r))
+        .collect::<Vec<_>>();
+    let r_a = E::G1Projective::batch_normalization_into_affine(&r_a);
+
+    let mut pairs = r_a
+        .into_iter()
+        .zip(proofs)
+        .map(|(r_a, proof)| (r_a.into(), proof.b.into()))
+        .collect::<Vec<(E::G1Prepared, E::G2Prepared)>>();
+    pairs.push((g_ic.into_affine().into(), pvk.gamma_g2_neg_pc.clone()));
+    pairs.push((g_c.into_affine().into(), pvk.delta_g2_neg_pc.clone()));
+
+    let qap = E::miller_loop(pairs.iter());
+    let test = E::final_exponentiation(&qap).ok_or(SynthesisError::UnexpectedIdentity)?;
+
+    Ok(test == pvk.alpha_g1_beta_g2.pow(r_sum.into_repr()))
+}
@@ -0,0 +1,142 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// This file was generated by the groth16 crate. Do not edit it by hand.
+pragma solidity ^0.8.0;
+
+/// @title Groth16 verifier over BLS12-381
+/// @notice Verifies proofs against a fixed verifying key, using the
+/// precompiles of EIP-2537.
+contract Groth16Verifier {
+    /// The modulus of the scalar field, which bounds the public inputs.
+    uint256 internal constant SCALAR_MODULUS = 0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001;
+    uint256 internal constant NUM_INPUTS = 2;
+
+    /// The lengths in bytes of the encodings of points of G1 and G2.
+    uint256 internal constant G1_LENGTH = 128;
+    uint256 internal constant G2_LENGTH = 256;
+
+    address internal constant G1_MSM = address(0x0c);
+    address internal constant PAIRING = address(0x0f);
+
+    /// alpha in G1, followed by -beta in G2.
+    bytes internal constant ALPHA_G1_NEG_BETA_G2 =
+        hex"000000000000000000000000000000000572cbea904d67468808c8eb50a9450c"
+        hex"9721db309128012543902d0ac358a62ae28f75bb8f1c7c42c39a8c5529bf0f4e"
+        hex"00000000000000000000000000000000166a9d8cabc673a322fda673779d8e38"
+        hex"22ba3ecb8670e461f73bb9021d5fd76a4c56d9d4cd16bd1bba86881979749d28"
+        hex"00000000000000000000000000000000122915c824a0857e2ee414a3dccb23ae"
+        hex"691ae54329781315a0c75df1c04d6d7a50a030fc866f09d516020ef82324afae"
+        hex"0000000000000000000000000000000009380275bbc8e5dcea7dc4dd7e0550ff"
+        hex"2ac480905396eda55062650f8d251c96eb480673937cc6d9d6a44aaa56ca66dc"
+        hex"000000000000000000000000000000000edf3770e3e948394a0f2d9b87313dd6"
+        hex"2de12e66b864611834c60b67f7bb2f02d70e026a2601020d74a0bb7ec12fd219"
+        hex"00000000000000000000000000000000110ed83006e4ad324cd2d09d9fdeae78"
+        hex"01cf6756e79350d1f5ef81ff8ff138b84f40c4a5f7de4611cfa82ac0dc5ec262";
+
+    /// -gamma in G2.
+    bytes internal constant NEG_GAMMA_G2 =
+        hex"000000000000000000000000000000000411a5de6730ffece671a9f21d65028c"
+        hex"c0f1102378de124562cb1ff49db6f004fcd14d683024b0548eff3d1468df2688"
+        hex"0000000000000000000000000000000000fb837804dba8213329db46608b6c12"
+        hex"1d973363c1234a86dd183baff112709cf97096c5e9a1a770ee9d7dc641a894d6"
+        hex"00000000000000000000000000000000004b28f464d8b76ed59a8cf5bea3b4c3"
+        hex"3303eaca2e55a8145141fe8a41c15ceb3dee3b7854913b2ebc6a818396d9ad97"
+        hex"0000000000000000000000000000000010cbaa3616f4051b64ee9613ee5ddc95"
+        hex"762bb648f3cc59f76e0b153a93fccc987283dd4a6a5e4a217e75c1e41a556125";
+
+    /// -delta in G2.
+    bytes internal constant NEG_DELTA_G2 =
+        hex"00000000000000000000000000000000049cd1dbb2d2c3581e54c088135fef36"
+        hex"505a6823d61b859437bfc79b617030dc8b40e32bad1fa85b9c0f368af6d38d3c"
+        hex"000000000000000000000000000000000d0273f6bf31ed37c3b8d68083ec3d8e"
+        hex"20b5f2cc170fa24b9b5be35b34ed013f9a921f1cad1644d4bdb14674247234c8"
+        hex"000000000000000000000000000000001149639c79ffba82a4b71f73b11f186f"
+        hex"8016a4686ab17ed0ec3d7bc6e476c6ee04c3f3c2d48b1d4ddfac073266ebddce"
+        hex"00000000000000000000000000000000141418b3e4c84511f485fcc78b80b8bc"
+        hex"623d6f3f1282e6da09f9c1860402272ba7129c72c4fcd2174f8ac87671053a8b";
+
+    /// The points of G1 that the public inputs are committed to.
+    bytes internal constant GAMMA_ABC_G1 =
+        hex"0000000000000000000000000000000000fd75ebcc0a21649e3177bcce15426d"
+        hex"a0e4f25d6828fbf4038d4d7ed3bd4421de3ef61d70f794687b12b2d571971a55"
+        hex"0000000000000000000000000000000004523f5a3915fc57ee889cdb057e3e76"
+        hex"109112d125217546ccfe26810c99b130d1b27820595ad61c7527dc5bbb132a90"
+        hex"00000000000000000000000000000000051f8a0b82a6d86202a61cbc3b0f3db7"
+        hex"d19650b914587bde4715ccd372e1e40cab95517779d840416e1679c84a6db24e"
+        hex"000000000000000000000000000000000b6a63ac48b7d7666ccfcf1e7de0097c"
+        hex"5e6e1aacd03507d23fb975d8daec42857b3a471bf3fc471425b63864e045f4df"
+        hex"0000000000000000000000000000000000000000000000000000000000000000"
+        hex"0000000000000000000000000000000000000000000000000000000000000000"
+        hex"0000000000000000000000000000000000000000000000000000000000000000"
+        hex"0000000000000000000000000000000000000000000000000000000000000000";
+
+    /// @notice Checks a proof against its public inputs.
+    /// @param proof The points A, B and C of the proof, encoded as the
+    /// precompiles expect them.
+    /// @param input The public inputs, each less than the scalar field modulus.
+    /// @return Whether the proof is valid.
+    function verifyProof(bytes calldata proof, uint256[] calldata input)
+        external
+        view
+        returns (bool)
+    {
+        require(proof.length == 2 * G1_LENGTH + G2_LENGTH, "invalid proof length");
+        require(input.length == NUM_INPUTS, "invalid number of public inputs");
+
+        // e(A, B) * e(alpha, -beta) * e(vk_x, -gamma) * e(C, -delta) == 1
+        bytes memory pairingInput = abi.encodePacked(
+            proof[:G1_LENGTH + G2_LENGTH],
+            ALPHA_G1_NEG_BETA_G2,
+            inputCommitment(input),
+            NEG_GAMMA_G2,
+            proof[G1_LENGTH + G2_LENGTH:],
+            NEG_DELTA_G2
+        );
+        (bool success, bytes memory output) = PAIRING.staticcall(pairingInput);
+        return success && output.length == 32 && abi.decode(output, (uint256)) == 1;
+    }
+
+    /// Computes vk_x = gamma_abc[0] + sum(input[i] * gamma_abc[i + 1]) with
+    /// a single call to G1MSM.
+    function inputCommitment(uint256[] calldata input) private view returns (bytes memory) {
+        bytes memory gammaAbc = GAMMA_ABC_G1;
+        bytes memory msmInput = abi.encodePacked(
+            word(gammaAbc, 0),
+            word(gammaAbc, 1),
+            word(gammaAbc, 2),
+            word(gammaAbc, 3),
+            uint256(1)
+        );
+        for (uint256 i = 0; i < input.length; i++) {
+            require(input[i] < SCALAR_MODULUS, "public input is not a field element");
+            uint256 j = 4 * i + 4;
+            msmInput = abi.encodePacked(
+                msmInput,
+                word(gammaAbc, j),
+                word(gammaAbc, j + 1),
+                word(gammaAbc, j + 2),
+                word(gammaAbc, j + 3),
+                input[i]
+            );
+        }
+        return callPrecompile(G1_MSM, msmInput, G1_LENGTH);
+    }
+
+    /// Calls `precompile`, and checks that it succeeds with `outputLength`
+    /// bytes of output.
+    function callPrecompile(address precompile, bytes memory input, uint256 outputLength)
+        private
+        view
+        returns (bytes memory output)
+    {
+        bool success;
+        (success, output) = precompile.staticcall(input);
+        require(success && output.length == outputLength, "precompile call failed");
+    }
+
+    /// Reads the word at `index` of `data`.
+    function word(bytes memory data, uint256 index) private pure returns (uint256 value) {
+        assembly {
+            value := mload(add(data, mul(add(index, 1), 32)))
+        }
+    }
+}
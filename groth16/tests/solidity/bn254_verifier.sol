@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// This file was generated by the groth16 crate. Do not edit it by hand.
+pragma solidity ^0.8.0;
+
+/// @title Groth16 verifier over BN254
+/// @notice Verifies proofs against a fixed verifying key, using the
+/// precompiles of EIP-196 and EIP-197.
+contract Groth16Verifier {
+    /// The modulus of the scalar field, which bounds the public inputs.
+    uint256 internal constant SCALAR_MODULUS = 0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001;
+    uint256 internal constant NUM_INPUTS = 2;
+
+    /// The lengths in bytes of the encodings of points of G1 and G2.
+    uint256 internal constant G1_LENGTH = 64;
+    uint256 internal constant G2_LENGTH = 128;
+
+    address internal constant EC_ADD = address(0x06);
+    address internal constant EC_MUL = address(0x07);
+    address internal constant PAIRING = address(0x08);
+
+    /// alpha in G1, followed by -beta in G2.
+    bytes internal constant ALPHA_G1_NEG_BETA_G2 =
+        hex"030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3"
+        hex"15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4"
+        hex"1014772f57bb9742735191cd5dcfe4ebbc04156b6878a0a7c9824f32ffb66e85"
+        hex"06064e784db10e9051e52826e192715e8d7e478cb09a5e0012defa0694fbc7f5"
+        hex"2e462b3cedfc5472262048f38df42539b9ed16e51ebc764bf6f5a14cc405eb95"
+        hex"2ad6311c5f7be649b1053619f454efbd2dc849ba234fe2f5d71d1ebf7210a7b0";
+
+    /// -gamma in G2.
+    bytes internal constant NEG_GAMMA_G2 =
+        hex"0a09ccf561b55fd99d1c1208dee1162457b57ac5af3759d50671e510e428b2a1"
+        hex"2e539c423b302d13f4e5773c603948eaf5db5df8ae8a9a9113708390a06410d8"
+        hex"16aceb21a80cf8f2d36188a908b83c41d5ab129284508a356a0c7815bb8035e4"
+        hex"00d6aed828fa78623e2059b044c9a177ac5e6644991e6fb89ed9b881dd0d9151";
+
+    /// -delta in G2.
+    bytes internal constant NEG_DELTA_G2 =
+        hex"2903ba015a9abde26a5d081e84551e63be0fd4516e46ee6d593edeba46362455"
+        hex"224bdc5d4327fcf8ed702e01de1c2f1657a253ba75e32a89c390142aaa28b308"
+        hex"2c9b96a53a7ec14e3d619656a73b0d8c274aabefa38ad2df81336d58d5471c6f"
+        hex"12d14e7db70b5011c98392439e03dde1c34fea829e48eab50dfea4119768da08";
+
+    /// The points of G1 that the public inputs are committed to.
+    bytes internal constant GAMMA_ABC_G1 =
+        hex"2a14705537b009189da8808651eecdb82482477fe92ac12ca8b71f80fc3d49ef"
+        hex"2df7ee7f243ea8b38e1ddf14029258877a618c779fd4717db6177e19ea67ec38"
+        hex"05e86f8cc8a7a4f10f56093465679f17f8b8c3fdb41469e408b529e030f52f3f"
+        hex"2857bd14bbc09767bed8e913d3ccb42b2bc8738f715417dd6f020725d22bcd90"
+        hex"0000000000000000000000000000000000000000000000000000000000000000"
+        hex"0000000000000000000000000000000000000000000000000000000000000000";
+
+    /// @notice Checks a proof against its public inputs.
+    /// @param proof The points A, B and C of the proof, encoded as the
+    /// precompiles expect them.
+    /// @param input The public inputs, each less than the scalar field modulus.
+    /// @return Whether the proof is valid.
+    function verifyProof(bytes calldata proof, uint256[] calldata input)
+        external
+        view
+        returns (bool)
+    {
+        require(proof.length == 2 * G1_LENGTH + G2_LENGTH, "invalid proof length");
+        require(input.length == NUM_INPUTS, "invalid number of public inputs");
+
+        // e(A, B) * e(alpha, -beta) * e(vk_x, -gamma) * e(C, -delta) == 1
+        bytes memory pairingInput = abi.encodePacked(
+            proof[:G1_LENGTH + G2_LENGTH],
+            ALPHA_G1_NEG_BETA_G2,
+            inputCommitment(input),
+            NEG_GAMMA_G2,
+            proof[G1_LENGTH + G2_LENGTH:],
+            NEG_DELTA_G2
+        );
+        (bool success, bytes memory output) = PAIRING.staticcall(pairingInput);
+        return success && output.length == 32 && abi.decode(output, (uint256)) == 1;
+    }
+
+    /// Computes vk_x = gamma_abc[0] + sum(input[i] * gamma_abc[i + 1]) with
+    /// ecMul and ecAdd.
+    function inputCommitment(uint256[] calldata input)
+        private
+        view
+        returns (bytes memory commitment)
+    {
+        bytes memory gammaAbc = GAMMA_ABC_G1;
+        commitment = abi.encodePacked(word(gammaAbc, 0), word(gammaAbc, 1));
+        for (uint256 i = 0; i < input.length; i++) {
+            require(input[i] < SCALAR_MODULUS, "public input is not a field element");
+            uint256 j = 2 * i + 2;
+            bytes memory term = callPrecompile(
+                EC_MUL,
+                abi.encodePacked(word(gammaAbc, j), word(gammaAbc, j + 1), input[i]),
+                G1_LENGTH
+            );
+            commitment = callPrecompile(EC_ADD, abi.encodePacked(commitment, term), G1_LENGTH);
+        }
+    }
+
+    /// Calls `precompile`, and checks that it succeeds with `outputLength`
+    /// bytes of output.
+    function callPrecompile(address precompile, bytes memory input, uint256 outputLength)
+        private
+        view
+        returns (bytes memory output)
+    {
+        bool success;
+        (success, output) = precompile.staticcall(input);
+        require(success && output.length == outputLength, "precompile call failed");
+    }
+
+    /// Reads the word at `index` of `data`.
+    function word(bytes memory data, uint256 index) private pure returns (uint256 value) {
+        assembly {
+            value := mload(add(data, mul(add(index, 1), 32)))
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use algebra_core::{
+    format,
+    io::{self, Read},
+    vec, BigInteger, FpParameters, FromBytes, PrimeField, Vec,
+};
+use core::fmt;
+
+use crate::{
+    recorder::{field_size, CONSTRAINT_SECTION, HEADER_SECTION, R1CS_MAGIC, R1CS_VERSION},
+    ConstraintSynthesizer, ConstraintSystem, LinearCombination, SynthesisError,
+};
+
+/// The magic number at the start of circom `.wtns` files.
+const WTNS_MAGIC: &[u8; 4] = b"wtns";
+const WTNS_HEADER_SECTION: u32 = 1;
+const WTNS_WITNESS_SECTION: u32 = 2;
+
+/// The number of bytes of a section that are read at once, so that memory is
+/// only allocated for the bytes that the file actually contains, whatever
+/// size it claims for the section.
+const SECTION_CHUNK_SIZE: usize = 1 << 12;
+
+/// This is an error that could occur while loading circom `.r1cs` and `.wtns`
+/// files.
+#[derive(Debug)]
+pub enum CircomError {
+    /// The file could not be read.
+    IoError(io::Error),
+    /// The file does not start with the magic number of the named format.
+    InvalidMagic(&'static str),
+    /// The file has a version of its format that is not supported.
+    UnsupportedVersion(u32),
+    /// The file lacks a section of the given type.
+    MissingSection(u32),
+    /// The prime of the file is not the modulus of the field.
+    PrimeMismatch,
+    /// A field element in the file is not smaller than the modulus.
+    InvalidFieldElement,
+    /// The header counts no "one" wire, or more public wires than there are
+    /// wires besides it.
+    InvalidHeader,
+    /// A section has bytes left over once its contents are read.
+    TrailingBytes(u32),
+    /// A constraint refers to a wire that the circuit does not have.
+    InvalidWire(u32),
+    /// The witness does not assign every wire of the circuit.
+    WitnessLength {
+        /// The number of wires of the circuit.
+        expected: usize,
+        /// The number of values in the witness.
+        found: usize,
+    },
+}
+
+impl From<io::Error> for CircomError {
+    fn from(e: io::Error) -> CircomError {
+        CircomError::IoError(e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CircomError {}
+
+impl fmt::Display for CircomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            CircomError::IoError(err) => write!(f, "I/O error: {:?}", err),
+            CircomError::InvalidMagic(name) => write!(f, "not a {} file", name),
+            CircomError::UnsupportedVersion(version) => {
+                write!(f, "unsupported format version {}", version)
+            },
+            CircomError::MissingSection(section) => write!(f, "missing section {}", section),
+            CircomError::PrimeMismatch => write!(f, "the prime is not the field modulus"),
+            CircomError::InvalidFieldElement => write!(f, "invalid field element"),
+            CircomError::InvalidHeader => write!(f, "the header has invalid wire counts"),
+            CircomError::TrailingBytes(section) => {
+                write!(f, "section {} has trailing bytes", section)
+            },
+            CircomError::InvalidWire(wire) => write!(f, "wire {} does not exist", wire),
+            CircomError::WitnessLength { expected, found } => write!(
+                f,
+                "the witness has {} values, but the circuit has {} wires",
+                found, expected
+            ),
+        }
+    }
+}
+
+/// A circuit compiled by circom, loaded from its `.r1cs` file and optionally
+/// the `.wtns` file of a witness.
+///
+/// The wires of the circuit become variables in the order in which circom
+/// numbers them: wire 0 is the "one" variable, the public outputs and public
+/// inputs become input variables, and the remaining wires become auxiliary
+/// variables.
+#[derive(Clone, Debug)]
+pub struct CircomCircuit<F: PrimeField> {
+    /// The number of wires, including the "one" wire.
+    pub num_wires: usize,
+    /// The number of public outputs and public inputs.
+    pub num_public: usize,
+    /// The A, B and C linear combinations of each constraint, as pairs of a
+    /// coefficient and a wire.
+    pub constraints: Vec<[Vec<(F, usize)>; 3]>,
+    /// The value of each wire, if a witness was loaded.
+    pub witness: Option<Vec<F>>,
+}
+
+impl<F: PrimeField> CircomCircuit<F> {
+    /// Reads a circuit from a `.r1cs` file, checking that its prime is the
+    /// modulus of `F`.
+    pub fn read_r1cs<R: Read>(mut reader: R) -> Result<Self, CircomError> {
+        read_magic(&mut reader, R1CS_MAGIC, "r1cs")?;
+        let version = u32::read(&mut reader)?;
+        if version != R1CS_VERSION {
+            return Err(CircomError::UnsupportedVersion(version));
+        }
+
+        // The constraints can only be parsed once the header is known, and
+        // the sections may come in any order.
+        let mut header = None;
+        let mut constraints = None;
+        for _ in 0..u32::read(&mut reader)? {
+            let section = u32::read(&mut reader)?;
+            // The labels, and any later additions to the format, are not
+            // needed for proving.
+            let keep = section == HEADER_SECTION || section == CONSTRAINT_SECTION;
+            let bytes = read_section(&mut reader, keep)?;
+            match section {
+                HEADER_SECTION => header = Some(bytes),
+                CONSTRAINT_SECTION => constraints = Some(bytes),
+                _ => {},
+            }
+        }
+        let header = header.ok_or(CircomError::MissingSection(HEADER_SECTION))?;
+        let constraints = constraints.ok_or(CircomError::MissingSection(CONSTRAINT_SECTION))?;
+
+        let mut header = &header[..];
+        let n8 = read_prime::<F, _>(&mut header)?;
+        let num_wires = u32::read(&mut header)? as usize;
+        let num_public_outputs = u32::read(&mut header)? as usize;
+        let num_public_inputs = u32::read(&mut header)? as usize;
+        let _num_private_inputs = u32::read(&mut header)?;
+        let _num_labels = u64::read(&mut header)?;
+        let num_constraints = u32::read(&mut header)?;
+        // Wire 0 is the "one" wire, and the public wires follow it.
+        let num_public = num_public_outputs
+            .checked_add(num_public_inputs)
+            .ok_or(CircomError::InvalidHeader)?;
+        if num_wires == 0 || num_public >= num_wires {
+            return Err(CircomError::InvalidHeader);
+        }
+
+        let mut bytes = &constraints[..];
+        let mut read_lc = || -> Result<Vec<(F, usize)>, CircomError> {
+            let num_terms = u32::read(&mut bytes)?;
+            let mut terms = Vec::new();
+            for _ in 0..num_terms {
+                let wire = u32::read(&mut bytes)?;
+                if wire as usize >= num_wires {
+                    return Err(CircomError::InvalidWire(wire));
+                }
+                terms.push((read_field_element(&mut bytes, n8)?, wire as usize));
+            }
+            Ok(terms)
+        };
+        let mut circuit_constraints = Vec::new();
+        for _ in 0..num_constraints {
+            circuit_constraints.push([read_lc()?, read_lc()?, read_lc()?]);
+        }
+        if !bytes.is_empty() {
+            return Err(CircomError::TrailingBytes(CONSTRAINT_SECTION));
+        }
+
+        Ok(Self {
+            num_wires,
+            num_public,
+            constraints: circuit_constraints,
+            witness: None,
+        })
+    }
+
+    /// Reads a witness from a `.wtns` file, of version 1 or 2, checking that
+    /// its prime is the modulus of `F` and that it assigns every wire.
+    pub fn read_witness<R: Read>(&mut self, mut reader: R) -> Result<(), CircomError> {
+        read_magic(&mut reader, WTNS_MAGIC, "wtns")?;
+        let witness = match u32::read(&mut reader)? {
+            1 => read_witness_values(&mut reader)?,
+            2 => {
+                let mut header = None;
+                let mut values = None;
+                for _ in 0..u32::read(&mut reader)? {
+                    let section = u32::read(&mut reader)?;
+                    let keep = section == WTNS_HEADER_SECTION || section == WTNS_WITNESS_SECTION;
+                    let bytes = read_section(&mut reader, keep)?;
+                    match section {
+                        WTNS_HEADER_SECTION => header = Some(bytes),
+                        WTNS_WITNESS_SECTION => values = Some(bytes),
+                        _ => {},
+                    }
+                }
+                let header = header.ok_or(CircomError::MissingSection(WTNS_HEADER_SECTION))?;
+                let values = values.ok_or(CircomError::MissingSection(WTNS_WITNESS_SECTION))?;
+                let mut bytes = header;
+                bytes.extend_from_slice(&values);
+                read_witness_values(&mut &bytes[..])?
+            },
+            version => return Err(CircomError::UnsupportedVersion(version)),
+        };
+        self.set_witness(witness)
+    }
+
+    /// Sets the value of each wire, checking that every wire is assigned.
+    pub fn set_witness(&mut self, witness: Vec<F>) -> Result<(), CircomError> {
+        if witness.len() != self.num_wires {
+            return Err(CircomError::WitnessLength {
+                expected: self.num_wires,
+                found: witness.len(),
+            });
+        }
+        self.witness = Some(witness);
+        Ok(())
+    }
+
+    /// Returns the public outputs and public inputs of the witness, which
+    /// are the public inputs that a proof of the circuit is verified against.
+    pub fn public_inputs(&self) -> Option<Vec<F>> {
+        self.witness
+            .as_ref()
+            .map(|witness| witness[1..=self.num_public].to_vec())
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for CircomCircuit<F> {
+    fn generate_constraints<CS: ConstraintSystem<F>>(
+        self,
+        cs: &mut CS,
+    ) -> Result<(), SynthesisError> {
+        let witness = self.witness.as_ref();
+        let value = |wire: usize| {
+            witness
+                .map(|witness| witness[wire])
+                .ok_or(SynthesisError::AssignmentMissing)
+        };
+
+        let mut variables = Vec::new();
+        variables.push(CS::one());
+        for wire in 1..self.num_wires {
+            let variable = if wire <= self.num_public {
+                cs.alloc_input(|| format!("wire {}", wire), || value(wire))?
+            } else {
+                cs.alloc(|| format!("wire {}", wire), || value(wire))?
+            };
+            variables.push(variable);
+        }
+
+        let to_lc = |terms: &[(F, usize)], mut lc: LinearCombination<F>| {
+            for (coeff, wire) in terms {
+                lc += (*coeff, variables[*wire]);
+            }
+            lc
+        };
+        for (i, [a, b, c]) in self.constraints.iter().enumerate() {
+            cs.enforce(
+                || format!("constraint {}", i),
+                |lc| to_lc(a, lc),
+                |lc| to_lc(b, lc),
+                |lc| to_lc(c, lc),
+            );
+        }
+        Ok(())
+    }
+}
+
+fn read_magic<R: Read>(
+    mut reader: R,
+    magic: &[u8; 4],
+    name: &'static str,
+) -> Result<(), CircomError> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    if &bytes != magic {
+        return Err(CircomError::InvalidMagic(name));
+    }
+    Ok(())
+}
+
+/// Reads a section, which is returned if `keep` is set and skipped otherwise.
+/// The section is read a chunk at a time, so that a size that overstates the
+/// length of the file fails without allocating it.
+fn read_section<R: Read>(mut reader: R, keep: bool) -> Result<Vec<u8>, CircomError> {
+    let mut remaining = u64::read(&mut reader)?;
+    let mut bytes = Vec::new();
+    let mut chunk = [0u8; SECTION_CHUNK_SIZE];
+    while remaining > 0 {
+        let len = remaining.min(SECTION_CHUNK_SIZE as u64) as usize;
+        reader.read_exact(&mut chunk[..len])?;
+        if keep {
+            bytes.extend_from_slice(&chunk[..len]);
+        }
+        remaining -= len as u64;
+    }
+    Ok(bytes)
+}
+
+/// Reads the size of field elements and the prime, as they start the
+/// headers of both formats, and returns the size, which is at most the size
+/// of the representation of `F`.
+fn read_prime<F: PrimeField, R: Read>(mut reader: R) -> Result<usize, CircomError> {
+    let n8 = u32::read(&mut reader)? as usize;
+    // A larger prime could not be the modulus.
+    if n8 > field_size::<F>() {
+        return Err(CircomError::PrimeMismatch);
+    }
+    if read_integer::<F, _>(&mut reader, n8)? != F::Params::MODULUS {
+        return Err(CircomError::PrimeMismatch);
+    }
+    Ok(n8)
+}
+
+/// Reads an `n8`-byte little-endian integer into the representation of `F`,
+/// where `n8` is at most the size of that representation.
+fn read_integer<F: PrimeField, R: Read>(
+    mut reader: R,
+    n8: usize,
+) -> Result<F::BigInt, CircomError> {
+    let mut bytes = vec![0u8; field_size::<F>()];
+    reader.read_exact(&mut bytes[..n8])?;
+    Ok(F::BigInt::read(&bytes[..])?)
+}
+
+fn read_field_element<F: PrimeField, R: Read>(reader: R, n8: usize) -> Result<F, CircomError> {
+    let repr = read_integer::<F, _>(reader, n8)?;
+    if repr < F::Params::MODULUS {
+        Ok(F::from_repr(repr))
+    } else {
+        Err(CircomError::InvalidFieldElement)
+    }
+}
+
+/// Reads the header of a witness, followed by its values.
+fn read_witness_values<F: PrimeField, R: Read>(mut reader: R) -> Result<Vec<F>, CircomError> {
+    let n8 = read_prime::<F, _>(&mut reader)?;
+    let num_values = u32::read(&mut reader)?;
+    (0..num_values)
+        .map(|_| read_field_element(&mut reader, n8))
+        .collect()
+}
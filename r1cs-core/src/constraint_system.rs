@@ -1,8 +1,8 @@
 use crate::String;
-use algebra_core::Field;
+use algebra_core::{format, Field, Vec};
 use core::marker::PhantomData;
 
-use crate::{Index, LinearCombination, SynthesisError, Variable};
+use crate::{ForkSynthesizer, Index, LinearCombination, SynthesisError, Variable};
 
 /// Represents a constraint system which can have new variables
 /// allocated and constrains between them formed.
@@ -34,6 +34,23 @@ pub trait ConstraintSystem<F: Field>: Sized {
         A: FnOnce() -> AR,
         AR: Into<String>;
 
+    /// Allocate a private variable that proof systems supporting
+    /// commitments to the witness, such as the commit-and-prove variant of
+    /// Groth16, include in the commitment published with the proof. Other
+    /// constraint systems treat it like any other private variable.
+    fn alloc_committed<FN, A, AR>(
+        &mut self,
+        annotation: A,
+        f: FN,
+    ) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.alloc(annotation, f)
+    }
+
     /// Enforce that `A` * `B` = `C`. The `annotation` function is invoked in
     /// testing contexts in order to derive a unique name for the constraint
     /// in the current namespace.
@@ -73,6 +90,34 @@ pub trait ConstraintSystem<F: Field>: Sized {
 
     /// Output the number of constraints in the system.
     fn num_constraints(&self) -> usize;
+
+    /// Returns whether the constraint system only computes the assignments
+    /// of variables, and ignores the constraints passed to `enforce`.
+    /// Gadgets can then skip building linear combinations outside of the
+    /// closures passed to `enforce`, which are not invoked at all.
+    fn is_witness_generator(&self) -> bool {
+        false
+    }
+
+    /// Synthesizes independent parts of a circuit, and returns their outputs
+    /// in order.
+    ///
+    /// By default, the parts are synthesized one after the other, the `i`th
+    /// of them in a namespace named `fork i`. `WitnessGenerator` and the
+    /// Groth16 prover instead synthesize each part into a fork of their own,
+    /// in parallel when the `parallel` feature is enabled, and then append
+    /// the variables and constraints of the forks in order, which gives the
+    /// same system as synthesizing the parts one after the other.
+    fn fork<S: ForkSynthesizer<F>>(
+        &mut self,
+        forks: Vec<S>,
+    ) -> Result<Vec<S::Output>, SynthesisError> {
+        forks
+            .into_iter()
+            .enumerate()
+            .map(|(i, fork)| fork.synthesize(&mut self.ns(|| format!("fork {}", i))))
+            .collect()
+    }
 }
 
 /// This is a "namespaced" constraint system which borrows a constraint system
@@ -119,6 +164,20 @@ impl<F: Field, CS: ConstraintSystem<F>> ConstraintSystem<F> for Namespace<'_, F,
         self.0.alloc_input(annotation, f)
     }
 
+    #[inline]
+    fn alloc_committed<FN, A, AR>(
+        &mut self,
+        annotation: A,
+        f: FN,
+    ) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.0.alloc_committed(annotation, f)
+    }
+
     #[inline]
     fn enforce<A, AR, LA, LB, LC>(&mut self, annotation: A, a: LA, b: LB, c: LC)
     where
@@ -158,6 +217,19 @@ impl<F: Field, CS: ConstraintSystem<F>> ConstraintSystem<F> for Namespace<'_, F,
     fn num_constraints(&self) -> usize {
         self.0.num_constraints()
     }
+
+    #[inline]
+    fn is_witness_generator(&self) -> bool {
+        self.0.is_witness_generator()
+    }
+
+    #[inline]
+    fn fork<S: ForkSynthesizer<F>>(
+        &mut self,
+        forks: Vec<S>,
+    ) -> Result<Vec<S::Output>, SynthesisError> {
+        self.0.fork(forks)
+    }
 }
 
 impl<F: Field, CS: ConstraintSystem<F>> Drop for Namespace<'_, F, CS> {
@@ -197,6 +269,20 @@ impl<F: Field, CS: ConstraintSystem<F>> ConstraintSystem<F> for &mut CS {
         (**self).alloc_input(annotation, f)
     }
 
+    #[inline]
+    fn alloc_committed<FN, A, AR>(
+        &mut self,
+        annotation: A,
+        f: FN,
+    ) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        (**self).alloc_committed(annotation, f)
+    }
+
     #[inline]
     fn enforce<A, AR, LA, LB, LC>(&mut self, annotation: A, a: LA, b: LB, c: LC)
     where
@@ -232,4 +318,17 @@ impl<F: Field, CS: ConstraintSystem<F>> ConstraintSystem<F> for &mut CS {
     fn num_constraints(&self) -> usize {
         (**self).num_constraints()
     }
+
+    #[inline]
+    fn is_witness_generator(&self) -> bool {
+        (**self).is_witness_generator()
+    }
+
+    #[inline]
+    fn fork<S: ForkSynthesizer<F>>(
+        &mut self,
+        forks: Vec<S>,
+    ) -> Result<Vec<S::Output>, SynthesisError> {
+        (**self).fork(forks)
+    }
 }
@@ -0,0 +1,315 @@
+use algebra_core::{
+    io::{Read, Write},
+    CanonicalDeserialize, CanonicalSerialize, ConstantSerializedSize, Field, SerializationError,
+    ToBytes, Vec,
+};
+use blake2::{Blake2s, Digest};
+use core::{fmt, marker::PhantomData};
+
+use crate::{
+    optimizer::normalize, ConstraintSynthesizer, ConstraintSystem, Index, LinearCombination,
+    MatrixRow, R1CSRecorder, String, SynthesisError, Variable,
+};
+
+/// Separates circuit digests from other BLAKE2s hashes.
+const PERSONALIZATION: &[u8] = b"zexe-r1cs-digest-v1";
+
+/// A digest of the structure of a circuit: its numbers of variables and the
+/// A, B and C matrices of its constraints, but not the names of anything nor
+/// the assignment of any variable.
+///
+/// The terms of each linear combination are sorted, merged and stripped of
+/// zero coefficients before hashing, so that circuits that build the same
+/// constraints in different ways have the same digest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CircuitDigest(pub [u8; 32]);
+
+impl CircuitDigest {
+    /// Computes the digest of `circuit`, without computing any assignment.
+    pub fn of<F, C>(circuit: C) -> Result<Self, SynthesisError>
+    where
+        F: Field,
+        C: ConstraintSynthesizer<F>,
+    {
+        let mut hasher = CircuitHasher::new();
+        circuit.generate_constraints(&mut hasher)?;
+        Ok(hasher.finish())
+    }
+}
+
+impl fmt::Display for CircuitDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl CanonicalSerialize for CircuitDigest {
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), SerializationError> {
+        Ok(writer.write_all(&self.0)?)
+    }
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        Self::SERIALIZED_SIZE
+    }
+}
+
+impl ConstantSerializedSize for CircuitDigest {
+    const SERIALIZED_SIZE: usize = 32;
+    const UNCOMPRESSED_SIZE: usize = Self::SERIALIZED_SIZE;
+}
+
+impl CanonicalDeserialize for CircuitDigest {
+    #[inline]
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self, SerializationError> {
+        let mut bytes = [0u8; 32];
+        reader.read_exact(&mut bytes)?;
+        Ok(CircuitDigest(bytes))
+    }
+}
+
+/// A constraint system that hashes the structure of a circuit into a
+/// [`CircuitDigest`], without invoking the closures that compute the
+/// assignments of variables.
+///
+/// Constraints that were already recorded as matrices are hashed with
+/// [`hash_constraint`](CircuitHasher::hash_constraint), which gives the same
+/// digest as synthesizing them.
+#[derive(Clone)]
+pub struct CircuitHasher<F: Field> {
+    hasher: Blake2s,
+    num_inputs: usize,
+    num_aux: usize,
+    num_constraints: usize,
+    _field: PhantomData<F>,
+}
+
+impl<F: Field> Default for CircuitHasher<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> CircuitHasher<F> {
+    /// Creates a hasher that holds just the "one" input variable.
+    pub fn new() -> Self {
+        Self::with_variables(1, 0)
+    }
+
+    /// Creates a hasher for matrices over `num_inputs` input variables,
+    /// including the "one" variable, and `num_aux` auxiliary variables,
+    /// whose constraints are then hashed with
+    /// [`hash_constraint`](CircuitHasher::hash_constraint).
+    pub fn with_variables(num_inputs: usize, num_aux: usize) -> Self {
+        let mut hasher = Blake2s::new();
+        hasher.input(PERSONALIZATION);
+        Self {
+            hasher,
+            num_inputs,
+            num_aux,
+            num_constraints: 0,
+            _field: PhantomData,
+        }
+    }
+
+    /// Hashes the constraint `a * b = c`, whose linear combinations are given
+    /// as pairs of a coefficient and a variable.
+    pub fn hash_constraint(&mut self, a: &[(F, Index)], b: &[(F, Index)], c: &[(F, Index)]) {
+        let mut bytes = Vec::new();
+        for terms in &[a, b, c] {
+            let terms = normalize(terms.to_vec());
+            (terms.len() as u64)
+                .write(&mut bytes)
+                .expect("writing into a vector never fails");
+            for (coeff, index) in terms {
+                let (kind, index) = match index {
+                    Index::Input(i) => (0u8, i),
+                    Index::Aux(i) => (1u8, i),
+                };
+                bytes.push(kind);
+                (index as u64)
+                    .write(&mut bytes)
+                    .and_then(|_| coeff.write(&mut bytes))
+                    .expect("writing into a vector never fails");
+            }
+        }
+        self.hasher.input(&bytes);
+        self.num_constraints += 1;
+    }
+
+    /// Returns the digest of the variables and constraints so far.
+    pub fn finish(self) -> CircuitDigest {
+        let mut hasher = self.hasher;
+        for count in &[self.num_inputs, self.num_aux, self.num_constraints] {
+            hasher.input(&(*count as u64).to_le_bytes());
+        }
+        let mut digest = [0u8; 32];
+        digest.copy_from_slice(hasher.result().as_slice());
+        CircuitDigest(digest)
+    }
+}
+
+fn terms<F: Field>(lc: &LinearCombination<F>) -> MatrixRow<F> {
+    lc.as_ref()
+        .iter()
+        .map(|(var, coeff)| (*coeff, var.get_unchecked()))
+        .collect()
+}
+
+impl<F: Field> ConstraintSystem<F> for CircuitHasher<F> {
+    type Root = Self;
+
+    #[inline]
+    fn alloc<FN, A, AR>(&mut self, _: A, _: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let index = self.num_aux;
+        self.num_aux += 1;
+        Ok(Variable::new_unchecked(Index::Aux(index)))
+    }
+
+    #[inline]
+    fn alloc_input<FN, A, AR>(&mut self, _: A, _: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let index = self.num_inputs;
+        self.num_inputs += 1;
+        Ok(Variable::new_unchecked(Index::Input(index)))
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, _: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LB: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LC: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+    {
+        let a = terms(&a(LinearCombination::zero()));
+        let b = terms(&b(LinearCombination::zero()));
+        let c = terms(&c(LinearCombination::zero()));
+        self.hash_constraint(&a, &b, &c);
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        // Do nothing; names are not part of the digest.
+    }
+
+    fn pop_namespace(&mut self) {
+        // Do nothing; names are not part of the digest.
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_constraints
+    }
+}
+
+impl<F: Field> R1CSRecorder<F> {
+    /// Returns the digest of the recorded constraint system, which is the
+    /// digest of the circuit it was recorded from.
+    pub fn digest(&self) -> CircuitDigest {
+        let mut hasher = CircuitHasher::with_variables(self.num_inputs(), self.num_aux());
+        for ((a, b), c) in self.a.iter().zip(&self.b).zip(&self.c) {
+            hasher.hash_constraint(a, b, c);
+        }
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algebra::bls12_381::Fr;
+
+    const ONE: Variable = Variable(Index::Input(0));
+
+    // Enforces `x * x = y`, building the linear combinations as given.
+    struct Square<A, C> {
+        a: A,
+        c: C,
+        inputs: usize,
+    }
+
+    impl<A, C> ConstraintSynthesizer<Fr> for Square<A, C>
+    where
+        A: FnOnce(LinearCombination<Fr>, Variable) -> LinearCombination<Fr>,
+        C: FnOnce(LinearCombination<Fr>, Variable) -> LinearCombination<Fr>,
+    {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let mut y = None;
+            for i in 0..self.inputs {
+                y = Some(cs.alloc_input(|| format!("y {}", i), || Ok(Fr::from(9u64)))?);
+            }
+            let x = cs.alloc(|| "x", || Ok(Fr::from(3u64)))?;
+            let y = y.unwrap();
+            let (a, c) = (self.a, self.c);
+            cs.enforce(|| "x*x=y", |lc| a(lc, x), |lc| lc + x, |lc| c(lc, y));
+            Ok(())
+        }
+    }
+
+    fn digest<A, C>(inputs: usize, a: A, c: C) -> CircuitDigest
+    where
+        A: FnOnce(LinearCombination<Fr>, Variable) -> LinearCombination<Fr>,
+        C: FnOnce(LinearCombination<Fr>, Variable) -> LinearCombination<Fr>,
+    {
+        CircuitDigest::of(Square { a, c, inputs }).unwrap()
+    }
+
+    #[test]
+    fn digests_structure_only() {
+        let plain = digest(1, |lc, x| lc + x, |lc, y| lc + y);
+        assert_eq!(plain, digest(1, |lc, x| lc + x, |lc, y| lc + y));
+
+        // Equivalent linear combinations have the same digest.
+        let two = Fr::from(2u64);
+        assert_eq!(plain, digest(1, |lc, x| lc + (two, x) - x, |lc, y| lc + y));
+        assert_eq!(
+            plain,
+            digest(1, |lc, x| lc + x, |lc, y| lc + y + (two, ONE) - (two, ONE))
+        );
+
+        // Different coefficients, variables or numbers of variables do not.
+        assert_ne!(plain, digest(1, |lc, x| lc + (two, x), |lc, y| lc + y));
+        assert_ne!(plain, digest(1, |lc, x| lc + x, |lc, _| lc + ONE));
+        assert_ne!(plain, digest(2, |lc, x| lc + x, |lc, y| lc + y));
+
+        // Recording the circuit does not change its digest.
+        let mut recorder = R1CSRecorder::new();
+        Square {
+            a: |lc, x| lc + x,
+            c: |lc, y| lc + y,
+            inputs: 1,
+        }
+        .generate_constraints(&mut recorder)
+        .unwrap();
+        assert_eq!(recorder.digest(), plain);
+
+        let mut bytes = Vec::new();
+        plain.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), CircuitDigest::SERIALIZED_SIZE);
+        assert_eq!(CircuitDigest::deserialize(&mut &bytes[..]).unwrap(), plain);
+        assert_eq!(plain.to_string().len(), 64);
+    }
+}
@@ -21,6 +21,22 @@ pub enum SynthesisError {
     MalformedVerifyingKey,
     /// During CRS generation, we observed an unconstrained auxiliary variable
     UnconstrainedVariable,
+    /// During proof generation, the prover was cancelled
+    Cancelled,
+    /// During proof generation, the circuit differed from the one that the
+    /// parameters were generated for
+    CircuitMismatch,
+    /// During synthesis, a gadget needed more bits than the elements of the
+    /// constraint field can hold
+    FieldTooSmall,
+    /// During synthesis, a gadget received a longer input than it accepts
+    InputTooLong,
+    /// During batch verification, the numbers of proofs and of sets of public
+    /// inputs differed
+    ProofCountMismatch,
+    /// During commitment, the commitment key had fewer bases than there were
+    /// values to commit to
+    CommitmentKeyTooShort,
 }
 
 impl From<io::Error> for SynthesisError {
@@ -53,6 +69,21 @@ impl fmt::Display for SynthesisError {
             SynthesisError::UnconstrainedVariable => {
                 write!(f, "auxiliary variable was unconstrained")
             }
+            SynthesisError::Cancelled => write!(f, "proof generation was cancelled"),
+            SynthesisError::CircuitMismatch => write!(
+                f,
+                "the circuit differs from the one the parameters were generated for"
+            ),
+            SynthesisError::FieldTooSmall => {
+                write!(f, "the constraint field is too small for the gadget")
+            }
+            SynthesisError::InputTooLong => write!(f, "the input is too long for the gadget"),
+            SynthesisError::ProofCountMismatch => {
+                write!(f, "the numbers of proofs and of public inputs differ")
+            }
+            SynthesisError::CommitmentKeyTooShort => {
+                write!(f, "the commitment key has too few bases for the values")
+            }
         }
     }
 }
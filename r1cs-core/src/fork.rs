@@ -0,0 +1,189 @@
+use algebra_core::{Field, Vec};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{ConstraintSystem, SynthesisError, WitnessGenerator};
+
+/// A part of a circuit that does not depend on the variables allocated by
+/// the parts it is forked with, so that it can be synthesized independently
+/// of them with [`ConstraintSystem::fork`].
+pub trait ForkSynthesizer<F: Field>: Send {
+    /// The result of synthesizing the part, such as the gadgets it
+    /// allocated.
+    type Output: Send;
+
+    /// Synthesizes the part into `cs`.
+    fn synthesize<CS: ConstraintSystem<F>>(
+        self,
+        cs: &mut CS,
+    ) -> Result<Self::Output, SynthesisError>;
+}
+
+/// Synthesizes each of `forks` into a witness of its own, and appends the
+/// witnesses to `cs` in order.
+///
+/// The variables that a fork allocates are numbered from zero within the
+/// fork, rather than by their position in `cs`. This is harmless, because
+/// witness generators never build the linear combinations that refer to
+/// them.
+pub(crate) fn generate_witnesses<F, CS, S>(
+    cs: &mut CS,
+    forks: Vec<S>,
+) -> Result<Vec<S::Output>, SynthesisError>
+where
+    F: Field,
+    CS: ConstraintSystem<F>,
+    S: ForkSynthesizer<F>,
+{
+    let synthesize = |fork: S| -> Result<_, SynthesisError> {
+        let mut witness = WitnessGenerator::<F>::fork();
+        let output = fork.synthesize(&mut witness)?;
+        Ok((witness, output))
+    };
+    #[cfg(feature = "parallel")]
+    let witnesses = forks
+        .into_par_iter()
+        .map(synthesize)
+        .collect::<Result<Vec<_>, SynthesisError>>()?;
+    #[cfg(not(feature = "parallel"))]
+    let witnesses = forks
+        .into_iter()
+        .map(synthesize)
+        .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+    let mut outputs = Vec::with_capacity(witnesses.len());
+    for (witness, output) in witnesses {
+        for value in witness.input_assignment {
+            cs.alloc_input(|| "", || Ok(value))?;
+        }
+        for value in witness.aux_assignment {
+            cs.alloc(|| "", || Ok(value))?;
+        }
+        for _ in 0..witness.num_constraints() {
+            cs.enforce(|| "", |lc| lc, |lc| lc, |lc| lc);
+        }
+        outputs.push(output);
+    }
+    Ok(outputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ConstraintSynthesizer, ConstraintSystem, ForkSynthesizer, R1CSRecorder, SynthesisError,
+        WitnessGenerator,
+    };
+    use algebra::bls12_381::Fr;
+    use algebra_core::{One, Vec};
+
+    // Publishes `start`, and squares it `length` times.
+    struct Squarings {
+        start: Option<Fr>,
+        length: usize,
+    }
+
+    impl ForkSynthesizer<Fr> for Squarings {
+        type Output = Option<Fr>;
+
+        fn synthesize<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<Option<Fr>, SynthesisError> {
+            let mut value = self.start;
+            let mut x = cs.alloc_input(
+                || "start",
+                || value.ok_or(SynthesisError::AssignmentMissing),
+            )?;
+            for i in 0..self.length {
+                value = value.map(|value| value * &value);
+                let square = cs.alloc(
+                    || format!("square {}", i),
+                    || value.ok_or(SynthesisError::AssignmentMissing),
+                )?;
+                cs.enforce(
+                    || format!("squaring {}", i),
+                    |lc| lc + x,
+                    |lc| lc + x,
+                    |lc| lc + square,
+                );
+                x = square;
+            }
+            Ok(value)
+        }
+    }
+
+    // Allocates a variable, then forks chains of squarings of different
+    // lengths, then allocates another variable.
+    struct Forked {
+        starts: Vec<Option<Fr>>,
+    }
+
+    impl ConstraintSynthesizer<Fr> for Forked {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            cs.alloc(|| "before", || Ok(Fr::one()))?;
+            let forks = self
+                .starts
+                .iter()
+                .enumerate()
+                .map(|(i, &start)| Squarings {
+                    start,
+                    length: i + 1,
+                })
+                .collect();
+            let outputs = cs.ns(|| "chains").fork(forks)?;
+            assert_eq!(outputs.len(), self.starts.len());
+            for (i, (output, start)) in outputs.iter().zip(&self.starts).enumerate() {
+                let expected = start.map(|start| (0..=i).fold(start, |x, _| x * &x));
+                assert_eq!(*output, expected);
+            }
+            cs.alloc(|| "after", || Ok(Fr::one() + &Fr::one()))?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn forks_give_sequential_witness() {
+        let starts = (2..10u64).map(|i| Some(Fr::from(i))).collect::<Vec<_>>();
+
+        let mut witness = WitnessGenerator::new();
+        Forked {
+            starts: starts.clone(),
+        }
+        .generate_constraints(&mut witness)
+        .unwrap();
+
+        // The recorder is not a witness generator, so it synthesizes the
+        // forks one after the other.
+        let mut recorder = R1CSRecorder::new();
+        Forked { starts }
+            .generate_constraints(&mut recorder)
+            .unwrap();
+        assert_eq!(recorder.input_names[1], "chains/fork 0/start");
+        assert_eq!(recorder.aux_names[1], "chains/fork 0/square 0");
+        assert_eq!(recorder.input_names[2], "chains/fork 1/start");
+
+        assert_eq!(witness.num_constraints(), recorder.num_constraints());
+        let unwrap = |values: &[Option<Fr>]| {
+            values
+                .iter()
+                .map(|value| value.unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(witness.input_assignment, unwrap(&recorder.input_assignment));
+        assert_eq!(witness.aux_assignment, unwrap(&recorder.aux_assignment));
+    }
+
+    #[test]
+    fn fork_errors_are_returned() {
+        let mut witness = WitnessGenerator::new();
+        let result = Forked {
+            starts: vec![Some(Fr::one()), None, Some(Fr::one())],
+        }
+        .generate_constraints(&mut witness);
+        assert!(matches!(result, Err(SynthesisError::AssignmentMissing)));
+    }
+}
@@ -18,14 +18,29 @@ pub(crate) use alloc::string::String;
 #[cfg(feature = "std")]
 pub(crate) use std::string::String;
 
+mod circom;
 mod constraint_system;
+mod digest;
 mod error;
+mod fork;
 mod impl_constraint_var;
 mod impl_lc;
+mod optimizer;
+mod prover_observer;
+mod recorder;
+mod unsatisfied;
+mod witness_generator;
 
 pub use algebra_core::{Field, ToConstraintField};
+pub use circom::{CircomCircuit, CircomError};
 pub use constraint_system::{ConstraintSynthesizer, ConstraintSystem, Namespace};
+pub use digest::{CircuitDigest, CircuitHasher};
 pub use error::SynthesisError;
+pub use fork::ForkSynthesizer;
+pub use prover_observer::{CancellationToken, ProverObserver, ProverPhase};
+pub use recorder::{MatrixRow, R1CSRecorder};
+pub use unsatisfied::UnsatisfiedConstraint;
+pub use witness_generator::WitnessGenerator;
 
 use core::cmp::Ordering;
 use smallvec::SmallVec as StackVec;
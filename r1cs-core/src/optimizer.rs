@@ -0,0 +1,353 @@
+use algebra_core::{vec, Field, Vec};
+
+use crate::{Index, MatrixRow, R1CSRecorder};
+
+/// Substitutes variables out of linear combinations, keeping track of the
+/// eliminated variables that each substitution refers to.
+struct Substitutions<F: Field> {
+    /// The linear combination that replaces each eliminated auxiliary
+    /// variable, which only refers to variables that are not eliminated.
+    definitions: Vec<Option<MatrixRow<F>>>,
+    /// The eliminated variables whose definitions may refer to each
+    /// auxiliary variable.
+    uses: Vec<Vec<usize>>,
+}
+
+impl<F: Field> Substitutions<F> {
+    fn new(num_aux: usize) -> Self {
+        Self {
+            definitions: vec![None; num_aux],
+            uses: vec![Vec::new(); num_aux],
+        }
+    }
+
+    /// Replaces the eliminated variables in `row` by their definitions.
+    fn apply(&self, row: &[(F, Index)]) -> MatrixRow<F> {
+        let mut terms = Vec::with_capacity(row.len());
+        for &(coeff, index) in row {
+            match index {
+                Index::Aux(i) if self.definitions[i].is_some() => {
+                    for &(def_coeff, def_index) in self.definitions[i].as_ref().unwrap() {
+                        terms.push((coeff * &def_coeff, def_index));
+                    }
+                },
+                _ => terms.push((coeff, index)),
+            }
+        }
+        normalize(terms)
+    }
+
+    /// Eliminates `variable` by `definition`, which must not refer to any
+    /// eliminated variable, and substitutes it out of earlier definitions.
+    fn eliminate(&mut self, variable: usize, definition: MatrixRow<F>) {
+        for &(_, index) in &definition {
+            if let Index::Aux(i) = index {
+                self.uses[i].push(variable);
+            }
+        }
+        self.definitions[variable] = Some(definition);
+
+        for user in core::mem::replace(&mut self.uses[variable], Vec::new()) {
+            let old = self.definitions[user].take().unwrap();
+            if !old.iter().any(|&(_, index)| index == Index::Aux(variable)) {
+                self.definitions[user] = Some(old);
+                continue;
+            }
+            let new = self.apply(&old);
+            for &(_, index) in &new {
+                if let Index::Aux(i) = index {
+                    self.uses[i].push(user);
+                }
+            }
+            self.definitions[user] = Some(new);
+        }
+    }
+}
+
+/// Sorts the terms of a linear combination by variable, merging the terms of
+/// the same variable and dropping those with zero coefficients.
+pub(crate) fn normalize<F: Field>(mut terms: MatrixRow<F>) -> MatrixRow<F> {
+    terms.sort_by_key(|&(_, index)| index);
+    let mut normalized: MatrixRow<F> = Vec::with_capacity(terms.len());
+    for (coeff, index) in terms {
+        if let Some((last_coeff, last_index)) = normalized.last_mut() {
+            if *last_index == index {
+                *last_coeff += &coeff;
+                continue;
+            }
+        }
+        normalized.push((coeff, index));
+    }
+    normalized.retain(|(coeff, _)| !coeff.is_zero());
+    normalized
+}
+
+/// Returns the value of a linear combination that only refers to the "one"
+/// variable.
+fn constant<F: Field>(row: &[(F, Index)]) -> Option<F> {
+    match row {
+        [] => Some(F::zero()),
+        [(coeff, Index::Input(0))] => Some(*coeff),
+        _ => None,
+    }
+}
+
+/// Returns `scalar * row - c`.
+fn scaled_difference<F: Field>(scalar: F, row: &[(F, Index)], c: &[(F, Index)]) -> MatrixRow<F> {
+    let terms = row
+        .iter()
+        .map(|&(coeff, index)| (coeff * &scalar, index))
+        .chain(c.iter().map(|&(coeff, index)| (-coeff, index)))
+        .collect();
+    normalize(terms)
+}
+
+impl<F: Field> R1CSRecorder<F> {
+    /// Returns an equivalent constraint system without linear constraints
+    /// that fix auxiliary variables.
+    ///
+    /// A constraint is linear if one of A and B is a constant. For each
+    /// linear constraint, an auxiliary variable in it is substituted out of
+    /// all constraints, and the constraint itself is dropped, as are
+    /// constraints that become trivial. Auxiliary variables that no
+    /// remaining constraint refers to are dropped too. Input variables are
+    /// never eliminated, so proofs of the optimized system are verified
+    /// against the same public inputs.
+    ///
+    /// The result only depends on the constraints, so recording a circuit
+    /// without and with a witness and optimizing both yields the same system
+    /// for the generator and the prover, which consume it as a
+    /// `ConstraintSynthesizer`. The assignments of the remaining variables are
+    /// kept.
+    pub fn optimize(&self) -> Self {
+        let mut optimized = self.eliminate_linear_constraints();
+        // Substitutions can make constraints linear that were not before, so
+        // repeat until nothing changes.
+        loop {
+            let next = optimized.eliminate_linear_constraints();
+            if next.a.len() == optimized.a.len()
+                && next.aux_names.len() == optimized.aux_names.len()
+            {
+                return next;
+            }
+            optimized = next;
+        }
+    }
+
+    fn eliminate_linear_constraints(&self) -> Self {
+        let mut substitutions = Substitutions::new(self.num_aux());
+        let mut kept = Vec::new();
+        for i in 0..self.a.len() {
+            let a = substitutions.apply(&self.a[i]);
+            let b = substitutions.apply(&self.b[i]);
+            let c = substitutions.apply(&self.c[i]);
+            let linear = match (constant(&a), constant(&b)) {
+                (_, Some(scalar)) => Some(scaled_difference(scalar, &a, &c)),
+                (Some(scalar), None) => Some(scaled_difference(scalar, &b, &c)),
+                (None, None) => None,
+            };
+
+            if let Some(linear) = linear {
+                // The constraint is trivial.
+                if linear.is_empty() {
+                    continue;
+                }
+                // The constraint fixes the most recently allocated auxiliary
+                // variable in it, which is usually an intermediate result.
+                let pivot = linear.iter().rev().find_map(|&(coeff, index)| match index {
+                    Index::Aux(j) => Some((coeff, j)),
+                    Index::Input(_) => None,
+                });
+                if let Some((coeff, variable)) = pivot {
+                    let factor = -coeff.inverse().unwrap();
+                    let definition = linear
+                        .into_iter()
+                        .filter(|&(_, index)| index != Index::Aux(variable))
+                        .map(|(coeff, index)| (coeff * &factor, index))
+                        .collect();
+                    substitutions.eliminate(variable, definition);
+                    continue;
+                }
+            }
+            kept.push((i, a, b, c));
+        }
+
+        // Renumber the auxiliary variables that are still referred to.
+        let mut used = vec![false; self.num_aux()];
+        let mut rows = Vec::with_capacity(kept.len());
+        for (i, a, b, c) in kept {
+            let (a, b, c) = (
+                substitutions.apply(&a),
+                substitutions.apply(&b),
+                substitutions.apply(&c),
+            );
+            for &(_, index) in a.iter().chain(&b).chain(&c) {
+                if let Index::Aux(j) = index {
+                    used[j] = true;
+                }
+            }
+            rows.push((i, a, b, c));
+        }
+
+        let mut optimized = Self::new();
+        optimized.input_names = self.input_names.clone();
+        optimized.input_assignment = self.input_assignment.clone();
+        let mut new_indices = vec![0; self.num_aux()];
+        for j in (0..self.num_aux()).filter(|j| used[*j]) {
+            new_indices[j] = optimized.aux_names.len();
+            optimized.aux_names.push(self.aux_names[j].clone());
+            optimized.aux_assignment.push(self.aux_assignment[j]);
+        }
+        let renumber = |row: MatrixRow<F>| -> MatrixRow<F> {
+            row.into_iter()
+                .map(|(coeff, index)| match index {
+                    Index::Aux(j) => (coeff, Index::Aux(new_indices[j])),
+                    input => (coeff, input),
+                })
+                .collect()
+        };
+        for (i, a, b, c) in rows {
+            optimized.a.push(renumber(a));
+            optimized.b.push(renumber(b));
+            optimized.c.push(renumber(c));
+            optimized
+                .constraint_names
+                .push(self.constraint_names[i].clone());
+            optimized
+                .constraint_namespaces
+                .push(self.constraint_namespaces[i]);
+        }
+        optimized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ConstraintSynthesizer, ConstraintSystem, Index, MatrixRow, R1CSRecorder, SynthesisError,
+    };
+    use algebra::{bls12_381::Fr, Field, One, Zero};
+
+    // Computes `(x + y) * (x + y) + 2 * x`, with linear constraints for the
+    // sums that a gadget library might create.
+    struct SumSquare {
+        x: Option<Fr>,
+        y: Option<Fr>,
+    }
+
+    impl ConstraintSynthesizer<Fr> for SumSquare {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let value = |v: Option<Fr>| v.ok_or(SynthesisError::AssignmentMissing);
+            let sum_value = self.x.and_then(|x| self.y.map(|y| x + &y));
+            let square_value = sum_value.map(|sum| sum.square());
+            let out_value = square_value.and_then(|square| self.x.map(|x| square + &x.double()));
+
+            let out = cs.alloc_input(|| "out", || value(out_value))?;
+            let x = cs.alloc(|| "x", || value(self.x))?;
+            let y = cs.alloc(|| "y", || value(self.y))?;
+            let sum = cs.alloc(|| "sum", || value(sum_value))?;
+            cs.enforce(
+                || "sum",
+                |lc| lc + x + y,
+                |lc| lc + CS::one(),
+                |lc| lc + sum,
+            );
+            let square = cs.alloc(|| "square", || value(square_value))?;
+            cs.enforce(|| "square", |lc| lc + sum, |lc| lc + sum, |lc| lc + square);
+            let double = cs.alloc(|| "double", || value(self.x.map(|x| x.double())))?;
+            cs.enforce(
+                || "double",
+                |lc| lc + (Fr::one().double(), CS::one()),
+                |lc| lc + x,
+                |lc| lc + double,
+            );
+            cs.enforce(
+                || "out",
+                |lc| lc + square + double,
+                |lc| lc + CS::one(),
+                |lc| lc + out,
+            );
+            // A duplicate of the first constraint, which becomes trivial.
+            cs.enforce(
+                || "sum again",
+                |lc| lc + CS::one(),
+                |lc| lc + x + y,
+                |lc| lc + sum,
+            );
+            Ok(())
+        }
+    }
+
+    fn eval(cs: &R1CSRecorder<Fr>, row: &MatrixRow<Fr>) -> Fr {
+        row.iter()
+            .map(|(coeff, index)| {
+                let value = match index {
+                    Index::Input(i) => cs.input_assignment[*i],
+                    Index::Aux(i) => cs.aux_assignment[*i],
+                };
+                value.unwrap() * coeff
+            })
+            .fold(Fr::zero(), |acc, term| acc + &term)
+    }
+
+    fn is_satisfied(cs: &R1CSRecorder<Fr>) -> bool {
+        (0..cs.a.len()).all(|i| eval(cs, &cs.a[i]) * &eval(cs, &cs.b[i]) == eval(cs, &cs.c[i]))
+    }
+
+    #[test]
+    fn eliminates_linear_constraints() {
+        let mut cs = R1CSRecorder::new();
+        SumSquare {
+            x: Some(Fr::from(3u64)),
+            y: Some(Fr::from(4u64)),
+        }
+        .generate_constraints(&mut cs)
+        .unwrap();
+        assert_eq!(cs.num_constraints(), 5);
+        assert_eq!(cs.num_aux(), 5);
+        assert!(is_satisfied(&cs));
+
+        // Only the square remains, with `sum`, `double` and `square`
+        // substituted out, and `out = (x + y)^2 + 2x`.
+        let optimized = cs.optimize();
+        assert_eq!(optimized.num_constraints(), 1);
+        assert_eq!(optimized.constraint_names, vec!["square"]);
+        assert_eq!(optimized.num_inputs(), 2);
+        assert_eq!(optimized.aux_names, vec!["x", "y"]);
+        assert!(is_satisfied(&optimized));
+        assert_eq!(optimized.optimize().a, optimized.a);
+
+        // A wrong output no longer satisfies the optimized system.
+        let mut wrong = optimized.clone();
+        wrong.input_assignment[1] = Some(Fr::zero());
+        assert!(!is_satisfied(&wrong));
+
+        // The structure does not depend on the witness.
+        let mut empty = R1CSRecorder::new();
+        SumSquare { x: None, y: None }
+            .generate_constraints(&mut empty)
+            .unwrap();
+        let empty = empty.optimize();
+        assert_eq!(empty.a, optimized.a);
+        assert_eq!(empty.b, optimized.b);
+        assert_eq!(empty.c, optimized.c);
+    }
+
+    #[test]
+    fn keeps_linear_constraints_on_inputs() {
+        let mut cs = R1CSRecorder::<Fr>::new();
+        let input = cs.alloc_input(|| "input", || Ok(Fr::one())).unwrap();
+        cs.enforce(
+            || "input is one",
+            |lc| lc + input,
+            |lc| lc + R1CSRecorder::<Fr>::one(),
+            |lc| lc + R1CSRecorder::<Fr>::one(),
+        );
+        let optimized = cs.optimize();
+        assert_eq!(optimized.num_constraints(), 1);
+        assert!(is_satisfied(&optimized));
+    }
+}
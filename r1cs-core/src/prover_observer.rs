@@ -0,0 +1,72 @@
+#[cfg(not(feature = "std"))]
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+use crate::SynthesisError;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// A phase of proof generation, as reported to a [`ProverObserver`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ProverPhase {
+    /// Synthesizing the circuit and computing its witness.
+    Synthesis,
+    /// Reducing the witness to a QAP or SAP witness, which is dominated by
+    /// FFTs.
+    WitnessMap,
+    /// A multi-scalar multiplication, named after the proof element that it
+    /// computes.
+    Msm(&'static str),
+}
+
+/// Receives progress reports from a prover.
+///
+/// Any `Fn(ProverPhase, f64)` closure is an observer.
+pub trait ProverObserver {
+    /// Called as the prover works through `phase`, where `fraction` is the
+    /// fraction of the phase that is done, from `0.0` when the phase starts
+    /// to `1.0` when it ends.
+    fn on_progress(&self, phase: ProverPhase, fraction: f64);
+}
+
+impl<F: Fn(ProverPhase, f64)> ProverObserver for F {
+    fn on_progress(&self, phase: ProverPhase, fraction: f64) {
+        self(phase, fraction)
+    }
+}
+
+/// A handle that lets another thread stop a prover early. Clones share the
+/// same state, so cancelling any of them cancels the proof.
+///
+/// The prover checks the token between phases and between chunks of work,
+/// and then fails with [`SynthesisError::Cancelled`].
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a token that has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks the provers holding this token to stop.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns whether the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Returns `SynthesisError::Cancelled` if the token has been cancelled.
+    pub fn check(&self) -> Result<(), SynthesisError> {
+        if self.is_cancelled() {
+            Err(SynthesisError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
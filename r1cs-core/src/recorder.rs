@@ -0,0 +1,559 @@
+use algebra_core::{
+    format,
+    io::{self, Write},
+    vec, BigInteger, Field, FpParameters, PrimeField, ToBytes, Vec,
+};
+use core::fmt::{self, Write as _};
+
+use crate::{
+    ConstraintSynthesizer, ConstraintSystem, Index, LinearCombination, String, SynthesisError,
+    Variable,
+};
+
+/// One row of a constraint matrix: the terms of one linear combination, as
+/// pairs of a coefficient and the index of its variable.
+pub type MatrixRow<F> = Vec<(F, Index)>;
+
+/// A constraint system that records the A, B and C matrices of a circuit,
+/// together with the full names of its variables and constraints, so that
+/// they can be inspected or exported for other tools.
+///
+/// Names are the `/`-separated paths of the namespaces that a variable or
+/// constraint was created in, followed by its own annotation. The "one"
+/// input variable is named `ONE`.
+///
+/// Assignments are recorded when they can be computed, so recording a
+/// circuit without a witness yields the matrices alone.
+#[derive(Clone, Debug)]
+pub struct R1CSRecorder<F: Field> {
+    /// The rows of the A matrix, one per constraint.
+    pub a: Vec<MatrixRow<F>>,
+    /// The rows of the B matrix, one per constraint.
+    pub b: Vec<MatrixRow<F>>,
+    /// The rows of the C matrix, one per constraint.
+    pub c: Vec<MatrixRow<F>>,
+    /// The full name of each constraint.
+    pub constraint_names: Vec<String>,
+    /// The full name of each input variable, starting with `ONE`.
+    pub input_names: Vec<String>,
+    /// The full name of each auxiliary variable.
+    pub aux_names: Vec<String>,
+    /// The assignment of each input variable, if it could be computed.
+    pub input_assignment: Vec<Option<F>>,
+    /// The assignment of each auxiliary variable, if it could be computed.
+    pub aux_assignment: Vec<Option<F>>,
+    /// The length of the namespace prefix of each constraint name.
+    pub(crate) constraint_namespaces: Vec<usize>,
+    current_namespace: Vec<String>,
+}
+
+impl<F: Field> Default for R1CSRecorder<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> R1CSRecorder<F> {
+    /// Creates an empty recorder, which holds just the "one" input variable.
+    pub fn new() -> Self {
+        Self {
+            a: Vec::new(),
+            b: Vec::new(),
+            c: Vec::new(),
+            constraint_names: Vec::new(),
+            input_names: vec!["ONE".into()],
+            aux_names: Vec::new(),
+            input_assignment: vec![Some(F::one())],
+            aux_assignment: Vec::new(),
+            constraint_namespaces: Vec::new(),
+            current_namespace: Vec::new(),
+        }
+    }
+
+    /// Returns the number of input variables, including the "one" variable.
+    pub fn num_inputs(&self) -> usize {
+        self.input_names.len()
+    }
+
+    /// Returns the number of auxiliary variables.
+    pub fn num_aux(&self) -> usize {
+        self.aux_names.len()
+    }
+
+    /// Returns the full name of `variable`.
+    pub fn variable_name(&self, variable: Variable) -> &str {
+        match variable.get_unchecked() {
+            Index::Input(i) => &self.input_names[i],
+            Index::Aux(i) => &self.aux_names[i],
+        }
+    }
+
+    /// Returns the path of the namespace that constraint `i` was created in,
+    /// which is empty for constraints created at the top level.
+    pub fn constraint_namespace(&self, i: usize) -> &str {
+        &self.constraint_names[i][..self.constraint_namespaces[i]]
+    }
+
+    /// Returns the assignment of `variable`, if it is known.
+    pub fn assignment(&self, variable: Variable) -> Option<F> {
+        match variable.get_unchecked() {
+            Index::Input(i) => self.input_assignment[i],
+            Index::Aux(i) => self.aux_assignment[i],
+        }
+    }
+
+    /// Returns the index of the wire that represents `index` in the circom
+    /// formats: the "one" variable, then the inputs, then the auxiliary
+    /// variables.
+    pub fn wire(&self, index: Index) -> usize {
+        match index {
+            Index::Input(i) => i,
+            Index::Aux(i) => self.num_inputs() + i,
+        }
+    }
+
+    fn path(&self, name: String) -> String {
+        let mut path = self.namespace_path();
+        if !path.is_empty() {
+            path.push('/');
+        }
+        path.push_str(&name);
+        path
+    }
+
+    fn namespace_path(&self) -> String {
+        self.current_namespace.join("/")
+    }
+}
+
+fn to_row<F: Field>(lc: LinearCombination<F>) -> MatrixRow<F> {
+    lc.0.into_iter()
+        .map(|(var, coeff)| (coeff, var.get_unchecked()))
+        .collect()
+}
+
+impl<F: Field> ConstraintSystem<F> for R1CSRecorder<F> {
+    type Root = Self;
+
+    fn alloc<FN, A, AR>(&mut self, annotation: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let index = self.aux_names.len();
+        let path = self.path(annotation().into());
+        self.aux_names.push(path);
+        self.aux_assignment.push(f().ok());
+        Ok(Variable::new_unchecked(Index::Aux(index)))
+    }
+
+    fn alloc_input<FN, A, AR>(&mut self, annotation: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let index = self.input_names.len();
+        let path = self.path(annotation().into());
+        self.input_names.push(path);
+        self.input_assignment.push(f().ok());
+        Ok(Variable::new_unchecked(Index::Input(index)))
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, annotation: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LB: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LC: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+    {
+        self.constraint_namespaces.push(self.namespace_path().len());
+        let path = self.path(annotation().into());
+        self.constraint_names.push(path);
+        self.a.push(to_row(a(LinearCombination::zero())));
+        self.b.push(to_row(b(LinearCombination::zero())));
+        self.c.push(to_row(c(LinearCombination::zero())));
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.current_namespace.push(name_fn().into());
+    }
+
+    fn pop_namespace(&mut self) {
+        assert!(self.current_namespace.pop().is_some());
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.a.len()
+    }
+}
+
+/// Replays the recorded constraints, so that a recorded (and possibly
+/// optimized) constraint system can be given to a generator or a prover.
+///
+/// Variables and constraints are annotated with their indices, rather than
+/// their recorded names, since constraint systems may reject the `/` that
+/// separates namespaces.
+impl<F: Field> ConstraintSynthesizer<F> for R1CSRecorder<F> {
+    fn generate_constraints<CS: ConstraintSystem<F>>(
+        self,
+        cs: &mut CS,
+    ) -> Result<(), SynthesisError> {
+        let mut inputs = vec![CS::one()];
+        for (i, value) in self.input_assignment.iter().enumerate().skip(1) {
+            inputs.push(cs.alloc_input(
+                || format!("input {}", i),
+                || value.ok_or(SynthesisError::AssignmentMissing),
+            )?);
+        }
+        let mut aux = Vec::with_capacity(self.num_aux());
+        for (i, value) in self.aux_assignment.iter().enumerate() {
+            aux.push(cs.alloc(
+                || format!("aux {}", i),
+                || value.ok_or(SynthesisError::AssignmentMissing),
+            )?);
+        }
+
+        let to_lc = |row: &MatrixRow<F>, mut lc: LinearCombination<F>| {
+            for (coeff, index) in row {
+                let variable = match index {
+                    Index::Input(i) => inputs[*i],
+                    Index::Aux(i) => aux[*i],
+                };
+                lc += (*coeff, variable);
+            }
+            lc
+        };
+        for i in 0..self.a.len() {
+            cs.enforce(
+                || format!("constraint {}", i),
+                |lc| to_lc(&self.a[i], lc),
+                |lc| to_lc(&self.b[i], lc),
+                |lc| to_lc(&self.c[i], lc),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// The magic number at the start of circom `.r1cs` files.
+pub(crate) const R1CS_MAGIC: &[u8; 4] = b"r1cs";
+/// The version of the `.r1cs` format that is written and read.
+pub(crate) const R1CS_VERSION: u32 = 1;
+pub(crate) const HEADER_SECTION: u32 = 1;
+pub(crate) const CONSTRAINT_SECTION: u32 = 2;
+pub(crate) const WIRE_TO_LABEL_SECTION: u32 = 3;
+
+impl<F: PrimeField> R1CSRecorder<F> {
+    /// Writes the constraint system in the binary `.r1cs` format of circom
+    /// and iden3, version 1.
+    ///
+    /// The inputs other than `ONE` are written as public inputs, and the
+    /// auxiliary variables as internal wires. Each wire is its own label.
+    pub fn write_r1cs<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let field_size = field_size::<F>();
+        let num_wires = self.num_inputs() + self.num_aux();
+
+        let mut header = Vec::new();
+        write_u32(&mut header, field_size as u32)?;
+        F::Params::MODULUS.write(&mut header)?;
+        write_u32(&mut header, num_wires as u32)?;
+        // Public outputs, public inputs and private inputs.
+        write_u32(&mut header, 0)?;
+        write_u32(&mut header, (self.num_inputs() - 1) as u32)?;
+        write_u32(&mut header, 0)?;
+        (num_wires as u64).write(&mut header)?;
+        write_u32(&mut header, self.num_constraints() as u32)?;
+
+        let mut constraints = Vec::new();
+        for i in 0..self.num_constraints() {
+            for row in &[&self.a[i], &self.b[i], &self.c[i]] {
+                write_u32(&mut constraints, row.len() as u32)?;
+                for (coeff, index) in row.iter() {
+                    write_u32(&mut constraints, self.wire(*index) as u32)?;
+                    coeff.into_repr().write(&mut constraints)?;
+                }
+            }
+        }
+
+        let mut labels = Vec::new();
+        for wire in 0..num_wires {
+            (wire as u64).write(&mut labels)?;
+        }
+
+        writer.write_all(R1CS_MAGIC)?;
+        write_u32(&mut writer, R1CS_VERSION)?;
+        write_u32(&mut writer, 3)?;
+        for (section, bytes) in &[
+            (HEADER_SECTION, header),
+            (CONSTRAINT_SECTION, constraints),
+            (WIRE_TO_LABEL_SECTION, labels),
+        ] {
+            write_u32(&mut writer, *section)?;
+            (bytes.len() as u64).write(&mut writer)?;
+            writer.write_all(bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the constraint system as JSON, in the layout of
+    /// `snarkjs r1cs export json`: each constraint is a list of the A, B and
+    /// C linear combinations, which map wire indices to decimal
+    /// coefficients.
+    ///
+    /// The names of the wires and of the constraints are included as
+    /// `wireNames` and `constraintNames`.
+    pub fn write_json<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let mut json = String::new();
+        self.format_json(&mut json)
+            .expect("writing to a string never fails");
+        writer.write_all(json.as_bytes())
+    }
+
+    fn format_json(&self, json: &mut String) -> fmt::Result {
+        let num_wires = self.num_inputs() + self.num_aux();
+        writeln!(json, "{{")?;
+        writeln!(json, " \"n8\": {},", field_size::<F>())?;
+        writeln!(json, " \"prime\": \"{}\",", to_decimal(F::Params::MODULUS))?;
+        writeln!(json, " \"nVars\": {},", num_wires)?;
+        writeln!(json, " \"nOutputs\": 0,")?;
+        writeln!(json, " \"nPubInputs\": {},", self.num_inputs() - 1)?;
+        writeln!(json, " \"nPrvInputs\": 0,")?;
+        writeln!(json, " \"nLabels\": {},", num_wires)?;
+        writeln!(json, " \"nConstraints\": {},", self.num_constraints())?;
+
+        write!(json, " \"constraints\": [")?;
+        for i in 0..self.num_constraints() {
+            write!(json, "{}\n  [", if i == 0 { "" } else { "," })?;
+            for (j, row) in [&self.a[i], &self.b[i], &self.c[i]].iter().enumerate() {
+                write!(json, "{}{{", if j == 0 { "" } else { ", " })?;
+                for (k, (coeff, index)) in row.iter().enumerate() {
+                    write!(
+                        json,
+                        "{}\"{}\": \"{}\"",
+                        if k == 0 { "" } else { ", " },
+                        self.wire(*index),
+                        to_decimal(coeff.into_repr())
+                    )?;
+                }
+                write!(json, "}}")?;
+            }
+            write!(json, "]")?;
+        }
+        writeln!(json, "\n ],")?;
+
+        write!(json, " \"map\": [")?;
+        for wire in 0..num_wires {
+            write!(json, "{}{}", if wire == 0 { "" } else { ", " }, wire)?;
+        }
+        writeln!(json, "],")?;
+
+        write!(json, " \"wireNames\": [")?;
+        write_json_strings(json, self.input_names.iter().chain(&self.aux_names))?;
+        writeln!(json, "],")?;
+
+        write!(json, " \"constraintNames\": [")?;
+        write_json_strings(json, &self.constraint_names)?;
+        writeln!(json, "]\n}}")
+    }
+}
+
+/// Returns the number of bytes in which the circom formats store elements of
+/// `F`: the size of its representation, which is a whole number of 64-bit
+/// limbs.
+pub(crate) fn field_size<F: PrimeField>() -> usize {
+    8 * <F::BigInt as BigInteger>::NUM_LIMBS
+}
+
+pub(crate) fn write_u32<W: Write>(writer: &mut W, value: u32) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+/// Formats an integer, given by its little-endian limbs, in decimal.
+fn to_decimal<B: BigInteger>(value: B) -> String {
+    // Divide by 10^19, the largest power of ten below 2^64, until nothing is
+    // left, collecting the remainders.
+    const CHUNK: u128 = 10_000_000_000_000_000_000;
+    let mut limbs = value.as_ref().to_vec();
+    let mut chunks = Vec::new();
+    while limbs.iter().any(|limb| *limb != 0) {
+        let mut remainder = 0u128;
+        for limb in limbs.iter_mut().rev() {
+            let current = (remainder << 64) | u128::from(*limb);
+            *limb = (current / CHUNK) as u64;
+            remainder = current % CHUNK;
+        }
+        chunks.push(remainder as u64);
+    }
+
+    match chunks.split_last() {
+        None => "0".into(),
+        Some((most_significant, rest)) => {
+            let mut decimal = format!("{}", most_significant);
+            for chunk in rest.iter().rev() {
+                decimal.push_str(&format!("{:019}", chunk));
+            }
+            decimal
+        },
+    }
+}
+
+fn write_json_strings<'a>(
+    json: &mut String,
+    strings: impl IntoIterator<Item = &'a String>,
+) -> fmt::Result {
+    for (i, string) in strings.into_iter().enumerate() {
+        write!(json, "{}\"", if i == 0 { "" } else { ", " })?;
+        for c in string.chars() {
+            match c {
+                '"' => write!(json, "\\\"")?,
+                '\\' => write!(json, "\\\\")?,
+                '\n' => write!(json, "\\n")?,
+                c if (c as u32) < 0x20 => write!(json, "\\u{:04x}", c as u32)?,
+                c => write!(json, "{}", c)?,
+            }
+        }
+        write!(json, "\"")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConstraintSynthesizer;
+    use algebra::{bls12_381::Fr, One};
+
+    // Proves knowledge of `x` such that `x^3 + x + 5 = out`.
+    struct Cubic(Option<Fr>);
+
+    impl ConstraintSynthesizer<Fr> for Cubic {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let x_val = self.0;
+            let out = cs.alloc_input(
+                || "out",
+                || {
+                    let x = x_val.ok_or(SynthesisError::AssignmentMissing)?;
+                    Ok(x * &x * &x + &x + &Fr::from(5u64))
+                },
+            )?;
+            let x = cs.alloc(|| "x", || x_val.ok_or(SynthesisError::AssignmentMissing))?;
+            let mut cs = cs.ns(|| "cube");
+            let x2 = cs.alloc(
+                || "x^2",
+                || {
+                    x_val
+                        .map(|x| x * &x)
+                        .ok_or(SynthesisError::AssignmentMissing)
+                },
+            )?;
+            cs.enforce(|| "square", |lc| lc + x, |lc| lc + x, |lc| lc + x2);
+            cs.enforce(
+                || "cube",
+                |lc| lc + x2,
+                |lc| lc + x,
+                |lc| lc + out - x - (Fr::from(5u64), CS::one()),
+            );
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn records_matrices_and_names() {
+        let mut cs = R1CSRecorder::new();
+        Cubic(Some(Fr::from(3u64)))
+            .generate_constraints(&mut cs)
+            .unwrap();
+
+        assert_eq!(cs.num_inputs(), 2);
+        assert_eq!(cs.num_aux(), 2);
+        assert_eq!(cs.num_constraints(), 2);
+        assert_eq!(cs.input_names, vec!["ONE", "out"]);
+        assert_eq!(cs.aux_names, vec!["x", "cube/x^2"]);
+        assert_eq!(cs.constraint_names, vec!["cube/square", "cube/cube"]);
+        assert_eq!(cs.constraint_namespace(1), "cube");
+        assert_eq!(cs.input_assignment[1], Some(Fr::from(35u64)));
+        assert_eq!(cs.aux_assignment[1], Some(Fr::from(9u64)));
+
+        assert_eq!(cs.a[0], vec![(Fr::one(), Index::Aux(0))]);
+        assert_eq!(cs.c[0], vec![(Fr::one(), Index::Aux(1))]);
+        assert_eq!(cs.c[1].len(), 3);
+        assert_eq!(cs.wire(Index::Aux(1)), 3);
+
+        // Without a witness, only the assignments are missing.
+        let mut empty = R1CSRecorder::new();
+        Cubic(None).generate_constraints(&mut empty).unwrap();
+        assert_eq!(empty.a, cs.a);
+        assert_eq!(empty.b, cs.b);
+        assert_eq!(empty.c, cs.c);
+        assert_eq!(empty.aux_assignment, vec![None, None]);
+    }
+
+    #[test]
+    fn exports_r1cs() {
+        let mut cs = R1CSRecorder::new();
+        Cubic(None).generate_constraints(&mut cs).unwrap();
+        let mut bytes = Vec::new();
+        cs.write_r1cs(&mut bytes).unwrap();
+
+        let u32_at = |i: usize| {
+            let mut le = [0u8; 4];
+            le.copy_from_slice(&bytes[i..i + 4]);
+            u32::from_le_bytes(le)
+        };
+        assert_eq!(&bytes[..4], b"r1cs");
+        assert_eq!(u32_at(4), 1);
+        assert_eq!(u32_at(8), 3);
+        // The header section: the field size, the modulus, and the counts.
+        assert_eq!(u32_at(12), HEADER_SECTION);
+        assert_eq!(u32_at(24), 32);
+        let mut modulus = Vec::new();
+        <Fr as PrimeField>::Params::MODULUS
+            .write(&mut modulus)
+            .unwrap();
+        assert_eq!(&bytes[28..60], &modulus[..]);
+        assert_eq!(u32_at(60), 4);
+        assert_eq!(u32_at(68), 1);
+        assert_eq!(u32_at(84), 2);
+        // The constraint section starts with `x` times `x`.
+        assert_eq!(u32_at(88), CONSTRAINT_SECTION);
+        assert_eq!(u32_at(100), 1);
+        assert_eq!(u32_at(104), 2);
+        assert_eq!(&bytes[108..140], &[&[1u8][..], &[0u8; 31][..]].concat()[..]);
+    }
+
+    #[test]
+    fn exports_json() {
+        let mut cs = R1CSRecorder::new();
+        Cubic(None).generate_constraints(&mut cs).unwrap();
+        let mut bytes = Vec::new();
+        cs.write_json(&mut bytes).unwrap();
+        let json = String::from_utf8(bytes).unwrap();
+
+        assert!(json.contains(
+            "\"prime\": \"52435875175126190479447740508185965837690552500527637822603658699938581184513\""
+        ));
+        assert!(json.contains("\"nVars\": 4"));
+        assert!(json.contains("\"nPubInputs\": 1"));
+        assert!(json.contains("[{\"2\": \"1\"}, {\"2\": \"1\"}, {\"3\": \"1\"}]"));
+        // -1 and -5 are written as field elements.
+        assert!(json.contains(
+            "\"0\": \"52435875175126190479447740508185965837690552500527637822603658699938581184508\""
+        ));
+        assert!(json.contains("\"wireNames\": [\"ONE\", \"out\", \"x\", \"cube/x^2\"]"));
+        assert!(json.contains("\"constraintNames\": [\"cube/square\", \"cube/cube\"]"));
+    }
+}
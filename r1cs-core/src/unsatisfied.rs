@@ -0,0 +1,81 @@
+use algebra_core::{Field, Vec};
+use core::fmt;
+
+use crate::{Index, String};
+
+/// A constraint `A * B = C` that an assignment does not satisfy, as reported
+/// by constraint systems that check assignments.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnsatisfiedConstraint<F: Field> {
+    /// The index of the constraint, in the order of creation.
+    pub index: usize,
+    /// The full name of the constraint.
+    pub name: String,
+    /// The path of the namespace that the constraint was created in, which
+    /// is empty for constraints created at the top level.
+    pub namespace: String,
+    /// The value of the A linear combination.
+    pub a: F,
+    /// The value of the B linear combination.
+    pub b: F,
+    /// The value of the C linear combination.
+    pub c: F,
+    /// The full names and values of the variables that occur in the
+    /// constraint, in the order of their first occurrence.
+    pub variables: Vec<(String, F)>,
+}
+
+impl<F: Field> UnsatisfiedConstraint<F> {
+    /// Describes constraint `index`, whose full name is `name`, whose linear
+    /// combinations evaluate to `a`, `b` and `c`, and whose terms have the
+    /// variables `indices`. The full name and value of each variable are
+    /// looked up with `variable`.
+    pub fn new(
+        index: usize,
+        name: String,
+        (a, b, c): (F, F, F),
+        indices: impl IntoIterator<Item = Index>,
+        mut variable: impl FnMut(Index) -> (String, F),
+    ) -> Self {
+        let mut seen = Vec::new();
+        let mut variables = Vec::new();
+        for variable_index in indices {
+            if !seen.contains(&variable_index) {
+                seen.push(variable_index);
+                variables.push(variable(variable_index));
+            }
+        }
+        // Names cannot contain the separator, so the namespace is everything
+        // before the last one.
+        let namespace = name.rfind('/').map_or("", |separator| &name[..separator]);
+        Self {
+            index,
+            namespace: namespace.into(),
+            name,
+            a,
+            b,
+            c,
+            variables,
+        }
+    }
+}
+
+impl<F: Field> fmt::Display for UnsatisfiedConstraint<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        writeln!(
+            f,
+            "constraint {} `{}` is unsatisfied",
+            self.index, self.name
+        )?;
+        if !self.namespace.is_empty() {
+            writeln!(f, "  in namespace `{}`", self.namespace)?;
+        }
+        writeln!(f, "  A = {}", self.a)?;
+        writeln!(f, "  B = {}", self.b)?;
+        writeln!(f, "  C = {}", self.c)?;
+        for (name, value) in &self.variables {
+            writeln!(f, "  `{}` = {}", name, value)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use algebra_core::{vec, Field, Vec};
+
+use crate::{
+    fork, ConstraintSystem, ForkSynthesizer, Index, LinearCombination, String, SynthesisError,
+    Variable,
+};
+
+/// A constraint system that only computes the assignments of variables.
+///
+/// Constraints are counted, but the closures that build their linear
+/// combinations are never invoked. A prover can combine the witness with
+/// constraints that were recorded once for the circuit, rather than
+/// synthesizing them for every proof.
+///
+/// Linear combinations that gadgets build outside of those closures are
+/// still built, unless the gadget checks `is_witness_generator`: of the
+/// gadgets in `r1cs-std`, only `UInt32::addmany`, which dominates Blake2s,
+/// does so. Field gadgets, and the curve gadgets built on them, such as
+/// those of Pedersen hashes, carry linear combinations as their values, so
+/// those are always built and gain only from skipping the closures.
+#[derive(Clone, Debug)]
+pub struct WitnessGenerator<F: Field> {
+    /// The assignment of each input variable, starting with the "one"
+    /// variable.
+    pub input_assignment: Vec<F>,
+    /// The assignment of each auxiliary variable.
+    pub aux_assignment: Vec<F>,
+    num_constraints: usize,
+}
+
+impl<F: Field> Default for WitnessGenerator<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> WitnessGenerator<F> {
+    /// Creates a witness that holds just the "one" input variable.
+    pub fn new() -> Self {
+        Self {
+            input_assignment: vec![F::one()],
+            aux_assignment: Vec::new(),
+            num_constraints: 0,
+        }
+    }
+
+    /// Creates a witness without any variables, for a fork that is appended
+    /// to another witness once it is synthesized.
+    pub(crate) fn fork() -> Self {
+        Self {
+            input_assignment: Vec::new(),
+            aux_assignment: Vec::new(),
+            num_constraints: 0,
+        }
+    }
+}
+
+impl<F: Field> ConstraintSystem<F> for WitnessGenerator<F> {
+    type Root = Self;
+
+    #[inline]
+    fn alloc<FN, A, AR>(&mut self, _: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let index = self.aux_assignment.len();
+        self.aux_assignment.push(f()?);
+        Ok(Variable::new_unchecked(Index::Aux(index)))
+    }
+
+    #[inline]
+    fn alloc_input<FN, A, AR>(&mut self, _: A, f: FN) -> Result<Variable, SynthesisError>
+    where
+        FN: FnOnce() -> Result<F, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let index = self.input_assignment.len();
+        self.input_assignment.push(f()?);
+        Ok(Variable::new_unchecked(Index::Input(index)))
+    }
+
+    #[inline]
+    fn enforce<A, AR, LA, LB, LC>(&mut self, _: A, _: LA, _: LB, _: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LB: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+        LC: FnOnce(LinearCombination<F>) -> LinearCombination<F>,
+    {
+        self.num_constraints += 1;
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn pop_namespace(&mut self) {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_constraints
+    }
+
+    fn is_witness_generator(&self) -> bool {
+        true
+    }
+
+    fn fork<S: ForkSynthesizer<F>>(
+        &mut self,
+        forks: Vec<S>,
+    ) -> Result<Vec<S::Output>, SynthesisError> {
+        fork::generate_witnesses(self, forks)
+    }
+}
@@ -6,6 +6,7 @@ use algebra::Field;
 use r1cs_core::{ConstraintSystem, SynthesisError};
 
 pub mod boolean;
+pub mod uint;
 pub mod uint32;
 pub mod uint64;
 pub mod uint8;
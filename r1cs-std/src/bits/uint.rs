@@ -0,0 +1,1200 @@
+use algebra::{Field, FpParameters, PrimeField};
+use core::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt::Debug,
+    ops::{Add, BitAnd, BitOr, BitXor, Mul, Not, Shl, Shr},
+};
+
+use r1cs_core::{ConstraintSystem, ConstraintVar, LinearCombination, SynthesisError};
+
+use crate::{
+    boolean::{AllocatedBit, Boolean},
+    fields::fp::FpGadget,
+    prelude::*,
+    Assignment, Vec,
+};
+
+/// A native unsigned integer type, whose values a [`UInt`] holds.
+pub trait NativeUInt:
+    Copy
+    + Debug
+    + Eq
+    + Ord
+    + Into<u128>
+    + From<bool>
+    + Add<Output = Self>
+    + Mul<Output = Self>
+    + Not<Output = Self>
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Shl<usize, Output = Self>
+    + Shr<usize, Output = Self>
+{
+    /// The width of the integer, in bits.
+    const SIZE: usize;
+    const ZERO: Self;
+    const MAX: Self;
+
+    fn overflowing_add(self, other: Self) -> (Self, bool);
+    fn overflowing_sub(self, other: Self) -> (Self, bool);
+    fn wrapping_mul(self, other: Self) -> Self;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+
+    /// Returns bit `i` of `self`, counting from the least significant bit.
+    fn bit(self, i: usize) -> bool {
+        (self >> i) & Self::from(true) == Self::from(true)
+    }
+}
+
+macro_rules! impl_native_uint {
+    ($($native:ident),*) => {
+        $(
+            impl NativeUInt for $native {
+                const SIZE: usize = core::mem::size_of::<$native>() * 8;
+                const ZERO: Self = 0;
+                const MAX: Self = <$native>::max_value();
+
+                fn overflowing_add(self, other: Self) -> (Self, bool) {
+                    <$native>::overflowing_add(self, other)
+                }
+
+                fn overflowing_sub(self, other: Self) -> (Self, bool) {
+                    <$native>::overflowing_sub(self, other)
+                }
+
+                fn wrapping_mul(self, other: Self) -> Self {
+                    <$native>::wrapping_mul(self, other)
+                }
+
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    <$native>::checked_mul(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_native_uint!(u16, u32, u64, u128);
+
+/// Represents an interpretation of `Boolean` objects as an unsigned integer
+/// of the same width as the native integer type `T`.
+#[derive(Clone, Debug)]
+pub struct UInt<T: NativeUInt> {
+    // Least significant bit first
+    pub(crate) bits: Vec<Boolean>,
+    pub(crate) value: Option<T>,
+}
+
+pub type UInt16 = UInt<u16>;
+pub type UInt32 = UInt<u32>;
+pub type UInt64 = UInt<u64>;
+pub type UInt128 = UInt<u128>;
+
+/// Returns whether every bit of `bits` is a constant.
+pub(crate) fn is_constant(bits: &[Boolean]) -> bool {
+    bits.iter().all(|bit| matches!(bit, Boolean::Constant(_)))
+}
+
+/// Returns the linear combination of `bits`, which are in little-endian
+/// order, that is equal to the integer they represent.
+pub(crate) fn lc<ConstraintF, CS>(bits: &[Boolean]) -> LinearCombination<ConstraintF>
+where
+    ConstraintF: Field,
+    CS: ConstraintSystem<ConstraintF>,
+{
+    let mut lc = LinearCombination::zero();
+    let mut coeff = ConstraintF::one();
+    for bit in bits {
+        lc = lc + &bit.lc(CS::one(), coeff);
+        coeff.double_in_place();
+    }
+    lc
+}
+
+/// Returns the lowest `num_bits` bits of `value`, in little-endian order.
+pub(crate) fn field_bits<ConstraintF: PrimeField>(
+    value: Option<ConstraintF>,
+    num_bits: usize,
+) -> Vec<Option<bool>> {
+    match value {
+        Some(value) => {
+            let repr = value.into_repr();
+            let limbs = repr.as_ref();
+            (0..num_bits)
+                .map(|i| Some((limbs[i / 64] >> (i % 64)) & 1 == 1))
+                .collect()
+        },
+        None => vec![None; num_bits],
+    }
+}
+
+/// Fails with `SynthesisError::FieldTooSmall` unless the integers of
+/// `num_bits` bits are distinct elements of `ConstraintF`.
+pub(crate) fn check_capacity<ConstraintF: PrimeField>(
+    num_bits: usize,
+) -> Result<(), SynthesisError> {
+    if (ConstraintF::Params::CAPACITY as usize) < num_bits {
+        return Err(SynthesisError::FieldTooSmall);
+    }
+    Ok(())
+}
+
+/// Allocates a bit for each of `values`, and enforces that `lc` is equal to
+/// the integer that the bits represent in little-endian order.
+pub(crate) fn unpack<ConstraintF, CS>(
+    mut cs: CS,
+    lc: LinearCombination<ConstraintF>,
+    values: &[Option<bool>],
+) -> Result<Vec<Boolean>, SynthesisError>
+where
+    ConstraintF: PrimeField,
+    CS: ConstraintSystem<ConstraintF>,
+{
+    // The bits would not determine `lc` if the integers they represent
+    // wrapped around the modulus.
+    check_capacity::<ConstraintF>(values.len())?;
+
+    let mut lc = lc;
+    let mut coeff = ConstraintF::one();
+    let mut bits = Vec::with_capacity(values.len());
+    for (i, value) in values.iter().enumerate() {
+        let bit = AllocatedBit::alloc(cs.ns(|| format!("bit {}", i)), || value.get())?;
+        lc = lc - (coeff, bit.get_variable());
+        bits.push(Boolean::from(bit));
+        coeff.double_in_place();
+    }
+    cs.enforce(|| "unpacking", |lc| lc, |lc| lc, |_| lc);
+
+    Ok(bits)
+}
+
+impl<T: NativeUInt> UInt<T> {
+    /// Construct a constant integer from its native value
+    pub fn constant(value: T) -> Self {
+        let bits = (0..T::SIZE)
+            .map(|i| Boolean::constant(value.bit(i)))
+            .collect();
+
+        Self {
+            bits,
+            value: Some(value),
+        }
+    }
+
+    /// Returns the value of this integer, if it is known.
+    pub fn get_value(&self) -> Option<T> {
+        self.value
+    }
+
+    /// Turns this integer into its little-endian bit order representation.
+    pub fn to_bits_le(&self) -> Vec<Boolean> {
+        self.bits.clone()
+    }
+
+    /// Converts a little-endian bit order representation of bits into an
+    /// integer.
+    pub fn from_bits_le(bits: &[Boolean]) -> Self {
+        assert_eq!(bits.len(), T::SIZE);
+
+        let mut value = Some(T::ZERO);
+        for b in bits.iter().rev() {
+            value = match (value, b.get_value()) {
+                (Some(value), Some(b)) => Some((value << 1) | T::from(b)),
+                _ => None,
+            };
+        }
+
+        Self {
+            bits: bits.to_vec(),
+            value,
+        }
+    }
+
+    /// Rotates the bits of this integer right by `by` bits.
+    pub fn rotr(&self, by: usize) -> Self {
+        let by = by % T::SIZE;
+
+        let bits = self
+            .bits
+            .iter()
+            .skip(by)
+            .chain(self.bits.iter())
+            .take(T::SIZE)
+            .cloned()
+            .collect();
+
+        Self {
+            bits,
+            value: self.value.map(|v| match by {
+                0 => v,
+                _ => (v >> by) | (v << (T::SIZE - by)),
+            }),
+        }
+    }
+
+    /// XOR this integer with another integer
+    pub fn xor<ConstraintF, CS>(&self, mut cs: CS, other: &Self) -> Result<Self, SynthesisError>
+    where
+        ConstraintF: Field,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        let bits = self
+            .bits
+            .iter()
+            .zip(other.bits.iter())
+            .enumerate()
+            .map(|(i, (a, b))| Boolean::xor(cs.ns(|| format!("xor of bit_gadget {}", i)), a, b))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            bits,
+            value: self.value.and_then(|a| other.value.map(|b| a ^ b)),
+        })
+    }
+
+    /// Computes the bitwise negation of `self`, without any constraints.
+    pub fn not(&self) -> Self {
+        Self {
+            bits: self.bits.iter().map(|bit| bit.not()).collect(),
+            value: self.value.map(|value| !value),
+        }
+    }
+
+    /// Computes the bitwise AND of `self` and `other`.
+    pub fn and<ConstraintF, CS>(&self, mut cs: CS, other: &Self) -> Result<Self, SynthesisError>
+    where
+        ConstraintF: Field,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        let bits = self
+            .bits
+            .iter()
+            .zip(&other.bits)
+            .enumerate()
+            .map(|(i, (a, b))| Boolean::and(cs.ns(|| format!("and of bit {}", i)), a, b))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            bits,
+            value: self.value.and_then(|a| other.value.map(|b| a & b)),
+        })
+    }
+
+    /// Computes the bitwise OR of `self` and `other`.
+    pub fn or<ConstraintF, CS>(&self, mut cs: CS, other: &Self) -> Result<Self, SynthesisError>
+    where
+        ConstraintF: Field,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        let bits = self
+            .bits
+            .iter()
+            .zip(&other.bits)
+            .enumerate()
+            .map(|(i, (a, b))| Boolean::or(cs.ns(|| format!("or of bit {}", i)), a, b))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            bits,
+            value: self.value.and_then(|a| other.value.map(|b| a | b)),
+        })
+    }
+
+    /// Shifts `self` left by `by` bits, without any constraints. Shifting by
+    /// the width of the integer or more gives zero.
+    pub fn shl(&self, by: usize) -> Self {
+        let by = core::cmp::min(by, T::SIZE);
+        let bits = core::iter::repeat(Boolean::constant(false))
+            .take(by)
+            .chain(self.bits.iter().cloned())
+            .take(T::SIZE)
+            .collect();
+
+        Self {
+            bits,
+            value: self
+                .value
+                .map(|value| if by == T::SIZE { T::ZERO } else { value << by }),
+        }
+    }
+
+    /// Shifts `self` right by `by` bits, without any constraints. Shifting by
+    /// the width of the integer or more gives zero.
+    pub fn shr(&self, by: usize) -> Self {
+        let by = core::cmp::min(by, T::SIZE);
+        let bits = self
+            .bits
+            .iter()
+            .cloned()
+            .skip(by)
+            .chain(core::iter::repeat(Boolean::constant(false)))
+            .take(T::SIZE)
+            .collect();
+
+        Self {
+            bits,
+            value: self
+                .value
+                .map(|value| if by == T::SIZE { T::ZERO } else { value >> by }),
+        }
+    }
+
+    /// Perform modular addition of several integers.
+    pub fn addmany<ConstraintF, CS>(mut cs: CS, operands: &[Self]) -> Result<Self, SynthesisError>
+    where
+        ConstraintF: PrimeField,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        assert!(operands.len() >= 1);
+        assert!(operands.len() <= 10);
+
+        if operands.len() == 1 {
+            return Ok(operands[0].clone());
+        }
+
+        // The sum of `n` operands is less than `n * 2^N`, so it needs as many
+        // carry bits as `n - 1` has bits, and the bits of the sum must not
+        // wrap around the modulus of the scalar field.
+        let num_carry_bits =
+            core::mem::size_of::<usize>() * 8 - (operands.len() - 1).leading_zeros() as usize;
+        let num_bits = T::SIZE + num_carry_bits;
+        check_capacity::<ConstraintF>(num_bits)?;
+
+        // Keep track of the resulting value, as the sum modulo 2^N and the
+        // number of times it wrapped around
+        let mut result_value = Some((T::ZERO, 0u128));
+
+        // This is a linear combination that we will enforce to be "zero".
+        // Constraint systems that only compute the witness don't need it.
+        let mut lc = LinearCombination::zero();
+        let build_lc = !cs.is_witness_generator();
+
+        let mut all_constants = true;
+
+        // Iterate over the operands
+        for op in operands {
+            // Accumulate the value. If any of our operands have unknown
+            // value, we won't know the value of the result
+            result_value = match (result_value, op.value) {
+                (Some((low, high)), Some(val)) => {
+                    let (low, carry) = low.overflowing_add(val);
+                    Some((low, high + u128::from(carry)))
+                },
+                _ => None,
+            };
+
+            // Iterate over each bit_gadget of the operand and add the operand to
+            // the linear combination
+            let mut coeff = ConstraintF::one();
+            for bit in &op.bits {
+                match *bit {
+                    Boolean::Is(ref bit) => {
+                        all_constants = false;
+
+                        // Add coeff * bit_gadget
+                        if build_lc {
+                            lc += (coeff, bit.get_variable());
+                        }
+                    },
+                    Boolean::Not(ref bit) => {
+                        all_constants = false;
+
+                        // Add coeff * (1 - bit_gadget) = coeff * ONE - coeff * bit_gadget
+                        if build_lc {
+                            lc = lc + (coeff, CS::one()) - (coeff, bit.get_variable());
+                        }
+                    },
+                    Boolean::Constant(bit) => {
+                        if bit && build_lc {
+                            lc += (coeff, CS::one());
+                        }
+                    },
+                }
+
+                coeff.double_in_place();
+            }
+        }
+
+        // The value of the actual result is modulo 2^N
+        let modular_value = result_value.map(|(low, _)| low);
+
+        if all_constants && modular_value.is_some() {
+            // We can just return a constant, rather than
+            // unpacking the result into allocated bits.
+
+            return Ok(Self::constant(modular_value.unwrap()));
+        }
+
+        // Storage area for the resulting bits
+        let mut result_bits = Vec::with_capacity(num_bits);
+
+        // Allocate each bit_gadget of the result
+        let mut coeff = ConstraintF::one();
+        for i in 0..num_bits {
+            // Allocate the bit_gadget
+            let b = AllocatedBit::alloc(cs.ns(|| format!("result bit_gadget {}", i)), || {
+                result_value
+                    .map(|(low, high)| match i.checked_sub(T::SIZE) {
+                        None => low.bit(i),
+                        Some(j) => (high >> j) & 1 == 1,
+                    })
+                    .get()
+            })?;
+
+            // Subtract this bit_gadget from the linear combination to ensure the sums
+            // balance out
+            if build_lc {
+                lc = lc - (coeff, b.get_variable());
+            }
+
+            result_bits.push(b.into());
+
+            coeff.double_in_place();
+        }
+
+        // Enforce that the linear combination equals zero
+        cs.enforce(|| "modular addition", |lc| lc, |lc| lc, |_| lc);
+
+        // Discard carry bits that we don't care about
+        result_bits.truncate(T::SIZE);
+
+        Ok(Self {
+            bits: result_bits,
+            value: modular_value,
+        })
+    }
+
+    /// Unpacks `lc` into an integer and a carry bit, whose values are given
+    /// by `value`.
+    fn unpack_with_carry<ConstraintF, CS>(
+        cs: CS,
+        lc: LinearCombination<ConstraintF>,
+        value: Option<(T, bool)>,
+        constant: bool,
+    ) -> Result<(Self, Boolean), SynthesisError>
+    where
+        ConstraintF: PrimeField,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        if let (true, Some((value, carry))) = (constant, value) {
+            return Ok((Self::constant(value), Boolean::constant(carry)));
+        }
+
+        let mut values = (0..T::SIZE)
+            .map(|i| value.map(|(value, _)| value.bit(i)))
+            .collect::<Vec<_>>();
+        values.push(value.map(|(_, carry)| carry));
+        let mut bits = unpack(cs, lc, &values)?;
+        let carry = bits.pop().unwrap();
+
+        Ok((
+            Self {
+                bits,
+                value: value.map(|(value, _)| value),
+            },
+            carry,
+        ))
+    }
+
+    /// Computes `self + other` modulo the width of the integer, and whether
+    /// the addition overflowed, like `overflowing_add` on the native integer.
+    pub fn overflowing_add<ConstraintF, CS>(
+        &self,
+        cs: CS,
+        other: &Self,
+    ) -> Result<(Self, Boolean), SynthesisError>
+    where
+        ConstraintF: PrimeField,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        let lc = lc::<ConstraintF, CS>(&self.bits) + &lc::<ConstraintF, CS>(&other.bits);
+        let value = self
+            .value
+            .and_then(|a| other.value.map(|b| a.overflowing_add(b)));
+        let constant = is_constant(&self.bits) && is_constant(&other.bits);
+
+        Self::unpack_with_carry(cs, lc, value, constant)
+    }
+
+    /// Computes `self + other` modulo the width of the integer.
+    pub fn wrapping_add<ConstraintF, CS>(
+        &self,
+        cs: CS,
+        other: &Self,
+    ) -> Result<Self, SynthesisError>
+    where
+        ConstraintF: PrimeField,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        Ok(self.overflowing_add(cs, other)?.0)
+    }
+
+    /// Computes `self + other`, and enforces that the addition does not
+    /// overflow.
+    pub fn checked_add<ConstraintF, CS>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+    ) -> Result<Self, SynthesisError>
+    where
+        ConstraintF: PrimeField,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        let (sum, carry) = self.overflowing_add(cs.ns(|| "add"), other)?;
+        Self::enforce_false(cs.ns(|| "no overflow"), &carry)?;
+        Ok(sum)
+    }
+
+    /// Computes `self - other` modulo the width of the integer, and whether
+    /// the subtraction overflowed, like `overflowing_sub` on the native
+    /// integer.
+    pub fn overflowing_sub<ConstraintF, CS>(
+        &self,
+        cs: CS,
+        other: &Self,
+    ) -> Result<(Self, Boolean), SynthesisError>
+    where
+        ConstraintF: PrimeField,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        // self - other + 2^N = self + !other + 1, which carries exactly when
+        // the subtraction does not overflow.
+        let lc = lc::<ConstraintF, CS>(&self.bits)
+            + &lc::<ConstraintF, CS>(&other.not().bits)
+            + (ConstraintF::one(), CS::one());
+        let value = self.value.and_then(|a| {
+            other.value.map(|b| {
+                let (difference, borrow) = a.overflowing_sub(b);
+                (difference, !borrow)
+            })
+        });
+        let constant = is_constant(&self.bits) && is_constant(&other.bits);
+
+        let (difference, carry) = Self::unpack_with_carry(cs, lc, value, constant)?;
+        Ok((difference, carry.not()))
+    }
+
+    /// Computes `self - other` modulo the width of the integer.
+    pub fn wrapping_sub<ConstraintF, CS>(
+        &self,
+        cs: CS,
+        other: &Self,
+    ) -> Result<Self, SynthesisError>
+    where
+        ConstraintF: PrimeField,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        Ok(self.overflowing_sub(cs, other)?.0)
+    }
+
+    /// Computes `self - other`, and enforces that the subtraction does not
+    /// overflow.
+    pub fn checked_sub<ConstraintF, CS>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+    ) -> Result<Self, SynthesisError>
+    where
+        ConstraintF: PrimeField,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        let (difference, borrow) = self.overflowing_sub(cs.ns(|| "sub"), other)?;
+        Self::enforce_false(cs.ns(|| "no overflow"), &borrow)?;
+        Ok(difference)
+    }
+
+    /// Enforces that `bit` is false, failing right away if it is the constant
+    /// true.
+    fn enforce_false<ConstraintF, CS>(cs: CS, bit: &Boolean) -> Result<(), SynthesisError>
+    where
+        ConstraintF: PrimeField,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        match bit {
+            Boolean::Constant(true) => Err(SynthesisError::Unsatisfiable),
+            _ => bit.enforce_equal(cs, &Boolean::constant(false)),
+        }
+    }
+
+    /// Computes `self * other` modulo the width of the integer.
+    pub fn wrapping_mul<ConstraintF, CS>(
+        &self,
+        cs: CS,
+        other: &Self,
+    ) -> Result<Self, SynthesisError>
+    where
+        ConstraintF: PrimeField,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        self.mul(cs, other, false)
+    }
+
+    /// Computes `self * other`, and enforces that the multiplication does not
+    /// overflow.
+    pub fn checked_mul<ConstraintF, CS>(&self, cs: CS, other: &Self) -> Result<Self, SynthesisError>
+    where
+        ConstraintF: PrimeField,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        self.mul(cs, other, true)
+    }
+
+    /// Multiplies `self` and `other` by their halves, so that no intermediate
+    /// value is much wider than the integers themselves.
+    ///
+    /// Writing `a = a_0 + 2^H a_1` and `b = b_0 + 2^H b_1` for half the width
+    /// `H`, the product modulo `2^N` is the low half of
+    /// `a_0 b_0 + 2^H (a_0 b_1 + a_1 b_0)`, which has fewer than `N + H + 2`
+    /// bits. When `checked` is set, the product must not overflow, so
+    /// `a_1 b_1` must be zero and the sum must fit in `N` bits.
+    fn mul<ConstraintF, CS>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        checked: bool,
+    ) -> Result<Self, SynthesisError>
+    where
+        ConstraintF: PrimeField,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        let half = T::SIZE / 2;
+
+        if is_constant(&self.bits) && is_constant(&other.bits) {
+            let (a, b) = (self.value.unwrap(), other.value.unwrap());
+            if checked && a.checked_mul(b).is_none() {
+                return Err(SynthesisError::Unsatisfiable);
+            }
+            return Ok(Self::constant(a.wrapping_mul(b)));
+        }
+
+        let mask = T::MAX >> half;
+        let products = match (self.value, other.value) {
+            (Some(a), Some(b)) => {
+                let (a_0, a_1) = (a & mask, a >> half);
+                let (b_0, b_1) = (b & mask, b >> half);
+                [Some(a_0 * b_0), Some(a_0 * b_1), Some(a_1 * b_0)]
+            },
+            _ => [None; 3],
+        };
+
+        let (a_0, a_1) = self.bits.split_at(half);
+        let (b_0, b_1) = other.bits.split_at(half);
+        let pack = |bits: &[Boolean]| lc::<ConstraintF, CS>(bits);
+
+        let mut shift = ConstraintF::one();
+        for _ in 0..half {
+            shift.double_in_place();
+        }
+        let mut lc = LinearCombination::zero();
+        let factors = [(a_0, b_0), (a_0, b_1), (a_1, b_0)];
+        for (i, (&(x, y), product)) in factors.iter().zip(&products).enumerate() {
+            let variable = cs.alloc(
+                || format!("product {}", i),
+                || {
+                    product
+                        .map(|product| ConstraintF::from(Into::<u128>::into(product)))
+                        .get()
+                },
+            )?;
+            cs.enforce(
+                || format!("multiplication {}", i),
+                |_| pack(x),
+                |_| pack(y),
+                |lc| lc + variable,
+            );
+            let coeff = if i == 0 { ConstraintF::one() } else { shift };
+            lc = lc + (coeff, variable);
+        }
+
+        // Split the sum into the product modulo 2^N and the part above it.
+        let value = match products {
+            [Some(p_0), Some(p_1), Some(p_2)] => {
+                let (middle, middle_carry) = p_1.overflowing_add(p_2);
+                let (low, low_carry) = p_0.overflowing_add((middle & mask) << half);
+                let high = (middle >> half) + (T::from(middle_carry) << half) + T::from(low_carry);
+                Some((low, high))
+            },
+            _ => None,
+        };
+        let mut values = (0..T::SIZE)
+            .map(|i| value.map(|(low, _)| low.bit(i)))
+            .collect::<Vec<_>>();
+        if checked {
+            cs.enforce(|| "no high product", |_| pack(a_1), |_| pack(b_1), |lc| lc);
+        } else {
+            values.extend((0..half + 2).map(|i| value.map(|(_, high)| high.bit(i))));
+        }
+
+        let mut bits = unpack(cs.ns(|| "product"), lc, &values)?;
+        bits.truncate(T::SIZE);
+
+        Ok(Self {
+            bits,
+            value: value.map(|(low, _)| low),
+        })
+    }
+
+    /// Outputs a `Boolean` that is true exactly when `self` and `other` are
+    /// equal.
+    pub fn is_eq<ConstraintF, CS>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+    ) -> Result<Boolean, SynthesisError>
+    where
+        ConstraintF: Field,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        let differences = self
+            .bits
+            .iter()
+            .zip(&other.bits)
+            .enumerate()
+            .map(|(i, (a, b))| Boolean::xor(cs.ns(|| format!("xor of bit {}", i)), a, b))
+            .collect::<Result<Vec<_>, _>>()?;
+        let different = Boolean::kary_or(cs.ns(|| "any difference"), &differences)?;
+
+        Ok(different.not())
+    }
+
+    /// This function checks the ordering between `self` and `other`. It
+    /// outputs a `Boolean` that contains the result - `1` if true, `0`
+    /// otherwise. If `self` should also be checked for equality, e.g.
+    /// `a <= b` instead of `a < b`, set `should_also_check_equality` to
+    /// `true`. `Ordering::Equal` checks for equality alone.
+    pub fn is_cmp<ConstraintF, CS>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        ordering: Ordering,
+        should_also_check_equality: bool,
+    ) -> Result<Boolean, SynthesisError>
+    where
+        ConstraintF: PrimeField,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        // `left - right` overflows exactly when `left < right`.
+        let (left, right, negate) = match (ordering, should_also_check_equality) {
+            (Ordering::Less, false) => (self, other, false),
+            (Ordering::Less, true) => (other, self, true),
+            (Ordering::Greater, false) => (other, self, false),
+            (Ordering::Greater, true) => (self, other, true),
+            (Ordering::Equal, _) => return self.is_eq(cs, other),
+        };
+        let (_, borrow) = left.overflowing_sub(cs.ns(|| "subtract"), right)?;
+
+        Ok(if negate { borrow.not() } else { borrow })
+    }
+
+    /// This function enforces the ordering between `self` and `other`. The
+    /// constraint system will not be satisfied otherwise. If `self` should
+    /// also be checked for equality, e.g. `a <= b` instead of `a < b`, set
+    /// `should_also_check_equality` to `true`.
+    pub fn enforce_cmp<ConstraintF, CS>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        ordering: Ordering,
+        should_also_check_equality: bool,
+    ) -> Result<(), SynthesisError>
+    where
+        ConstraintF: PrimeField,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        let result = self.is_cmp(
+            cs.ns(|| "compare"),
+            other,
+            ordering,
+            should_also_check_equality,
+        )?;
+        Self::enforce_false(cs.ns(|| "enforce ordering"), &result.not())
+    }
+
+    /// Converts `self` into a field element, without any constraints. Fails
+    /// with `SynthesisError::FieldTooSmall` if the integers do not fit in the
+    /// field.
+    pub fn to_fp<ConstraintF, CS>(&self, _cs: CS) -> Result<FpGadget<ConstraintF>, SynthesisError>
+    where
+        ConstraintF: PrimeField,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        check_capacity::<ConstraintF>(T::SIZE)?;
+
+        Ok(FpGadget {
+            value: self
+                .value
+                .map(|value| ConstraintF::from(Into::<u128>::into(value))),
+            variable: ConstraintVar::LC(lc::<ConstraintF, CS>(&self.bits)),
+        })
+    }
+
+    /// Converts the field element `fp` into an integer, and enforces that it
+    /// fits in the width of the integer. Fails with
+    /// `SynthesisError::FieldTooSmall` if the integers do not fit in the
+    /// field.
+    pub fn from_fp<ConstraintF, CS>(
+        mut cs: CS,
+        fp: &FpGadget<ConstraintF>,
+    ) -> Result<Self, SynthesisError>
+    where
+        ConstraintF: PrimeField,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        let lc = &fp.variable + LinearCombination::zero();
+        let values = field_bits(fp.value, T::SIZE);
+        let bits = unpack(cs.ns(|| "unpack"), lc, &values)?;
+        Ok(Self::from_bits_le(&bits))
+    }
+
+    fn alloc_bits<ConstraintF, CS>(
+        mut cs: CS,
+        value: Option<T>,
+        input: bool,
+    ) -> Result<Self, SynthesisError>
+    where
+        ConstraintF: Field,
+        CS: ConstraintSystem<ConstraintF>,
+    {
+        let bits = (0..T::SIZE)
+            .map(|i| {
+                let cs = cs.ns(|| format!("allocated bit_gadget {}", i));
+                let value = || value.map(|v| v.bit(i)).get();
+                let bit = if input {
+                    AllocatedBit::alloc_input(cs, value)?
+                } else {
+                    AllocatedBit::alloc(cs, value)?
+                };
+                Ok(Boolean::from(bit))
+            })
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+        Ok(Self { bits, value })
+    }
+}
+
+impl<T: NativeUInt, ConstraintF: Field> AllocGadget<T, ConstraintF> for UInt<T> {
+    fn alloc_constant<V, CS: ConstraintSystem<ConstraintF>>(
+        _cs: CS,
+        t: V,
+    ) -> Result<Self, SynthesisError>
+    where
+        V: Borrow<T>,
+    {
+        Ok(Self::constant(*t.borrow()))
+    }
+
+    fn alloc<F, V, CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        value_gen: F,
+    ) -> Result<Self, SynthesisError>
+    where
+        F: FnOnce() -> Result<V, SynthesisError>,
+        V: Borrow<T>,
+    {
+        let value = value_gen().map(|val| *val.borrow()).ok();
+        Self::alloc_bits(cs, value, false)
+    }
+
+    fn alloc_input<F, V, CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        value_gen: F,
+    ) -> Result<Self, SynthesisError>
+    where
+        F: FnOnce() -> Result<V, SynthesisError>,
+        V: Borrow<T>,
+    {
+        let value = value_gen().map(|val| *val.borrow()).ok();
+        Self::alloc_bits(cs, value, true)
+    }
+}
+
+impl<T: NativeUInt, ConstraintF: Field> ToBytesGadget<ConstraintF> for UInt<T> {
+    #[inline]
+    fn to_bytes<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        _cs: CS,
+    ) -> Result<Vec<UInt8>, SynthesisError> {
+        let value = self.value.map(Into::<u128>::into);
+        let bytes = self
+            .bits
+            .chunks(8)
+            .enumerate()
+            .map(|(i, chunk8)| UInt8 {
+                bits: chunk8.to_vec(),
+                value: value.map(|value| (value >> (8 * i)) as u8),
+            })
+            .collect();
+
+        Ok(bytes)
+    }
+}
+
+impl<T: NativeUInt> PartialEq for UInt<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value.is_some() && other.value.is_some() && self.value == other.value
+    }
+}
+
+impl<T: NativeUInt> Eq for UInt<T> {}
+
+impl<T: NativeUInt, ConstraintF: Field> ConditionalEqGadget<ConstraintF> for UInt<T> {
+    fn conditional_enforce_equal<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        condition: &Boolean,
+    ) -> Result<(), SynthesisError> {
+        for (i, (a, b)) in self.bits.iter().zip(&other.bits).enumerate() {
+            a.conditional_enforce_equal(&mut cs.ns(|| format!("bit {} equal", i)), b, condition)?;
+        }
+        Ok(())
+    }
+
+    fn cost() -> usize {
+        T::SIZE * <Boolean as ConditionalEqGadget<ConstraintF>>::cost()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    // Checks each operation of an integer gadget against the same operation
+    // on its native integer type.
+    macro_rules! test_uint_ops {
+        ($module:ident, $name:ty, $native:ident, $size:expr) => {
+            mod $module {
+                use crate::{
+                    alloc::AllocGadget, bits::boolean::AllocatedBit, boolean::Boolean,
+                    fields::fp::FpGadget, test_constraint_system::TestConstraintSystem, Vec,
+                };
+                use algebra::{bls12_381::Fr, Field, One};
+                use core::cmp::Ordering;
+                use r1cs_core::ConstraintSystem;
+                use rand::{Rng, SeedableRng};
+                use rand_xorshift::XorShiftRng;
+
+                // Edge cases, random operands, and random operands that are
+                // small enough to multiply without overflow.
+                fn operands() -> Vec<($native, $native)> {
+                    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+                    let edges = [0, 1, <$native>::max_value() - 1, <$native>::max_value()];
+
+                    let mut operands = Vec::new();
+                    for &a in &edges {
+                        for &b in &edges {
+                            operands.push((a, b));
+                        }
+                    }
+                    for _ in 0..20 {
+                        operands.push((rng.gen(), rng.gen()));
+                        let (a, b): ($native, $native) = (rng.gen(), rng.gen());
+                        operands.push((a >> ($size / 2), b >> ($size / 2)));
+                    }
+                    operands
+                }
+
+                fn alloc<CS: ConstraintSystem<Fr>>(mut cs: CS, value: $native) -> $name {
+                    let bits = (0..$size)
+                        .map(|i| {
+                            AllocatedBit::alloc(cs.ns(|| format!("bit {}", i)), || {
+                                Ok((value >> i) & 1 == 1)
+                            })
+                            .unwrap()
+                            .into()
+                        })
+                        .collect::<Vec<Boolean>>();
+                    <$name>::from_bits_le(&bits)
+                }
+
+                // Checks both the value of `x` and the values of its bits.
+                fn assert_value(x: &$name, expected: $native) {
+                    assert_eq!(x.get_value(), Some(expected));
+                    assert_eq!(
+                        <$name>::from_bits_le(&x.to_bits_le()).get_value(),
+                        Some(expected)
+                    );
+                }
+
+                // Runs `op` on allocated operands, and on an allocated and
+                // a constant operand, and returns whether the constraints
+                // are satisfied.
+                fn run<F>(a: $native, b: $native, op: F) -> bool
+                where
+                    F: Fn(&mut TestConstraintSystem<Fr>, &$name, &$name),
+                {
+                    let mut cs = TestConstraintSystem::<Fr>::new();
+                    let a_bits = alloc(cs.ns(|| "a"), a);
+                    let b_bits = alloc(cs.ns(|| "b"), b);
+                    op(&mut cs, &a_bits, &b_bits);
+                    let allocated = cs.is_satisfied();
+
+                    let mut cs = TestConstraintSystem::<Fr>::new();
+                    let a_bits = alloc(cs.ns(|| "a"), a);
+                    op(&mut cs, &a_bits, &<$name>::constant(b));
+                    assert_eq!(cs.is_satisfied(), allocated);
+
+                    allocated
+                }
+
+                #[test]
+                fn bitwise() {
+                    for (a, b) in operands() {
+                        assert!(run(a, b, |cs, x, y| {
+                            assert_value(&x.and(cs.ns(|| "and"), y).unwrap(), a & b);
+                            assert_value(&x.or(cs.ns(|| "or"), y).unwrap(), a | b);
+                            assert_value(&x.not(), !a);
+                            assert_value(&y.not(), !b);
+                        }));
+                    }
+                }
+
+                #[test]
+                fn shifts() {
+                    for (a, b) in operands() {
+                        for &by in &[0, 1, $size / 2, $size - 1, $size, $size + 1] {
+                            let x = alloc(TestConstraintSystem::<Fr>::new(), a);
+                            assert_value(&x.shl(by), a.checked_shl(by as u32).unwrap_or(0));
+                            assert_value(&x.shr(by), a.checked_shr(by as u32).unwrap_or(0));
+                        }
+                        let by = (b % $size) as usize;
+                        let x = <$name>::constant(a);
+                        assert_value(&x.shl(by), a << by);
+                        assert_value(&x.shr(by), a >> by);
+                    }
+                }
+
+                #[test]
+                fn add() {
+                    for (a, b) in operands() {
+                        let (sum, overflow) = a.overflowing_add(b);
+                        assert!(run(a, b, |cs, x, y| {
+                            let (result, carry) = x.overflowing_add(cs.ns(|| "add"), y).unwrap();
+                            assert_value(&result, sum);
+                            assert_eq!(carry.get_value(), Some(overflow));
+                            let result = x.wrapping_add(cs.ns(|| "wrapping add"), y).unwrap();
+                            assert_value(&result, sum);
+                        }));
+                        let satisfied = run(a, b, |cs, x, y| {
+                            assert_value(&x.checked_add(cs.ns(|| "add"), y).unwrap(), sum);
+                        });
+                        assert_eq!(satisfied, a.checked_add(b).is_some());
+                    }
+                }
+
+                #[test]
+                fn sub() {
+                    for (a, b) in operands() {
+                        let (difference, overflow) = a.overflowing_sub(b);
+                        assert!(run(a, b, |cs, x, y| {
+                            let (result, borrow) = x.overflowing_sub(cs.ns(|| "sub"), y).unwrap();
+                            assert_value(&result, difference);
+                            assert_eq!(borrow.get_value(), Some(overflow));
+                            let result = x.wrapping_sub(cs.ns(|| "wrapping sub"), y).unwrap();
+                            assert_value(&result, difference);
+                        }));
+                        let satisfied = run(a, b, |cs, x, y| {
+                            assert_value(&x.checked_sub(cs.ns(|| "sub"), y).unwrap(), difference);
+                        });
+                        assert_eq!(satisfied, a.checked_sub(b).is_some());
+                    }
+                }
+
+                #[test]
+                fn mul() {
+                    for (a, b) in operands() {
+                        let product = a.wrapping_mul(b);
+                        assert!(run(a, b, |cs, x, y| {
+                            assert_value(&x.wrapping_mul(cs.ns(|| "mul"), y).unwrap(), product);
+                        }));
+                        let satisfied = run(a, b, |cs, x, y| {
+                            assert_value(&x.checked_mul(cs.ns(|| "mul"), y).unwrap(), product);
+                        });
+                        assert_eq!(satisfied, a.checked_mul(b).is_some());
+                    }
+
+                    let max = <$name>::constant(<$native>::max_value());
+                    let two = <$name>::constant(2);
+                    let mut cs = TestConstraintSystem::<Fr>::new();
+                    assert!(max.checked_mul(cs.ns(|| "mul"), &two).is_err());
+                    assert_value(
+                        &max.wrapping_mul(cs.ns(|| "wrapping mul"), &two).unwrap(),
+                        <$native>::max_value() - 1,
+                    );
+                }
+
+                #[test]
+                fn comparisons() {
+                    for (a, b) in operands() {
+                        let cases = [
+                            (Ordering::Less, false, a < b),
+                            (Ordering::Less, true, a <= b),
+                            (Ordering::Greater, false, a > b),
+                            (Ordering::Greater, true, a >= b),
+                            (Ordering::Equal, false, a == b),
+                        ];
+                        for &(ordering, or_equal, expected) in &cases {
+                            assert!(run(a, b, |cs, x, y| {
+                                let result = x
+                                    .is_cmp(cs.ns(|| "compare"), y, ordering, or_equal)
+                                    .unwrap();
+                                assert_eq!(result.get_value(), Some(expected));
+                            }));
+                            let satisfied = run(a, b, |cs, x, y| {
+                                x.enforce_cmp(cs.ns(|| "compare"), y, ordering, or_equal)
+                                    .unwrap();
+                            });
+                            assert_eq!(satisfied, expected);
+                        }
+                    }
+                }
+
+                #[test]
+                fn addmany() {
+                    for (a, b) in operands() {
+                        let sum = a.wrapping_add(b).wrapping_add(a).wrapping_add(1);
+                        assert!(run(a, b, |cs, x, y| {
+                            let operands = [x.clone(), y.clone(), x.clone(), <$name>::constant(1)];
+                            assert_value(
+                                &<$name>::addmany(cs.ns(|| "add"), &operands).unwrap(),
+                                sum,
+                            );
+                        }));
+                    }
+                }
+
+                #[test]
+                fn fp_conversion() {
+                    let mut two_to_the_size = Fr::one();
+                    for _ in 0..$size {
+                        two_to_the_size.double_in_place();
+                    }
+
+                    for (a, _) in operands() {
+                        let mut cs = TestConstraintSystem::<Fr>::new();
+                        let x = alloc(cs.ns(|| "x"), a);
+                        let fp = x.to_fp(cs.ns(|| "to fp")).unwrap();
+                        assert_eq!(fp.value, Some(Fr::from(a)));
+
+                        let y = <$name>::from_fp(cs.ns(|| "from fp"), &fp).unwrap();
+                        assert_value(&y, a);
+                        assert!(cs.is_satisfied());
+
+                        // Field elements that do not fit in the integer are
+                        // rejected.
+                        let mut cs = TestConstraintSystem::<Fr>::new();
+                        let too_big = Fr::from(a) + &two_to_the_size;
+                        let fp = FpGadget::alloc(cs.ns(|| "fp"), || Ok(too_big)).unwrap();
+                        <$name>::from_fp(cs.ns(|| "from fp"), &fp).unwrap();
+                        assert!(!cs.is_satisfied());
+                    }
+                }
+            }
+        };
+    }
+
+    test_uint_ops!(uint16, crate::bits::uint::UInt16, u16, 16);
+    test_uint_ops!(uint32, crate::bits::uint::UInt32, u32, 32);
+    test_uint_ops!(uint64, crate::bits::uint::UInt64, u64, 64);
+    test_uint_ops!(uint128, crate::bits::uint::UInt128, u128, 128);
+}
@@ -1,347 +1,12 @@
-use algebra::{Field, FpParameters, PrimeField};
-
-use r1cs_core::{ConstraintSystem, LinearCombination, SynthesisError};
-
-use crate::{
-    boolean::{AllocatedBit, Boolean},
-    prelude::*,
-    Assignment, Vec,
-};
-
-/// Represents an interpretation of 32 `Boolean` objects as an
-/// unsigned integer.
-#[derive(Clone, Debug)]
-pub struct UInt32 {
-    // Least significant bit_gadget first
-    bits: Vec<Boolean>,
-    value: Option<u32>,
-}
-
-impl UInt32 {
-    /// Construct a constant `UInt32` from a `u32`
-    pub fn constant(value: u32) -> Self {
-        let mut bits = Vec::with_capacity(32);
-
-        let mut tmp = value;
-        for _ in 0..32 {
-            if tmp & 1 == 1 {
-                bits.push(Boolean::constant(true))
-            } else {
-                bits.push(Boolean::constant(false))
-            }
-
-            tmp >>= 1;
-        }
-
-        UInt32 {
-            bits,
-            value: Some(value),
-        }
-    }
-
-    /// Allocate a `UInt32` in the constraint system
-    pub fn alloc<ConstraintF, CS>(mut cs: CS, value: Option<u32>) -> Result<Self, SynthesisError>
-    where
-        ConstraintF: Field,
-        CS: ConstraintSystem<ConstraintF>,
-    {
-        let values = match value {
-            Some(mut val) => {
-                let mut v = Vec::with_capacity(32);
-
-                for _ in 0..32 {
-                    v.push(Some(val & 1 == 1));
-                    val >>= 1;
-                }
-
-                v
-            }
-            None => vec![None; 32],
-        };
-
-        let bits = values
-            .into_iter()
-            .enumerate()
-            .map(|(i, v)| {
-                Ok(Boolean::from(AllocatedBit::alloc(
-                    cs.ns(|| format!("allocated bit_gadget {}", i)),
-                    || v.get(),
-                )?))
-            })
-            .collect::<Result<Vec<_>, SynthesisError>>()?;
-
-        Ok(UInt32 { bits, value })
-    }
-
-    /// Turns this `UInt32` into its little-endian byte order representation.
-    pub fn to_bits_le(&self) -> Vec<Boolean> {
-        self.bits.clone()
-    }
-
-    /// Converts a little-endian byte order representation of bits into a
-    /// `UInt32`.
-    pub fn from_bits_le(bits: &[Boolean]) -> Self {
-        assert_eq!(bits.len(), 32);
-
-        let bits = bits.to_vec();
-
-        let mut value = Some(0u32);
-        for b in bits.iter().rev() {
-            value.as_mut().map(|v| *v <<= 1);
-
-            match b {
-                &Boolean::Constant(b) => {
-                    if b {
-                        value.as_mut().map(|v| *v |= 1);
-                    }
-                }
-                &Boolean::Is(ref b) => match b.get_value() {
-                    Some(true) => {
-                        value.as_mut().map(|v| *v |= 1);
-                    }
-                    Some(false) => {}
-                    None => value = None,
-                },
-                &Boolean::Not(ref b) => match b.get_value() {
-                    Some(false) => {
-                        value.as_mut().map(|v| *v |= 1);
-                    }
-                    Some(true) => {}
-                    None => value = None,
-                },
-            }
-        }
-
-        Self { value, bits }
-    }
-
-    pub fn rotr(&self, by: usize) -> Self {
-        let by = by % 32;
-
-        let new_bits = self
-            .bits
-            .iter()
-            .skip(by)
-            .chain(self.bits.iter())
-            .take(32)
-            .cloned()
-            .collect();
-
-        UInt32 {
-            bits: new_bits,
-            value: self.value.map(|v| v.rotate_right(by as u32)),
-        }
-    }
-
-    /// XOR this `UInt32` with another `UInt32`
-    pub fn xor<ConstraintF, CS>(&self, mut cs: CS, other: &Self) -> Result<Self, SynthesisError>
-    where
-        ConstraintF: Field,
-        CS: ConstraintSystem<ConstraintF>,
-    {
-        let new_value = match (self.value, other.value) {
-            (Some(a), Some(b)) => Some(a ^ b),
-            _ => None,
-        };
-
-        let bits = self
-            .bits
-            .iter()
-            .zip(other.bits.iter())
-            .enumerate()
-            .map(|(i, (a, b))| Boolean::xor(cs.ns(|| format!("xor of bit_gadget {}", i)), a, b))
-            .collect::<Result<_, _>>()?;
-
-        Ok(UInt32 {
-            bits,
-            value: new_value,
-        })
-    }
-
-    /// Perform modular addition of several `UInt32` objects.
-    pub fn addmany<ConstraintF, CS>(mut cs: CS, operands: &[Self]) -> Result<Self, SynthesisError>
-    where
-        ConstraintF: PrimeField,
-        CS: ConstraintSystem<ConstraintF>,
-    {
-        // Make some arbitrary bounds for ourselves to avoid overflows
-        // in the scalar field
-        assert!(ConstraintF::Params::MODULUS_BITS >= 64);
-
-        assert!(operands.len() >= 1);
-        assert!(operands.len() <= 10);
-
-        if operands.len() == 1 {
-            return Ok(operands[0].clone());
-        }
-
-        // Compute the maximum value of the sum so we allocate enough bits for
-        // the result
-        let mut max_value = (operands.len() as u64) * u64::from(u32::max_value());
-
-        // Keep track of the resulting value
-        let mut result_value = Some(0u64);
-
-        // This is a linear combination that we will enforce to be "zero"
-        let mut lc = LinearCombination::zero();
-
-        let mut all_constants = true;
-
-        // Iterate over the operands
-        for op in operands {
-            // Accumulate the value
-            match op.value {
-                Some(val) => {
-                    result_value.as_mut().map(|v| *v += u64::from(val));
-                }
-                None => {
-                    // If any of our operands have unknown value, we won't
-                    // know the value of the result
-                    result_value = None;
-                }
-            }
-
-            // Iterate over each bit_gadget of the operand and add the operand to
-            // the linear combination
-            let mut coeff = ConstraintF::one();
-            for bit in &op.bits {
-                match *bit {
-                    Boolean::Is(ref bit) => {
-                        all_constants = false;
-
-                        // Add coeff * bit_gadget
-                        lc += (coeff, bit.get_variable());
-                    }
-                    Boolean::Not(ref bit) => {
-                        all_constants = false;
-
-                        // Add coeff * (1 - bit_gadget) = coeff * ONE - coeff * bit_gadget
-                        lc = lc + (coeff, CS::one()) - (coeff, bit.get_variable());
-                    }
-                    Boolean::Constant(bit) => {
-                        if bit {
-                            lc += (coeff, CS::one());
-                        }
-                    }
-                }
-
-                coeff.double_in_place();
-            }
-        }
-
-        // The value of the actual result is modulo 2^32
-        let modular_value = result_value.map(|v| v as u32);
-
-        if all_constants && modular_value.is_some() {
-            // We can just return a constant, rather than
-            // unpacking the result into allocated bits.
-
-            return Ok(UInt32::constant(modular_value.unwrap()));
-        }
-
-        // Storage area for the resulting bits
-        let mut result_bits = vec![];
-
-        // Allocate each bit_gadget of the result
-        let mut coeff = ConstraintF::one();
-        let mut i = 0;
-        while max_value != 0 {
-            // Allocate the bit_gadget
-            let b = AllocatedBit::alloc(cs.ns(|| format!("result bit_gadget {}", i)), || {
-                result_value.map(|v| (v >> i) & 1 == 1).get()
-            })?;
-
-            // Subtract this bit_gadget from the linear combination to ensure the sums
-            // balance out
-            lc = lc - (coeff, b.get_variable());
-
-            result_bits.push(b.into());
-
-            max_value >>= 1;
-            i += 1;
-            coeff.double_in_place();
-        }
-
-        // Enforce that the linear combination equals zero
-        cs.enforce(|| "modular addition", |lc| lc, |lc| lc, |_| lc);
-
-        // Discard carry bits that we don't care about
-        result_bits.truncate(32);
-
-        Ok(UInt32 {
-            bits: result_bits,
-            value: modular_value,
-        })
-    }
-}
-
-impl<ConstraintF: Field> ToBytesGadget<ConstraintF> for UInt32 {
-    #[inline]
-    fn to_bytes<CS: ConstraintSystem<ConstraintF>>(
-        &self,
-        _cs: CS,
-    ) -> Result<Vec<UInt8>, SynthesisError> {
-        let value_chunks = match self.value.map(|val| {
-            use algebra::bytes::ToBytes;
-            let mut bytes = [0u8; 4];
-            val.write(bytes.as_mut()).unwrap();
-            bytes
-        }) {
-            Some(chunks) => [
-                Some(chunks[0]),
-                Some(chunks[1]),
-                Some(chunks[2]),
-                Some(chunks[3]),
-            ],
-            None => [None, None, None, None],
-        };
-        let mut bytes = Vec::new();
-        for (i, chunk8) in self.to_bits_le().chunks(8).enumerate() {
-            let byte = UInt8 {
-                bits: chunk8.to_vec(),
-                value: value_chunks[i],
-            };
-            bytes.push(byte);
-        }
-
-        Ok(bytes)
-    }
-}
-
-impl PartialEq for UInt32 {
-    fn eq(&self, other: &Self) -> bool {
-        self.value.is_some() && other.value.is_some() && self.value == other.value
-    }
-}
-
-impl Eq for UInt32 {}
-
-impl<ConstraintF: Field> ConditionalEqGadget<ConstraintF> for UInt32 {
-    fn conditional_enforce_equal<CS: ConstraintSystem<ConstraintF>>(
-        &self,
-        mut cs: CS,
-        other: &Self,
-        condition: &Boolean,
-    ) -> Result<(), SynthesisError> {
-        for (i, (a, b)) in self.bits.iter().zip(&other.bits).enumerate() {
-            a.conditional_enforce_equal(
-                &mut cs.ns(|| format!("uint32_equal_{}", i)),
-                b,
-                condition,
-            )?;
-        }
-        Ok(())
-    }
-
-    fn cost() -> usize {
-        32 * <Boolean as ConditionalEqGadget<ConstraintF>>::cost()
-    }
-}
+pub use super::uint::UInt32;
 
 #[cfg(test)]
 mod test {
     use super::UInt32;
-    use crate::{bits::boolean::Boolean, test_constraint_system::TestConstraintSystem, Vec};
+    use crate::{
+        alloc::AllocGadget, bits::boolean::Boolean, test_constraint_system::TestConstraintSystem,
+        Vec,
+    };
     use algebra::{bls12_381::Fr, One, Zero};
     use r1cs_core::ConstraintSystem;
     use rand::{Rng, SeedableRng};
@@ -392,9 +57,9 @@ mod test {
 
             let mut expected = a ^ b ^ c;
 
-            let a_bit = UInt32::alloc(cs.ns(|| "a_bit"), Some(a)).unwrap();
+            let a_bit = UInt32::alloc(cs.ns(|| "a_bit"), || Ok(a)).unwrap();
             let b_bit = UInt32::constant(b);
-            let c_bit = UInt32::alloc(cs.ns(|| "c_bit"), Some(c)).unwrap();
+            let c_bit = UInt32::alloc(cs.ns(|| "c_bit"), || Ok(c)).unwrap();
 
             let r = a_bit.xor(cs.ns(|| "first xor"), &b_bit).unwrap();
             let r = r.xor(cs.ns(|| "second xor"), &c_bit).unwrap();
@@ -470,10 +135,10 @@ mod test {
 
             let mut expected = (a ^ b).wrapping_add(c).wrapping_add(d);
 
-            let a_bit = UInt32::alloc(cs.ns(|| "a_bit"), Some(a)).unwrap();
+            let a_bit = UInt32::alloc(cs.ns(|| "a_bit"), || Ok(a)).unwrap();
             let b_bit = UInt32::constant(b);
             let c_bit = UInt32::constant(c);
-            let d_bit = UInt32::alloc(cs.ns(|| "d_bit"), Some(d)).unwrap();
+            let d_bit = UInt32::alloc(cs.ns(|| "d_bit"), || Ok(d)).unwrap();
 
             let r = a_bit.xor(cs.ns(|| "xor"), &b_bit).unwrap();
             let r = UInt32::addmany(cs.ns(|| "addition"), &[r, c_bit, d_bit]).unwrap();
@@ -535,4 +200,39 @@ mod test {
             num = num.rotate_right(1);
         }
     }
+
+    #[test]
+    fn test_uint32_addmany_witness_generation() {
+        use r1cs_core::{R1CSRecorder, WitnessGenerator};
+
+        fn add<CS: ConstraintSystem<Fr>>(mut cs: CS, a: u32, b: u32) -> UInt32 {
+            let a_bit = UInt32::alloc(cs.ns(|| "a_bit"), || Ok(a)).unwrap();
+            let b_bit = UInt32::alloc(cs.ns(|| "b_bit"), || Ok(b)).unwrap();
+            let c_bit = UInt32::constant(a ^ b);
+            UInt32::addmany(cs.ns(|| "addition"), &[a_bit, b_bit, c_bit]).unwrap()
+        }
+
+        let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+        for _ in 0..100 {
+            let a: u32 = rng.gen();
+            let b: u32 = rng.gen();
+
+            let mut recorder = R1CSRecorder::<Fr>::new();
+            let recorded = add(&mut recorder, a, b);
+            let mut witness = WitnessGenerator::<Fr>::new();
+            assert!(witness.is_witness_generator());
+            let generated = add(&mut witness, a, b);
+
+            assert_eq!(generated.value, recorded.value);
+            assert_eq!(witness.num_constraints(), recorder.num_constraints());
+            assert_eq!(
+                witness.aux_assignment,
+                recorder
+                    .aux_assignment
+                    .iter()
+                    .map(|value| value.unwrap())
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
 }
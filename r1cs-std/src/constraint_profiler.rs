@@ -0,0 +1,333 @@
+use crate::{BTreeMap, String, Vec};
+use algebra::Field;
+use core::fmt;
+use r1cs_core::{ConstraintSystem, Index, LinearCombination, SynthesisError, Variable};
+
+/// The number of constraints and variables created in a namespace, including
+/// the namespaces inside it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NamespaceCost {
+    pub name: String,
+    pub num_constraints: usize,
+    pub num_aux: usize,
+    pub num_inputs: usize,
+    /// The namespaces directly inside this one. Namespaces that are entered
+    /// repeatedly under the same name are merged.
+    pub children: Vec<NamespaceCost>,
+}
+
+/// A quantity that a profile can attribute to namespaces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CostMetric {
+    Constraints,
+    PrivateVariables,
+    PublicInputs,
+}
+
+impl NamespaceCost {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            ..Self::default()
+        }
+    }
+
+    pub fn cost(&self, metric: CostMetric) -> usize {
+        match metric {
+            CostMetric::Constraints => self.num_constraints,
+            CostMetric::PrivateVariables => self.num_aux,
+            CostMetric::PublicInputs => self.num_inputs,
+        }
+    }
+
+    /// Returns the cost of this namespace that is not attributed to any of
+    /// the namespaces inside it.
+    pub fn own_cost(&self, metric: CostMetric) -> usize {
+        self.cost(metric)
+            - self
+                .children
+                .iter()
+                .map(|child| child.cost(metric))
+                .sum::<usize>()
+    }
+
+    /// Sorts the namespaces inside this one, recursively, from the most to
+    /// the least constraints.
+    pub fn sort(&mut self) {
+        self.children.sort_by(|a, b| {
+            b.num_constraints
+                .cmp(&a.num_constraints)
+                .then_with(|| b.num_aux.cmp(&a.num_aux))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        for child in &mut self.children {
+            child.sort();
+        }
+    }
+
+    /// Returns the costs in the folded-stack format read by flamegraph
+    /// tools: one line per namespace, with the path of the namespace,
+    /// separated by `;`, and its own cost. Namespaces without costs of their
+    /// own are omitted, and any `;` in names is replaced by `:`.
+    pub fn folded_stacks(&self, metric: CostMetric) -> String {
+        let mut folded = String::new();
+        self.fold(&mut String::new(), metric, &mut folded);
+        folded
+    }
+
+    fn fold(&self, stack: &mut String, metric: CostMetric, folded: &mut String) {
+        let len = stack.len();
+        if !stack.is_empty() {
+            stack.push(';');
+        }
+        stack.push_str(&self.name.replace(';', ":"));
+
+        let own_cost = self.own_cost(metric);
+        if own_cost > 0 {
+            folded.push_str(&format!("{} {}\n", stack, own_cost));
+        }
+        for child in &self.children {
+            child.fold(stack, metric, folded);
+        }
+        stack.truncate(len);
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        writeln!(
+            f,
+            "{:indent$}{}: {} constraints, {} private variables, {} public inputs",
+            "",
+            self.name,
+            self.num_constraints,
+            self.num_aux,
+            self.num_inputs,
+            indent = 2 * depth
+        )?;
+        for child in &self.children {
+            child.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Prints the tree of namespaces, indenting each namespace under the one it
+/// is in.
+impl fmt::Display for NamespaceCost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+/// The positions of the children of a namespace by name, mirroring a tree of
+/// `NamespaceCost`s, so that entering a namespace does not scan its siblings.
+#[derive(Default)]
+struct ChildIndex {
+    by_name: BTreeMap<String, usize>,
+    children: Vec<ChildIndex>,
+}
+
+/// Constraint system that attributes constraints, private variables and
+/// public inputs to the namespaces they are created in, for finding the
+/// gadgets that dominate the size of a circuit.
+pub struct ConstraintProfiler {
+    root: NamespaceCost,
+    index: ChildIndex,
+    // The indices of the children that lead from the root to the current
+    // namespace.
+    current_namespace: Vec<usize>,
+}
+
+impl ConstraintProfiler {
+    pub fn new() -> Self {
+        Self {
+            root: NamespaceCost::new("circuit".into()),
+            index: ChildIndex::default(),
+            current_namespace: Vec::new(),
+        }
+    }
+
+    /// Returns the costs of the circuit, whose namespaces are sorted from the
+    /// most to the least constraints.
+    pub fn profile(&self) -> NamespaceCost {
+        let mut profile = self.root.clone();
+        profile.sort();
+        profile
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.root.num_constraints
+    }
+
+    // Applies `f` to the current namespace and every namespace it is in.
+    fn attribute(&mut self, f: impl Fn(&mut NamespaceCost)) {
+        let mut namespace = &mut self.root;
+        f(namespace);
+        for &i in &self.current_namespace {
+            namespace = &mut namespace.children[i];
+            f(namespace);
+        }
+    }
+}
+
+impl Default for ConstraintProfiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<ConstraintF: Field> ConstraintSystem<ConstraintF> for ConstraintProfiler {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(&mut self, _: A, _: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<ConstraintF, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let var = Variable::new_unchecked(Index::Aux(self.root.num_aux));
+        self.attribute(|namespace| namespace.num_aux += 1);
+        Ok(var)
+    }
+
+    fn alloc_input<F, A, AR>(&mut self, _: A, _: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<ConstraintF, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        // The "one" input variable is not allocated, so the first input has
+        // index 1.
+        let var = Variable::new_unchecked(Index::Input(self.root.num_inputs + 1));
+        self.attribute(|namespace| namespace.num_inputs += 1);
+        Ok(var)
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, _: A, _: LA, _: LB, _: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<ConstraintF>) -> LinearCombination<ConstraintF>,
+        LB: FnOnce(LinearCombination<ConstraintF>) -> LinearCombination<ConstraintF>,
+        LC: FnOnce(LinearCombination<ConstraintF>) -> LinearCombination<ConstraintF>,
+    {
+        self.attribute(|namespace| namespace.num_constraints += 1);
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        let name = name_fn().into();
+        let mut parent = &mut self.root;
+        let mut index = &mut self.index;
+        for &i in &self.current_namespace {
+            parent = &mut parent.children[i];
+            index = &mut index.children[i];
+        }
+        let i = match index.by_name.get(&name) {
+            Some(&i) => i,
+            None => {
+                let i = parent.children.len();
+                index.by_name.insert(name.clone(), i);
+                index.children.push(ChildIndex::default());
+                parent.children.push(NamespaceCost::new(name));
+                i
+            },
+        };
+        self.current_namespace.push(i);
+    }
+
+    fn pop_namespace(&mut self) {
+        assert!(self.current_namespace.pop().is_some());
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.root.num_constraints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algebra::bls12_381::Fr;
+    use r1cs_core::ConstraintSynthesizer;
+
+    // Squares its input twice, in a namespace per squaring, and checks the
+    // result against a public input.
+    struct Squarings;
+
+    impl ConstraintSynthesizer<Fr> for Squarings {
+        fn generate_constraints<CS: ConstraintSystem<Fr>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let mut x = cs.alloc(|| "x", || Ok(Fr::from(3u64)))?;
+            for _ in 0..2 {
+                let mut cs = cs.ns(|| "square");
+                let square = cs.alloc(|| "square", || Ok(Fr::from(9u64)))?;
+                cs.enforce(|| "x*x", |lc| lc + x, |lc| lc + x, |lc| lc + square);
+                x = square;
+            }
+            let mut cs = cs.ns(|| "output");
+            let out = cs.alloc_input(|| "out", || Ok(Fr::from(81u64)))?;
+            cs.enforce(|| "x=out", |lc| lc + x, |lc| lc + CS::one(), |lc| lc + out);
+            let mut cs = cs.ns(|| "range");
+            for i in 0..3 {
+                cs.enforce(
+                    || format!("bit {}", i),
+                    |lc| lc + x,
+                    |lc| lc + x,
+                    |lc| lc + x,
+                );
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn attributes_costs_to_namespaces() {
+        let mut profiler = ConstraintProfiler::new();
+        Squarings.generate_constraints(&mut profiler).unwrap();
+        assert_eq!(profiler.num_constraints(), 6);
+
+        let profile = profiler.profile();
+        assert_eq!(profile.num_constraints, 6);
+        assert_eq!(profile.num_aux, 3);
+        assert_eq!(profile.num_inputs, 1);
+        assert_eq!(profile.own_cost(CostMetric::PrivateVariables), 1);
+
+        // The output namespace holds more constraints than the merged
+        // squaring namespaces, so it comes first.
+        let names = profile
+            .children
+            .iter()
+            .map(|child| child.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["output", "square"]);
+        let output = &profile.children[0];
+        assert_eq!(output.num_constraints, 4);
+        assert_eq!(output.num_inputs, 1);
+        assert_eq!(output.own_cost(CostMetric::Constraints), 1);
+        assert_eq!(output.children[0].num_constraints, 3);
+        let square = &profile.children[1];
+        assert_eq!(square.num_constraints, 2);
+        assert_eq!(square.num_aux, 2);
+
+        assert_eq!(
+            profile.folded_stacks(CostMetric::Constraints),
+            "circuit;output 1\ncircuit;output;range 3\ncircuit;square 2\n"
+        );
+        assert_eq!(
+            profile.folded_stacks(CostMetric::PrivateVariables),
+            "circuit 1\ncircuit;square 2\n"
+        );
+        assert!(profile
+            .to_string()
+            .starts_with("circuit: 6 constraints, 3 private variables, 1 public inputs\n"));
+    }
+}
@@ -10,40 +10,43 @@ use algebra::{
 };
 use core::{borrow::Borrow, marker::PhantomData};
 
-use crate::{prelude::*, Vec};
+use crate::{fields::fp::FpGadget, prelude::*, Vec};
 
-type Fp2Gadget<P, ConstraintF> = super::fp2::Fp2Gadget<
+type Fp2Gadget<P, ConstraintF, BF> = super::fp2::Fp2Gadget<
     <<P as Fp12Parameters>::Fp6Params as Fp6Parameters>::Fp2Params,
     ConstraintF,
+    BF,
 >;
-type Fp6Gadget<P, ConstraintF> =
-    super::fp6_3over2::Fp6Gadget<<P as Fp12Parameters>::Fp6Params, ConstraintF>;
-type Fp6GadgetVariable<P, ConstraintF> = <Fp6Gadget<P, ConstraintF> as FieldGadget<
+type Fp6Gadget<P, ConstraintF, BF> =
+    super::fp6_3over2::Fp6Gadget<<P as Fp12Parameters>::Fp6Params, ConstraintF, BF>;
+type Fp6GadgetVariable<P, ConstraintF, BF> = <Fp6Gadget<P, ConstraintF, BF> as FieldGadget<
     Fp6<<P as Fp12Parameters>::Fp6Params>,
     ConstraintF,
 >>::Variable;
 
+/// An element of the quadratic extension `Fp12<P>` of `Fp6`, whose base field
+/// is represented by `BF` as in [`Fp2Gadget`](super::fp2::Fp2Gadget).
 #[derive(Derivative)]
 #[derivative(Debug(bound = "ConstraintF: PrimeField"))]
 #[must_use]
-pub struct Fp12Gadget<P, ConstraintF: PrimeField>
+pub struct Fp12Gadget<P, ConstraintF: PrimeField, BF = FpGadget<ConstraintF>>
 where
     P: Fp12Parameters,
-    <P::Fp6Params as Fp6Parameters>::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<<P::Fp6Params as Fp6Parameters>::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
-    pub c0: Fp6Gadget<P, ConstraintF>,
-    pub c1: Fp6Gadget<P, ConstraintF>,
+    pub c0: Fp6Gadget<P, ConstraintF, BF>,
+    pub c1: Fp6Gadget<P, ConstraintF, BF>,
     #[derivative(Debug = "ignore")]
     _params: PhantomData<P>,
 }
 
-impl<P, ConstraintF: PrimeField> Fp12Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> Fp12Gadget<P, ConstraintF, BF>
 where
     P: Fp12Parameters,
-    <P::Fp6Params as Fp6Parameters>::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<<P::Fp6Params as Fp6Parameters>::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     #[inline]
-    pub fn new(c0: Fp6Gadget<P, ConstraintF>, c1: Fp6Gadget<P, ConstraintF>) -> Self {
+    pub fn new(c0: Fp6Gadget<P, ConstraintF, BF>, c1: Fp6Gadget<P, ConstraintF, BF>) -> Self {
         Self {
             c0,
             c1,
@@ -55,12 +58,12 @@ where
     #[inline]
     pub(crate) fn mul_fp6_by_nonresidue<CS: ConstraintSystem<ConstraintF>>(
         cs: CS,
-        fe: &Fp6Gadget<P, ConstraintF>,
-    ) -> Result<Fp6Gadget<P, ConstraintF>, SynthesisError> {
-        let new_c0 = Fp6Gadget::<P, ConstraintF>::mul_fp2_gadget_by_nonresidue(cs, &fe.c2)?;
+        fe: &Fp6Gadget<P, ConstraintF, BF>,
+    ) -> Result<Fp6Gadget<P, ConstraintF, BF>, SynthesisError> {
+        let new_c0 = Fp6Gadget::<P, ConstraintF, BF>::mul_fp2_gadget_by_nonresidue(cs, &fe.c2)?;
         let new_c1 = fe.c0.clone();
         let new_c2 = fe.c1.clone();
-        Ok(Fp6Gadget::<P, ConstraintF>::new(new_c0, new_c1, new_c2))
+        Ok(Fp6Gadget::<P, ConstraintF, BF>::new(new_c0, new_c1, new_c2))
     }
 
     #[inline]
@@ -77,9 +80,9 @@ where
     pub fn mul_by_014<CS: ConstraintSystem<ConstraintF>>(
         &self,
         mut cs: CS,
-        c0: &Fp2Gadget<P, ConstraintF>,
-        c1: &Fp2Gadget<P, ConstraintF>,
-        d1: &Fp2Gadget<P, ConstraintF>,
+        c0: &Fp2Gadget<P, ConstraintF, BF>,
+        c1: &Fp2Gadget<P, ConstraintF, BF>,
+        d1: &Fp2Gadget<P, ConstraintF, BF>,
     ) -> Result<Self, SynthesisError> {
         let v0 = self.c0.mul_by_c0_c1_0(cs.ns(|| "v0"), &c0, &c1)?;
         let v1 = self.c1.mul_by_0_c1_0(cs.ns(|| "v1"), &d1)?;
@@ -102,14 +105,14 @@ where
     pub fn mul_by_034<CS: ConstraintSystem<ConstraintF>>(
         &self,
         mut cs: CS,
-        c0: &Fp2Gadget<P, ConstraintF>,
-        d0: &Fp2Gadget<P, ConstraintF>,
-        d1: &Fp2Gadget<P, ConstraintF>,
+        c0: &Fp2Gadget<P, ConstraintF, BF>,
+        d0: &Fp2Gadget<P, ConstraintF, BF>,
+        d1: &Fp2Gadget<P, ConstraintF, BF>,
     ) -> Result<Self, SynthesisError> {
         let a0 = self.c0.c0.mul(cs.ns(|| "a0"), &c0)?;
         let a1 = self.c0.c1.mul(cs.ns(|| "a1"), &c0)?;
         let a2 = self.c0.c2.mul(cs.ns(|| "a2"), &c0)?;
-        let a = Fp6Gadget::<P, ConstraintF>::new(a0, a1, a2);
+        let a = Fp6Gadget::<P, ConstraintF, BF>::new(a0, a1, a2);
         let b = self.c1.mul_by_c0_c1_0(cs.ns(|| "b"), &d0, &d1)?;
 
         let c0 = c0.add(cs.ns(|| "c0 + d0"), &d0)?;
@@ -270,14 +273,15 @@ where
     }
 }
 
-impl<P, ConstraintF: PrimeField> FieldGadget<Fp12<P>, ConstraintF> for Fp12Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> FieldGadget<Fp12<P>, ConstraintF>
+    for Fp12Gadget<P, ConstraintF, BF>
 where
     P: Fp12Parameters,
-    <P::Fp6Params as Fp6Parameters>::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<<P::Fp6Params as Fp6Parameters>::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     type Variable = (
-        Fp6GadgetVariable<P, ConstraintF>,
-        Fp6GadgetVariable<P, ConstraintF>,
+        Fp6GadgetVariable<P, ConstraintF, BF>,
+        Fp6GadgetVariable<P, ConstraintF, BF>,
     );
 
     #[inline]
@@ -292,15 +296,15 @@ where
 
     #[inline]
     fn zero<CS: ConstraintSystem<ConstraintF>>(mut cs: CS) -> Result<Self, SynthesisError> {
-        let c0 = Fp6Gadget::<P, ConstraintF>::zero(cs.ns(|| "c0"))?;
-        let c1 = Fp6Gadget::<P, ConstraintF>::zero(cs.ns(|| "c1"))?;
+        let c0 = Fp6Gadget::<P, ConstraintF, BF>::zero(cs.ns(|| "c0"))?;
+        let c1 = Fp6Gadget::<P, ConstraintF, BF>::zero(cs.ns(|| "c1"))?;
         Ok(Self::new(c0, c1))
     }
 
     #[inline]
     fn one<CS: ConstraintSystem<ConstraintF>>(mut cs: CS) -> Result<Self, SynthesisError> {
-        let c0 = Fp6Gadget::<P, ConstraintF>::one(cs.ns(|| "c0"))?;
-        let c1 = Fp6Gadget::<P, ConstraintF>::zero(cs.ns(|| "c1"))?;
+        let c0 = Fp6Gadget::<P, ConstraintF, BF>::one(cs.ns(|| "c0"))?;
+        let c1 = Fp6Gadget::<P, ConstraintF, BF>::zero(cs.ns(|| "c1"))?;
         Ok(Self::new(c0, c1))
     }
 
@@ -598,7 +602,7 @@ where
     }
 
     fn cost_of_mul() -> usize {
-        3 * Fp6Gadget::<P, ConstraintF>::cost_of_mul()
+        3 * Fp6Gadget::<P, ConstraintF, BF>::cost_of_mul()
     }
 
     fn cost_of_mul_equals() -> usize {
@@ -606,34 +610,35 @@ where
     }
 }
 
-impl<P, ConstraintF: PrimeField> PartialEq for Fp12Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> PartialEq for Fp12Gadget<P, ConstraintF, BF>
 where
     P: Fp12Parameters,
-    <P::Fp6Params as Fp6Parameters>::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<<P::Fp6Params as Fp6Parameters>::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     fn eq(&self, other: &Self) -> bool {
         self.c0 == other.c0 && self.c1 == other.c1
     }
 }
 
-impl<P, ConstraintF: PrimeField> Eq for Fp12Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> Eq for Fp12Gadget<P, ConstraintF, BF>
 where
     P: Fp12Parameters,
-    <P::Fp6Params as Fp6Parameters>::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<<P::Fp6Params as Fp6Parameters>::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
 }
 
-impl<P, ConstraintF: PrimeField> EqGadget<ConstraintF> for Fp12Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> EqGadget<ConstraintF> for Fp12Gadget<P, ConstraintF, BF>
 where
     P: Fp12Parameters,
-    <P::Fp6Params as Fp6Parameters>::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<<P::Fp6Params as Fp6Parameters>::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
 }
 
-impl<P, ConstraintF: PrimeField> ConditionalEqGadget<ConstraintF> for Fp12Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> ConditionalEqGadget<ConstraintF>
+    for Fp12Gadget<P, ConstraintF, BF>
 where
     P: Fp12Parameters,
-    <P::Fp6Params as Fp6Parameters>::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<<P::Fp6Params as Fp6Parameters>::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     #[inline]
     fn conditional_enforce_equal<CS: ConstraintSystem<ConstraintF>>(
@@ -650,14 +655,14 @@ where
     }
 
     fn cost() -> usize {
-        2 * <Fp6Gadget<P, ConstraintF> as ConditionalEqGadget<ConstraintF>>::cost()
+        2 * <Fp6Gadget<P, ConstraintF, BF> as ConditionalEqGadget<ConstraintF>>::cost()
     }
 }
 
-impl<P, ConstraintF: PrimeField> NEqGadget<ConstraintF> for Fp12Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> NEqGadget<ConstraintF> for Fp12Gadget<P, ConstraintF, BF>
 where
     P: Fp12Parameters,
-    <P::Fp6Params as Fp6Parameters>::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<<P::Fp6Params as Fp6Parameters>::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     #[inline]
     fn enforce_not_equal<CS: ConstraintSystem<ConstraintF>>(
@@ -671,14 +676,14 @@ where
     }
 
     fn cost() -> usize {
-        2 * <Fp6Gadget<P, ConstraintF> as NEqGadget<ConstraintF>>::cost()
+        2 * <Fp6Gadget<P, ConstraintF, BF> as NEqGadget<ConstraintF>>::cost()
     }
 }
 
-impl<P, ConstraintF: PrimeField> ToBitsGadget<ConstraintF> for Fp12Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> ToBitsGadget<ConstraintF> for Fp12Gadget<P, ConstraintF, BF>
 where
     P: Fp12Parameters,
-    <P::Fp6Params as Fp6Parameters>::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<<P::Fp6Params as Fp6Parameters>::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     fn to_bits<CS: ConstraintSystem<ConstraintF>>(
         &self,
@@ -701,10 +706,10 @@ where
     }
 }
 
-impl<P, ConstraintF: PrimeField> ToBytesGadget<ConstraintF> for Fp12Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> ToBytesGadget<ConstraintF> for Fp12Gadget<P, ConstraintF, BF>
 where
     P: Fp12Parameters,
-    <P::Fp6Params as Fp6Parameters>::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<<P::Fp6Params as Fp6Parameters>::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     fn to_bytes<CS: ConstraintSystem<ConstraintF>>(
         &self,
@@ -727,20 +732,21 @@ where
     }
 }
 
-impl<P, ConstraintF: PrimeField> Clone for Fp12Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> Clone for Fp12Gadget<P, ConstraintF, BF>
 where
     P: Fp12Parameters,
-    <P::Fp6Params as Fp6Parameters>::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<<P::Fp6Params as Fp6Parameters>::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     fn clone(&self) -> Self {
         Self::new(self.c0.clone(), self.c1.clone())
     }
 }
 
-impl<P, ConstraintF: PrimeField> CondSelectGadget<ConstraintF> for Fp12Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> CondSelectGadget<ConstraintF>
+    for Fp12Gadget<P, ConstraintF, BF>
 where
     P: Fp12Parameters,
-    <P::Fp6Params as Fp6Parameters>::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<<P::Fp6Params as Fp6Parameters>::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     #[inline]
     fn conditionally_select<CS: ConstraintSystem<ConstraintF>>(
@@ -749,13 +755,13 @@ where
         true_value: &Self,
         false_value: &Self,
     ) -> Result<Self, SynthesisError> {
-        let c0 = Fp6Gadget::<P, ConstraintF>::conditionally_select(
+        let c0 = Fp6Gadget::<P, ConstraintF, BF>::conditionally_select(
             &mut cs.ns(|| "c0"),
             cond,
             &true_value.c0,
             &false_value.c0,
         )?;
-        let c1 = Fp6Gadget::<P, ConstraintF>::conditionally_select(
+        let c1 = Fp6Gadget::<P, ConstraintF, BF>::conditionally_select(
             &mut cs.ns(|| "c1"),
             cond,
             &true_value.c1,
@@ -766,14 +772,15 @@ where
     }
 
     fn cost() -> usize {
-        2 * <Fp6Gadget<P, ConstraintF> as CondSelectGadget<ConstraintF>>::cost()
+        2 * <Fp6Gadget<P, ConstraintF, BF> as CondSelectGadget<ConstraintF>>::cost()
     }
 }
 
-impl<P, ConstraintF: PrimeField> TwoBitLookupGadget<ConstraintF> for Fp12Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> TwoBitLookupGadget<ConstraintF>
+    for Fp12Gadget<P, ConstraintF, BF>
 where
     P: Fp12Parameters,
-    <P::Fp6Params as Fp6Parameters>::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<<P::Fp6Params as Fp6Parameters>::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     type TableConstant = Fp12<P>;
     fn two_bit_lookup<CS: ConstraintSystem<ConstraintF>>(
@@ -783,21 +790,21 @@ where
     ) -> Result<Self, SynthesisError> {
         let c0s = c.iter().map(|f| f.c0).collect::<Vec<_>>();
         let c1s = c.iter().map(|f| f.c1).collect::<Vec<_>>();
-        let c0 = Fp6Gadget::<P, ConstraintF>::two_bit_lookup(cs.ns(|| "Lookup c0"), b, &c0s)?;
-        let c1 = Fp6Gadget::<P, ConstraintF>::two_bit_lookup(cs.ns(|| "Lookup c1"), b, &c1s)?;
+        let c0 = Fp6Gadget::<P, ConstraintF, BF>::two_bit_lookup(cs.ns(|| "Lookup c0"), b, &c0s)?;
+        let c1 = Fp6Gadget::<P, ConstraintF, BF>::two_bit_lookup(cs.ns(|| "Lookup c1"), b, &c1s)?;
         Ok(Self::new(c0, c1))
     }
 
     fn cost() -> usize {
-        2 * <Fp6Gadget<P, ConstraintF> as TwoBitLookupGadget<ConstraintF>>::cost()
+        2 * <Fp6Gadget<P, ConstraintF, BF> as TwoBitLookupGadget<ConstraintF>>::cost()
     }
 }
 
-impl<P, ConstraintF: PrimeField> ThreeBitCondNegLookupGadget<ConstraintF>
-    for Fp12Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> ThreeBitCondNegLookupGadget<ConstraintF>
+    for Fp12Gadget<P, ConstraintF, BF>
 where
     P: Fp12Parameters,
-    <P::Fp6Params as Fp6Parameters>::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<<P::Fp6Params as Fp6Parameters>::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     type TableConstant = Fp12<P>;
 
@@ -809,13 +816,13 @@ where
     ) -> Result<Self, SynthesisError> {
         let c0s = c.iter().map(|f| f.c0).collect::<Vec<_>>();
         let c1s = c.iter().map(|f| f.c1).collect::<Vec<_>>();
-        let c0 = Fp6Gadget::<P, ConstraintF>::three_bit_cond_neg_lookup(
+        let c0 = Fp6Gadget::<P, ConstraintF, BF>::three_bit_cond_neg_lookup(
             cs.ns(|| "Lookup c0"),
             b,
             b0b1,
             &c0s,
         )?;
-        let c1 = Fp6Gadget::<P, ConstraintF>::three_bit_cond_neg_lookup(
+        let c1 = Fp6Gadget::<P, ConstraintF, BF>::three_bit_cond_neg_lookup(
             cs.ns(|| "Lookup c1"),
             b,
             b0b1,
@@ -825,14 +832,15 @@ where
     }
 
     fn cost() -> usize {
-        2 * <Fp6Gadget<P, ConstraintF> as ThreeBitCondNegLookupGadget<ConstraintF>>::cost()
+        2 * <Fp6Gadget<P, ConstraintF, BF> as ThreeBitCondNegLookupGadget<ConstraintF>>::cost()
     }
 }
 
-impl<P, ConstraintF: PrimeField> AllocGadget<Fp12<P>, ConstraintF> for Fp12Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> AllocGadget<Fp12<P>, ConstraintF>
+    for Fp12Gadget<P, ConstraintF, BF>
 where
     P: Fp12Parameters,
-    <P::Fp6Params as Fp6Parameters>::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<<P::Fp6Params as Fp6Parameters>::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     #[inline]
     fn alloc_constant<T, CS: ConstraintSystem<ConstraintF>>(
@@ -865,8 +873,8 @@ where
             ),
         };
 
-        let c0 = Fp6Gadget::<P, ConstraintF>::alloc(&mut cs.ns(|| "c0"), || c0)?;
-        let c1 = Fp6Gadget::<P, ConstraintF>::alloc(&mut cs.ns(|| "c1"), || c1)?;
+        let c0 = Fp6Gadget::<P, ConstraintF, BF>::alloc(&mut cs.ns(|| "c0"), || c0)?;
+        let c1 = Fp6Gadget::<P, ConstraintF, BF>::alloc(&mut cs.ns(|| "c1"), || c1)?;
         Ok(Self::new(c0, c1))
     }
 
@@ -890,8 +898,8 @@ where
             ),
         };
 
-        let c0 = Fp6Gadget::<P, ConstraintF>::alloc_input(&mut cs.ns(|| "c0"), || c0)?;
-        let c1 = Fp6Gadget::<P, ConstraintF>::alloc_input(&mut cs.ns(|| "c1"), || c1)?;
+        let c0 = Fp6Gadget::<P, ConstraintF, BF>::alloc_input(&mut cs.ns(|| "c0"), || c0)?;
+        let c1 = Fp6Gadget::<P, ConstraintF, BF>::alloc_input(&mut cs.ns(|| "c1"), || c1)?;
         Ok(Self::new(c0, c1))
     }
 }
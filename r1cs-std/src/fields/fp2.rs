@@ -3,22 +3,32 @@ use algebra::{
     PrimeField,
 };
 use core::{borrow::Borrow, marker::PhantomData};
-use r1cs_core::{ConstraintSystem, ConstraintVar, SynthesisError};
+use r1cs_core::{ConstraintSystem, SynthesisError};
 
 use crate::{fields::fp::FpGadget, prelude::*, Vec};
 
+/// An element of the quadratic extension `Fp2<P>`, whose coefficients are
+/// elements of the base field represented by `BF`. The base field is the
+/// constraint field by default, and can be emulated in another one with
+/// [`NonNativeFieldGadget`](super::nonnative::NonNativeFieldGadget).
 #[derive(Derivative)]
 #[derivative(Debug(bound = "P: Fp2Parameters, ConstraintF: PrimeField"))]
 #[must_use]
-pub struct Fp2Gadget<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> {
-    pub c0: FpGadget<ConstraintF>,
-    pub c1: FpGadget<ConstraintF>,
+pub struct Fp2Gadget<
+    P: Fp2Parameters,
+    ConstraintF: PrimeField,
+    BF: FieldGadget<P::Fp, ConstraintF> = FpGadget<ConstraintF>,
+> {
+    pub c0: BF,
+    pub c1: BF,
     #[derivative(Debug = "ignore")]
-    _params: PhantomData<P>,
+    _params: PhantomData<(P, ConstraintF)>,
 }
 
-impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> Fp2Gadget<P, ConstraintF> {
-    pub fn new(c0: FpGadget<ConstraintF>, c1: FpGadget<ConstraintF>) -> Self {
+impl<P: Fp2Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>>
+    Fp2Gadget<P, ConstraintF, BF>
+{
+    pub fn new(c0: BF, c1: BF) -> Self {
         Self {
             c0,
             c1,
@@ -26,12 +36,12 @@ impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> Fp2Gadget<P, C
         }
     }
 
-    /// Multiply a FpGadget by quadratic nonresidue P::NONRESIDUE.
+    /// Multiply a base field gadget by quadratic nonresidue P::NONRESIDUE.
     #[inline]
     pub fn mul_fp_gadget_by_nonresidue<CS: ConstraintSystem<ConstraintF>>(
         cs: CS,
-        fe: &FpGadget<ConstraintF>,
-    ) -> Result<FpGadget<ConstraintF>, SynthesisError> {
+        fe: &BF,
+    ) -> Result<BF, SynthesisError> {
         fe.mul_by_constant(cs, &P::NONRESIDUE)
     }
 
@@ -60,14 +70,14 @@ impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> Fp2Gadget<P, C
     }
 }
 
-impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> FieldGadget<Fp2<P>, ConstraintF>
-    for Fp2Gadget<P, ConstraintF>
+impl<P: Fp2Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>>
+    FieldGadget<Fp2<P>, ConstraintF> for Fp2Gadget<P, ConstraintF, BF>
 {
-    type Variable = (ConstraintVar<ConstraintF>, ConstraintVar<ConstraintF>);
+    type Variable = (BF::Variable, BF::Variable);
 
     #[inline]
     fn get_value(&self) -> Option<Fp2<P>> {
-        match (self.c0.value, self.c1.value) {
+        match (self.c0.get_value(), self.c1.get_value()) {
             (Some(c0), Some(c1)) => Some(Fp2::new(c0, c1)),
             (..) => None,
         }
@@ -80,15 +90,15 @@ impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> FieldGadget<Fp
 
     #[inline]
     fn zero<CS: ConstraintSystem<ConstraintF>>(mut cs: CS) -> Result<Self, SynthesisError> {
-        let c0 = FpGadget::zero(cs.ns(|| "c0"))?;
-        let c1 = FpGadget::zero(cs.ns(|| "c1"))?;
+        let c0 = BF::zero(cs.ns(|| "c0"))?;
+        let c1 = BF::zero(cs.ns(|| "c1"))?;
         Ok(Self::new(c0, c1))
     }
 
     #[inline]
     fn one<CS: ConstraintSystem<ConstraintF>>(mut cs: CS) -> Result<Self, SynthesisError> {
-        let c0 = FpGadget::one(cs.ns(|| "c0"))?;
-        let c1 = FpGadget::zero(cs.ns(|| "c1"))?;
+        let c0 = BF::one(cs.ns(|| "c0"))?;
+        let c1 = BF::zero(cs.ns(|| "c1"))?;
         Ok(Self::new(c0, c1))
     }
 
@@ -409,7 +419,7 @@ impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> FieldGadget<Fp
     }
 
     fn cost_of_mul() -> usize {
-        3 * FpGadget::<ConstraintF>::cost_of_mul()
+        3 * BF::cost_of_mul()
     }
 
     fn cost_of_mul_equals() -> usize {
@@ -417,23 +427,26 @@ impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> FieldGadget<Fp
     }
 }
 
-impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> PartialEq
-    for Fp2Gadget<P, ConstraintF>
+impl<P: Fp2Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>> PartialEq
+    for Fp2Gadget<P, ConstraintF, BF>
 {
     fn eq(&self, other: &Self) -> bool {
         self.c0 == other.c0 && self.c1 == other.c1
     }
 }
 
-impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> Eq for Fp2Gadget<P, ConstraintF> {}
+impl<P: Fp2Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>> Eq
+    for Fp2Gadget<P, ConstraintF, BF>
+{
+}
 
-impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> EqGadget<ConstraintF>
-    for Fp2Gadget<P, ConstraintF>
+impl<P: Fp2Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>>
+    EqGadget<ConstraintF> for Fp2Gadget<P, ConstraintF, BF>
 {
 }
 
-impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> ConditionalEqGadget<ConstraintF>
-    for Fp2Gadget<P, ConstraintF>
+impl<P: Fp2Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>>
+    ConditionalEqGadget<ConstraintF> for Fp2Gadget<P, ConstraintF, BF>
 {
     #[inline]
     fn conditional_enforce_equal<CS: ConstraintSystem<ConstraintF>>(
@@ -450,12 +463,12 @@ impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> ConditionalEqG
     }
 
     fn cost() -> usize {
-        2
+        2 * <BF as ConditionalEqGadget<ConstraintF>>::cost()
     }
 }
 
-impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> NEqGadget<ConstraintF>
-    for Fp2Gadget<P, ConstraintF>
+impl<P: Fp2Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>>
+    NEqGadget<ConstraintF> for Fp2Gadget<P, ConstraintF, BF>
 {
     #[inline]
     fn enforce_not_equal<CS: ConstraintSystem<ConstraintF>>(
@@ -469,12 +482,12 @@ impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> NEqGadget<Cons
     }
 
     fn cost() -> usize {
-        2
+        2 * <BF as NEqGadget<ConstraintF>>::cost()
     }
 }
 
-impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> ToBitsGadget<ConstraintF>
-    for Fp2Gadget<P, ConstraintF>
+impl<P: Fp2Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>>
+    ToBitsGadget<ConstraintF> for Fp2Gadget<P, ConstraintF, BF>
 {
     fn to_bits<CS: ConstraintSystem<ConstraintF>>(
         &self,
@@ -497,8 +510,8 @@ impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> ToBitsGadget<C
     }
 }
 
-impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> ToBytesGadget<ConstraintF>
-    for Fp2Gadget<P, ConstraintF>
+impl<P: Fp2Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>>
+    ToBytesGadget<ConstraintF> for Fp2Gadget<P, ConstraintF, BF>
 {
     fn to_bytes<CS: ConstraintSystem<ConstraintF>>(
         &self,
@@ -521,8 +534,8 @@ impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> ToBytesGadget<
     }
 }
 
-impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> Clone
-    for Fp2Gadget<P, ConstraintF>
+impl<P: Fp2Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>> Clone
+    for Fp2Gadget<P, ConstraintF, BF>
 {
     fn clone(&self) -> Self {
         Self {
@@ -533,8 +546,8 @@ impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> Clone
     }
 }
 
-impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> CondSelectGadget<ConstraintF>
-    for Fp2Gadget<P, ConstraintF>
+impl<P: Fp2Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>>
+    CondSelectGadget<ConstraintF> for Fp2Gadget<P, ConstraintF, BF>
 {
     #[inline]
     fn conditionally_select<CS: ConstraintSystem<ConstraintF>>(
@@ -543,29 +556,21 @@ impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> CondSelectGadg
         true_value: &Self,
         false_value: &Self,
     ) -> Result<Self, SynthesisError> {
-        let c0 = FpGadget::<ConstraintF>::conditionally_select(
-            &mut cs.ns(|| "c0"),
-            cond,
-            &true_value.c0,
-            &false_value.c0,
-        )?;
-        let c1 = FpGadget::<ConstraintF>::conditionally_select(
-            &mut cs.ns(|| "c1"),
-            cond,
-            &true_value.c1,
-            &false_value.c1,
-        )?;
+        let c0 =
+            BF::conditionally_select(&mut cs.ns(|| "c0"), cond, &true_value.c0, &false_value.c0)?;
+        let c1 =
+            BF::conditionally_select(&mut cs.ns(|| "c1"), cond, &true_value.c1, &false_value.c1)?;
 
         Ok(Self::new(c0, c1))
     }
 
     fn cost() -> usize {
-        2
+        2 * <BF as CondSelectGadget<ConstraintF>>::cost()
     }
 }
 
-impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> TwoBitLookupGadget<ConstraintF>
-    for Fp2Gadget<P, ConstraintF>
+impl<P: Fp2Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>>
+    TwoBitLookupGadget<ConstraintF> for Fp2Gadget<P, ConstraintF, BF>
 {
     type TableConstant = Fp2<P>;
     fn two_bit_lookup<CS: ConstraintSystem<ConstraintF>>(
@@ -575,18 +580,18 @@ impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> TwoBitLookupGa
     ) -> Result<Self, SynthesisError> {
         let c0s = c.iter().map(|f| f.c0).collect::<Vec<_>>();
         let c1s = c.iter().map(|f| f.c1).collect::<Vec<_>>();
-        let c0 = FpGadget::two_bit_lookup(cs.ns(|| "Lookup c0"), b, &c0s)?;
-        let c1 = FpGadget::two_bit_lookup(cs.ns(|| "Lookup c1"), b, &c1s)?;
+        let c0 = BF::two_bit_lookup(cs.ns(|| "Lookup c0"), b, &c0s)?;
+        let c1 = BF::two_bit_lookup(cs.ns(|| "Lookup c1"), b, &c1s)?;
         Ok(Self::new(c0, c1))
     }
 
     fn cost() -> usize {
-        2 * <FpGadget<ConstraintF> as TwoBitLookupGadget<ConstraintF>>::cost()
+        2 * <BF as TwoBitLookupGadget<ConstraintF>>::cost()
     }
 }
 
-impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField>
-    ThreeBitCondNegLookupGadget<ConstraintF> for Fp2Gadget<P, ConstraintF>
+impl<P: Fp2Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>>
+    ThreeBitCondNegLookupGadget<ConstraintF> for Fp2Gadget<P, ConstraintF, BF>
 {
     type TableConstant = Fp2<P>;
 
@@ -598,18 +603,18 @@ impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField>
     ) -> Result<Self, SynthesisError> {
         let c0s = c.iter().map(|f| f.c0).collect::<Vec<_>>();
         let c1s = c.iter().map(|f| f.c1).collect::<Vec<_>>();
-        let c0 = FpGadget::three_bit_cond_neg_lookup(cs.ns(|| "Lookup c0"), b, b0b1, &c0s)?;
-        let c1 = FpGadget::three_bit_cond_neg_lookup(cs.ns(|| "Lookup c1"), b, b0b1, &c1s)?;
+        let c0 = BF::three_bit_cond_neg_lookup(cs.ns(|| "Lookup c0"), b, b0b1, &c0s)?;
+        let c1 = BF::three_bit_cond_neg_lookup(cs.ns(|| "Lookup c1"), b, b0b1, &c1s)?;
         Ok(Self::new(c0, c1))
     }
 
     fn cost() -> usize {
-        2 * <FpGadget<ConstraintF> as ThreeBitCondNegLookupGadget<ConstraintF>>::cost()
+        2 * <BF as ThreeBitCondNegLookupGadget<ConstraintF>>::cost()
     }
 }
 
-impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> AllocGadget<Fp2<P>, ConstraintF>
-    for Fp2Gadget<P, ConstraintF>
+impl<P: Fp2Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>>
+    AllocGadget<Fp2<P>, ConstraintF> for Fp2Gadget<P, ConstraintF, BF>
 {
     #[inline]
     fn alloc_constant<T, CS: ConstraintSystem<ConstraintF>>(
@@ -642,8 +647,8 @@ impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> AllocGadget<Fp
             ),
         };
 
-        let c0 = FpGadget::alloc(&mut cs.ns(|| "c0"), || c0)?;
-        let c1 = FpGadget::alloc(&mut cs.ns(|| "c1"), || c1)?;
+        let c0 = BF::alloc(&mut cs.ns(|| "c0"), || c0)?;
+        let c1 = BF::alloc(&mut cs.ns(|| "c1"), || c1)?;
         Ok(Self::new(c0, c1))
     }
 
@@ -667,8 +672,8 @@ impl<P: Fp2Parameters<Fp = ConstraintF>, ConstraintF: PrimeField> AllocGadget<Fp
             ),
         };
 
-        let c0 = FpGadget::alloc_input(&mut cs.ns(|| "c0"), || c0)?;
-        let c1 = FpGadget::alloc_input(&mut cs.ns(|| "c1"), || c1)?;
+        let c0 = BF::alloc_input(&mut cs.ns(|| "c0"), || c0)?;
+        let c1 = BF::alloc_input(&mut cs.ns(|| "c1"), || c1)?;
         Ok(Self::new(c0, c1))
     }
 }
@@ -6,38 +6,40 @@ use algebra::{
     PrimeField,
 };
 use core::{borrow::Borrow, marker::PhantomData};
-use r1cs_core::{ConstraintSystem, ConstraintVar, SynthesisError};
+use r1cs_core::{ConstraintSystem, SynthesisError};
 
-use crate::{prelude::*, Vec};
+use crate::{fields::fp::FpGadget, prelude::*, Vec};
 
-type Fp2Gadget<P, ConstraintF> =
-    super::fp2::Fp2Gadget<<P as Fp6Parameters>::Fp2Params, ConstraintF>;
+type Fp2Gadget<P, ConstraintF, BF> =
+    super::fp2::Fp2Gadget<<P as Fp6Parameters>::Fp2Params, ConstraintF, BF>;
 
+/// An element of the cubic extension `Fp6<P>` of `Fp2`, whose base field is
+/// represented by `BF` as in [`Fp2Gadget`](super::fp2::Fp2Gadget).
 #[derive(Derivative)]
 #[derivative(Debug(bound = "ConstraintF: PrimeField"))]
 #[must_use]
-pub struct Fp6Gadget<P, ConstraintF: PrimeField>
+pub struct Fp6Gadget<P, ConstraintF: PrimeField, BF = FpGadget<ConstraintF>>
 where
     P: Fp6Parameters,
-    P::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<P::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
-    pub c0: Fp2Gadget<P, ConstraintF>,
-    pub c1: Fp2Gadget<P, ConstraintF>,
-    pub c2: Fp2Gadget<P, ConstraintF>,
+    pub c0: Fp2Gadget<P, ConstraintF, BF>,
+    pub c1: Fp2Gadget<P, ConstraintF, BF>,
+    pub c2: Fp2Gadget<P, ConstraintF, BF>,
     #[derivative(Debug = "ignore")]
     _params: PhantomData<P>,
 }
 
-impl<P, ConstraintF: PrimeField> Fp6Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> Fp6Gadget<P, ConstraintF, BF>
 where
     P: Fp6Parameters,
-    P::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<P::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     #[inline]
     pub fn new(
-        c0: Fp2Gadget<P, ConstraintF>,
-        c1: Fp2Gadget<P, ConstraintF>,
-        c2: Fp2Gadget<P, ConstraintF>,
+        c0: Fp2Gadget<P, ConstraintF, BF>,
+        c1: Fp2Gadget<P, ConstraintF, BF>,
+        c2: Fp2Gadget<P, ConstraintF, BF>,
     ) -> Self {
         Self {
             c0,
@@ -50,8 +52,8 @@ where
     #[inline]
     pub fn mul_fp2_gadget_by_nonresidue<CS: ConstraintSystem<ConstraintF>>(
         cs: CS,
-        fe: &Fp2Gadget<P, ConstraintF>,
-    ) -> Result<Fp2Gadget<P, ConstraintF>, SynthesisError> {
+        fe: &Fp2Gadget<P, ConstraintF, BF>,
+    ) -> Result<Fp2Gadget<P, ConstraintF, BF>, SynthesisError> {
         fe.mul_by_constant(cs, &P::NONRESIDUE)
     }
 
@@ -59,7 +61,7 @@ where
     pub fn mul_by_0_c1_0<CS: ConstraintSystem<ConstraintF>>(
         &self,
         mut cs: CS,
-        c1: &Fp2Gadget<P, ConstraintF>,
+        c1: &Fp2Gadget<P, ConstraintF, BF>,
     ) -> Result<Self, SynthesisError> {
         // Karatsuba multiplication
         // v0 = a0 * b0 = 0
@@ -96,8 +98,8 @@ where
     pub fn mul_by_c0_c1_0<CS: ConstraintSystem<ConstraintF>>(
         &self,
         mut cs: CS,
-        c0: &Fp2Gadget<P, ConstraintF>,
-        c1: &Fp2Gadget<P, ConstraintF>,
+        c0: &Fp2Gadget<P, ConstraintF, BF>,
+        c1: &Fp2Gadget<P, ConstraintF, BF>,
     ) -> Result<Self, SynthesisError> {
         let v0 = self.c0.mul(cs.ns(|| "v0"), c0)?;
         let v1 = self.c1.mul(cs.ns(|| "v1"), c1)?;
@@ -139,15 +141,16 @@ where
     }
 }
 
-impl<P, ConstraintF: PrimeField> FieldGadget<Fp6<P>, ConstraintF> for Fp6Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> FieldGadget<Fp6<P>, ConstraintF>
+    for Fp6Gadget<P, ConstraintF, BF>
 where
     P: Fp6Parameters,
-    P::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<P::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     type Variable = (
-        (ConstraintVar<ConstraintF>, ConstraintVar<ConstraintF>),
-        (ConstraintVar<ConstraintF>, ConstraintVar<ConstraintF>),
-        (ConstraintVar<ConstraintF>, ConstraintVar<ConstraintF>),
+        (BF::Variable, BF::Variable),
+        (BF::Variable, BF::Variable),
+        (BF::Variable, BF::Variable),
     );
 
     #[inline]
@@ -173,17 +176,17 @@ where
 
     #[inline]
     fn zero<CS: ConstraintSystem<ConstraintF>>(mut cs: CS) -> Result<Self, SynthesisError> {
-        let c0 = Fp2Gadget::<P, ConstraintF>::zero(cs.ns(|| "c0"))?;
-        let c1 = Fp2Gadget::<P, ConstraintF>::zero(cs.ns(|| "c1"))?;
-        let c2 = Fp2Gadget::<P, ConstraintF>::zero(cs.ns(|| "c2"))?;
+        let c0 = Fp2Gadget::<P, ConstraintF, BF>::zero(cs.ns(|| "c0"))?;
+        let c1 = Fp2Gadget::<P, ConstraintF, BF>::zero(cs.ns(|| "c1"))?;
+        let c2 = Fp2Gadget::<P, ConstraintF, BF>::zero(cs.ns(|| "c2"))?;
         Ok(Self::new(c0, c1, c2))
     }
 
     #[inline]
     fn one<CS: ConstraintSystem<ConstraintF>>(mut cs: CS) -> Result<Self, SynthesisError> {
-        let c0 = Fp2Gadget::<P, ConstraintF>::one(cs.ns(|| "c0"))?;
-        let c1 = Fp2Gadget::<P, ConstraintF>::zero(cs.ns(|| "c1"))?;
-        let c2 = Fp2Gadget::<P, ConstraintF>::zero(cs.ns(|| "c2"))?;
+        let c0 = Fp2Gadget::<P, ConstraintF, BF>::one(cs.ns(|| "c0"))?;
+        let c1 = Fp2Gadget::<P, ConstraintF, BF>::zero(cs.ns(|| "c1"))?;
+        let c2 = Fp2Gadget::<P, ConstraintF, BF>::zero(cs.ns(|| "c2"))?;
         Ok(Self::new(c0, c1, c2))
     }
 
@@ -694,42 +697,43 @@ where
     }
 
     fn cost_of_mul() -> usize {
-        5 * Fp2Gadget::<P, ConstraintF>::cost_of_mul()
+        5 * Fp2Gadget::<P, ConstraintF, BF>::cost_of_mul()
     }
 
     fn cost_of_mul_equals() -> usize {
-        6 * Fp2Gadget::<P, ConstraintF>::cost_of_mul()
+        6 * Fp2Gadget::<P, ConstraintF, BF>::cost_of_mul()
     }
 }
 
-impl<P, ConstraintF: PrimeField> PartialEq for Fp6Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> PartialEq for Fp6Gadget<P, ConstraintF, BF>
 where
     P: Fp6Parameters,
-    P::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<P::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     fn eq(&self, other: &Self) -> bool {
         self.c0 == other.c0 && self.c1 == other.c1 && self.c2 == other.c2
     }
 }
 
-impl<P, ConstraintF: PrimeField> Eq for Fp6Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> Eq for Fp6Gadget<P, ConstraintF, BF>
 where
     P: Fp6Parameters,
-    P::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<P::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
 }
 
-impl<P, ConstraintF: PrimeField> EqGadget<ConstraintF> for Fp6Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> EqGadget<ConstraintF> for Fp6Gadget<P, ConstraintF, BF>
 where
     P: Fp6Parameters,
-    P::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<P::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
 }
 
-impl<P, ConstraintF: PrimeField> ConditionalEqGadget<ConstraintF> for Fp6Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> ConditionalEqGadget<ConstraintF>
+    for Fp6Gadget<P, ConstraintF, BF>
 where
     P: Fp6Parameters,
-    P::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<P::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     #[inline]
     fn conditional_enforce_equal<CS: ConstraintSystem<ConstraintF>>(
@@ -748,14 +752,14 @@ where
     }
 
     fn cost() -> usize {
-        3 * <Fp2Gadget<P, ConstraintF> as ConditionalEqGadget<ConstraintF>>::cost()
+        3 * <Fp2Gadget<P, ConstraintF, BF> as ConditionalEqGadget<ConstraintF>>::cost()
     }
 }
 
-impl<P, ConstraintF: PrimeField> NEqGadget<ConstraintF> for Fp6Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> NEqGadget<ConstraintF> for Fp6Gadget<P, ConstraintF, BF>
 where
     P: Fp6Parameters,
-    P::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<P::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     #[inline]
     fn enforce_not_equal<CS: ConstraintSystem<ConstraintF>>(
@@ -770,14 +774,14 @@ where
     }
 
     fn cost() -> usize {
-        3 * <Fp2Gadget<P, ConstraintF> as NEqGadget<ConstraintF>>::cost()
+        3 * <Fp2Gadget<P, ConstraintF, BF> as NEqGadget<ConstraintF>>::cost()
     }
 }
 
-impl<P, ConstraintF: PrimeField> ToBitsGadget<ConstraintF> for Fp6Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> ToBitsGadget<ConstraintF> for Fp6Gadget<P, ConstraintF, BF>
 where
     P: Fp6Parameters,
-    P::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<P::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     fn to_bits<CS: ConstraintSystem<ConstraintF>>(
         &self,
@@ -808,10 +812,10 @@ where
     }
 }
 
-impl<P, ConstraintF: PrimeField> ToBytesGadget<ConstraintF> for Fp6Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> ToBytesGadget<ConstraintF> for Fp6Gadget<P, ConstraintF, BF>
 where
     P: Fp6Parameters,
-    P::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<P::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     fn to_bytes<CS: ConstraintSystem<ConstraintF>>(
         &self,
@@ -842,20 +846,20 @@ where
     }
 }
 
-impl<P, ConstraintF: PrimeField> Clone for Fp6Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> Clone for Fp6Gadget<P, ConstraintF, BF>
 where
     P: Fp6Parameters,
-    P::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<P::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     fn clone(&self) -> Self {
         Self::new(self.c0.clone(), self.c1.clone(), self.c2.clone())
     }
 }
 
-impl<P, ConstraintF: PrimeField> CondSelectGadget<ConstraintF> for Fp6Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> CondSelectGadget<ConstraintF> for Fp6Gadget<P, ConstraintF, BF>
 where
     P: Fp6Parameters,
-    P::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<P::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     #[inline]
     fn conditionally_select<CS: ConstraintSystem<ConstraintF>>(
@@ -864,19 +868,19 @@ where
         true_value: &Self,
         false_value: &Self,
     ) -> Result<Self, SynthesisError> {
-        let c0 = Fp2Gadget::<P, ConstraintF>::conditionally_select(
+        let c0 = Fp2Gadget::<P, ConstraintF, BF>::conditionally_select(
             &mut cs.ns(|| "c0"),
             cond,
             &true_value.c0,
             &false_value.c0,
         )?;
-        let c1 = Fp2Gadget::<P, ConstraintF>::conditionally_select(
+        let c1 = Fp2Gadget::<P, ConstraintF, BF>::conditionally_select(
             &mut cs.ns(|| "c1"),
             cond,
             &true_value.c1,
             &false_value.c1,
         )?;
-        let c2 = Fp2Gadget::<P, ConstraintF>::conditionally_select(
+        let c2 = Fp2Gadget::<P, ConstraintF, BF>::conditionally_select(
             &mut cs.ns(|| "c2"),
             cond,
             &true_value.c2,
@@ -887,14 +891,15 @@ where
     }
 
     fn cost() -> usize {
-        3 * <Fp2Gadget<P, ConstraintF> as CondSelectGadget<ConstraintF>>::cost()
+        3 * <Fp2Gadget<P, ConstraintF, BF> as CondSelectGadget<ConstraintF>>::cost()
     }
 }
 
-impl<P, ConstraintF: PrimeField> TwoBitLookupGadget<ConstraintF> for Fp6Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> TwoBitLookupGadget<ConstraintF>
+    for Fp6Gadget<P, ConstraintF, BF>
 where
     P: Fp6Parameters,
-    P::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<P::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     type TableConstant = Fp6<P>;
     fn two_bit_lookup<CS: ConstraintSystem<ConstraintF>>(
@@ -905,22 +910,22 @@ where
         let c0s = c.iter().map(|f| f.c0).collect::<Vec<_>>();
         let c1s = c.iter().map(|f| f.c1).collect::<Vec<_>>();
         let c2s = c.iter().map(|f| f.c2).collect::<Vec<_>>();
-        let c0 = Fp2Gadget::<P, ConstraintF>::two_bit_lookup(cs.ns(|| "Lookup c0"), b, &c0s)?;
-        let c1 = Fp2Gadget::<P, ConstraintF>::two_bit_lookup(cs.ns(|| "Lookup c1"), b, &c1s)?;
-        let c2 = Fp2Gadget::<P, ConstraintF>::two_bit_lookup(cs.ns(|| "Lookup c2"), b, &c2s)?;
+        let c0 = Fp2Gadget::<P, ConstraintF, BF>::two_bit_lookup(cs.ns(|| "Lookup c0"), b, &c0s)?;
+        let c1 = Fp2Gadget::<P, ConstraintF, BF>::two_bit_lookup(cs.ns(|| "Lookup c1"), b, &c1s)?;
+        let c2 = Fp2Gadget::<P, ConstraintF, BF>::two_bit_lookup(cs.ns(|| "Lookup c2"), b, &c2s)?;
         Ok(Self::new(c0, c1, c2))
     }
 
     fn cost() -> usize {
-        3 * <Fp2Gadget<P, ConstraintF> as TwoBitLookupGadget<ConstraintF>>::cost()
+        3 * <Fp2Gadget<P, ConstraintF, BF> as TwoBitLookupGadget<ConstraintF>>::cost()
     }
 }
 
-impl<P, ConstraintF: PrimeField> ThreeBitCondNegLookupGadget<ConstraintF>
-    for Fp6Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> ThreeBitCondNegLookupGadget<ConstraintF>
+    for Fp6Gadget<P, ConstraintF, BF>
 where
     P: Fp6Parameters,
-    P::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<P::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     type TableConstant = Fp6<P>;
 
@@ -933,19 +938,19 @@ where
         let c0s = c.iter().map(|f| f.c0).collect::<Vec<_>>();
         let c1s = c.iter().map(|f| f.c1).collect::<Vec<_>>();
         let c2s = c.iter().map(|f| f.c2).collect::<Vec<_>>();
-        let c0 = Fp2Gadget::<P, ConstraintF>::three_bit_cond_neg_lookup(
+        let c0 = Fp2Gadget::<P, ConstraintF, BF>::three_bit_cond_neg_lookup(
             cs.ns(|| "Lookup c0"),
             b,
             b0b1,
             &c0s,
         )?;
-        let c1 = Fp2Gadget::<P, ConstraintF>::three_bit_cond_neg_lookup(
+        let c1 = Fp2Gadget::<P, ConstraintF, BF>::three_bit_cond_neg_lookup(
             cs.ns(|| "Lookup c1"),
             b,
             b0b1,
             &c1s,
         )?;
-        let c2 = Fp2Gadget::<P, ConstraintF>::three_bit_cond_neg_lookup(
+        let c2 = Fp2Gadget::<P, ConstraintF, BF>::three_bit_cond_neg_lookup(
             cs.ns(|| "Lookup c2"),
             b,
             b0b1,
@@ -955,14 +960,15 @@ where
     }
 
     fn cost() -> usize {
-        3 * <Fp2Gadget<P, ConstraintF> as ThreeBitCondNegLookupGadget<ConstraintF>>::cost()
+        3 * <Fp2Gadget<P, ConstraintF, BF> as ThreeBitCondNegLookupGadget<ConstraintF>>::cost()
     }
 }
 
-impl<P, ConstraintF: PrimeField> AllocGadget<Fp6<P>, ConstraintF> for Fp6Gadget<P, ConstraintF>
+impl<P, ConstraintF: PrimeField, BF> AllocGadget<Fp6<P>, ConstraintF>
+    for Fp6Gadget<P, ConstraintF, BF>
 where
     P: Fp6Parameters,
-    P::Fp2Params: Fp2Parameters<Fp = ConstraintF>,
+    BF: FieldGadget<<P::Fp2Params as Fp2Parameters>::Fp, ConstraintF>,
 {
     #[inline]
     fn alloc_constant<T, CS: ConstraintSystem<ConstraintF>>(
@@ -996,9 +1002,9 @@ where
             ),
         };
 
-        let c0 = Fp2Gadget::<P, ConstraintF>::alloc(&mut cs.ns(|| "c0"), || c0)?;
-        let c1 = Fp2Gadget::<P, ConstraintF>::alloc(&mut cs.ns(|| "c1"), || c1)?;
-        let c2 = Fp2Gadget::<P, ConstraintF>::alloc(&mut cs.ns(|| "c2"), || c2)?;
+        let c0 = Fp2Gadget::<P, ConstraintF, BF>::alloc(&mut cs.ns(|| "c0"), || c0)?;
+        let c1 = Fp2Gadget::<P, ConstraintF, BF>::alloc(&mut cs.ns(|| "c1"), || c1)?;
+        let c2 = Fp2Gadget::<P, ConstraintF, BF>::alloc(&mut cs.ns(|| "c2"), || c2)?;
         Ok(Self::new(c0, c1, c2))
     }
 
@@ -1023,9 +1029,9 @@ where
             ),
         };
 
-        let c0 = Fp2Gadget::<P, ConstraintF>::alloc_input(&mut cs.ns(|| "c0"), || c0)?;
-        let c1 = Fp2Gadget::<P, ConstraintF>::alloc_input(&mut cs.ns(|| "c1"), || c1)?;
-        let c2 = Fp2Gadget::<P, ConstraintF>::alloc_input(&mut cs.ns(|| "c2"), || c2)?;
+        let c0 = Fp2Gadget::<P, ConstraintF, BF>::alloc_input(&mut cs.ns(|| "c0"), || c0)?;
+        let c1 = Fp2Gadget::<P, ConstraintF, BF>::alloc_input(&mut cs.ns(|| "c1"), || c1)?;
+        let c2 = Fp2Gadget::<P, ConstraintF, BF>::alloc_input(&mut cs.ns(|| "c2"), || c2)?;
         Ok(Self::new(c0, c1, c2))
     }
 }
@@ -0,0 +1,169 @@
+use algebra::{FpParameters, PrimeField};
+use core::cmp::Ordering;
+
+use crate::Vec;
+
+/// A natural number of any size, stored as little-endian 64-bit limbs
+/// without trailing zero limbs.
+///
+/// It is only used to compute the witnesses and constants of non-native
+/// arithmetic, so it favours simplicity over speed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct BigNat(Vec<u64>);
+
+impl BigNat {
+    pub(super) fn zero() -> Self {
+        BigNat(Vec::new())
+    }
+
+    /// Returns `2^exponent`.
+    pub(super) fn power_of_two(exponent: usize) -> Self {
+        let mut result = Self::zero();
+        result.set_bit(exponent);
+        result
+    }
+
+    fn from_limbs(limbs: &[u64]) -> Self {
+        let mut result = BigNat(limbs.to_vec());
+        result.normalize();
+        result
+    }
+
+    /// Returns the integer that represents `value`.
+    pub(super) fn from_field<F: PrimeField>(value: &F) -> Self {
+        Self::from_limbs(value.into_repr().as_ref())
+    }
+
+    /// Returns the modulus of `F`.
+    pub(super) fn modulus<F: PrimeField>() -> Self {
+        Self::from_limbs(F::Params::MODULUS.as_ref())
+    }
+
+    /// Returns the element of `F` that `self` represents, which must be less
+    /// than the modulus of `F`.
+    pub(super) fn to_field<F: PrimeField>(&self) -> F {
+        debug_assert!(*self < Self::modulus::<F>());
+        let mut repr = F::BigInt::default();
+        repr.as_mut()[..self.0.len()].copy_from_slice(&self.0);
+        F::from_repr(repr)
+    }
+
+    fn normalize(&mut self) {
+        while self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+    }
+
+    pub(super) fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of bits of `self`, without leading zeros.
+    pub(super) fn num_bits(&self) -> usize {
+        match self.0.last() {
+            Some(last) => 64 * self.0.len() - last.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    pub(super) fn bit(&self, i: usize) -> bool {
+        self.0
+            .get(i / 64)
+            .map_or(false, |limb| (limb >> (i % 64)) & 1 == 1)
+    }
+
+    fn set_bit(&mut self, i: usize) {
+        if self.0.len() <= i / 64 {
+            self.0.resize(i / 64 + 1, 0);
+        }
+        self.0[i / 64] |= 1u64 << (i % 64);
+    }
+
+    /// Returns the `num_bits` bits of `self` that start at bit `start`.
+    pub(super) fn bits(&self, start: usize, num_bits: usize) -> Self {
+        let mut result = Self::zero();
+        for i in 0..num_bits {
+            if self.bit(start + i) {
+                result.set_bit(i);
+            }
+        }
+        result
+    }
+
+    pub(super) fn shl(&self, shift: usize) -> Self {
+        let bits = shift % 64;
+        let mut limbs = vec![0u64; shift / 64];
+        let mut carry = 0;
+        for &limb in &self.0 {
+            limbs.push((limb << bits) | carry);
+            carry = if bits == 0 { 0 } else { limb >> (64 - bits) };
+        }
+        limbs.push(carry);
+        Self::from_limbs(&limbs)
+    }
+
+    pub(super) fn add(&self, other: &Self) -> Self {
+        let len = self.0.len().max(other.0.len());
+        let mut limbs = Vec::with_capacity(len + 1);
+        let mut carry = false;
+        for i in 0..len {
+            let a = self.0.get(i).copied().unwrap_or(0);
+            let b = other.0.get(i).copied().unwrap_or(0);
+            let (sum, carry1) = a.overflowing_add(b);
+            let (sum, carry2) = sum.overflowing_add(u64::from(carry));
+            limbs.push(sum);
+            carry = carry1 || carry2;
+        }
+        limbs.push(u64::from(carry));
+        Self::from_limbs(&limbs)
+    }
+
+    /// Returns `self - other`, where `other` must not be greater than `self`.
+    pub(super) fn sub(&self, other: &Self) -> Self {
+        assert!(*self >= *other);
+        let mut limbs = Vec::with_capacity(self.0.len());
+        let mut borrow = false;
+        for (i, &a) in self.0.iter().enumerate() {
+            let b = other.0.get(i).copied().unwrap_or(0);
+            let (difference, borrow1) = a.overflowing_sub(b);
+            let (difference, borrow2) = difference.overflowing_sub(u64::from(borrow));
+            limbs.push(difference);
+            borrow = borrow1 || borrow2;
+        }
+        Self::from_limbs(&limbs)
+    }
+
+    /// Returns the quotient and the remainder of the division of `self` by
+    /// `divisor`.
+    pub(super) fn divrem(&self, divisor: &Self) -> (Self, Self) {
+        assert!(!divisor.is_zero());
+        let mut quotient = Self::zero();
+        let mut remainder = Self::zero();
+        for i in (0..self.num_bits()).rev() {
+            remainder = remainder.shl(1);
+            if self.bit(i) {
+                remainder.set_bit(0);
+            }
+            if remainder >= *divisor {
+                remainder = remainder.sub(divisor);
+                quotient.set_bit(i);
+            }
+        }
+        (quotient, remainder)
+    }
+}
+
+impl PartialOrd for BigNat {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigNat {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .len()
+            .cmp(&other.0.len())
+            .then_with(|| self.0.iter().rev().cmp(other.0.iter().rev()))
+    }
+}
@@ -0,0 +1,1083 @@
+use algebra::{log2, BigInteger, FpParameters, PrimeField};
+use core::{borrow::Borrow, marker::PhantomData};
+use r1cs_core::{ConstraintSystem, ConstraintVar, LinearCombination, SynthesisError};
+
+use crate::{boolean::AllocatedBit, fields::fp::FpGadget, prelude::*, Assignment, Vec};
+
+mod bignat;
+
+use self::bignat::BigNat;
+
+/// The shape of the limbs that represent the elements of a prime field
+/// `TargetF` in a prime field `ConstraintF`.
+#[derive(Clone, Copy, Debug)]
+struct Params {
+    /// The number of bits of the modulus of `TargetF`.
+    target_bits: usize,
+    /// The number of bits between consecutive limbs, which is also the
+    /// number of bits of a limb in reduced form.
+    limb_width: usize,
+    /// The number of limbs of an element.
+    num_limbs: usize,
+    /// The largest number of bits of the limbs of an element, which leaves
+    /// room to multiply it by an element in reduced form.
+    max_limb_bits: usize,
+    /// The largest number of bits of the limbs of an integer that can be
+    /// reduced modulo the modulus of `TargetF`.
+    max_product_bits: usize,
+}
+
+/// The shape of the check that an integer is equal to `quotient * modulus +
+/// remainder`.
+#[derive(Clone, Copy, Debug)]
+struct Reduction {
+    num_quotient_limbs: usize,
+    /// The number of limbs of `quotient * modulus`, or of the integer if it
+    /// has more.
+    num_positions: usize,
+    /// The carries between positions are at least `-2^carry_bits` and less
+    /// than `2^carry_bits`.
+    carry_bits: usize,
+}
+
+impl Params {
+    /// Returns `SynthesisError::FieldTooSmall` if `ConstraintF` is too small
+    /// to emulate `TargetF`.
+    fn new<TargetF: PrimeField, ConstraintF: PrimeField>() -> Result<Self, SynthesisError> {
+        let capacity = ConstraintF::Params::CAPACITY as usize;
+        let target_bits = TargetF::Params::MODULUS_BITS as usize;
+        // The sums that check a reduction have two more bits than the limbs
+        // they add up, and must not wrap around the modulus of `ConstraintF`.
+        let max_product_bits = capacity.saturating_sub(3);
+        let limb_width = max_product_bits / 3;
+        if limb_width == 0 {
+            return Err(SynthesisError::FieldTooSmall);
+        }
+        let num_limbs = (target_bits + limb_width - 1) / limb_width;
+        let max_limb_bits = max_product_bits
+            .checked_sub(limb_width + log2(num_limbs) as usize)
+            .filter(|max_limb_bits| *max_limb_bits >= limb_width + 2)
+            .ok_or(SynthesisError::FieldTooSmall)?;
+        Ok(Self {
+            target_bits,
+            limb_width,
+            num_limbs,
+            max_limb_bits,
+            max_product_bits,
+        })
+    }
+
+    /// Returns the limbs of `value` in reduced form.
+    fn split(&self, value: &BigNat) -> Vec<BigNat> {
+        (0..self.num_limbs)
+            .map(|i| value.bits(self.limb_width * i, self.limb_width))
+            .collect()
+    }
+
+    /// Returns the integer that `limbs` represent, if it is known.
+    fn limbs_value<F: PrimeField>(&self, limbs: &[FpGadget<F>]) -> Option<BigNat> {
+        let mut value = BigNat::zero();
+        for (i, limb) in limbs.iter().enumerate() {
+            value = value.add(&BigNat::from_field(&limb.value?).shl(self.limb_width * i));
+        }
+        Some(value)
+    }
+
+    /// Returns the shape of the check that an integer with `num_limbs` limbs
+    /// of at most `limb_bits` bits is equal to `quotient * modulus +
+    /// remainder`.
+    fn reduction(&self, limb_bits: usize, num_limbs: usize) -> Reduction {
+        // The integer is less than `2^(limb_bits + limb_width * (num_limbs -
+        // 1) + 1)`, and the modulus is at least `2^(target_bits - 1)`.
+        let quotient_bits =
+            (limb_bits + self.limb_width * (num_limbs - 1) + 2).saturating_sub(self.target_bits);
+        let num_quotient_limbs = ((quotient_bits + self.limb_width - 1) / self.limb_width).max(1);
+        let num_positions = num_limbs.max(num_quotient_limbs + self.num_limbs - 1);
+        // Each position of the integer minus `quotient * modulus + remainder`
+        // is greater than `-2^difference_bits` and less than
+        // `2^difference_bits`.
+        let num_terms = num_quotient_limbs.min(self.num_limbs);
+        let difference_bits = limb_bits.max(2 * self.limb_width + log2(num_terms) as usize + 1) + 1;
+        Reduction {
+            num_quotient_limbs,
+            num_positions,
+            carry_bits: difference_bits + 1 - self.limb_width,
+        }
+    }
+
+    /// Returns the number of constraints of `enforce_mod_p`.
+    fn reduction_cost(&self, limb_bits: usize, num_limbs: usize, with_remainder: bool) -> usize {
+        let reduction = self.reduction(limb_bits, num_limbs);
+        let num_remainder_limbs = if with_remainder { self.num_limbs } else { 0 };
+        (reduction.num_quotient_limbs + num_remainder_limbs) * self.limb_width
+            + (reduction.num_positions - 1) * (reduction.carry_bits + 1)
+            + reduction.num_positions
+    }
+}
+
+/// An element of the prime field `TargetF`, emulated in a constraint system
+/// over another prime field `ConstraintF`, for example to verify proofs over
+/// the other curve of a cycle.
+///
+/// The element is represented by limbs in little-endian order, which are
+/// integers of at most `limb_bits` bits, with a fixed number of bits between
+/// consecutive limbs. Additions and subtractions let the limbs grow instead
+/// of reducing them modulo the modulus of `TargetF`, which only happens when
+/// the limbs would otherwise overflow, after multiplications, and when the
+/// canonical bits of the element are needed. A reduction allocates the
+/// quotient and the remainder, and checks them limb by limb with carries.
+///
+/// The costs of the operations are given for elements in reduced form. They
+/// are zero if `ConstraintF` is too small to emulate `TargetF`, in which case
+/// the operations return `SynthesisError::FieldTooSmall`.
+#[derive(Derivative)]
+#[derivative(
+    Debug(bound = "ConstraintF: PrimeField"),
+    Clone(bound = "ConstraintF: PrimeField")
+)]
+#[must_use]
+pub struct NonNativeFieldGadget<TargetF: PrimeField, ConstraintF: PrimeField> {
+    limbs: Vec<FpGadget<ConstraintF>>,
+    limb_bits: usize,
+    #[derivative(Debug = "ignore")]
+    _target: PhantomData<TargetF>,
+}
+
+impl<TargetF: PrimeField, ConstraintF: PrimeField> NonNativeFieldGadget<TargetF, ConstraintF> {
+    fn new(limbs: Vec<FpGadget<ConstraintF>>, limb_bits: usize) -> Self {
+        Self {
+            limbs,
+            limb_bits,
+            _target: PhantomData,
+        }
+    }
+
+    /// Returns an element equal to `self`, whose limbs are in reduced form.
+    pub fn reduce<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        cs: CS,
+    ) -> Result<Self, SynthesisError> {
+        let limbs = Self::enforce_mod_p(cs, &self.limbs, self.limb_bits, true)?;
+        let limb_width = Params::new::<TargetF, ConstraintF>()?.limb_width;
+        Ok(Self::new(limbs, limb_width))
+    }
+
+    fn from_constant<CS: ConstraintSystem<ConstraintF>>(
+        value: &TargetF,
+    ) -> Result<Self, SynthesisError> {
+        let params = Params::new::<TargetF, ConstraintF>()?;
+        let limbs = params
+            .split(&BigNat::from_field(value))
+            .iter()
+            .map(|limb| {
+                let limb = limb.to_field::<ConstraintF>();
+                FpGadget {
+                    value: Some(limb),
+                    variable: LinearCombination::from((limb, CS::one())).into(),
+                }
+            })
+            .collect();
+        Ok(Self::new(limbs, params.limb_width))
+    }
+
+    /// Returns limbs of at least `2^limb_bits` each that represent a multiple
+    /// of the modulus, so that subtracting limbs of at most `limb_bits` bits
+    /// from them leaves nonnegative limbs.
+    fn pad(params: &Params, limb_bits: usize) -> Vec<ConstraintF> {
+        let modulus = BigNat::modulus::<TargetF>();
+        let mut sum = BigNat::zero();
+        for i in 0..params.num_limbs {
+            sum = sum.add(&BigNat::power_of_two(limb_bits + params.limb_width * i));
+        }
+        let remainder = sum.divrem(&modulus).1;
+        let complement = if remainder.is_zero() {
+            remainder
+        } else {
+            modulus.sub(&remainder)
+        };
+        let limb = BigNat::power_of_two(limb_bits);
+        params
+            .split(&complement)
+            .iter()
+            .map(|complement| limb.add(complement).to_field())
+            .collect()
+    }
+
+    /// Allocates a bit for each of `values`, and returns the bits with the
+    /// integer they represent in little-endian order.
+    fn alloc_bits<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        values: &[Option<bool>],
+    ) -> Result<(Vec<Boolean>, LinearCombination<ConstraintF>), SynthesisError> {
+        let mut lc = LinearCombination::zero();
+        let mut coeff = ConstraintF::one();
+        let mut bits = Vec::with_capacity(values.len());
+        for (i, value) in values.iter().enumerate() {
+            let bit = AllocatedBit::alloc(cs.ns(|| format!("bit {}", i)), || value.get())?;
+            lc = lc + (coeff, bit.get_variable());
+            bits.push(Boolean::from(bit));
+            coeff.double_in_place();
+        }
+        Ok((bits, lc))
+    }
+
+    /// Allocates the `num_limbs` limbs of `value` in reduced form, each as
+    /// the linear combination of its bits.
+    fn alloc_limbs<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        value: Option<&BigNat>,
+        num_limbs: usize,
+    ) -> Result<Vec<FpGadget<ConstraintF>>, SynthesisError> {
+        let limb_width = Params::new::<TargetF, ConstraintF>()?.limb_width;
+        let mut limbs = Vec::with_capacity(num_limbs);
+        for i in 0..num_limbs {
+            let values = (0..limb_width)
+                .map(|j| value.map(|value| value.bit(limb_width * i + j)))
+                .collect::<Vec<_>>();
+            let (_, lc) = Self::alloc_bits(cs.ns(|| format!("limb {}", i)), &values)?;
+            limbs.push(FpGadget {
+                value: value.map(|value| value.bits(limb_width * i, limb_width).to_field()),
+                variable: lc.into(),
+            });
+        }
+        Ok(limbs)
+    }
+
+    /// Enforces that the integer that `limbs` represent, whose limbs have at
+    /// most `limb_bits` bits, is equal to `quotient * modulus + remainder`
+    /// for an allocated `quotient`, and returns the limbs of `remainder` in
+    /// reduced form. If `with_remainder` is false, `remainder` is zero, so
+    /// this enforces that the integer is a multiple of the modulus.
+    fn enforce_mod_p<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        limbs: &[FpGadget<ConstraintF>],
+        limb_bits: usize,
+        with_remainder: bool,
+    ) -> Result<Vec<FpGadget<ConstraintF>>, SynthesisError> {
+        let params = Params::new::<TargetF, ConstraintF>()?;
+        if limb_bits > params.max_product_bits {
+            return Err(SynthesisError::FieldTooSmall);
+        }
+        let reduction = params.reduction(limb_bits, limbs.len());
+
+        let modulus = BigNat::modulus::<TargetF>();
+        let (quotient, remainder) = match params.limbs_value(limbs) {
+            Some(value) => {
+                let (quotient, remainder) = value.divrem(&modulus);
+                (Some(quotient), Some(remainder))
+            },
+            None => (None, None),
+        };
+        let quotient = Self::alloc_limbs(
+            cs.ns(|| "quotient"),
+            quotient.as_ref(),
+            reduction.num_quotient_limbs,
+        )?;
+        let remainder = if with_remainder {
+            Self::alloc_limbs(cs.ns(|| "remainder"), remainder.as_ref(), params.num_limbs)?
+        } else {
+            Vec::new()
+        };
+
+        // Computes the limbs of the integer minus `quotient * modulus +
+        // remainder`, whose sum with the powers of `2^limb_width` must be
+        // zero.
+        let modulus_limbs = params
+            .split(&modulus)
+            .iter()
+            .map(BigNat::to_field)
+            .collect::<Vec<ConstraintF>>();
+        let mut differences = Vec::with_capacity(reduction.num_positions);
+        for k in 0..reduction.num_positions {
+            let mut difference = match limbs.get(k) {
+                Some(limb) => limb.clone(),
+                None => FpGadget::zero(&mut cs)?,
+            };
+            for (i, quotient_limb) in quotient.iter().enumerate().take(k + 1) {
+                if let Some(modulus_limb) = modulus_limbs.get(k - i) {
+                    let product = quotient_limb.mul_by_constant(&mut cs, modulus_limb)?;
+                    difference = difference.sub(&mut cs, &product)?;
+                }
+            }
+            if let Some(remainder_limb) = remainder.get(k) {
+                difference = difference.sub(&mut cs, remainder_limb)?;
+            }
+            differences.push(difference);
+        }
+
+        // Enforces `difference + carry = 2^limb_width * next_carry` at each
+        // position, where the carries are allocated with an offset that makes
+        // them nonnegative, and the last carry is zero.
+        let shift = BigNat::power_of_two(params.limb_width).to_field::<ConstraintF>();
+        let shift_inverse = shift.inverse().unwrap();
+        let offset = BigNat::power_of_two(reduction.carry_bits).to_field::<ConstraintF>();
+        let mut carry = FpGadget::zero(&mut cs)?;
+        for (k, difference) in differences.iter().enumerate() {
+            let sum = difference.add(&mut cs, &carry)?;
+            let next_carry = if k + 1 < reduction.num_positions {
+                let value = sum.value.map(|sum| sum * &shift_inverse + &offset);
+                let integer = value.as_ref().map(BigNat::from_field);
+                let values = (0..=reduction.carry_bits)
+                    .map(|i| integer.as_ref().map(|integer| integer.bit(i)))
+                    .collect::<Vec<_>>();
+                let (_, lc) = Self::alloc_bits(cs.ns(|| format!("carry {}", k + 1)), &values)?;
+                FpGadget {
+                    value,
+                    variable: lc.into(),
+                }
+                .add_constant(&mut cs, &-offset)?
+            } else {
+                FpGadget::zero(&mut cs)?
+            };
+            let shifted_carry = next_carry.mul_by_constant(&mut cs, &shift)?;
+            let rest = sum.sub(&mut cs, &shifted_carry)?;
+            cs.enforce(
+                || format!("position {}", k),
+                |lc| lc,
+                |lc| lc,
+                |lc| &rest.variable + lc,
+            );
+            carry = next_carry;
+        }
+
+        Ok(remainder)
+    }
+
+    /// Reduces `a` or `b`, starting with the one with larger limbs, until
+    /// `limb_bits(a.limb_bits, b.limb_bits)` is at most `max_bits`.
+    fn with_room<CS, B>(
+        mut cs: CS,
+        a: &Self,
+        b: &Self,
+        max_bits: usize,
+        limb_bits: B,
+    ) -> Result<(Self, Self), SynthesisError>
+    where
+        CS: ConstraintSystem<ConstraintF>,
+        B: Fn(usize, usize) -> usize,
+    {
+        let mut a = a.clone();
+        let mut b = b.clone();
+        for i in 0..2 {
+            if limb_bits(a.limb_bits, b.limb_bits) <= max_bits {
+                break;
+            }
+            if a.limb_bits >= b.limb_bits {
+                a = a.reduce(cs.ns(|| format!("reduce {}", i)))?;
+            } else {
+                b = b.reduce(cs.ns(|| format!("reduce {}", i)))?;
+            }
+        }
+        if limb_bits(a.limb_bits, b.limb_bits) > max_bits {
+            return Err(SynthesisError::FieldTooSmall);
+        }
+        Ok((a, b))
+    }
+
+    /// Outputs the bits of the limbs of `self` in reduced form, which are
+    /// the bits of an integer that represents `self` in *big-endian* form.
+    fn to_limb_bits<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<Vec<Boolean>, SynthesisError> {
+        let params = Params::new::<TargetF, ConstraintF>()?;
+        let reduced = if self.limb_bits > params.limb_width {
+            self.reduce(cs.ns(|| "reduce"))?
+        } else {
+            self.clone()
+        };
+        let mut bits = Vec::with_capacity(params.target_bits);
+        for (i, limb) in reduced.limbs.iter().enumerate() {
+            // The limbs in reduced form are those of an integer less than the
+            // modulus, so the last limb has fewer bits.
+            let num_bits = params
+                .limb_width
+                .min(params.target_bits - params.limb_width * i);
+            let integer = limb.value.as_ref().map(BigNat::from_field);
+            let values = (0..num_bits)
+                .map(|j| integer.as_ref().map(|integer| integer.bit(j)))
+                .collect::<Vec<_>>();
+            let (limb_bits, packed) = Self::alloc_bits(cs.ns(|| format!("limb {}", i)), &values)?;
+            cs.enforce(
+                || format!("limb {} packing", i),
+                |lc| lc,
+                |lc| lc,
+                |_| &limb.variable - packed,
+            );
+            bits.extend(limb_bits);
+        }
+        bits.reverse();
+        Ok(bits)
+    }
+
+    /// Converts bits in *big-endian* form into bytes in *little-endian*
+    /// form, as many as in the representation of `TargetF`.
+    fn bits_to_bytes(bits: &[Boolean]) -> Vec<UInt8> {
+        let num_bits = <TargetF::BigInt as BigInteger>::NUM_LIMBS * 64;
+        let mut bits = bits.iter().rev().cloned().collect::<Vec<_>>();
+        bits.resize(num_bits, Boolean::constant(false));
+        bits.chunks(8).map(UInt8::from_bits_le).collect()
+    }
+}
+
+impl<TargetF: PrimeField, ConstraintF: PrimeField> FieldGadget<TargetF, ConstraintF>
+    for NonNativeFieldGadget<TargetF, ConstraintF>
+{
+    type Variable = Vec<ConstraintVar<ConstraintF>>;
+
+    fn get_value(&self) -> Option<TargetF> {
+        let params = Params::new::<TargetF, ConstraintF>().ok()?;
+        let modulus = BigNat::modulus::<TargetF>();
+        params
+            .limbs_value(&self.limbs)
+            .map(|value| value.divrem(&modulus).1.to_field())
+    }
+
+    fn get_variable(&self) -> Self::Variable {
+        self.limbs.iter().map(|limb| limb.get_variable()).collect()
+    }
+
+    fn zero<CS: ConstraintSystem<ConstraintF>>(_cs: CS) -> Result<Self, SynthesisError> {
+        Self::from_constant::<CS>(&TargetF::zero())
+    }
+
+    fn one<CS: ConstraintSystem<ConstraintF>>(_cs: CS) -> Result<Self, SynthesisError> {
+        Self::from_constant::<CS>(&TargetF::one())
+    }
+
+    fn conditionally_add_constant<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        bit: &Boolean,
+        coeff: TargetF,
+    ) -> Result<Self, SynthesisError> {
+        let params = Params::new::<TargetF, ConstraintF>()?;
+        let limb_bits = |limb_bits: usize| limb_bits.max(params.limb_width) + 1;
+        let reduced = if limb_bits(self.limb_bits) > params.max_limb_bits {
+            self.reduce(cs.ns(|| "reduce"))?
+        } else {
+            self.clone()
+        };
+        let constant = params.split(&BigNat::from_field(&coeff));
+        let mut limbs = Vec::with_capacity(params.num_limbs);
+        for (limb, constant) in reduced.limbs.iter().zip(&constant) {
+            limbs.push(limb.conditionally_add_constant(&mut cs, bit, constant.to_field())?);
+        }
+        Ok(Self::new(limbs, limb_bits(reduced.limb_bits)))
+    }
+
+    fn add<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+    ) -> Result<Self, SynthesisError> {
+        let params = Params::new::<TargetF, ConstraintF>()?;
+        let limb_bits = |a: usize, b: usize| a.max(b) + 1;
+        let (a, b) = Self::with_room(
+            cs.ns(|| "room"),
+            self,
+            other,
+            params.max_limb_bits,
+            limb_bits,
+        )?;
+        let mut limbs = Vec::with_capacity(params.num_limbs);
+        for (a_limb, b_limb) in a.limbs.iter().zip(&b.limbs) {
+            limbs.push(a_limb.add(&mut cs, b_limb)?);
+        }
+        Ok(Self::new(limbs, limb_bits(a.limb_bits, b.limb_bits)))
+    }
+
+    fn sub<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+    ) -> Result<Self, SynthesisError> {
+        let params = Params::new::<TargetF, ConstraintF>()?;
+        let limb_bits = |a: usize, b: usize| a.max(b).max(params.limb_width) + 2;
+        let (a, b) = Self::with_room(
+            cs.ns(|| "room"),
+            self,
+            other,
+            params.max_limb_bits,
+            limb_bits,
+        )?;
+        let pad = Self::pad(&params, b.limb_bits);
+        let mut limbs = Vec::with_capacity(params.num_limbs);
+        for ((a_limb, b_limb), pad) in a.limbs.iter().zip(&b.limbs).zip(&pad) {
+            limbs.push(a_limb.add_constant(&mut cs, pad)?.sub(&mut cs, b_limb)?);
+        }
+        Ok(Self::new(limbs, limb_bits(a.limb_bits, b.limb_bits)))
+    }
+
+    fn negate<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<Self, SynthesisError> {
+        Self::zero(cs.ns(|| "zero"))?.sub(cs.ns(|| "negate"), self)
+    }
+
+    fn mul<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+    ) -> Result<Self, SynthesisError> {
+        let params = Params::new::<TargetF, ConstraintF>()?;
+        let num_limbs = params.num_limbs;
+        let limb_bits = |a: usize, b: usize| a + b + log2(num_limbs) as usize;
+        let (a, b) = Self::with_room(
+            cs.ns(|| "room"),
+            self,
+            other,
+            params.max_product_bits,
+            limb_bits,
+        )?;
+        let mut products = vec![FpGadget::zero(&mut cs)?; 2 * num_limbs - 1];
+        for (i, a_limb) in a.limbs.iter().enumerate() {
+            for (j, b_limb) in b.limbs.iter().enumerate() {
+                let product = a_limb.mul(cs.ns(|| format!("limb {} * limb {}", i, j)), b_limb)?;
+                products[i + j] = products[i + j].add(&mut cs, &product)?;
+            }
+        }
+        let limbs = Self::enforce_mod_p(
+            cs.ns(|| "reduce"),
+            &products,
+            limb_bits(a.limb_bits, b.limb_bits),
+            true,
+        )?;
+        Ok(Self::new(limbs, params.limb_width))
+    }
+
+    fn add_constant<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        cs: CS,
+        other: &TargetF,
+    ) -> Result<Self, SynthesisError> {
+        self.add(cs, &Self::from_constant::<CS>(other)?)
+    }
+
+    fn mul_by_constant<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        other: &TargetF,
+    ) -> Result<Self, SynthesisError> {
+        let params = Params::new::<TargetF, ConstraintF>()?;
+        let num_limbs = params.num_limbs;
+        let constant = params.split(&BigNat::from_field(other));
+        let mut products = vec![FpGadget::zero(&mut cs)?; 2 * num_limbs - 1];
+        for (i, limb) in self.limbs.iter().enumerate() {
+            for (j, constant) in constant.iter().enumerate() {
+                let product = limb.mul_by_constant(&mut cs, &constant.to_field())?;
+                products[i + j] = products[i + j].add(&mut cs, &product)?;
+            }
+        }
+        // The limbs of `self` have at most `max_limb_bits` bits, which leaves
+        // room for a product by limbs in reduced form.
+        let limbs = Self::enforce_mod_p(
+            cs.ns(|| "reduce"),
+            &products,
+            self.limb_bits + params.limb_width + log2(num_limbs) as usize,
+            true,
+        )?;
+        Ok(Self::new(limbs, params.limb_width))
+    }
+
+    fn frobenius_map<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        _: CS,
+        _: usize,
+    ) -> Result<Self, SynthesisError> {
+        Ok(self.clone())
+    }
+
+    fn cost_of_mul() -> usize {
+        Params::new::<TargetF, ConstraintF>().map_or(0, |params| {
+            let num_limbs = params.num_limbs;
+            num_limbs * num_limbs
+                + params.reduction_cost(
+                    2 * params.limb_width + log2(num_limbs) as usize,
+                    2 * num_limbs - 1,
+                    true,
+                )
+        })
+    }
+
+    fn cost_of_inv() -> usize {
+        Params::new::<TargetF, ConstraintF>().map_or(0, |params| {
+            params.num_limbs * params.limb_width + Self::cost_of_mul_equals()
+        })
+    }
+}
+
+impl<TargetF: PrimeField, ConstraintF: PrimeField> PartialEq
+    for NonNativeFieldGadget<TargetF, ConstraintF>
+{
+    fn eq(&self, other: &Self) -> bool {
+        let value = self.get_value();
+        value.is_some() && value == other.get_value()
+    }
+}
+
+impl<TargetF: PrimeField, ConstraintF: PrimeField> Eq
+    for NonNativeFieldGadget<TargetF, ConstraintF>
+{
+}
+
+impl<TargetF: PrimeField, ConstraintF: PrimeField> EqGadget<ConstraintF>
+    for NonNativeFieldGadget<TargetF, ConstraintF>
+{
+}
+
+impl<TargetF: PrimeField, ConstraintF: PrimeField> ConditionalEqGadget<ConstraintF>
+    for NonNativeFieldGadget<TargetF, ConstraintF>
+{
+    fn conditional_enforce_equal<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        condition: &Boolean,
+    ) -> Result<(), SynthesisError> {
+        if let Boolean::Constant(false) = *condition {
+            return Ok(());
+        }
+        let difference = self.sub(cs.ns(|| "difference"), other)?;
+        let limbs = match *condition {
+            Boolean::Constant(_) => difference.limbs,
+            _ => {
+                let zero = FpGadget::zero(&mut cs)?;
+                let mut limbs = Vec::with_capacity(difference.limbs.len());
+                for (i, limb) in difference.limbs.iter().enumerate() {
+                    limbs.push(FpGadget::conditionally_select(
+                        cs.ns(|| format!("select limb {}", i)),
+                        condition,
+                        limb,
+                        &zero,
+                    )?);
+                }
+                limbs
+            },
+        };
+        Self::enforce_mod_p(
+            cs.ns(|| "multiple of modulus"),
+            &limbs,
+            difference.limb_bits,
+            false,
+        )?;
+        Ok(())
+    }
+
+    fn cost() -> usize {
+        Params::new::<TargetF, ConstraintF>().map_or(0, |params| {
+            params.num_limbs + params.reduction_cost(params.limb_width + 2, params.num_limbs, false)
+        })
+    }
+}
+
+impl<TargetF: PrimeField, ConstraintF: PrimeField> NEqGadget<ConstraintF>
+    for NonNativeFieldGadget<TargetF, ConstraintF>
+{
+    fn enforce_not_equal<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+    ) -> Result<(), SynthesisError> {
+        let difference = self.sub(cs.ns(|| "A - B"), other)?;
+        difference.inverse(cs.ns(|| "Enforce inverse exists"))?;
+        Ok(())
+    }
+
+    fn cost() -> usize {
+        Self::cost_of_inv()
+    }
+}
+
+impl<TargetF: PrimeField, ConstraintF: PrimeField> ToBitsGadget<ConstraintF>
+    for NonNativeFieldGadget<TargetF, ConstraintF>
+{
+    /// Outputs the unique bit-wise decomposition of `self` in *big-endian*
+    /// form.
+    fn to_bits<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        mut cs: CS,
+    ) -> Result<Vec<Boolean>, SynthesisError> {
+        let bits = self.to_non_unique_bits(&mut cs)?;
+        Boolean::enforce_in_field::<_, _, TargetF>(&mut cs, &bits)?;
+        Ok(bits)
+    }
+
+    fn to_non_unique_bits<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        cs: CS,
+    ) -> Result<Vec<Boolean>, SynthesisError> {
+        self.to_limb_bits(cs)
+    }
+}
+
+impl<TargetF: PrimeField, ConstraintF: PrimeField> ToBytesGadget<ConstraintF>
+    for NonNativeFieldGadget<TargetF, ConstraintF>
+{
+    /// Outputs the unique byte decomposition of `self` in *little-endian*
+    /// form.
+    fn to_bytes<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        cs: CS,
+    ) -> Result<Vec<UInt8>, SynthesisError> {
+        let bits = self.to_bits(cs)?;
+        Ok(Self::bits_to_bytes(&bits))
+    }
+
+    fn to_non_unique_bytes<CS: ConstraintSystem<ConstraintF>>(
+        &self,
+        cs: CS,
+    ) -> Result<Vec<UInt8>, SynthesisError> {
+        let bits = self.to_non_unique_bits(cs)?;
+        Ok(Self::bits_to_bytes(&bits))
+    }
+}
+
+impl<TargetF: PrimeField, ConstraintF: PrimeField> CondSelectGadget<ConstraintF>
+    for NonNativeFieldGadget<TargetF, ConstraintF>
+{
+    fn conditionally_select<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        cond: &Boolean,
+        true_value: &Self,
+        false_value: &Self,
+    ) -> Result<Self, SynthesisError> {
+        let mut limbs = Vec::with_capacity(true_value.limbs.len());
+        for (i, (true_limb, false_limb)) in
+            true_value.limbs.iter().zip(&false_value.limbs).enumerate()
+        {
+            limbs.push(FpGadget::conditionally_select(
+                cs.ns(|| format!("limb {}", i)),
+                cond,
+                true_limb,
+                false_limb,
+            )?);
+        }
+        let limb_bits = true_value.limb_bits.max(false_value.limb_bits);
+        Ok(Self::new(limbs, limb_bits))
+    }
+
+    fn cost() -> usize {
+        Params::new::<TargetF, ConstraintF>().map_or(0, |params| params.num_limbs)
+            * <FpGadget<ConstraintF> as CondSelectGadget<ConstraintF>>::cost()
+    }
+}
+
+/// Uses two bits to perform a lookup into a table
+/// `b` is little-endian: `b[0]` is LSB.
+impl<TargetF: PrimeField, ConstraintF: PrimeField> TwoBitLookupGadget<ConstraintF>
+    for NonNativeFieldGadget<TargetF, ConstraintF>
+{
+    type TableConstant = TargetF;
+
+    fn two_bit_lookup<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        b: &[Boolean],
+        c: &[Self::TableConstant],
+    ) -> Result<Self, SynthesisError> {
+        debug_assert!(b.len() == 2);
+        debug_assert!(c.len() == 4);
+
+        let params = Params::new::<TargetF, ConstraintF>()?;
+        let table = c
+            .iter()
+            .map(|c| params.split(&BigNat::from_field(c)))
+            .collect::<Vec<_>>();
+        let mut limbs = Vec::with_capacity(params.num_limbs);
+        for i in 0..params.num_limbs {
+            let limb_table = table
+                .iter()
+                .map(|limbs| limbs[i].to_field())
+                .collect::<Vec<ConstraintF>>();
+            limbs.push(FpGadget::two_bit_lookup(
+                cs.ns(|| format!("limb {}", i)),
+                b,
+                &limb_table,
+            )?);
+        }
+        Ok(Self::new(limbs, params.limb_width))
+    }
+
+    fn cost() -> usize {
+        Params::new::<TargetF, ConstraintF>().map_or(0, |params| params.num_limbs)
+            * <FpGadget<ConstraintF> as TwoBitLookupGadget<ConstraintF>>::cost()
+    }
+}
+
+impl<TargetF: PrimeField, ConstraintF: PrimeField> ThreeBitCondNegLookupGadget<ConstraintF>
+    for NonNativeFieldGadget<TargetF, ConstraintF>
+{
+    type TableConstant = TargetF;
+
+    fn three_bit_cond_neg_lookup<CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        b: &[Boolean],
+        _b0b1: &Boolean,
+        c: &[Self::TableConstant],
+    ) -> Result<Self, SynthesisError> {
+        debug_assert!(b.len() == 3);
+        debug_assert!(c.len() == 4);
+
+        let y = Self::two_bit_lookup(cs.ns(|| "lookup"), &b[..2], c)?;
+        let negated = y.negate(cs.ns(|| "negate"))?;
+        Self::conditionally_select(cs.ns(|| "select"), &b[2], &negated, &y)
+    }
+
+    fn cost() -> usize {
+        <Self as TwoBitLookupGadget<ConstraintF>>::cost()
+            + <Self as CondSelectGadget<ConstraintF>>::cost()
+    }
+}
+
+impl<TargetF: PrimeField, ConstraintF: PrimeField> AllocGadget<TargetF, ConstraintF>
+    for NonNativeFieldGadget<TargetF, ConstraintF>
+{
+    fn alloc_constant<T, CS: ConstraintSystem<ConstraintF>>(
+        _cs: CS,
+        t: T,
+    ) -> Result<Self, SynthesisError>
+    where
+        T: Borrow<TargetF>,
+    {
+        Self::from_constant::<CS>(t.borrow())
+    }
+
+    fn alloc<FN, T, CS: ConstraintSystem<ConstraintF>>(
+        cs: CS,
+        value_gen: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<TargetF>,
+    {
+        let params = Params::new::<TargetF, ConstraintF>()?;
+        let value = value_gen()
+            .ok()
+            .map(|value| BigNat::from_field::<TargetF>(value.borrow()));
+        let limbs = Self::alloc_limbs(cs, value.as_ref(), params.num_limbs)?;
+        Ok(Self::new(limbs, params.limb_width))
+    }
+
+    fn alloc_input<FN, T, CS: ConstraintSystem<ConstraintF>>(
+        mut cs: CS,
+        value_gen: FN,
+    ) -> Result<Self, SynthesisError>
+    where
+        FN: FnOnce() -> Result<T, SynthesisError>,
+        T: Borrow<TargetF>,
+    {
+        let params = Params::new::<TargetF, ConstraintF>()?;
+        let value = value_gen()
+            .ok()
+            .map(|value| BigNat::from_field::<TargetF>(value.borrow()));
+        let limb_width = params.limb_width;
+        let mut limbs = Vec::with_capacity(params.num_limbs);
+        for i in 0..params.num_limbs {
+            let limb_value = value.as_ref().map(|value| {
+                value
+                    .bits(limb_width * i, limb_width)
+                    .to_field::<ConstraintF>()
+            });
+            let limb = FpGadget::alloc_input(cs.ns(|| format!("limb {}", i)), || limb_value.get())?;
+            let values = (0..limb_width)
+                .map(|j| value.as_ref().map(|value| value.bit(limb_width * i + j)))
+                .collect::<Vec<_>>();
+            let (_, packed) = Self::alloc_bits(cs.ns(|| format!("limb {} bits", i)), &values)?;
+            cs.enforce(
+                || format!("limb {} packing", i),
+                |lc| lc,
+                |lc| lc,
+                |_| &limb.variable - packed,
+            );
+            limbs.push(limb);
+        }
+        Ok(Self::new(limbs, limb_width))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use algebra::{
+        bls12_381::{
+            g1, Bls12_381, Fq, Fq12, Fq12Parameters, Fq2, Fq2Parameters, Fr, G1Projective,
+            Parameters,
+        },
+        curves::bls12::G1Prepared,
+        test_rng, BitIterator, Field, Group, One, PrimeField, ProjectiveCurve, UniformRand,
+    };
+    use r1cs_core::ConstraintSystem;
+
+    use super::NonNativeFieldGadget;
+    use crate::{
+        fields::{
+            fp12::Fp12Gadget,
+            fp2::Fp2Gadget,
+            tests::{field_test, frobenius_tests},
+        },
+        groups::curves::short_weierstrass::AffineGadget,
+        pairing::bls12::PairingGadget as Bls12PairingGadget,
+        prelude::*,
+        test_constraint_system::TestConstraintSystem,
+        Vec,
+    };
+
+    type FqGadget = NonNativeFieldGadget<Fq, Fr>;
+
+    #[test]
+    fn nonnative_field_gadget_test() {
+        field_test::<Fq, Fr, FqGadget>();
+        field_test::<Fr, Fq, NonNativeFieldGadget<Fr, Fq>>();
+    }
+
+    #[test]
+    fn nonnative_lazy_reduction_test() {
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let mut rng = test_rng();
+
+        let a_native = Fq::rand(&mut rng);
+        let b_native = Fq::rand(&mut rng);
+        let a = FqGadget::alloc(cs.ns(|| "a"), || Ok(a_native)).unwrap();
+        let b = FqGadget::alloc(cs.ns(|| "b"), || Ok(b_native)).unwrap();
+
+        // Enough additions to overflow the limbs several times.
+        let mut sum = a.clone();
+        let mut sum_native = a_native;
+        for i in 0..200 {
+            sum = sum
+                .double(cs.ns(|| format!("double {}", i)))
+                .unwrap()
+                .sub(cs.ns(|| format!("sub {}", i)), &b)
+                .unwrap();
+            sum_native = sum_native.double() - &b_native;
+        }
+        assert_eq!(sum.get_value().unwrap(), sum_native);
+
+        let product = sum.mul(cs.ns(|| "product"), &sum).unwrap();
+        let product_native = sum_native.square();
+        assert_eq!(product.get_value().unwrap(), product_native);
+
+        let bits = product
+            .add(cs.ns(|| "product + a"), &a)
+            .unwrap()
+            .to_bits(cs.ns(|| "to_bits"))
+            .unwrap();
+        let native_bits =
+            BitIterator::new((product_native + &a_native).into_repr()).collect::<Vec<_>>();
+        assert_eq!(
+            bits.iter()
+                .map(|bit| bit.get_value().unwrap())
+                .collect::<Vec<_>>(),
+            &native_bits[native_bits.len() - bits.len()..]
+        );
+
+        if !cs.is_satisfied() {
+            println!("{:?}", cs.which_is_unsatisfied().unwrap());
+        }
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn nonnative_wrong_witness_test() {
+        let mut rng = test_rng();
+        let a_native = Fq::rand(&mut rng);
+        let b_native = Fq::rand(&mut rng);
+
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let a = FqGadget::alloc(cs.ns(|| "a"), || Ok(a_native)).unwrap();
+        let b = FqGadget::alloc(cs.ns(|| "b"), || Ok(b_native)).unwrap();
+        let _ = a.mul(cs.ns(|| "a * b"), &b).unwrap();
+        assert!(cs.is_satisfied());
+
+        // A product with another remainder is rejected.
+        let path = "a * b/reduce/remainder/limb 0/bit 0/boolean";
+        let bit = cs.get(path);
+        cs.set(path, Fr::one() - &bit);
+        assert!(!cs.is_satisfied());
+
+        // So is the equality of different elements.
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let a = FqGadget::alloc(cs.ns(|| "a"), || Ok(a_native)).unwrap();
+        let b = FqGadget::alloc(cs.ns(|| "b"), || Ok(b_native)).unwrap();
+        a.enforce_equal(cs.ns(|| "a == b"), &b).unwrap();
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn nonnative_short_weierstrass_test() {
+        type G1Gadget = AffineGadget<g1::Parameters, Fr, FqGadget>;
+
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let mut rng = test_rng();
+
+        let a = G1Projective::rand(&mut rng);
+        let b = G1Projective::rand(&mut rng);
+        let mut gadget_a = G1Gadget::alloc(cs.ns(|| "a"), || Ok(a)).unwrap();
+        let gadget_b = G1Gadget::alloc(cs.ns(|| "b"), || Ok(b)).unwrap();
+
+        let gadget_ab = gadget_a.add(cs.ns(|| "a + b"), &gadget_b).unwrap();
+        let gadget_ba = gadget_b.add(cs.ns(|| "b + a"), &gadget_a).unwrap();
+        gadget_ba
+            .enforce_equal(cs.ns(|| "a + b == b + a"), &gadget_ab)
+            .unwrap();
+        assert_eq!(gadget_ab.get_value().unwrap(), a + &b);
+
+        gadget_a.double_in_place(cs.ns(|| "2a")).unwrap();
+        assert_eq!(gadget_a.get_value().unwrap(), Group::double(&a));
+
+        if !cs.is_satisfied() {
+            println!("{:?}", cs.which_is_unsatisfied().unwrap());
+        }
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn nonnative_extension_field_test() {
+        type Fq2Gadget = Fp2Gadget<Fq2Parameters, Fr, FqGadget>;
+        type Fq12Gadget = Fp12Gadget<Fq12Parameters, Fr, FqGadget>;
+
+        field_test::<Fq2, Fr, Fq2Gadget>();
+        frobenius_tests::<Fq2, Fr, Fq2Gadget>(13);
+
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let mut rng = test_rng();
+
+        let a_native = Fq12::rand(&mut rng);
+        let b_native = Fq12::rand(&mut rng);
+        let a = Fq12Gadget::alloc(cs.ns(|| "a"), || Ok(a_native)).unwrap();
+        let b = Fq12Gadget::alloc(cs.ns(|| "b"), || Ok(b_native)).unwrap();
+        let product = a.mul(cs.ns(|| "a * b"), &b).unwrap();
+        assert_eq!(product.get_value().unwrap(), a_native * &b_native);
+        let frobenius = a.frobenius_map(cs.ns(|| "frobenius"), 1).unwrap();
+        let mut frobenius_native = a_native;
+        frobenius_native.frobenius_map(1);
+        assert_eq!(frobenius.get_value().unwrap(), frobenius_native);
+
+        if !cs.is_satisfied() {
+            println!("{:?}", cs.which_is_unsatisfied().unwrap());
+        }
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn nonnative_pairing_gadget_test() {
+        // A whole pairing is too large to check here, but its gadgets can be
+        // instantiated over the emulated base field.
+        type PG = Bls12PairingGadget<Parameters, Fr, FqGadget>;
+
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let mut rng = test_rng();
+
+        let a = G1Projective::rand(&mut rng);
+        let gadget_a =
+            <PG as PairingGadget<Bls12_381, Fr>>::G1Gadget::alloc(cs.ns(|| "a"), || Ok(a)).unwrap();
+        let prepared =
+            <PG as PairingGadget<Bls12_381, Fr>>::prepare_g1(cs.ns(|| "prepare a"), &gadget_a)
+                .unwrap();
+        assert_eq!(
+            prepared.get_value().unwrap(),
+            G1Prepared::from(a.into_affine())
+        );
+        assert!(cs.is_satisfied());
+    }
+}
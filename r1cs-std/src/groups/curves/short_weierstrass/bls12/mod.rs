@@ -1,7 +1,7 @@
 use algebra::{
     curves::bls12::{Bls12Parameters, G1Prepared, TwistType},
     fields::Field,
-    BitIterator, One, ProjectiveCurve,
+    BitIterator, One, PrimeField, ProjectiveCurve,
 };
 use r1cs_core::{ConstraintSystem, SynthesisError};
 
@@ -14,45 +14,56 @@ use crate::{
 
 use core::fmt::Debug;
 
-pub type G1Gadget<P> = AffineGadget<
-    <P as Bls12Parameters>::G1Parameters,
-    <P as Bls12Parameters>::Fp,
-    FpGadget<<P as Bls12Parameters>::Fp>,
->;
+/// The points of G1 in a constraint system over `ConstraintF`, whose
+/// coordinates are represented by `BF`. By default, `ConstraintF` is the base
+/// field of the curve, which can instead be emulated with
+/// [`NonNativeFieldGadget`](crate::fields::nonnative::NonNativeFieldGadget).
+pub type G1Gadget<P, ConstraintF = <P as Bls12Parameters>::Fp, BF = FpGadget<ConstraintF>> =
+    AffineGadget<<P as Bls12Parameters>::G1Parameters, ConstraintF, BF>;
 
-pub type G2Gadget<P> =
-    AffineGadget<<P as Bls12Parameters>::G2Parameters, <P as Bls12Parameters>::Fp, Fp2G<P>>;
+/// The points of G2, whose coordinates are in `Fp2` over the base field
+/// represented by `BF`, as for [`G1Gadget`].
+pub type G2Gadget<P, ConstraintF = <P as Bls12Parameters>::Fp, BF = FpGadget<ConstraintF>> =
+    AffineGadget<<P as Bls12Parameters>::G2Parameters, ConstraintF, Fp2G<P, ConstraintF, BF>>;
 
 #[derive(Derivative)]
 #[derivative(
-    Clone(bound = "G1Gadget<P>: Clone"),
-    Debug(bound = "G1Gadget<P>: Debug")
+    Clone(bound = "G1Gadget<P, ConstraintF, BF>: Clone"),
+    Debug(bound = "G1Gadget<P, ConstraintF, BF>: Debug")
 )]
-pub struct G1PreparedGadget<P: Bls12Parameters>(pub G1Gadget<P>);
-
-impl<P: Bls12Parameters> G1PreparedGadget<P> {
+pub struct G1PreparedGadget<
+    P: Bls12Parameters,
+    ConstraintF: PrimeField = <P as Bls12Parameters>::Fp,
+    BF: FieldGadget<<P as Bls12Parameters>::Fp, ConstraintF> = FpGadget<ConstraintF>,
+>(pub G1Gadget<P, ConstraintF, BF>);
+
+impl<P: Bls12Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>>
+    G1PreparedGadget<P, ConstraintF, BF>
+{
     pub fn get_value(&self) -> Option<G1Prepared<P>> {
         Some(G1Prepared::from(self.0.get_value().unwrap().into_affine()))
     }
 
-    pub fn from_affine<CS: ConstraintSystem<P::Fp>>(
+    pub fn from_affine<CS: ConstraintSystem<ConstraintF>>(
         _cs: CS,
-        q: &G1Gadget<P>,
+        q: &G1Gadget<P, ConstraintF, BF>,
     ) -> Result<Self, SynthesisError> {
         Ok(G1PreparedGadget(q.clone()))
     }
 }
 
-impl<P: Bls12Parameters> ToBytesGadget<P::Fp> for G1PreparedGadget<P> {
+impl<P: Bls12Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>>
+    ToBytesGadget<ConstraintF> for G1PreparedGadget<P, ConstraintF, BF>
+{
     #[inline]
-    fn to_bytes<CS: ConstraintSystem<P::Fp>>(
+    fn to_bytes<CS: ConstraintSystem<ConstraintF>>(
         &self,
         mut cs: CS,
     ) -> Result<Vec<UInt8>, SynthesisError> {
         self.0.to_bytes(&mut cs.ns(|| "g_alpha to bytes"))
     }
 
-    fn to_non_unique_bytes<CS: ConstraintSystem<P::Fp>>(
+    fn to_non_unique_bytes<CS: ConstraintSystem<ConstraintF>>(
         &self,
         mut cs: CS,
     ) -> Result<Vec<UInt8>, SynthesisError> {
@@ -61,20 +72,26 @@ impl<P: Bls12Parameters> ToBytesGadget<P::Fp> for G1PreparedGadget<P> {
     }
 }
 
-type Fp2G<P> = Fp2Gadget<<P as Bls12Parameters>::Fp2Params, <P as Bls12Parameters>::Fp>;
-type LCoeff<P> = (Fp2G<P>, Fp2G<P>);
+type Fp2G<P, ConstraintF, BF> = Fp2Gadget<<P as Bls12Parameters>::Fp2Params, ConstraintF, BF>;
+type LCoeff<P, ConstraintF, BF> = (Fp2G<P, ConstraintF, BF>, Fp2G<P, ConstraintF, BF>);
 #[derive(Derivative)]
 #[derivative(
-    Clone(bound = "Fp2Gadget<P::Fp2Params, P::Fp>: Clone"),
-    Debug(bound = "Fp2Gadget<P::Fp2Params, P::Fp>: Debug")
+    Clone(bound = "Fp2G<P, ConstraintF, BF>: Clone"),
+    Debug(bound = "Fp2G<P, ConstraintF, BF>: Debug")
 )]
-pub struct G2PreparedGadget<P: Bls12Parameters> {
-    pub ell_coeffs: Vec<LCoeff<P>>,
+pub struct G2PreparedGadget<
+    P: Bls12Parameters,
+    ConstraintF: PrimeField = <P as Bls12Parameters>::Fp,
+    BF: FieldGadget<<P as Bls12Parameters>::Fp, ConstraintF> = FpGadget<ConstraintF>,
+> {
+    pub ell_coeffs: Vec<LCoeff<P, ConstraintF, BF>>,
 }
 
-impl<P: Bls12Parameters> ToBytesGadget<P::Fp> for G2PreparedGadget<P> {
+impl<P: Bls12Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>>
+    ToBytesGadget<ConstraintF> for G2PreparedGadget<P, ConstraintF, BF>
+{
     #[inline]
-    fn to_bytes<CS: ConstraintSystem<P::Fp>>(
+    fn to_bytes<CS: ConstraintSystem<ConstraintF>>(
         &self,
         mut cs: CS,
     ) -> Result<Vec<UInt8>, SynthesisError> {
@@ -87,7 +104,7 @@ impl<P: Bls12Parameters> ToBytesGadget<P::Fp> for G2PreparedGadget<P> {
         Ok(bytes)
     }
 
-    fn to_non_unique_bytes<CS: ConstraintSystem<P::Fp>>(
+    fn to_non_unique_bytes<CS: ConstraintSystem<ConstraintF>>(
         &self,
         mut cs: CS,
     ) -> Result<Vec<UInt8>, SynthesisError> {
@@ -101,13 +118,15 @@ impl<P: Bls12Parameters> ToBytesGadget<P::Fp> for G2PreparedGadget<P> {
     }
 }
 
-impl<P: Bls12Parameters> G2PreparedGadget<P> {
-    pub fn from_affine<CS: ConstraintSystem<P::Fp>>(
+impl<P: Bls12Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>>
+    G2PreparedGadget<P, ConstraintF, BF>
+{
+    pub fn from_affine<CS: ConstraintSystem<ConstraintF>>(
         mut cs: CS,
-        q: &G2Gadget<P>,
+        q: &G2Gadget<P, ConstraintF, BF>,
     ) -> Result<Self, SynthesisError> {
         let two_inv = P::Fp::one().double().inverse().unwrap();
-        let zero = G2Gadget::<P>::zero(cs.ns(|| "zero"))?;
+        let zero = G2Gadget::<P, ConstraintF, BF>::zero(cs.ns(|| "zero"))?;
         q.enforce_not_equal(cs.ns(|| "enforce not zero"), &zero)?;
         let mut ell_coeffs = vec![];
         let mut r = q.clone();
@@ -124,11 +143,11 @@ impl<P: Bls12Parameters> G2PreparedGadget<P> {
         Ok(Self { ell_coeffs })
     }
 
-    fn double<CS: ConstraintSystem<P::Fp>>(
+    fn double<CS: ConstraintSystem<ConstraintF>>(
         mut cs: CS,
-        r: &mut G2Gadget<P>,
+        r: &mut G2Gadget<P, ConstraintF, BF>,
         two_inv: &P::Fp,
-    ) -> Result<LCoeff<P>, SynthesisError> {
+    ) -> Result<LCoeff<P, ConstraintF, BF>, SynthesisError> {
         let a = r.y.inverse(cs.ns(|| "Inverse"))?;
         let mut b = r.x.square(cs.ns(|| "square x"))?;
         let b_tmp = b.clone();
@@ -153,11 +172,11 @@ impl<P: Bls12Parameters> G2PreparedGadget<P> {
         }
     }
 
-    fn add<CS: ConstraintSystem<P::Fp>>(
+    fn add<CS: ConstraintSystem<ConstraintF>>(
         mut cs: CS,
-        r: &mut G2Gadget<P>,
-        q: &G2Gadget<P>,
-    ) -> Result<LCoeff<P>, SynthesisError> {
+        r: &mut G2Gadget<P, ConstraintF, BF>,
+        q: &G2Gadget<P, ConstraintF, BF>,
+    ) -> Result<LCoeff<P, ConstraintF, BF>, SynthesisError> {
         let a =
             q.x.sub(cs.ns(|| "q.x - r.x"), &r.x)?
                 .inverse(cs.ns(|| "calc a"))?;
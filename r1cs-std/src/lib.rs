@@ -36,6 +36,8 @@ use std::{collections::BTreeMap, string::String, vec::Vec};
 pub mod test_constraint_counter;
 pub mod test_constraint_system;
 
+pub mod constraint_profiler;
+
 pub mod bits;
 pub use self::bits::*;
 
@@ -9,28 +9,38 @@ use crate::{
 use algebra::{
     curves::bls12::{Bls12, Bls12Parameters, TwistType},
     fields::BitIterator,
+    PrimeField,
 };
 use core::marker::PhantomData;
 
-pub struct PairingGadget<P: Bls12Parameters>(PhantomData<P>);
+/// The pairing of a BLS12 curve in a constraint system over `ConstraintF`,
+/// with the base field of the curve represented by `BF` as in
+/// [`G1Gadget`](crate::groups::bls12::G1Gadget).
+pub struct PairingGadget<
+    P: Bls12Parameters,
+    ConstraintF: PrimeField = <P as Bls12Parameters>::Fp,
+    BF: FieldGadget<<P as Bls12Parameters>::Fp, ConstraintF> = FpGadget<ConstraintF>,
+>(PhantomData<(P, ConstraintF, BF)>);
 
-type Fp2G<P> = Fp2Gadget<<P as Bls12Parameters>::Fp2Params, <P as Bls12Parameters>::Fp>;
+type Fp2G<P, ConstraintF, BF> = Fp2Gadget<<P as Bls12Parameters>::Fp2Params, ConstraintF, BF>;
 
-impl<P: Bls12Parameters> PairingGadget<P> {
+impl<P: Bls12Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>>
+    PairingGadget<P, ConstraintF, BF>
+{
     // Evaluate the line function at point p.
-    fn ell<CS: ConstraintSystem<P::Fp>>(
+    fn ell<CS: ConstraintSystem<ConstraintF>>(
         mut cs: CS,
-        f: &mut Fp12Gadget<P::Fp12Params, P::Fp>,
-        coeffs: &(Fp2G<P>, Fp2G<P>),
-        p: &G1Gadget<P>,
+        f: &mut Fp12Gadget<P::Fp12Params, ConstraintF, BF>,
+        coeffs: &(Fp2G<P, ConstraintF, BF>, Fp2G<P, ConstraintF, BF>),
+        p: &G1Gadget<P, ConstraintF, BF>,
     ) -> Result<(), SynthesisError> {
-        let zero = FpGadget::<P::Fp>::zero(cs.ns(|| "fpg zero"))?;
+        let zero = BF::zero(cs.ns(|| "fpg zero"))?;
 
         match P::TWIST_TYPE {
             TwistType::M => {
                 let c0 = coeffs.0.clone();
                 let mut c1 = coeffs.1.clone();
-                let c2 = Fp2G::<P>::new(p.y.clone(), zero);
+                let c2 = Fp2G::<P, ConstraintF, BF>::new(p.y.clone(), zero);
 
                 c1.c0 = c1.c0.mul(cs.ns(|| "mul c1.c0"), &p.x)?;
                 c1.c1 = c1.c1.mul(cs.ns(|| "mul c1.c1"), &p.x)?;
@@ -38,7 +48,7 @@ impl<P: Bls12Parameters> PairingGadget<P> {
                 Ok(())
             }
             TwistType::D => {
-                let c0 = Fp2G::<P>::new(p.y.clone(), zero);
+                let c0 = Fp2G::<P, ConstraintF, BF>::new(p.y.clone(), zero);
                 let mut c1 = coeffs.0.clone();
                 let c2 = coeffs.1.clone();
 
@@ -50,10 +60,10 @@ impl<P: Bls12Parameters> PairingGadget<P> {
         }
     }
 
-    fn exp_by_x<CS: ConstraintSystem<P::Fp>>(
+    fn exp_by_x<CS: ConstraintSystem<ConstraintF>>(
         mut cs: CS,
-        f: &Fp12Gadget<P::Fp12Params, P::Fp>,
-    ) -> Result<Fp12Gadget<P::Fp12Params, P::Fp>, SynthesisError> {
+        f: &Fp12Gadget<P::Fp12Params, ConstraintF, BF>,
+    ) -> Result<Fp12Gadget<P::Fp12Params, ConstraintF, BF>, SynthesisError> {
         let mut result = f.cyclotomic_exp(cs.ns(|| "exp_by_x"), P::X)?;
         if P::X_IS_NEGATIVE {
             result.conjugate_in_place(cs.ns(|| "conjugate"))?;
@@ -62,14 +72,16 @@ impl<P: Bls12Parameters> PairingGadget<P> {
     }
 }
 
-impl<P: Bls12Parameters> PG<Bls12<P>, P::Fp> for PairingGadget<P> {
-    type G1Gadget = G1Gadget<P>;
-    type G2Gadget = G2Gadget<P>;
-    type G1PreparedGadget = G1PreparedGadget<P>;
-    type G2PreparedGadget = G2PreparedGadget<P>;
-    type GTGadget = Fp12Gadget<P::Fp12Params, P::Fp>;
+impl<P: Bls12Parameters, ConstraintF: PrimeField, BF: FieldGadget<P::Fp, ConstraintF>>
+    PG<Bls12<P>, ConstraintF> for PairingGadget<P, ConstraintF, BF>
+{
+    type G1Gadget = G1Gadget<P, ConstraintF, BF>;
+    type G2Gadget = G2Gadget<P, ConstraintF, BF>;
+    type G1PreparedGadget = G1PreparedGadget<P, ConstraintF, BF>;
+    type G2PreparedGadget = G2PreparedGadget<P, ConstraintF, BF>;
+    type GTGadget = Fp12Gadget<P::Fp12Params, ConstraintF, BF>;
 
-    fn miller_loop<CS: ConstraintSystem<P::Fp>>(
+    fn miller_loop<CS: ConstraintSystem<ConstraintF>>(
         mut cs: CS,
         ps: &[Self::G1PreparedGadget],
         qs: &[Self::G2PreparedGadget],
@@ -104,7 +116,7 @@ impl<P: Bls12Parameters> PG<Bls12<P>, P::Fp> for PairingGadget<P> {
         Ok(f)
     }
 
-    fn final_exponentiation<CS: ConstraintSystem<P::Fp>>(
+    fn final_exponentiation<CS: ConstraintSystem<ConstraintF>>(
         mut cs: CS,
         f: &Self::GTGadget,
     ) -> Result<Self::GTGadget, SynthesisError> {
@@ -163,14 +175,14 @@ impl<P: Bls12Parameters> PG<Bls12<P>, P::Fp> for PairingGadget<P> {
         })
     }
 
-    fn prepare_g1<CS: ConstraintSystem<P::Fp>>(
+    fn prepare_g1<CS: ConstraintSystem<ConstraintF>>(
         cs: CS,
         p: &Self::G1Gadget,
     ) -> Result<Self::G1PreparedGadget, SynthesisError> {
         Self::G1PreparedGadget::from_affine(cs, p)
     }
 
-    fn prepare_g2<CS: ConstraintSystem<P::Fp>>(
+    fn prepare_g2<CS: ConstraintSystem<ConstraintF>>(
         cs: CS,
         q: &Self::G2Gadget,
     ) -> Result<Self::G2PreparedGadget, SynthesisError> {
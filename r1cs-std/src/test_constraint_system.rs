@@ -1,6 +1,8 @@
 use crate::{BTreeMap, String, Vec};
 use algebra::Field;
-use r1cs_core::{ConstraintSystem, Index, LinearCombination, SynthesisError, Variable};
+use r1cs_core::{
+    ConstraintSystem, Index, LinearCombination, SynthesisError, UnsatisfiedConstraint, Variable,
+};
 
 #[derive(Debug)]
 enum NamedObject {
@@ -85,6 +87,41 @@ impl<ConstraintF: Field> TestConstraintSystem<ConstraintF> {
         None
     }
 
+    /// Returns every constraint that the assignment does not satisfy, with
+    /// the values of its linear combinations and of its variables.
+    pub fn unsatisfied_constraints(&self) -> Vec<UnsatisfiedConstraint<ConstraintF>> {
+        let mut unsatisfied = Vec::new();
+        for (i, &(ref a, ref b, ref c, ref path)) in self.constraints.iter().enumerate() {
+            let a_val = Self::eval_lc(a.as_ref(), &self.inputs, &self.aux);
+            let b_val = Self::eval_lc(b.as_ref(), &self.inputs, &self.aux);
+            let c_val = Self::eval_lc(c.as_ref(), &self.inputs, &self.aux);
+            if a_val * &b_val == c_val {
+                continue;
+            }
+
+            let indices = a
+                .as_ref()
+                .iter()
+                .chain(b.as_ref())
+                .chain(c.as_ref())
+                .map(|(var, _)| var.get_unchecked());
+            unsatisfied.push(UnsatisfiedConstraint::new(
+                i,
+                path.clone(),
+                (a_val, b_val, c_val),
+                indices,
+                |index| {
+                    let (value, name) = match index {
+                        Index::Input(index) => &self.inputs[index],
+                        Index::Aux(index) => &self.aux[index],
+                    };
+                    (name.clone(), *value)
+                },
+            ));
+        }
+        unsatisfied
+    }
+
     pub fn is_satisfied(&self) -> bool {
         self.which_is_unsatisfied().is_none()
     }
@@ -228,3 +265,88 @@ impl<ConstraintF: Field> ConstraintSystem<ConstraintF> for TestConstraintSystem<
         self.constraints.len()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::TestConstraintSystem;
+    use algebra::{bls12_381::Fr, Field, One};
+    use r1cs_core::{ConstraintSystem, ForkSynthesizer, SynthesisError};
+
+    // Squares 3 twice, under the same names every time.
+    struct Squarings;
+
+    impl ForkSynthesizer<Fr> for Squarings {
+        type Output = ();
+
+        fn synthesize<CS: ConstraintSystem<Fr>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+            let mut value = Fr::from(3u64);
+            let mut x = cs.alloc(|| "x", || Ok(value))?;
+            for i in 0..2 {
+                value.square_in_place();
+                let square = cs.alloc(|| format!("square {}", i), || Ok(value))?;
+                cs.enforce(
+                    || format!("squaring {}", i),
+                    |lc| lc + x,
+                    |lc| lc + x,
+                    |lc| lc + square,
+                );
+                x = square;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn forks_get_namespaces_of_their_own() {
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        cs.fork(vec![Squarings, Squarings]).unwrap();
+        assert!(cs.is_satisfied());
+        assert_eq!(cs.num_constraints(), 4);
+        assert_eq!(cs.get("fork 0/square 1"), Fr::from(81u64));
+        assert_eq!(cs.get("fork 1/square 1"), Fr::from(81u64));
+    }
+
+    #[test]
+    fn reports_all_unsatisfied_constraints() {
+        let mut cs = TestConstraintSystem::<Fr>::new();
+        let x = cs.alloc_input(|| "x", || Ok(Fr::from(3u64))).unwrap();
+        let (y, z) = {
+            let mut cs = cs.ns(|| "gadget");
+            let y = cs.alloc(|| "y", || Ok(Fr::from(4u64))).unwrap();
+            let z = cs.alloc(|| "z", || Ok(Fr::from(12u64))).unwrap();
+            cs.enforce(|| "x*y=z", |lc| lc + x, |lc| lc + y, |lc| lc + z);
+            cs.enforce(|| "x*x=z", |lc| lc + x, |lc| lc + x, |lc| lc + z);
+            (y, z)
+        };
+        cs.enforce(
+            || "y=z",
+            |lc| lc + y,
+            |lc| lc + TestConstraintSystem::<Fr>::one(),
+            |lc| lc + z,
+        );
+
+        assert_eq!(cs.which_is_unsatisfied(), Some("gadget/x*x=z"));
+        let unsatisfied = cs.unsatisfied_constraints();
+        assert_eq!(unsatisfied.len(), 2);
+
+        assert_eq!(unsatisfied[0].index, 1);
+        assert_eq!(unsatisfied[0].name, "gadget/x*x=z");
+        assert_eq!(unsatisfied[0].namespace, "gadget");
+        assert_eq!(unsatisfied[0].a, Fr::from(3u64));
+        assert_eq!(unsatisfied[0].b, Fr::from(3u64));
+        assert_eq!(unsatisfied[0].c, Fr::from(12u64));
+        assert_eq!(
+            unsatisfied[0].variables,
+            vec![
+                ("x".to_string(), Fr::from(3u64)),
+                ("gadget/z".to_string(), Fr::from(12u64))
+            ]
+        );
+
+        assert_eq!(unsatisfied[1].index, 2);
+        assert_eq!(unsatisfied[1].namespace, "");
+        assert_eq!(unsatisfied[1].b, Fr::one());
+        assert_eq!(unsatisfied[1].variables.len(), 3);
+        assert_eq!(unsatisfied[1].variables[1].0, "ONE");
+    }
+}